@@ -1,6 +1,10 @@
 use std::{env, path::Path};
 
 fn main() {
+    // With `dlopen` libqhyccd is loaded at runtime, there is nothing to link.
+    if env::var("CARGO_FEATURE_DLOPEN").is_ok() {
+        return;
+    }
     let vendored = env::var("CARGO_FEATURE_VENDORED").is_ok();
     // Specify `LIBQHYCCD_NO_VENDOR` to force to use system libqhyccd.
     // Due to the additive nature of Cargo features, if some crate in the
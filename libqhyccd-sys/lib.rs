@@ -1,5 +1,7 @@
 use core::ffi::c_char;
 
+pub const QHYCCD_READ_DIRECTLY: u32 = 0x2001;
+pub const QHYCCD_DELAY_200MS: u32 = 0x2000;
 pub const QHYCCD_PCIE: u32 = 9;
 pub const QHYCCD_WINPCAP: u32 = 8;
 pub const QHYCCD_QGIGAE: u32 = 7;
@@ -15,11 +17,79 @@ pub const QHYCCD_ERROR_F64: f64 = u32::MAX as f64;
 
 pub type QhyccdHandle = *const core::ffi::c_void;
 
-#[link(name = "qhyccd", kind = "static")]
-extern "C" {
+/// Declares the SDK functions. They are linked against libqhyccd at build time, or with the
+/// `dlopen` feature wrapped in functions that call into the library loaded at runtime. Older
+/// SDK releases lack some of the functions, `is_available` tells which ones are there, calling a
+/// missing one fails like a failed call into the SDK.
+macro_rules! qhyccd_functions {
+    ($(pub fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        #[cfg(not(feature = "dlopen"))]
+        #[link(name = "qhyccd", kind = "static")]
+        extern "C" {
+            $(pub fn $name($($arg: $ty),*) $(-> $ret)?;)*
+        }
 
+        /// the functions of the loaded library
+        #[cfg(feature = "dlopen")]
+        #[allow(non_snake_case)]
+        struct Functions {
+            $($name: Option<unsafe extern "C" fn($($ty),*) $(-> $ret)?>,)*
+        }
+
+        #[cfg(feature = "dlopen")]
+        impl Functions {
+            unsafe fn resolve(library: &libloading::Library) -> Self {
+                Functions {
+                    $($name: library
+                        .get(concat!(stringify!($name), "\0").as_bytes())
+                        .ok()
+                        .map(|function| *function),)*
+                }
+            }
+        }
+
+        /// Returns `true` if libqhyccd provides the function `name`, e.g. `"GetQHYCCDHumidity"`.
+        /// When linked at build time every declared function is there. With `dlopen` this is
+        /// `false` for functions missing from the loaded library and if it cannot be loaded.
+        pub fn is_available(name: &str) -> bool {
+            #[cfg(not(feature = "dlopen"))]
+            return [$(stringify!($name)),*].contains(&name);
+            #[cfg(feature = "dlopen")]
+            match functions() {
+                Some(functions) => match name {
+                    $(name if name == stringify!($name) => functions.$name.is_some(),)*
+                    _ => false,
+                },
+                None => false,
+            }
+        }
+
+        $(
+            /// # Safety
+            /// Calls into libqhyccd, which is loaded on first use if `load` was not called.
+            /// If it cannot be loaded or lacks this function nothing is called and the result is
+            /// the one of a failed call, e.g. `QHYCCD_ERROR` or a null handle. Call `load` and
+            /// `is_available` first to tell those cases apart.
+            #[cfg(feature = "dlopen")]
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                match functions().and_then(|functions| functions.$name) {
+                    Some(function) => function($($arg),*),
+                    None => Fallback::fallback(),
+                }
+            }
+        )*
+    };
+}
+
+qhyccd_functions! {
     pub fn InitQHYCCDResource() -> u32;
     pub fn ScanQHYCCD() -> u32;
+    pub fn SetQHYCCDLogLevel(log_level: u8);
+    pub fn EnableQHYCCDMessage(enable: bool);
+    pub fn EnableQHYCCDLogFile(enable: bool);
+    pub fn RegisterPnpEventIn(in_callback: extern "C" fn(id: *mut c_char));
+    pub fn RegisterPnpEventOut(out_callback: extern "C" fn(id: *mut c_char));
     pub fn GetQHYCCDSDKVersion(
         year: *mut u32,
         month: *mut u32,
@@ -96,6 +166,19 @@ extern "C" {
     pub fn GetQHYCCDReadModeName(handle: QhyccdHandle, mode: u32, name: *mut c_char) -> u32;
     pub fn GetQHYCCDReadMode(handle: QhyccdHandle, mode: *mut u32) -> u32;
     pub fn GetQHYCCDModel(handle: QhyccdHandle, model: *mut c_char) -> u32;
+    pub fn GetQHYCCDSensorName(handle: QhyccdHandle, name: *mut c_char) -> u32;
+    pub fn GetQHYCCDFPGAVersion(handle: QhyccdHandle, fpga_index: u8, buf: *mut u8) -> u32;
+    pub fn GetQHYCCDPreciseExposureInfo(
+        handle: QhyccdHandle,
+        pixel_period_ps: *mut u32,
+        line_period_ns: *mut u32,
+        frame_period_us: *mut u32,
+        clocks_per_line: *mut u32,
+        lines_per_frame: *mut u32,
+        actual_exposure_time: *mut u32,
+        is_long_exposure_mode: *mut u8,
+    ) -> u32;
+    pub fn GetQHYCCDRollingShutterEndOffset(handle: QhyccdHandle, row: u32, offset: *mut f64) -> u32;
     pub fn GetQHYCCDType(handle: QhyccdHandle) -> u32;
     pub fn GetQHYCCDExposureRemaining(handle: QhyccdHandle) -> u32;
     pub fn CancelQHYCCDExposing(handle: QhyccdHandle) -> u32;
@@ -110,4 +193,123 @@ extern "C" {
     ) -> u32;
     pub fn GetQHYCCDCFWStatus(handle: QhyccdHandle, status: *mut c_char) -> u32;
     pub fn SendOrder2QHYCCDCFW(handle: QhyccdHandle, order: *const c_char, length: u32) -> u32;
+    pub fn SetQHYCCDTrigerFunction(handle: QhyccdHandle, value: bool) -> u32;
+    pub fn SetQHYCCDGPSVCOXFreq(handle: QhyccdHandle, i: u16) -> u32;
+    pub fn SetQHYCCDGPSLedCalMode(handle: QhyccdHandle, i: u8) -> u32;
+    pub fn SetQHYCCDGPSLedCal(handle: QhyccdHandle, pos: u32, width: u8);
+    pub fn SetQHYCCDGPSPOSA(handle: QhyccdHandle, is_slave: u8, pos: u32, width: u8);
+    pub fn SetQHYCCDGPSPOSB(handle: QhyccdHandle, is_slave: u8, pos: u32, width: u8);
+    pub fn GetQHYCCDHumidity(handle: QhyccdHandle, hd: *mut f64) -> u32;
+    pub fn GetQHYCCDPressure(handle: QhyccdHandle, pressure: *mut f64) -> u32;
+    pub fn SetQHYCCDTrigerMode(handle: QhyccdHandle, trigger_mode: u32) -> u32;
+    pub fn EnableQHYCCDTrigerOut(handle: QhyccdHandle) -> u32;
+    pub fn GetQHYCCDTrigerInterfaceNumber(handle: QhyccdHandle, mode_number: *mut u32) -> u32;
+    pub fn GetQHYCCDTrigerInterfaceName(
+        handle: QhyccdHandle,
+        mode_number: u32,
+        name: *mut c_char,
+    ) -> u32;
+    pub fn SetQHYCCDTrigerInterface(handle: QhyccdHandle, trigger_interface: u32) -> u32;
+    pub fn QHYCCDReadInitConfigFlash(handle: QhyccdHandle, config: *mut u8);
+    pub fn QHYCCDEraseInitConfigFlash(handle: QhyccdHandle);
+    pub fn QHYCCDSetFlashInitPWM(handle: QhyccdHandle, pwm: u8);
+    pub fn QHYCCDResetFlashULVOError(handle: QhyccdHandle);
+    pub fn QHYCCDSensorPhaseReTrain(handle: QhyccdHandle);
+}
+
+/// the functions of the library loaded by `load` or `load_from`, set once and never freed, the
+/// library is never unloaded. Calls only read it, so they do not contend for a lock.
+#[cfg(feature = "dlopen")]
+static FUNCTIONS: std::sync::atomic::AtomicPtr<Functions> =
+    std::sync::atomic::AtomicPtr::new(std::ptr::null_mut());
+
+/// held while the library is loaded, so it is loaded only once
+#[cfg(feature = "dlopen")]
+static LOADING: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// the result of a wrapper whose function cannot be called, the same the SDK returns for a
+/// failed call
+#[cfg(feature = "dlopen")]
+trait Fallback {
+    fn fallback() -> Self;
+}
+
+#[cfg(feature = "dlopen")]
+impl Fallback for u32 {
+    fn fallback() -> Self {
+        QHYCCD_ERROR
+    }
+}
+
+#[cfg(feature = "dlopen")]
+impl Fallback for f64 {
+    fn fallback() -> Self {
+        QHYCCD_ERROR_F64
+    }
+}
+
+#[cfg(feature = "dlopen")]
+impl Fallback for QhyccdHandle {
+    fn fallback() -> Self {
+        std::ptr::null()
+    }
+}
+
+#[cfg(feature = "dlopen")]
+impl Fallback for () {
+    fn fallback() -> Self {}
+}
+
+/// Loads libqhyccd from the default library search path, e.g. `libqhyccd.so` on Linux.
+/// Does nothing if the library is already loaded.
+/// # Errors
+/// If the library cannot be found or opened
+#[cfg(feature = "dlopen")]
+pub fn load() -> Result<(), libloading::Error> {
+    if is_loaded() {
+        return Ok(());
+    }
+    load_from(libloading::library_filename("qhyccd"))
+}
+
+/// Loads libqhyccd from `path`. Does nothing if a library is already loaded.
+/// # Errors
+/// If the library cannot be found or opened
+#[cfg(feature = "dlopen")]
+pub fn load_from<P: AsRef<std::ffi::OsStr>>(path: P) -> Result<(), libloading::Error> {
+    let _loading = LOADING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !is_loaded() {
+        // SAFETY: libqhyccd has no initialization routines that could be unsound to run here
+        let library = unsafe { libloading::Library::new(path)? };
+        let functions = unsafe { Functions::resolve(&library) };
+        // the functions point into the library, it has to stay loaded for the rest of the process
+        std::mem::forget(library);
+        FUNCTIONS.store(
+            Box::into_raw(Box::new(functions)),
+            std::sync::atomic::Ordering::Release,
+        );
+    }
+    Ok(())
+}
+
+/// Returns `true` if libqhyccd has been loaded
+#[cfg(feature = "dlopen")]
+pub fn is_loaded() -> bool {
+    !FUNCTIONS
+        .load(std::sync::atomic::Ordering::Acquire)
+        .is_null()
+}
+
+/// the functions of libqhyccd, loading it on first use, `None` if it cannot be loaded
+#[cfg(feature = "dlopen")]
+fn functions() -> Option<&'static Functions> {
+    load().ok()?;
+    // SAFETY: set once by `load_from` to a box that is never freed
+    unsafe {
+        FUNCTIONS
+            .load(std::sync::atomic::Ordering::Acquire)
+            .as_ref()
+    }
 }
@@ -0,0 +1,139 @@
+//! Traits for accessories attached alongside cameras and filter wheels
+//!
+//! `FocusMotor` and `Rotator` give pipeline components like per-filter focus offsets or mosaic
+//! rotation a common interface. `SimulatedFocusMotor` and `SimulatedRotator` implement them in
+//! memory, so such components can be built and tested before hardware bindings exist.
+
+use std::sync::Mutex;
+
+use crate::QHYError::{InvalidFocuserPositionError, InvalidRotatorAngleError};
+use crate::Result;
+
+/// A motor moving the focuser of the optical train
+pub trait FocusMotor {
+    /// Returns the current position in steps
+    fn position(&self) -> Result<i32>;
+    /// Returns the highest position the focuser can move to, the lowest one is 0
+    fn max_position(&self) -> Result<i32>;
+    /// Starts moving to `position`
+    fn move_to(&self, position: i32) -> Result<()>;
+    /// Returns `true` while the focuser is still moving
+    fn is_moving(&self) -> Result<bool>;
+    /// Moves by `steps` relative to the current position
+    fn move_by(&self, steps: i32) -> Result<()> {
+        self.move_to(self.position()?.saturating_add(steps))
+    }
+}
+
+/// A rotator turning the camera around the optical axis
+pub trait Rotator {
+    /// Returns the current mechanical angle in degrees within `0.0..360.0`
+    fn angle(&self) -> Result<f64>;
+    /// Starts rotating to `angle` in degrees, the angle is normalized to `0.0..360.0`
+    fn rotate_to(&self, angle: f64) -> Result<()>;
+    /// Returns `true` while the rotator is still moving
+    fn is_moving(&self) -> Result<bool>;
+}
+
+#[derive(Debug)]
+/// A `FocusMotor` that arrives at every position immediately
+pub struct SimulatedFocusMotor {
+    position: Mutex<i32>,
+    max_position: i32,
+}
+
+impl SimulatedFocusMotor {
+    /// Creates a focuser at position 0 that can move up to `max_position`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{FocusMotor, SimulatedFocusMotor};
+    /// let focuser = SimulatedFocusMotor::new(10_000);
+    /// focuser.move_to(5_000).expect("move_to failed");
+    /// assert_eq!(focuser.position().unwrap(), 5_000);
+    /// ```
+    pub fn new(max_position: i32) -> Self {
+        Self {
+            position: Mutex::new(0),
+            max_position,
+        }
+    }
+}
+
+impl FocusMotor for SimulatedFocusMotor {
+    fn position(&self) -> Result<i32> {
+        Ok(*self
+            .position
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
+    fn max_position(&self) -> Result<i32> {
+        Ok(self.max_position)
+    }
+
+    fn move_to(&self, position: i32) -> Result<()> {
+        if !(0..=self.max_position).contains(&position) {
+            let error = InvalidFocuserPositionError {
+                position,
+                max: self.max_position,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        *self
+            .position
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = position;
+        Ok(())
+    }
+
+    fn is_moving(&self) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+#[derive(Debug, Default)]
+/// A `Rotator` that arrives at every angle immediately
+pub struct SimulatedRotator {
+    angle: Mutex<f64>,
+}
+
+impl SimulatedRotator {
+    /// Creates a rotator at 0°
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Rotator, SimulatedRotator};
+    /// let rotator = SimulatedRotator::new();
+    /// rotator.rotate_to(-90.0).expect("rotate_to failed");
+    /// assert_eq!(rotator.angle().unwrap(), 270.0);
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Rotator for SimulatedRotator {
+    fn angle(&self) -> Result<f64> {
+        Ok(*self
+            .angle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
+    fn rotate_to(&self, angle: f64) -> Result<()> {
+        if !angle.is_finite() {
+            let error = InvalidRotatorAngleError { angle };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        *self
+            .angle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = angle.rem_euclid(360.0);
+        Ok(())
+    }
+
+    fn is_moving(&self) -> Result<bool> {
+        Ok(false)
+    }
+}
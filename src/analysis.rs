@@ -0,0 +1,216 @@
+//! Star detection and focus metrics
+//!
+//! `detect_stars` finds stars as connected groups of pixels clearly above the background and
+//! measures their position, flux, half flux radius (HFR) and full width at half maximum (FWHM).
+//! `half_flux_radius` reduces a whole frame to the median HFR of its stars, the usual metric for
+//! autofocus. Only the first channel of frames with more channels is used.
+
+use crate::arithmetic::read_samples;
+use crate::ImageData;
+use crate::QHYError::NoStarSignalError;
+use crate::Result;
+
+/// pixels more than this many noise levels above the background belong to a star
+const DETECTION_SIGMA: f64 = 5.0;
+/// converts the median absolute deviation into the standard deviation of normal noise
+const MAD_TO_SIGMA: f64 = 1.4826;
+/// converts the standard deviation of a gaussian profile into its FWHM
+const SIGMA_TO_FWHM: f64 = 2.354_82;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// A star found by `detect_stars`
+pub struct Star {
+    /// the horizontal position of the flux weighted centroid in pixels
+    pub x: f64,
+    /// the vertical position of the flux weighted centroid in pixels
+    pub y: f64,
+    /// the sum of all samples of the star above the background
+    pub flux: f64,
+    /// the largest sample of the star above the background
+    pub peak: f64,
+    /// the half flux radius in pixels
+    pub hfr: f64,
+    /// the full width at half maximum in pixels, estimated from the second moment
+    pub fwhm: f64,
+}
+
+/// the first channel of a frame with its background level and noise
+struct Plane {
+    samples: Vec<f64>,
+    width: usize,
+    height: usize,
+    background: f64,
+    noise: f64,
+}
+
+impl Plane {
+    fn of(image: &ImageData) -> Result<Plane> {
+        let channels = image.channels.max(1) as usize;
+        let width = image.width as usize;
+        let mut samples = read_samples(image)?
+            .into_iter()
+            .step_by(channels)
+            .take(width * image.height as usize)
+            .map(|sample| sample as f64)
+            .collect::<Vec<_>>();
+        // only complete rows are analysed
+        let height = samples.len().checked_div(width).unwrap_or(0);
+        samples.truncate(width * height);
+        let background = median(samples.clone());
+        let noise = MAD_TO_SIGMA
+            * median(
+                samples
+                    .iter()
+                    .map(|sample| (sample - background).abs())
+                    .collect(),
+            );
+        Ok(Plane {
+            width,
+            height,
+            samples,
+            background,
+            noise,
+        })
+    }
+
+    /// the sample at `x`, `y` above the background
+    fn signal(&self, x: usize, y: usize) -> f64 {
+        self.samples[y * self.width + x] - self.background
+    }
+
+    /// collects the pixels connected to `start` that are above `threshold` and marks them
+    fn flood(&self, start: usize, threshold: f64, visited: &mut [bool]) -> Vec<usize> {
+        let mut pixels = Vec::new();
+        let mut stack = vec![start];
+        visited[start] = true;
+        while let Some(index) = stack.pop() {
+            pixels.push(index);
+            let (x, y) = ((index % self.width) as isize, (index / self.width) as isize);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 0 || ny < 0 || nx >= self.width as isize || ny >= self.height as isize {
+                        continue;
+                    }
+                    let neighbor = ny as usize * self.width + nx as usize;
+                    if !visited[neighbor] && self.samples[neighbor] > threshold {
+                        visited[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        pixels
+    }
+
+    /// measures the star made up of `pixels`, the profile is measured within a window around
+    /// the centroid that is a bit larger than the star itself
+    fn measure(&self, pixels: &[usize]) -> Star {
+        let coordinates = |index: usize| ((index % self.width) as f64, (index / self.width) as f64);
+        let flux = pixels
+            .iter()
+            .map(|&index| self.samples[index] - self.background)
+            .sum::<f64>();
+        let (cx, cy) = pixels.iter().fold((0.0, 0.0), |(cx, cy), &index| {
+            let (x, y) = coordinates(index);
+            let signal = self.samples[index] - self.background;
+            (cx + x * signal / flux, cy + y * signal / flux)
+        });
+        let extent = pixels
+            .iter()
+            .map(|&index| {
+                let (x, y) = coordinates(index);
+                ((x - cx).powi(2) + (y - cy).powi(2)).sqrt()
+            })
+            .fold(0.0, f64::max);
+        let window = extent + 3.0;
+        let x_range =
+            (cx - window).max(0.0) as usize..=((cx + window) as usize).min(self.width - 1);
+        let y_range =
+            (cy - window).max(0.0) as usize..=((cy + window) as usize).min(self.height - 1);
+        let (mut total, mut weighted_radius, mut second_moment, mut peak) = (0.0, 0.0, 0.0, 0.0);
+        for y in y_range {
+            for x in x_range.clone() {
+                let signal = self.signal(x, y);
+                let r2 = (x as f64 - cx).powi(2) + (y as f64 - cy).powi(2);
+                if signal <= 0.0 || r2 > window * window {
+                    continue;
+                }
+                total += signal;
+                weighted_radius += r2.sqrt() * signal;
+                second_moment += r2 * signal;
+                peak = f64::max(peak, signal);
+            }
+        }
+        Star {
+            x: cx,
+            y: cy,
+            flux,
+            peak,
+            hfr: weighted_radius / total,
+            // the second moment of a 2D gaussian is 2 sigma^2
+            fwhm: SIGMA_TO_FWHM * (second_moment / total / 2.0).sqrt(),
+        }
+    }
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_unstable_by(|a, b| a.total_cmp(b));
+    match values.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) / 2.0,
+        len => values[len / 2],
+    }
+}
+
+/// Finds all stars in `image` and returns them ordered from the brightest to the faintest
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::analysis::detect_stars;
+/// use qhyccd_rs::ImageData;
+/// let mut data = vec![10_u8; 100];
+/// data[44] = 200;
+/// data[45] = 120;
+/// let image = ImageData { data, width: 10, height: 10, bits_per_pixel: 8, channels: 1 };
+/// let stars = detect_stars(&image).expect("detect_stars failed");
+/// println!("star at {:.1}, {:.1} with HFR {:.2}", stars[0].x, stars[0].y, stars[0].hfr);
+/// ```
+pub fn detect_stars(image: &ImageData) -> Result<Vec<Star>> {
+    let plane = Plane::of(image)?;
+    let threshold = plane.background + (DETECTION_SIGMA * plane.noise).max(1.0);
+    let mut visited = vec![false; plane.samples.len()];
+    let mut stars = Vec::new();
+    for index in 0..plane.samples.len() {
+        if visited[index] || plane.samples[index] <= threshold {
+            continue;
+        }
+        let pixels = plane.flood(index, threshold, &mut visited);
+        stars.push(plane.measure(&pixels));
+    }
+    stars.sort_by(|a, b| b.flux.total_cmp(&a.flux));
+    Ok(stars)
+}
+
+/// Returns the median half flux radius of all stars in `image`. Fails with `NoStarSignalError`
+/// if no star was found.
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::analysis::half_flux_radius;
+/// use qhyccd_rs::Sdk;
+/// let sdk = Sdk::new().expect("SDK::new failed");
+/// let camera = sdk.cameras().last().expect("no camera found");
+/// camera.open().expect("open failed");
+/// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+/// let buffer_size = camera.get_image_size().expect("get_image_size failed");
+/// let image = camera.get_single_frame(buffer_size).expect("get_single_frame failed");
+/// println!("HFR: {:.2}", half_flux_radius(&image).expect("no stars found"));
+/// ```
+pub fn half_flux_radius(image: &ImageData) -> Result<f64> {
+    let stars = detect_stars(image)?;
+    if stars.is_empty() {
+        let error = NoStarSignalError;
+        tracing::error!(error = ?error);
+        return Err(error);
+    }
+    Ok(median(stars.iter().map(|star| star.hfr).collect()))
+}
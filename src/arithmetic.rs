@@ -0,0 +1,181 @@
+//! Pixel arithmetic on `ImageData` frames
+//!
+//! All operations work sample by sample on frames with matching geometry. When the two frames
+//! have different bit depths, the narrower one is promoted and the result uses the wider depth.
+//! A frame whose `data` is too short for its geometry fails with `BufferTooSmallError`, padding
+//! the SDK added after the pixels is ignored.
+//! Samples wider than 8 bits are stored little endian, just like the SDK delivers them.
+
+use crate::QHYError::{BufferTooSmallError, ImageGeometryMismatchError};
+use crate::{ImageData, PixelFormat, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// Decides what happens when the result of an operation does not fit into the bit depth
+pub enum OverflowPolicy {
+    /// clamp the result to `0..=max` of the bit depth
+    Saturating,
+    /// wrap around modulo the range of the bit depth
+    Wrapping,
+}
+
+/// returns the number of bytes used to store a single sample of the given bit depth
+pub(crate) fn bytes_per_sample(bits_per_pixel: u32) -> Result<usize> {
+    Ok(PixelFormat::from_bits_per_pixel(bits_per_pixel)?.bytes_per_sample())
+}
+
+/// reads all samples of a frame and widens them to `u64`
+pub(crate) fn read_samples(image: &ImageData) -> Result<Vec<u64>> {
+    let samples = match bytes_per_sample(image.bits_per_pixel)? {
+        1 => image.data.iter().map(|&s| s as u64).collect(),
+        2 => image
+            .data
+            .chunks_exact(2)
+            .map(|s| u16::from_le_bytes([s[0], s[1]]) as u64)
+            .collect(),
+        _ => image
+            .data
+            .chunks_exact(4)
+            .map(|s| u32::from_le_bytes([s[0], s[1], s[2], s[3]]) as u64)
+            .collect(),
+    };
+    Ok(samples)
+}
+
+/// reads the samples of the pixels of a frame without the padding the SDK may add to the buffer,
+/// fails with `BufferTooSmallError` if `data` does not hold all pixels of the frame
+pub(crate) fn pixel_samples(image: &ImageData) -> Result<Vec<u64>> {
+    let required = image.expected_len()?;
+    if image.data.len() < required {
+        let error = BufferTooSmallError {
+            required,
+            actual: image.data.len(),
+        };
+        tracing::error!(error = ?error);
+        return Err(error);
+    }
+    let mut samples = read_samples(image)?;
+    samples.truncate(image.width as usize * image.height as usize * image.channels as usize);
+    Ok(samples)
+}
+
+/// stores samples into a byte buffer, the samples have to fit into the given bit depth already
+pub(crate) fn write_samples(samples: &[u64], bits_per_pixel: u32) -> Result<Vec<u8>> {
+    let data = match bytes_per_sample(bits_per_pixel)? {
+        1 => samples.iter().map(|&s| s as u8).collect(),
+        2 => samples
+            .iter()
+            .flat_map(|&s| (s as u16).to_le_bytes())
+            .collect(),
+        _ => samples
+            .iter()
+            .flat_map(|&s| (s as u32).to_le_bytes())
+            .collect(),
+    };
+    Ok(data)
+}
+
+/// the largest sample value the camera can report with the given bit depth
+pub(crate) fn max_sample(bits_per_pixel: u32) -> Result<u64> {
+    bytes_per_sample(bits_per_pixel)?;
+    Ok((1_u64 << bits_per_pixel) - 1)
+}
+
+impl ImageData {
+    fn combine(
+        &self,
+        other: &ImageData,
+        policy: OverflowPolicy,
+        op: impl Fn(i128, i128) -> Option<i128>,
+    ) -> Result<ImageData> {
+        if self.width != other.width
+            || self.height != other.height
+            || self.channels != other.channels
+        {
+            let error = ImageGeometryMismatchError;
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let bits_per_pixel = self.bits_per_pixel.max(other.bits_per_pixel);
+        let max = max_sample(bits_per_pixel)? as i128;
+        let lhs = pixel_samples(self)?;
+        let rhs = pixel_samples(other)?;
+        let samples = lhs
+            .iter()
+            .zip(rhs.iter())
+            .map(|(&a, &b)| {
+                let result = op(a as i128, b as i128);
+                match policy {
+                    OverflowPolicy::Saturating => result.unwrap_or(max).clamp(0, max) as u64,
+                    OverflowPolicy::Wrapping => result.unwrap_or(0).rem_euclid(max + 1) as u64,
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(ImageData {
+            data: write_samples(&samples, bits_per_pixel)?,
+            width: self.width,
+            height: self.height,
+            bits_per_pixel,
+            channels: self.channels,
+        })
+    }
+
+    /// Adds `other` to this frame sample by sample
+    /// # Example
+    /// ```
+    /// use qhyccd_rs::{ImageData, OverflowPolicy};
+    /// let light = ImageData { data: vec![250, 10], width: 2, height: 1, bits_per_pixel: 8, channels: 1 };
+    /// let sum = light.add(&light, OverflowPolicy::Saturating).expect("add failed");
+    /// assert_eq!(sum.data, vec![255, 20]);
+    /// ```
+    pub fn add(&self, other: &ImageData, policy: OverflowPolicy) -> Result<ImageData> {
+        self.combine(other, policy, |a, b| Some(a + b))
+    }
+
+    /// Subtracts `other` from this frame sample by sample, e.g. to remove a master dark
+    /// # Example
+    /// ```
+    /// use qhyccd_rs::{ImageData, OverflowPolicy};
+    /// let light = ImageData { data: vec![100, 10], width: 2, height: 1, bits_per_pixel: 8, channels: 1 };
+    /// let dark = ImageData { data: vec![20, 20], width: 2, height: 1, bits_per_pixel: 8, channels: 1 };
+    /// let calibrated = light.subtract(&dark, OverflowPolicy::Saturating).expect("subtract failed");
+    /// assert_eq!(calibrated.data, vec![80, 0]);
+    /// ```
+    pub fn subtract(&self, other: &ImageData, policy: OverflowPolicy) -> Result<ImageData> {
+        self.combine(other, policy, |a, b| Some(a - b))
+    }
+
+    /// Divides this frame by `other` sample by sample and multiplies the quotient by `scale`,
+    /// rounding to the nearest integer. Without a scale, dividing a light by a flat gives
+    /// ratios around 1.0 that the integer samples cannot hold, so for flat fielding pass the
+    /// mean of the flat as `scale`, e.g. from `ImageData::statistics`, which keeps the result at
+    /// the level of the light. A division by zero is treated as an overflow, so it results in the
+    /// maximum sample value for `OverflowPolicy::Saturating` and in zero for
+    /// `OverflowPolicy::Wrapping`
+    /// # Example
+    /// ```
+    /// use qhyccd_rs::{ImageData, OverflowPolicy};
+    /// let light = ImageData { data: vec![100, 10], width: 2, height: 1, bits_per_pixel: 8, channels: 1 };
+    /// let flat = ImageData { data: vec![90, 0], width: 2, height: 1, bits_per_pixel: 8, channels: 1 };
+    /// let scale = flat.statistics().expect("statistics failed").mean;
+    /// let calibrated = light.divide(&flat, scale, OverflowPolicy::Saturating).expect("divide failed");
+    /// assert_eq!(calibrated.data, vec![50, 255]);
+    /// ```
+    pub fn divide(
+        &self,
+        other: &ImageData,
+        scale: f64,
+        policy: OverflowPolicy,
+    ) -> Result<ImageData> {
+        self.combine(other, policy, |a, b| match b {
+            0 => None,
+            b => {
+                let quotient = (a as f64 * scale / b as f64).round();
+                // NaN or out of range for a scale that is not finite
+                match quotient.is_finite() {
+                    true => Some(quotient as i128),
+                    false => None,
+                }
+            }
+        })
+    }
+}
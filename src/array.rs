@@ -0,0 +1,112 @@
+//! Conversion between `ImageData` and `ndarray` arrays
+//!
+//! Mono frames map to an `Array2` indexed by `[y, x]`, frames with more than one channel map to
+//! an `Array3` indexed by `[y, x, channel]`. Samples are widened to `u16`, so 8 bit frames can be
+//! converted as well.
+
+use ndarray::{Array2, Array3};
+
+use crate::QHYError::{self, ChannelCountMismatchError, ExternalError, PixelFormatMismatchError};
+use crate::{ImageData, PixelBuffer, PixelFormat, Result};
+
+/// the sample count does not match the frame dimensions
+fn shape_error(error: ndarray::ShapeError) -> QHYError {
+    tracing::error!(error = ?error);
+    ExternalError(Box::new(error))
+}
+
+impl ImageData {
+    /// the samples widened to `u16`, fails for 32 bit frames
+    fn u16_samples(&self) -> Result<Vec<u16>> {
+        match self.clone().into_pixel_buffer()? {
+            PixelBuffer::U8(samples) => Ok(samples.into_iter().map(u16::from).collect()),
+            PixelBuffer::U16(samples) => Ok(samples),
+            PixelBuffer::U32(_) => {
+                let error = PixelFormatMismatchError {
+                    expected: PixelFormat::U16,
+                    actual: PixelFormat::U32,
+                };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns a mono frame as array with the shape `(height, width)`. Fails with
+    /// `ChannelCountMismatchError` for frames with more than one channel and with
+    /// `PixelFormatMismatchError` for 32 bit frames.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData { data: vec![1, 0, 2, 0], width: 2, height: 1, bits_per_pixel: 16, channels: 1 };
+    /// let array = image.to_array2().expect("to_array2 failed");
+    /// assert_eq!(array[[0, 1]], 2);
+    /// ```
+    pub fn to_array2(&self) -> Result<Array2<u16>> {
+        if self.channels != 1 {
+            let error = ChannelCountMismatchError {
+                expected: 1,
+                actual: self.channels,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let shape = (self.height as usize, self.width as usize);
+        Array2::from_shape_vec(shape, self.u16_samples()?).map_err(shape_error)
+    }
+
+    /// Returns a frame as array with the shape `(height, width, channels)`, works for mono frames
+    /// as well. Fails with `PixelFormatMismatchError` for 32 bit frames.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData { data: vec![10, 20, 30], width: 1, height: 1, bits_per_pixel: 8, channels: 3 };
+    /// let array = image.to_array3().expect("to_array3 failed");
+    /// assert_eq!(array[[0, 0, 2]], 30);
+    /// ```
+    pub fn to_array3(&self) -> Result<Array3<u16>> {
+        let shape = (
+            self.height as usize,
+            self.width as usize,
+            self.channels as usize,
+        );
+        Array3::from_shape_vec(shape, self.u16_samples()?).map_err(shape_error)
+    }
+
+    /// Creates a 16 bit mono frame from an array with the shape `(height, width)`
+    /// # Example
+    /// ```no_run
+    /// use ndarray::Array2;
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData::from_array2(&Array2::from_elem((480, 640), 1000));
+    /// assert_eq!((image.width, image.height), (640, 480));
+    /// ```
+    pub fn from_array2(array: &Array2<u16>) -> ImageData {
+        let (height, width) = array.dim();
+        ImageData {
+            data: array
+                .iter()
+                .flat_map(|sample| sample.to_le_bytes())
+                .collect(),
+            width: width as u32,
+            height: height as u32,
+            bits_per_pixel: 16,
+            channels: 1,
+        }
+    }
+
+    /// Creates a 16 bit frame from an array with the shape `(height, width, channels)`
+    pub fn from_array3(array: &Array3<u16>) -> ImageData {
+        let (height, width, channels) = array.dim();
+        ImageData {
+            data: array
+                .iter()
+                .flat_map(|sample| sample.to_le_bytes())
+                .collect(),
+            width: width as u32,
+            height: height as u32,
+            bits_per_pixel: 16,
+            channels: channels as u32,
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Async API for use with tokio
+//!
+//! The SDK calls block, a single frame download for example returns only after the exposure
+//! finished. `AsyncCamera` and `AsyncFilterWheel` move these calls onto a dedicated executor
+//! thread and hand the results back through a channel, so the async runtime is never blocked.
+//! All calls made through one executor run one after the other in the order they were made.
+
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+
+use crate::QHYError::{AsyncExecutorError, GetLiveFrameError};
+use crate::{Camera, ExposureHandle, FilterWheel, ImageData, Result, NO_FRAME_YET};
+
+/// how long `AsyncCamera::next_live_frame` waits before asking the camera for a frame again
+const LIVE_FRAME_POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// how long `AsyncCamera::expose` waits before checking again whether the frame arrived
+const EXPOSURE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Debug)]
+/// runs jobs one after the other on its own thread
+struct Executor {
+    jobs: Sender<Job>,
+}
+
+impl Executor {
+    fn new() -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        // the thread ends once all senders are gone and the queue is drained, it is not joined
+        // on drop, so dropping an executor during a long exposure does not block the runtime
+        thread::spawn(move || {
+            for job in queue {
+                job();
+            }
+        });
+        Self { jobs }
+    }
+
+    /// queues `f` without waiting for it, for the work left to do when a caller went away
+    fn spawn(&self, f: impl FnOnce() + Send + 'static) {
+        if self.jobs.send(Box::new(f)).is_err() {
+            tracing::warn!(error = ?AsyncExecutorError, "could not queue job");
+        }
+    }
+
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        let (result, receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            // the receiver is gone if the caller stopped waiting, nothing left to do then
+            let _ = result.send(f());
+        });
+        if self.jobs.send(job).is_err() {
+            let error = AsyncExecutorError;
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        receiver.await.map_err(|_| {
+            let error = AsyncExecutorError;
+            tracing::error!(error = ?error);
+            error
+        })?
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Async wrapper around a `Camera`, clones share the same executor thread
+pub struct AsyncCamera {
+    camera: Camera,
+    executor: Arc<Executor>,
+}
+
+impl AsyncCamera {
+    /// Wraps `camera` and starts its executor thread
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{AsyncCamera, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// let camera = AsyncCamera::new(camera.clone());
+    /// ```
+    pub fn new(camera: Camera) -> Self {
+        Self {
+            camera,
+            executor: Arc::new(Executor::new()),
+        }
+    }
+
+    /// Returns the wrapped camera
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Returns an `AsyncFilterWheel` for the filter wheel plugged into this camera. It shares the
+    /// executor thread with the camera, because both talk to the SDK through the same handle.
+    pub fn filter_wheel(&self) -> AsyncFilterWheel {
+        AsyncFilterWheel {
+            filter_wheel: FilterWheel::new(self.camera.clone()),
+            executor: self.executor.clone(),
+        }
+    }
+
+    /// Runs `f` with the wrapped camera on the executor thread, for all calls that have no
+    /// dedicated async method
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{AsyncCamera, Control, Sdk};
+    /// # async fn example() {
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = AsyncCamera::new(sdk.cameras().last().expect("no camera found").clone());
+    /// let temperature = camera
+    ///     .run(|camera| camera.get_parameter(Control::CurTemp))
+    ///     .await
+    ///     .expect("get_parameter failed");
+    /// # }
+    /// ```
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Camera) -> Result<T> + Send + 'static,
+    {
+        let camera = self.camera.clone();
+        self.executor.run(move || f(&camera)).await
+    }
+
+    /// Takes a single frame with the given exposure time, the camera has to be open, initialized
+    /// and in single frame mode. Only starting the exposure runs on the executor thread, the
+    /// download runs on a thread of its own, see `Camera::start_exposure`, so other calls like
+    /// an abort are not queued behind the exposure. Dropping the future before it finished
+    /// aborts the exposure and discards the frame, see `ExposureHandle::abort_and_discard`.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{AsyncCamera, Sdk, StreamMode};
+    /// # async fn example() {
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// let camera = AsyncCamera::new(camera.clone());
+    /// let image = camera.expose(Duration::from_secs(300)).await.expect("expose failed");
+    /// # }
+    /// ```
+    pub async fn expose(&self, exposure: Duration) -> Result<ImageData> {
+        let handle = self
+            .run(move |camera| camera.start_exposure(exposure))
+            .await?;
+        let mut pending = PendingExposure {
+            handle: Some(handle),
+            executor: self.executor.clone(),
+        };
+        while !pending.is_finished() {
+            tokio::time::sleep(EXPOSURE_POLL_INTERVAL).await;
+        }
+        pending.finish()
+    }
+
+    /// Waits for the next frame while the camera is in Live Video Mode. Every attempt to read a
+    /// frame is a separate call on the executor thread, so other calls are not held up while
+    /// waiting.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{AsyncCamera, Sdk, StreamMode};
+    /// # async fn example() {
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// let camera = AsyncCamera::new(camera.clone());
+    /// for _ in 0..100 {
+    ///     let image = camera.next_live_frame().await.expect("next_live_frame failed");
+    /// }
+    /// # }
+    /// ```
+    pub async fn next_live_frame(&self) -> Result<ImageData> {
+        let buffer_size = self.run(|camera| camera.get_image_size()).await?;
+        loop {
+            match self
+                .run(move |camera| camera.get_live_frame(buffer_size))
+                .await
+            {
+                Err(GetLiveFrameError {
+                    error_code: NO_FRAME_YET,
+                }) => tokio::time::sleep(LIVE_FRAME_POLL_INTERVAL).await,
+                result => return result,
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+/// the exposure of an `AsyncCamera::expose` future, aborted if the future is dropped early
+struct PendingExposure {
+    handle: Option<ExposureHandle>,
+    executor: Arc<Executor>,
+}
+
+impl PendingExposure {
+    fn is_finished(&self) -> bool {
+        self.handle
+            .as_ref()
+            .map_or(true, ExposureHandle::is_finished)
+    }
+
+    fn finish(&mut self) -> Result<ImageData> {
+        match self.handle.take() {
+            Some(handle) => handle.wait(),
+            None => Err(AsyncExecutorError),
+        }
+    }
+}
+
+impl Drop for PendingExposure {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            // waiting for the download to end blocks, so it is left to the executor thread
+            self.executor.spawn(move || {
+                if let Err(error) = handle.abort_and_discard() {
+                    tracing::warn!(error = ?error, "could not abort dropped exposure");
+                }
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Async wrapper around a `FilterWheel`, clones share the same executor thread
+pub struct AsyncFilterWheel {
+    filter_wheel: FilterWheel,
+    executor: Arc<Executor>,
+}
+
+impl AsyncFilterWheel {
+    /// Wraps `filter_wheel` and starts its executor thread. Use `AsyncCamera::filter_wheel`
+    /// instead if the camera it is plugged into is used asynchronously as well.
+    pub fn new(filter_wheel: FilterWheel) -> Self {
+        Self {
+            filter_wheel,
+            executor: Arc::new(Executor::new()),
+        }
+    }
+
+    /// Returns the wrapped filter wheel
+    pub fn filter_wheel(&self) -> &FilterWheel {
+        &self.filter_wheel
+    }
+
+    /// Runs `f` with the wrapped filter wheel on the executor thread, for all calls that have no
+    /// dedicated async method
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&FilterWheel) -> Result<T> + Send + 'static,
+    {
+        let filter_wheel = self.filter_wheel.clone();
+        self.executor.run(move || f(&filter_wheel)).await
+    }
+
+    /// Moves the filter wheel to `position` and waits until it stopped there, see
+    /// `FilterWheel::move_to_position`
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{AsyncFilterWheel, Sdk};
+    /// # async fn example() {
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let fw = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// fw.open().expect("open failed");
+    /// let fw = AsyncFilterWheel::new(fw.clone());
+    /// fw.move_filter(3, Duration::from_secs(30)).await.expect("move_filter failed");
+    /// # }
+    /// ```
+    pub async fn move_filter(&self, position: u32, timeout: Duration) -> Result<()> {
+        self.run(move |filter_wheel| filter_wheel.move_to_position(position, timeout))
+            .await
+    }
+}
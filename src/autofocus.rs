@@ -0,0 +1,253 @@
+//! V-curve autofocus
+//!
+//! `autofocus` sweeps a `FocusMotor` through a range of positions around the current one, takes
+//! a short exposure at every position and measures the half flux diameter (HFD) of the star
+//! light in the frame. The HFD of a star grows linearly with the defocus on both sides of the
+//! focus, so the samples form a V that is fitted with the hyperbola
+//! `hfd(x) = a * sqrt(1 + ((x - c) / b)^2)`, whose center `c` is the best focus position.
+//!
+//! Every position is approached from below, so the mechanical backlash of the focuser does
+//! not distort the curve. The measurement is a closure in `run_autofocus`, which keeps the
+//! sweep usable with other cameras or in tests.
+
+use std::time::{Duration, Instant};
+
+use crate::analysis::half_flux_radius;
+use crate::QHYError::{AutofocusFitError, FocuserTimeoutError};
+use crate::{Camera, FocusMotor, ImageData, Result};
+
+/// the minimum number of samples needed to fit the hyperbola
+const MIN_SAMPLES: usize = 3;
+/// how often the focuser is polled while it is moving
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, PartialEq, Clone)]
+/// Settings used by `autofocus`
+pub struct AutofocusSettings {
+    /// the focuser steps between two samples
+    pub step_size: i32,
+    /// the number of samples taken on each side of the starting position
+    pub steps_per_side: u32,
+    /// the exposure time of every sample frame
+    pub exposure: Duration,
+    /// the focuser steps to overshoot below a position before approaching it
+    pub backlash: i32,
+    /// the maximum time a single focuser move may take
+    pub move_timeout: Duration,
+}
+
+impl Default for AutofocusSettings {
+    fn default() -> Self {
+        Self {
+            step_size: 100,
+            steps_per_side: 4,
+            exposure: Duration::from_secs(2),
+            backlash: 0,
+            move_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// The result of an autofocus run
+pub struct AutofocusResult {
+    /// the position at the center of the fitted V-curve, the focuser is left there
+    pub best_position: i32,
+    /// the HFD in pixels the fit predicts at `best_position`
+    pub best_hfd: f64,
+    /// the coefficient of determination of the fit, 1.0 is a perfect fit
+    pub r_squared: f64,
+    /// the measured samples as focuser position and HFD in pixels
+    pub samples: Vec<(i32, f64)>,
+}
+
+/// Returns the half flux diameter in pixels of the stars in `image`, twice the median half flux
+/// radius measured by `analysis::half_flux_radius`. Only pixels clearly above the background
+/// noise and within a window around each detected star count as star flux, for frames with
+/// more channels only the first one is used. Fails with `NoStarSignalError` if no star was
+/// found.
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::{half_flux_diameter, ImageData};
+/// let mut data = vec![10_u8; 25];
+/// data[12] = 250;
+/// let image = ImageData { data, width: 5, height: 5, bits_per_pixel: 8, channels: 1 };
+/// assert_eq!(half_flux_diameter(&image).unwrap(), 0.0);
+/// ```
+pub fn half_flux_diameter(image: &ImageData) -> Result<f64> {
+    Ok(2.0 * half_flux_radius(image)?)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// the fitted hyperbola `hfd(x) = a * sqrt(1 + ((x - c) / b)^2)`
+struct Hyperbola {
+    a: f64,
+    b: f64,
+    c: f64,
+}
+
+impl Hyperbola {
+    fn hfd(&self, position: f64) -> f64 {
+        self.a * (1.0 + ((position - self.c) / self.b).powi(2)).sqrt()
+    }
+}
+
+/// solves the 3x3 linear system `m * x = v` with Cramer's rule
+fn solve3(m: [[f64; 3]; 3], v: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(m);
+    if d.abs() < f64::EPSILON {
+        return None;
+    }
+    let mut x = [0.0; 3];
+    for (column, x) in x.iter_mut().enumerate() {
+        let mut mc = m;
+        for row in 0..3 {
+            mc[row][column] = v[row];
+        }
+        *x = det(mc) / d;
+    }
+    Some(x)
+}
+
+/// fits the hyperbola by fitting a parabola to the squared HFD, returns it with its R²
+fn fit_hyperbola(samples: &[(i32, f64)]) -> Result<(Hyperbola, f64)> {
+    let fail = || {
+        let error = AutofocusFitError;
+        tracing::error!(error = ?error, ?samples);
+        error
+    };
+    if samples.len() < MIN_SAMPLES {
+        return Err(fail());
+    }
+    // center the positions to keep the normal equations well conditioned
+    let n = samples.len() as f64;
+    let mean = samples.iter().map(|(x, _)| *x as f64).sum::<f64>() / n;
+    let scale = samples
+        .iter()
+        .map(|(x, _)| (*x as f64 - mean).abs())
+        .fold(1.0, f64::max);
+    let mut m = [[0.0; 3]; 3];
+    let mut v = [0.0; 3];
+    for (x, hfd) in samples {
+        let x = (*x as f64 - mean) / scale;
+        let powers = [x * x, x, 1.0];
+        for row in 0..3 {
+            for column in 0..3 {
+                m[row][column] += powers[row] * powers[column];
+            }
+            v[row] += powers[row] * hfd * hfd;
+        }
+    }
+    let [qa, qb, qc] = solve3(m, v).ok_or_else(fail)?;
+    let vertex = -qb / (2.0 * qa);
+    let minimum = qc - qb * qb / (4.0 * qa);
+    if qa <= 0.0 || minimum <= 0.0 {
+        return Err(fail());
+    }
+    let a = minimum.sqrt();
+    let hyperbola = Hyperbola {
+        a,
+        b: a / qa.sqrt() * scale,
+        c: vertex * scale + mean,
+    };
+    let mean_hfd = samples.iter().map(|(_, hfd)| hfd).sum::<f64>() / n;
+    let total = samples
+        .iter()
+        .map(|(_, hfd)| (hfd - mean_hfd).powi(2))
+        .sum::<f64>();
+    let residual = samples
+        .iter()
+        .map(|(x, hfd)| (hfd - hyperbola.hfd(*x as f64)).powi(2))
+        .sum::<f64>();
+    let r_squared = match total > 0.0 {
+        true => 1.0 - residual / total,
+        false => 1.0,
+    };
+    Ok((hyperbola, r_squared))
+}
+
+/// waits until the focuser stopped moving
+fn wait_for_focuser(focuser: &dyn FocusMotor, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    while focuser.is_moving()? {
+        if Instant::now() >= deadline {
+            let error = FocuserTimeoutError { timeout };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// moves to `position`, overshooting by `backlash` first if the move goes downwards
+fn approach(focuser: &dyn FocusMotor, position: i32, settings: &AutofocusSettings) -> Result<()> {
+    if settings.backlash > 0 && position < focuser.position()? {
+        focuser.move_to((position - settings.backlash).max(0))?;
+        wait_for_focuser(focuser, settings.move_timeout)?;
+    }
+    focuser.move_to(position)?;
+    wait_for_focuser(focuser, settings.move_timeout)
+}
+
+/// Runs the V-curve sweep around the current focuser position with `measure` returning the
+/// HFD at the current position, see the module documentation. The focuser is left at the best
+/// position.
+pub fn run_autofocus(
+    focuser: &dyn FocusMotor,
+    settings: &AutofocusSettings,
+    mut measure: impl FnMut() -> Result<f64>,
+) -> Result<AutofocusResult> {
+    let start = focuser.position()?;
+    let max = focuser.max_position()?;
+    let half_range = settings.step_size * settings.steps_per_side as i32;
+    let first = (start - half_range).max(0);
+    let last = (start + half_range).min(max);
+    let mut samples = Vec::new();
+    for position in (first..=last).step_by(settings.step_size.max(1) as usize) {
+        approach(focuser, position, settings)?;
+        let hfd = measure()?;
+        tracing::debug!(position, hfd, "autofocus sample");
+        samples.push((position, hfd));
+    }
+    let (hyperbola, r_squared) = fit_hyperbola(&samples)?;
+    let best_position = (hyperbola.c.round() as i32).clamp(0, max);
+    approach(focuser, best_position, settings)?;
+    Ok(AutofocusResult {
+        best_position,
+        best_hfd: hyperbola.hfd(best_position as f64),
+        r_squared,
+        samples,
+    })
+}
+
+/// Runs the V-curve autofocus with single frame exposures of `camera`. The camera has to be
+/// open, initialized in single frame mode and pointed at a single, not saturated star.
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::{autofocus, AutofocusSettings, Sdk, SimulatedFocusMotor};
+/// let sdk = Sdk::new().expect("SDK::new failed");
+/// let camera = sdk.cameras().last().expect("no camera found");
+/// camera.open().expect("open failed");
+/// let focuser = SimulatedFocusMotor::new(50_000);
+/// let result = autofocus(camera, &focuser, &AutofocusSettings::default()).expect("autofocus failed");
+/// println!("best focus at {} with HFD {:.2}", result.best_position, result.best_hfd);
+/// ```
+pub fn autofocus(
+    camera: &Camera,
+    focuser: &dyn FocusMotor,
+    settings: &AutofocusSettings,
+) -> Result<AutofocusResult> {
+    camera.set_exposure(settings.exposure)?;
+    run_autofocus(focuser, settings, || {
+        camera.start_single_frame_exposure()?;
+        let buffer_size = camera.get_image_size()?;
+        let hfr = half_flux_radius(&camera.get_single_frame(buffer_size)?)?;
+        Ok(2.0 * hfr)
+    })
+}
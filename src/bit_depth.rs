@@ -0,0 +1,111 @@
+//! The significant bits of 16 bit frames
+//!
+//! Many sensors digitize with 12 or 14 bits, their 16 bit frames only carry that many significant
+//! bits. Cameras supporting `Control::OutputDataActualBits` and `Control::OutputDataAlignment`
+//! report how many bits are significant and whether they sit in the high or the low bits of a
+//! sample. `Camera::get_sample_depth` reads both, `FrameMetadata::sample_depth` keeps them with a
+//! frame and `ImageData::normalize_to_16bit` moves the significant bits to the top, so frames of
+//! different cameras share the same full scale.
+
+use crate::QHYError::{InvalidActualBitsError, UnknownDataAlignmentError};
+use crate::{Camera, Control, ImageData, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Where the significant bits sit in a 16 bit sample, as reported by
+/// `Control::OutputDataAlignment`
+pub enum DataAlignment {
+    /// the significant bits are the high bits, the samples already use the full 16 bit scale
+    HighBits = 0,
+    /// the significant bits are the low bits, the samples top out at `2^actual_bits - 1`
+    LowBits = 1,
+}
+
+impl TryFrom<u32> for DataAlignment {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            x if x == DataAlignment::HighBits as u32 => Ok(DataAlignment::HighBits),
+            x if x == DataAlignment::LowBits as u32 => Ok(DataAlignment::LowBits),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The significant bits of the samples of a frame, returned from `Camera::get_sample_depth`
+pub struct SampleDepth {
+    /// the number of significant bits per sample, e.g. 12 or 14
+    pub actual_bits: u32,
+    /// where the significant bits sit in a sample
+    pub alignment: DataAlignment,
+}
+
+impl Camera {
+    /// Returns the significant bits of the frames the camera currently delivers. Fails with
+    /// `IsControlAvailableError` for cameras not reporting `Control::OutputDataActualBits` or
+    /// `Control::OutputDataAlignment`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let depth = camera.get_sample_depth().expect("get_sample_depth failed");
+    /// println!("{} significant bits, {:?}", depth.actual_bits, depth.alignment);
+    /// ```
+    pub fn get_sample_depth(&self) -> Result<SampleDepth> {
+        let actual_bits = self.get_if_available(Control::OutputDataActualBits)? as u32;
+        let value = self.get_if_available(Control::OutputDataAlignment)? as u32;
+        match DataAlignment::try_from(value) {
+            Ok(alignment) => Ok(SampleDepth {
+                actual_bits,
+                alignment,
+            }),
+            Err(_) => {
+                let error = UnknownDataAlignmentError { value };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl ImageData {
+    /// Moves the significant bits of a 16 bit frame to the top of the samples, so the frame uses
+    /// the full 16 bit scale. Frames whose significant bits already are the high bits are
+    /// returned unchanged. Fails with `PixelFormatMismatchError` for frames with other bit
+    /// depths and with `InvalidActualBitsError` if `depth` has no or more than 16 bits.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{DataAlignment, ImageData, SampleDepth};
+    /// let image = ImageData { data: vec![0xff, 0x0f], width: 1, height: 1, bits_per_pixel: 16, channels: 1 };
+    /// let depth = SampleDepth { actual_bits: 12, alignment: DataAlignment::LowBits };
+    /// let normalized = image.normalize_to_16bit(&depth).expect("normalize_to_16bit failed");
+    /// assert_eq!(normalized.data, vec![0xf0, 0xff]);
+    /// ```
+    pub fn normalize_to_16bit(&self, depth: &SampleDepth) -> Result<ImageData> {
+        if !(1..=16).contains(&depth.actual_bits) {
+            let error = InvalidActualBitsError {
+                bits: depth.actual_bits,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let samples = self.as_u16_slice()?;
+        let shift = match depth.alignment {
+            DataAlignment::HighBits => 0,
+            DataAlignment::LowBits => 16 - depth.actual_bits,
+        };
+        let max = u16::MAX >> shift;
+        Ok(ImageData {
+            data: samples
+                .iter()
+                .flat_map(|&s| (s.min(max) << shift).to_le_bytes())
+                .collect(),
+            ..self.clone()
+        })
+    }
+}
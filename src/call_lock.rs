@@ -0,0 +1,75 @@
+//! Serialization of the SDK calls for one camera
+//!
+//! The SDK is not thread safe per handle, two threads calling into it with the same handle at
+//! the same time can corrupt its state. All clones of a `Camera`, and the `FilterWheel` attached
+//! to it, share one `CallLock` that every call through `read_lock!` holds until the SDK returned.
+//! The lock is reentrant, a method calling other methods of the same camera does not deadlock.
+//!
+//! The blocking single frame download holds the lock for the whole exposure as well, other calls
+//! on the camera wait until the frame arrived. The only exceptions are the calls meant to be made
+//! from another thread while it blocks: `get_remaining_exposure_us`, `stop_exposure` and
+//! `abort_exposure_and_readout`.
+
+use std::marker::PhantomData;
+use std::sync::{Condvar, Mutex};
+use std::thread::{self, ThreadId};
+
+#[derive(Debug, Default)]
+/// the thread currently calling into the SDK and how often it locked
+struct Owner {
+    thread: Option<ThreadId>,
+    depth: usize,
+}
+
+#[derive(Debug, Default)]
+/// a reentrant lock around the SDK calls of one camera
+pub(crate) struct CallLock {
+    owner: Mutex<Owner>,
+    released: Condvar,
+}
+
+impl CallLock {
+    /// blocks until no other thread holds the lock
+    pub(crate) fn lock(&self) -> CallGuard<'_> {
+        let current = thread::current().id();
+        let mut owner = self
+            .owner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        while matches!(owner.thread, Some(thread) if thread != current) {
+            owner = self
+                .released
+                .wait(owner)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        owner.thread = Some(current);
+        owner.depth += 1;
+        CallGuard {
+            lock: self,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// releases the `CallLock` when dropped, it must stay on the thread that locked
+pub(crate) struct CallGuard<'a> {
+    lock: &'a CallLock,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for CallGuard<'_> {
+    fn drop(&mut self) {
+        let mut owner = self
+            .lock
+            .owner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        owner.depth -= 1;
+        if owner.depth == 0 {
+            owner.thread = None;
+            drop(owner);
+            self.lock.released.notify_one();
+        }
+    }
+}
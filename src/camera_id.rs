@@ -0,0 +1,100 @@
+//! Finding cameras by id, serial number or model
+//!
+//! The SDK identifies a camera by a string like `QHY178M-222b16468c5966524`, the model followed
+//! by the serial number. `CameraId` splits it into both parts, so configurations can refer to a
+//! camera by its serial number without taking the string apart themselves.
+
+use std::fmt;
+
+use crate::{Camera, Sdk};
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The model and serial number a camera id consists of
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::CameraId;
+/// let id = CameraId::parse("QHY178M-222b16468c5966524").expect("not a camera id");
+/// assert_eq!(id.model, "QHY178M");
+/// assert_eq!(id.serial, "222b16468c5966524");
+/// ```
+pub struct CameraId {
+    /// the camera model, e.g. `QHY178M`
+    pub model: String,
+    /// the serial number of the camera
+    pub serial: String,
+}
+
+impl CameraId {
+    /// Splits `id` at its last `-` into model and serial number, returns `None` if either part
+    /// would be empty
+    pub fn parse(id: &str) -> Option<Self> {
+        match id.rsplit_once('-') {
+            Some((model, serial)) if !model.is_empty() && !serial.is_empty() => Some(Self {
+                model: model.to_owned(),
+                serial: serial.to_owned(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CameraId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.model, self.serial)
+    }
+}
+
+impl Camera {
+    /// Returns the id of the camera split into model and serial number, `None` if the id does
+    /// not have the usual form
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// if let Some(id) = camera.camera_id() {
+    ///     println!("{} with serial number {}", id.model, id.serial);
+    /// }
+    /// ```
+    pub fn camera_id(&self) -> Option<CameraId> {
+        CameraId::parse(self.id())
+    }
+}
+
+impl Sdk {
+    /// Returns the camera with the given id
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.camera_by_id("QHY178M-222b16468c5966524").expect("camera not found");
+    /// ```
+    pub fn camera_by_id(&self, id: &str) -> Option<&Camera> {
+        self.cameras().find(|camera| camera.id() == id)
+    }
+
+    /// Returns the camera with the given serial number
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.camera_by_serial("222b16468c5966524").expect("camera not found");
+    /// ```
+    pub fn camera_by_serial(&self, serial: &str) -> Option<&Camera> {
+        self.cameras()
+            .find(|camera| camera.camera_id().map_or(false, |id| id.serial == serial))
+    }
+
+    /// Returns all cameras of the given model
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// println!("{} QHY600M connected", sdk.cameras_by_model("QHY600M").count());
+    /// ```
+    pub fn cameras_by_model<'a>(&'a self, model: &'a str) -> impl Iterator<Item = &'a Camera> {
+        self.cameras()
+            .filter(move |camera| camera.camera_id().map_or(false, |id| id.model == model))
+    }
+}
@@ -0,0 +1,104 @@
+//! Capabilities report for a camera
+//!
+//! `Camera::capabilities` probes every known `Control` once and condenses the result into a
+//! `CameraCapabilities`, so applications do not have to write their own probing loop. With the
+//! `serde` feature enabled the report can be serialized, e.g. to cache it or send it to a client.
+
+use crate::{BayerMode, Camera, Control, PixelFormat};
+
+/// the bin mode controls together with the bin factor they stand for
+pub(crate) const BIN_MODES: [(Control, u32); 6] = [
+    (Control::CamBin1x1mode, 1),
+    (Control::CamBin2x2mode, 2),
+    (Control::CamBin3x3mode, 3),
+    (Control::CamBin4x4mode, 4),
+    (Control::CamBin6x6mode, 6),
+    (Control::CamBin8x8mode, 8),
+];
+
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// What a camera supports, returned from `Camera::capabilities`
+pub struct CameraCapabilities {
+    /// all controls the camera reported as available
+    pub controls: Vec<Control>,
+    /// the camera has a regulated cooler
+    pub cooler: bool,
+    /// the bayer pattern of a color camera, `None` for mono cameras
+    pub bayer_mode: Option<BayerMode>,
+    /// the supported symmetric bin factors, e.g. `2` for 2x2 binning
+    pub bin_modes: Vec<u32>,
+    /// the largest supported bin factor, `1` if the camera reports no bin modes
+    pub max_bin: u32,
+    /// the pixel formats the camera can deliver
+    pub pixel_formats: Vec<PixelFormat>,
+    /// the camera has a trigger interface
+    pub trigger: bool,
+    /// the camera has a GPS module
+    pub gps: bool,
+    /// the camera has a port for a filter wheel
+    pub filter_wheel_port: bool,
+    /// the camera has a humidity sensor
+    pub humidity: bool,
+}
+
+impl CameraCapabilities {
+    /// Returns `true` if `control` was reported as available
+    pub fn supports(&self, control: Control) -> bool {
+        self.controls.contains(&control)
+    }
+
+    /// Returns `true` for color cameras
+    pub fn is_color(&self) -> bool {
+        self.bayer_mode.is_some()
+    }
+}
+
+impl Camera {
+    /// Probes all known controls and returns what the camera supports
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let capabilities = camera.capabilities();
+    /// println!("cooler: {}, bin modes: {:?}", capabilities.cooler, capabilities.bin_modes);
+    /// ```
+    pub fn capabilities(&self) -> CameraCapabilities {
+        let mut bayer_mode = None;
+        let controls = Control::all()
+            .iter()
+            .copied()
+            .filter(|control| match self.is_control_available(*control) {
+                Some(value) => {
+                    if *control == Control::CamColor {
+                        bayer_mode = BayerMode::try_from(value).ok();
+                    }
+                    true
+                }
+                None => false,
+            })
+            .collect::<Vec<_>>();
+        let bin_modes = BIN_MODES
+            .iter()
+            .filter(|(control, _)| controls.contains(control))
+            .map(|(_, bin)| *bin)
+            .collect::<Vec<_>>();
+        CameraCapabilities {
+            cooler: controls.contains(&Control::Cooler),
+            bayer_mode,
+            max_bin: bin_modes.iter().copied().max().unwrap_or(1),
+            bin_modes,
+            pixel_formats: [PixelFormat::U8, PixelFormat::U16, PixelFormat::U32]
+                .into_iter()
+                .filter(|format| controls.contains(&format.control()))
+                .collect(),
+            trigger: controls.contains(&Control::CamTrigerInterface),
+            gps: controls.contains(&Control::CamGps),
+            filter_wheel_port: controls.contains(&Control::CfwPort),
+            humidity: controls.contains(&Control::CamHumidity),
+            controls,
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Capture settings and single frame capture in one call
+//!
+//! `CaptureSettings` collects everything that is usually set before an exposure.
+//! `Camera::apply` checks all of it against what the camera supports before touching anything,
+//! then applies it in the order the SDK expects and restores the previous values if a step
+//! fails. `Camera::capture` additionally switches to single frame mode, exposes and downloads,
+//! which otherwise has to be spelled out step by step as in the examples of the individual
+//! methods.
+
+use std::time::{Duration, Instant};
+
+use crate::capabilities::BIN_MODES;
+use crate::QHYError::{
+    CaptureTimeoutError, ReadoutModeChangeError, ReadoutModeOutOfRangeError,
+    UnsupportedBinModeError, UnsupportedBitDepthError,
+};
+use crate::{CCDChipArea, Camera, Control, ImageData, PixelFormat, Result, StreamMode};
+
+#[derive(Debug, PartialEq, Clone, Default)]
+/// The settings `Camera::apply` and `Camera::capture` apply before exposing, settings left as
+/// `None` are not touched
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use qhyccd_rs::CaptureSettings;
+/// let settings = CaptureSettings::new(Duration::from_secs(2))
+///     .gain(26.0)
+///     .offset(30.0)
+///     .binning(2, 2)
+///     .bit_depth(16);
+/// ```
+pub struct CaptureSettings {
+    /// the exposure time
+    pub exposure: Duration,
+    /// the gain
+    pub gain: Option<f64>,
+    /// the offset
+    pub offset: Option<f64>,
+    /// the region of interest, in binned pixels
+    pub roi: Option<CCDChipArea>,
+    /// the horizontal and vertical binning
+    pub binning: Option<(u32, u32)>,
+    /// the transfer bit depth, 8, 16 or 32
+    pub bit_depth: Option<u32>,
+    /// the index of the readout mode
+    pub readout_mode: Option<u32>,
+    /// the USB traffic
+    pub usb_traffic: Option<u32>,
+    /// how long `Camera::capture` waits for the frame on top of the exposure time, `None` waits
+    /// forever
+    pub timeout: Option<Duration>,
+}
+
+impl CaptureSettings {
+    /// Creates settings with the given exposure time that leave everything else untouched
+    pub fn new(exposure: Duration) -> Self {
+        Self {
+            exposure,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the gain
+    pub fn gain(mut self, gain: f64) -> Self {
+        self.gain = Some(gain);
+        self
+    }
+
+    /// Sets the offset
+    pub fn offset(mut self, offset: f64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the region of interest in binned pixels
+    pub fn roi(mut self, roi: CCDChipArea) -> Self {
+        self.roi = Some(roi);
+        self
+    }
+
+    /// Sets the horizontal and vertical binning
+    pub fn binning(mut self, bin_x: u32, bin_y: u32) -> Self {
+        self.binning = Some((bin_x, bin_y));
+        self
+    }
+
+    /// Sets the transfer bit depth
+    pub fn bit_depth(mut self, bit_depth: u32) -> Self {
+        self.bit_depth = Some(bit_depth);
+        self
+    }
+
+    /// Sets the readout mode by its index
+    pub fn readout_mode(mut self, readout_mode: u32) -> Self {
+        self.readout_mode = Some(readout_mode);
+        self
+    }
+
+    /// Sets the USB traffic
+    pub fn usb_traffic(mut self, usb_traffic: u32) -> Self {
+        self.usb_traffic = Some(usb_traffic);
+        self
+    }
+
+    /// Sets how long `Camera::capture` waits for the frame on top of the exposure time
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// the settings as steps in the order they have to be applied, the bit depth changes the
+    /// frame geometry and the ROI is given in binned pixels. The readout mode is not a step,
+    /// the SDK only applies it on `Camera::init`.
+    fn steps(&self) -> Vec<Step> {
+        [
+            self.bit_depth.map(Step::BitDepth),
+            self.binning
+                .map(|(bin_x, bin_y)| Step::Binning(bin_x, bin_y)),
+            self.roi.map(Step::Roi),
+            self.usb_traffic.map(Step::UsbTraffic),
+            self.gain.map(Step::Gain),
+            self.offset.map(Step::Offset),
+            Some(Step::Exposure(self.exposure)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// a single setting, used both for the new and the previous value
+enum Step {
+    BitDepth(u32),
+    Binning(u32, u32),
+    Roi(CCDChipArea),
+    UsbTraffic(u32),
+    Gain(f64),
+    Offset(f64),
+    Exposure(Duration),
+}
+
+impl Camera {
+    /// Checks all settings against the camera and applies them, the camera has to be open and
+    /// initialized. Nothing is changed if a check fails. If applying a setting fails, the
+    /// settings applied before are restored to their previous values. Binning that was not set
+    /// through this crate before is restored to 1x1 and the ROI to the one from `get_roi`.
+    /// The SDK only applies a readout mode on `init`, so a `readout_mode` other than the
+    /// current one fails with `ReadoutModeChangeError`, `capture` sets it before initializing.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{CaptureSettings, Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// let settings = CaptureSettings::new(Duration::from_secs(2)).gain(26.0).binning(2, 2);
+    /// camera.apply(&settings).expect("apply failed");
+    /// ```
+    pub fn apply(&self, settings: &CaptureSettings) -> Result<()> {
+        if let Some(mode) = settings.readout_mode {
+            self.check_readout_mode(mode)?;
+            let current = self.get_readout_mode()?;
+            if mode != current {
+                let error = ReadoutModeChangeError { mode, current };
+                tracing::error!(error = ?error);
+                return Err(error);
+            }
+        }
+        let steps = settings.steps();
+        for step in &steps {
+            self.validate_step(step, settings)?;
+        }
+        let previous = steps
+            .iter()
+            .map(|step| self.read_step(step))
+            .collect::<Result<Vec<_>>>()?;
+        for (applied, step) in steps.iter().enumerate() {
+            if let Err(error) = self.write_step(step) {
+                for previous in previous[..applied].iter().rev() {
+                    if let Err(restore) = self.write_step(previous) {
+                        tracing::warn!(restore = ?restore, step = ?previous);
+                    }
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// Captures a single frame with the given settings, the camera has to be open. Switches the
+    /// camera to single frame mode and initializes it unless that was already done, a new
+    /// readout mode is set before and always initializes the camera again. Then applies the
+    /// remaining settings with `apply`, exposes and downloads the frame. Fails with `CaptureTimeoutError`
+    /// if the frame did not arrive within the exposure time plus `settings.timeout`. A running
+    /// exposure is aborted if anything fails.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{CaptureSettings, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let settings = CaptureSettings::new(Duration::from_secs(2))
+    ///     .gain(26.0)
+    ///     .binning(2, 2)
+    ///     .timeout(Duration::from_secs(30));
+    /// let image = camera.capture(&settings).expect("capture failed");
+    /// println!("captured {}x{} pixels", image.width, image.height);
+    /// ```
+    pub fn capture(&self, settings: &CaptureSettings) -> Result<ImageData> {
+        let readout_mode = match settings.readout_mode {
+            Some(mode) => {
+                self.check_readout_mode(mode)?;
+                (mode != self.get_readout_mode()?).then_some(mode)
+            }
+            None => None,
+        };
+        // the SDK only applies a readout mode on init, otherwise a series of captures only
+        // initializes the camera for the first one
+        if readout_mode.is_some() || !self.handle.is_initialized_in(StreamMode::SingleFrameMode) {
+            if let Some(mode) = readout_mode {
+                self.set_readout_mode(mode)?;
+            }
+            self.set_stream_mode(StreamMode::SingleFrameMode)?;
+            self.init()?;
+        }
+        self.apply(settings)?;
+        let exposure = self.spawn_exposure(settings.exposure)?;
+        let Some(timeout) = settings.timeout else {
+            return exposure.wait().map_err(|error| {
+                self.abort_after_error();
+                error
+            });
+        };
+        let deadline = Instant::now() + settings.exposure + timeout;
+        while !exposure.is_finished() {
+            if Instant::now() >= deadline {
+                let error = CaptureTimeoutError { timeout };
+                tracing::error!(error = ?error);
+                if let Err(abort) = exposure.abort_and_discard() {
+                    tracing::warn!(abort = ?abort);
+                }
+                return Err(error);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        exposure.wait().map_err(|error| {
+            self.abort_after_error();
+            error
+        })
+    }
+
+    /// leaves the camera ready for the next exposure after a failed one
+    fn abort_after_error(&self) {
+        if let Err(abort) = self.abort_exposure_and_readout() {
+            tracing::warn!(abort = ?abort);
+        }
+    }
+
+    /// checks that the camera has a readout mode with index `mode`
+    fn check_readout_mode(&self, mode: u32) -> Result<()> {
+        let count = self.get_number_of_readout_modes()?;
+        if mode >= count {
+            let error = ReadoutModeOutOfRangeError { mode, count };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// checks `step` against the capabilities and limits of the camera
+    fn validate_step(&self, step: &Step, settings: &CaptureSettings) -> Result<()> {
+        match *step {
+            Step::BitDepth(bits_per_pixel) => {
+                let format = PixelFormat::from_bits_per_pixel(bits_per_pixel)?;
+                if format.bits() != bits_per_pixel
+                    || self.is_control_available(format.control()).is_none()
+                {
+                    let error = UnsupportedBitDepthError { bits_per_pixel };
+                    tracing::error!(error = ?error);
+                    return Err(error);
+                }
+                Ok(())
+            }
+            Step::Binning(bin_x, bin_y) => {
+                let supported = bin_x == bin_y
+                    && BIN_MODES.iter().any(|(control, bin)| {
+                        *bin == bin_x && self.is_control_available(*control).is_some()
+                    });
+                if !supported {
+                    let error = UnsupportedBinModeError { bin_x, bin_y };
+                    tracing::error!(error = ?error);
+                    return Err(error);
+                }
+                Ok(())
+            }
+            Step::Roi(roi) => self.check_roi(
+                roi,
+                settings
+                    .binning
+                    .or(self.frame_geometry().binning)
+                    .unwrap_or((1, 1)),
+            ),
+            Step::UsbTraffic(usb_traffic) => {
+                self.check_within_limits(Control::UsbTraffic, usb_traffic as f64)
+            }
+            Step::Gain(gain) => self.check_within_limits(Control::Gain, gain),
+            Step::Offset(offset) => self.check_within_limits(Control::Offset, offset),
+            Step::Exposure(exposure) => {
+                self.check_within_limits(Control::Exposure, exposure.as_micros() as f64)
+            }
+        }
+    }
+
+    /// reads the current value of the setting changed by `step`
+    fn read_step(&self, step: &Step) -> Result<Step> {
+        Ok(match step {
+            Step::BitDepth(_) => Step::BitDepth(self.get_parameter(Control::TransferBit)? as u32),
+            Step::Binning(..) => {
+                let (bin_x, bin_y) = self.frame_geometry().binning.unwrap_or((1, 1));
+                Step::Binning(bin_x, bin_y)
+            }
+            Step::Roi(_) => Step::Roi(self.get_roi()?),
+            Step::UsbTraffic(_) => Step::UsbTraffic(self.get_usb_traffic()?),
+            Step::Gain(_) => Step::Gain(self.get_gain()?),
+            Step::Offset(_) => Step::Offset(self.get_offset()?),
+            Step::Exposure(_) => Step::Exposure(self.get_exposure()?),
+        })
+    }
+
+    /// applies `step` without checking it again
+    fn write_step(&self, step: &Step) -> Result<()> {
+        match *step {
+            Step::BitDepth(bits_per_pixel) => self.set_bit_mode(bits_per_pixel),
+            Step::Binning(bin_x, bin_y) => self.set_bin_mode(bin_x, bin_y),
+            Step::Roi(roi) => self.write_roi(roi),
+            Step::UsbTraffic(usb_traffic) => {
+                self.set_parameter(Control::UsbTraffic, usb_traffic as f64)
+            }
+            Step::Gain(gain) => self.set_parameter(Control::Gain, gain),
+            Step::Offset(offset) => self.set_parameter(Control::Offset, offset),
+            Step::Exposure(exposure) => {
+                self.set_parameter(Control::Exposure, exposure.as_micros() as f64)
+            }
+        }
+    }
+}
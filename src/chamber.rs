@@ -0,0 +1,156 @@
+//! Vacuum pump and sensor chamber cycle pump of the large format scientific cameras
+//!
+//! Only a few cameras have these pumps, `Camera::chamber` gives typed access to both of them
+//! and every call checks that the pump is actually available first.
+
+use crate::{Camera, Control, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The pumps of a sensor chamber
+pub enum Pump {
+    /// the vacuum pump evacuating the sensor chamber, `Control::VacuumPump`
+    Vacuum,
+    /// the internal circulation pump of the sensor chamber, `Control::SensorChamberCyclePump`
+    ChamberCycle,
+}
+
+impl Pump {
+    fn control(self) -> Control {
+        match self {
+            Pump::Vacuum => Control::VacuumPump,
+            Pump::ChamberCycle => Control::SensorChamberCyclePump,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+/// The state of both pumps, `None` means the camera does not have the pump
+pub struct ChamberStatus {
+    /// `Some(true)` if the vacuum pump is running
+    pub vacuum_pump: Option<bool>,
+    /// `Some(true)` if the chamber cycle pump is running
+    pub cycle_pump: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Typed access to the sensor chamber pumps of a camera, returned from `Camera::chamber`
+pub struct Chamber {
+    camera: Camera,
+}
+
+impl Chamber {
+    /// Returns `true` if the camera has the given pump
+    pub fn has_pump(&self, pump: Pump) -> bool {
+        self.camera.is_control_available(pump.control()).is_some()
+    }
+
+    /// Returns `true` if the camera has any of the pumps
+    pub fn is_present(&self) -> bool {
+        self.has_pump(Pump::Vacuum) || self.has_pump(Pump::ChamberCycle)
+    }
+
+    /// Starts the given pump
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Pump, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.chamber().start_pump(Pump::Vacuum).expect("start_pump failed");
+    /// ```
+    pub fn start_pump(&self, pump: Pump) -> Result<()> {
+        self.camera.set_if_available(pump.control(), 1.0)
+    }
+
+    /// Stops the given pump
+    pub fn stop_pump(&self, pump: Pump) -> Result<()> {
+        self.camera.set_if_available(pump.control(), 0.0)
+    }
+
+    /// Starts the given pump if `on` is `true` and stops it otherwise
+    pub fn set_pump(&self, pump: Pump, on: bool) -> Result<()> {
+        match on {
+            true => self.start_pump(pump),
+            false => self.stop_pump(pump),
+        }
+    }
+
+    /// Returns `true` if the given pump is running, fails with `IsControlAvailableError` if the
+    /// camera does not have it
+    pub fn is_pump_running(&self, pump: Pump) -> Result<bool> {
+        Ok(self.camera.get_if_available(pump.control())? != 0.0)
+    }
+
+    /// Returns the state of both pumps
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let status = camera.chamber().status().expect("status failed");
+    /// println!("vacuum pump: {:?}", status.vacuum_pump);
+    /// ```
+    pub fn status(&self) -> Result<ChamberStatus> {
+        let state = |pump: Pump| match self.has_pump(pump) {
+            true => self
+                .camera
+                .get_parameter(pump.control())
+                .map(|v| Some(v != 0.0)),
+            false => Ok(None),
+        };
+        Ok(ChamberStatus {
+            vacuum_pump: state(Pump::Vacuum)?,
+            cycle_pump: state(Pump::ChamberCycle)?,
+        })
+    }
+}
+
+impl Camera {
+    /// Returns typed access to the vacuum and chamber cycle pumps of the camera
+    pub fn chamber(&self) -> Chamber {
+        Chamber {
+            camera: self.clone(),
+        }
+    }
+
+    /// Starts or stops the vacuum pump, fails with `IsControlAvailableError` if the camera does
+    /// not have one
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_vacuum_pump(true).expect("set_vacuum_pump failed");
+    /// ```
+    pub fn set_vacuum_pump(&self, on: bool) -> Result<()> {
+        self.chamber().set_pump(Pump::Vacuum, on)
+    }
+
+    /// Returns `true` if the vacuum pump is running, fails with `IsControlAvailableError` if the
+    /// camera does not have one
+    pub fn is_vacuum_pump_running(&self) -> Result<bool> {
+        self.chamber().is_pump_running(Pump::Vacuum)
+    }
+
+    /// Starts or stops the sensor chamber cycle pump, fails with `IsControlAvailableError` if the
+    /// camera does not have one
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_chamber_pump(false).expect("set_chamber_pump failed");
+    /// ```
+    pub fn set_chamber_pump(&self, on: bool) -> Result<()> {
+        self.chamber().set_pump(Pump::ChamberCycle, on)
+    }
+
+    /// Returns `true` if the sensor chamber cycle pump is running, fails with
+    /// `IsControlAvailableError` if the camera does not have one
+    pub fn is_chamber_pump_running(&self) -> Result<bool> {
+        self.chamber().is_pump_running(Pump::ChamberCycle)
+    }
+}
@@ -0,0 +1,476 @@
+//! Cooler control on top of the raw `Control::Cooler` set-point
+//!
+//! Jumping the set-point straight to the target makes the sensor cool down as fast as the TEC
+//! allows, which can lead to condensation on sensors that are prone to it. `Cooler::ramp_to`
+//! steps the set-point gradually instead and reports every step as a `RampEvent`.
+//!
+//! While ramping, the sensor temperature and the cooler power are fed into a `ThermalModel`,
+//! which estimates the lowest temperature reachable at the current ambient. If the target is
+//! out of reach the ramp ends with `RampEvent::TargetUnreachable` instead of leaving the TEC
+//! at full power indefinitely.
+//!
+//! `Cooler::ramp_in_background` runs the same ramp on a background thread, so applications do
+//! not need a thread of their own to drive it.
+
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::QHYError::{
+    CoolerThreadError, InvalidRampRateError, InvalidRampSettingsError, IsControlAvailableError,
+};
+use crate::{Camera, Control, Result};
+
+#[derive(Debug, PartialEq, Clone)]
+/// Settings used by `Cooler::ramp_to`
+pub struct RampSettings {
+    /// the time between two set-point steps
+    pub step_interval: Duration,
+    /// the maximum difference between sensor temperature and target in °C to count as stable
+    pub tolerance: f64,
+    /// the number of consecutive steps the temperature has to stay within `tolerance`
+    pub stable_steps: u32,
+}
+
+impl Default for RampSettings {
+    fn default() -> Self {
+        Self {
+            step_interval: Duration::from_secs(10),
+            tolerance: 0.5,
+            stable_steps: 6,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// Progress reported by a `TemperatureRamp`
+pub enum RampEvent {
+    /// the set-point was moved one step closer to the target
+    Step {
+        /// the set-point in °C sent to the camera
+        set_point: f64,
+        /// the sensor temperature in °C read after setting the set-point
+        temperature: f64,
+    },
+    /// the set-point reached the target and the sensor temperature stayed within the tolerance
+    /// for `RampSettings::stable_steps` steps, this is always the last event
+    Stabilized {
+        /// the sensor temperature in °C
+        temperature: f64,
+    },
+    /// the cooler runs close to full power and the `ThermalModel` estimates the target cannot
+    /// be reached at the current ambient, this is always the last event
+    TargetUnreachable {
+        /// the sensor temperature in °C
+        temperature: f64,
+        /// the estimated lowest reachable temperature in °C
+        achievable: f64,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The state of the cooler returned from `Cooler::cooler_status`
+pub struct CoolerStatus {
+    /// the sensor temperature in °C
+    pub current_temp: f64,
+    /// the cooler power from 0 to 100%
+    pub power_percent: f64,
+    /// the set-point in °C as reported by the camera
+    pub target: f64,
+}
+
+/// the minimum number of observations before `ThermalModel` gives an estimate
+const MIN_OBSERVATIONS: usize = 3;
+/// the cooler power in percent above which an unreachable target ends the ramp
+const NEAR_FULL_POWER: f64 = 90.0;
+
+/// converts the raw `Control::CurPWM` value (0-255) into percent
+pub(crate) fn pwm_to_percent(pwm: f64) -> f64 {
+    (pwm / 255.0 * 100.0).clamp(0.0, 100.0)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// The cooling headroom estimated by `ThermalModel`
+pub struct HeadroomEstimate {
+    /// the estimated sensor temperature in °C with the cooler off, roughly the ambient
+    pub ambient: f64,
+    /// the estimated temperature difference to ambient in °C at full cooler power
+    pub max_delta: f64,
+}
+
+impl HeadroomEstimate {
+    /// the estimated lowest sensor temperature in °C
+    pub fn achievable(&self) -> f64 {
+        self.ambient - self.max_delta
+    }
+
+    /// returns `true` if `target` in °C is estimated to be reachable
+    pub fn is_reachable(&self, target: f64) -> bool {
+        target >= self.achievable()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+/// Estimates the achievable temperature difference from observed cooler behavior. The TEC is
+/// modeled as linear, the sensor temperature drops by a fixed amount per percent of power, so
+/// a least squares fit of temperature over power extrapolated to 100% gives the headroom.
+pub struct ThermalModel {
+    observations: Vec<(f64, f64)>,
+}
+
+impl ThermalModel {
+    /// Creates a model without observations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a sensor `temperature` in °C observed at `power_percent` cooler power
+    pub fn observe(&mut self, temperature: f64, power_percent: f64) {
+        self.observations.push((power_percent, temperature));
+    }
+
+    /// Returns the headroom estimate, or `None` if there are too few observations, the power
+    /// did not vary enough or the temperature did not drop with increasing power
+    pub fn estimate(&self) -> Option<HeadroomEstimate> {
+        if self.observations.len() < MIN_OBSERVATIONS {
+            return None;
+        }
+        let n = self.observations.len() as f64;
+        let mean_power = self.observations.iter().map(|(p, _)| p).sum::<f64>() / n;
+        let mean_temperature = self.observations.iter().map(|(_, t)| t).sum::<f64>() / n;
+        let (covariance, variance) = self.observations.iter().fold((0.0, 0.0), |(c, v), (p, t)| {
+            (
+                c + (p - mean_power) * (t - mean_temperature),
+                v + (p - mean_power).powi(2),
+            )
+        });
+        if variance < n {
+            return None;
+        }
+        let slope = covariance / variance;
+        if slope >= 0.0 {
+            return None;
+        }
+        Some(HeadroomEstimate {
+            ambient: mean_temperature - slope * mean_power,
+            max_delta: -slope * 100.0,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The cooler of a camera. It wraps a clone of the camera, so the camera can still be used
+/// for capturing while the cooler is ramping.
+pub struct Cooler {
+    camera: Camera,
+    settings: RampSettings,
+}
+
+impl Cooler {
+    /// Creates a cooler for the given camera with the default `RampSettings`. Fails with
+    /// `IsControlAvailableError` if the camera has no cooler.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Cooler, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let cooler = Cooler::new(camera.clone()).expect("camera has no cooler");
+    /// ```
+    pub fn new(camera: Camera) -> Result<Self> {
+        Self::with_settings(camera, RampSettings::default())
+    }
+
+    /// Creates a cooler for the given camera with the given `RampSettings`, see `new`. Fails
+    /// with `InvalidRampSettingsError` if `step_interval` or `stable_steps` is zero, a ramp
+    /// with them would never move the set-point or never wait between steps.
+    pub fn with_settings(camera: Camera, settings: RampSettings) -> Result<Self> {
+        if settings.step_interval.is_zero() || settings.stable_steps == 0 {
+            let error = InvalidRampSettingsError {
+                step_interval: settings.step_interval,
+                stable_steps: settings.stable_steps,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        if camera.is_control_available(Control::Cooler).is_none() {
+            let error = IsControlAvailableError {
+                control: Control::Cooler,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        Ok(Self { camera, settings })
+    }
+
+    /// Returns the camera the cooler belongs to
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Sets the set-point to `target` in °C right away, use `ramp_to` or `ramp_in_background`
+    /// for sensors that should not be cooled down at full speed
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Cooler, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let cooler = Cooler::new(camera.clone()).expect("camera has no cooler");
+    /// cooler.set_target_temperature(-10.0).expect("set_target_temperature failed");
+    /// ```
+    pub fn set_target_temperature(&self, target: f64) -> Result<()> {
+        self.camera.set_parameter(Control::Cooler, target)
+    }
+
+    /// Returns the sensor temperature, the cooler power and the set-point
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Cooler, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let cooler = Cooler::new(camera.clone()).expect("camera has no cooler");
+    /// let status = cooler.cooler_status().expect("cooler_status failed");
+    /// println!("{:.1}°C at {:.0}% power", status.current_temp, status.power_percent);
+    /// ```
+    pub fn cooler_status(&self) -> Result<CoolerStatus> {
+        Ok(CoolerStatus {
+            current_temp: self.camera.get_parameter(Control::CurTemp)?,
+            power_percent: pwm_to_percent(self.camera.get_parameter(Control::CurPWM)?),
+            target: self.camera.get_parameter(Control::Cooler)?,
+        })
+    }
+
+    /// Starts ramping the set-point from the current sensor temperature to `target` in °C with
+    /// `rate` in °C per minute. The returned `TemperatureRamp` is an iterator, every call to
+    /// `next` waits for `RampSettings::step_interval` (except for the first one), moves the
+    /// set-point one step and returns the resulting `RampEvent`. The iterator ends after
+    /// `RampEvent::Stabilized`, `RampEvent::TargetUnreachable` or the first error.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Cooler, RampEvent, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let cooler = Cooler::new(camera.clone()).expect("camera has no cooler");
+    /// for event in cooler.ramp_to(-10.0, 2.0).expect("ramp_to failed") {
+    ///     match event.expect("ramp step failed") {
+    ///         RampEvent::Step { set_point, temperature } => {
+    ///             println!("set-point {:.1}°C, sensor {:.1}°C", set_point, temperature)
+    ///         }
+    ///         RampEvent::Stabilized { temperature } => println!("stable at {:.1}°C", temperature),
+    ///         RampEvent::TargetUnreachable { achievable, .. } => {
+    ///             println!("only {:.1}°C reachable", achievable)
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn ramp_to(&self, target: f64, rate: f64) -> Result<TemperatureRamp> {
+        if !(rate.is_finite() && rate > 0.0) {
+            let error = InvalidRampRateError { rate };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let start = self.camera.get_parameter(Control::CurTemp)?;
+        Ok(TemperatureRamp {
+            cooler: self.clone(),
+            target,
+            step: rate / 60.0 * self.settings.step_interval.as_secs_f64(),
+            set_point: start,
+            model: ThermalModel::new(),
+            stable_steps: 0,
+            started: false,
+            finished: false,
+        })
+    }
+
+    /// Same as `ramp_to`, but drives the ramp on a background thread until it ends or the
+    /// returned `BackgroundRamp` is stopped or dropped
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{Cooler, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let cooler = Cooler::new(camera.clone()).expect("camera has no cooler");
+    /// let ramp = cooler.ramp_in_background(-10.0, 2.0).expect("ramp_in_background failed");
+    /// while !ramp.is_finished() {
+    ///     println!("{:?}", ramp.last_event());
+    ///     std::thread::sleep(Duration::from_secs(10));
+    /// }
+    /// ```
+    pub fn ramp_in_background(&self, target: f64, rate: f64) -> Result<BackgroundRamp> {
+        let mut ramp = self.ramp_to(target, rate)?;
+        let interval = self.settings.step_interval;
+        let last_event = Arc::new(Mutex::new(None));
+        let events = last_event.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || loop {
+            let event = ramp.advance()?;
+            *events
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(event);
+            if ramp.finished {
+                return Ok(Some(event));
+            }
+            if !matches!(
+                stopped.recv_timeout(interval),
+                Err(RecvTimeoutError::Timeout)
+            ) {
+                return Ok(Some(event));
+            }
+        });
+        Ok(BackgroundRamp {
+            last_event,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+}
+
+#[derive(Debug)]
+/// A ramp running on a background thread, returned from `Cooler::ramp_in_background`. Dropping
+/// it stops the ramp and leaves the last set-point in place.
+pub struct BackgroundRamp {
+    last_event: Arc<Mutex<Option<RampEvent>>>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<Result<Option<RampEvent>>>>,
+}
+
+impl BackgroundRamp {
+    /// Returns the latest event of the ramp, `None` before the first step
+    pub fn last_event(&self) -> Option<RampEvent> {
+        *self
+            .last_event
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns `true` once the ramp ended, `wait` does not block after that
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().map_or(true, JoinHandle::is_finished)
+    }
+
+    /// Waits until the ramp ends by itself and returns its last event, or the error that ended it
+    pub fn wait(mut self) -> Result<Option<RampEvent>> {
+        self.join()
+    }
+
+    /// Stops the ramp, leaving the last set-point in place, and returns its last event
+    pub fn stop(mut self) -> Result<Option<RampEvent>> {
+        // dropping the sender wakes the thread up with a disconnect
+        self.stop.take();
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<Option<RampEvent>> {
+        match self.thread.take() {
+            Some(thread) => thread.join().map_err(|_| {
+                let error = CoolerThreadError;
+                tracing::error!(error = ?error);
+                error
+            })?,
+            None => Ok(self.last_event()),
+        }
+    }
+}
+
+impl Drop for BackgroundRamp {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Err(error) = self.join() {
+            tracing::warn!(ramp = ?error);
+        }
+    }
+}
+
+#[derive(Debug)]
+/// An ongoing set-point ramp returned from `Cooler::ramp_to`. Dropping it stops the ramp and
+/// leaves the last set-point in place.
+pub struct TemperatureRamp {
+    cooler: Cooler,
+    target: f64,
+    step: f64,
+    set_point: f64,
+    model: ThermalModel,
+    stable_steps: u32,
+    started: bool,
+    finished: bool,
+}
+
+impl TemperatureRamp {
+    /// Returns the target temperature of the ramp in °C
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    /// Returns the headroom estimated from the steps so far, see `ThermalModel`
+    pub fn estimate(&self) -> Option<HeadroomEstimate> {
+        self.model.estimate()
+    }
+
+    fn advance(&mut self) -> Result<RampEvent> {
+        let camera = &self.cooler.camera;
+        let settings = &self.cooler.settings;
+        self.set_point = match self.target - self.set_point {
+            delta if delta.abs() <= self.step => self.target,
+            delta => self.set_point + self.step.copysign(delta),
+        };
+        camera.set_parameter(Control::Cooler, self.set_point)?;
+        let temperature = camera.get_parameter(Control::CurTemp)?;
+        let power = pwm_to_percent(camera.get_parameter(Control::CurPWM)?);
+        self.model.observe(temperature, power);
+        if let Some(estimate) = self.model.estimate() {
+            if power >= NEAR_FULL_POWER && !estimate.is_reachable(self.target) {
+                let achievable = estimate.achievable();
+                tracing::warn!(
+                    target = self.target,
+                    achievable,
+                    "target temperature unreachable"
+                );
+                self.finished = true;
+                return Ok(RampEvent::TargetUnreachable {
+                    temperature,
+                    achievable,
+                });
+            }
+        }
+        if self.set_point == self.target && (temperature - self.target).abs() <= settings.tolerance
+        {
+            self.stable_steps += 1;
+        } else {
+            self.stable_steps = 0;
+        }
+        if self.stable_steps >= settings.stable_steps {
+            self.finished = true;
+            return Ok(RampEvent::Stabilized { temperature });
+        }
+        Ok(RampEvent::Step {
+            set_point: self.set_point,
+            temperature,
+        })
+    }
+}
+
+impl Iterator for TemperatureRamp {
+    type Item = Result<RampEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if self.started {
+            std::thread::sleep(self.cooler.settings.step_interval);
+        }
+        self.started = true;
+        let event = self.advance();
+        if event.is_err() {
+            self.finished = true;
+        }
+        Some(event)
+    }
+}
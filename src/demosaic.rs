@@ -0,0 +1,211 @@
+//! Demosaicing of raw color frames in software
+//!
+//! Converts a raw single channel frame of a color camera into a frame with three channels,
+//! without relying on the debayering of the SDK. The channels are stored in the same BGR order
+//! the SDK uses for color frames. Pixels outside of the frame are mirrored at the border, which
+//! keeps the colors of the filter pattern intact.
+
+use crate::arithmetic::{bytes_per_sample, read_samples, write_samples};
+use crate::QHYError::ChannelCountMismatchError;
+use crate::{BayerMode, ImageData, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The algorithm used by `ImageData::demosaic`
+pub enum DemosaicAlgorithm {
+    /// combines every 2x2 block of the filter pattern into a single pixel, so the result has
+    /// half the width and height, fast and free of interpolation artifacts
+    Superpixel,
+    /// interpolates the missing colors of every pixel from its direct neighbors
+    Bilinear,
+    /// variable number of gradients, interpolates only along directions with little change,
+    /// which keeps edges sharper than `Bilinear` at the cost of speed
+    Vng,
+}
+
+/// index of the red, green and blue color in a pixel of the result
+const BLUE: usize = 0;
+const GREEN: usize = 1;
+const RED: usize = 2;
+
+/// the eight directions `Vng` compares
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+/// a raw frame together with its filter pattern
+struct Mosaic {
+    samples: Vec<u64>,
+    width: usize,
+    height: usize,
+    pattern: [[usize; 2]; 2],
+}
+
+impl Mosaic {
+    /// mirrors `i` at the borders of `0..n`, the step of two keeps the color of the pattern
+    fn reflect(i: isize, n: usize) -> usize {
+        let n = n as isize;
+        let mut i = i;
+        while i < 0 || i >= n {
+            i = if i < 0 { -i } else { 2 * (n - 1) - i };
+            if n == 1 {
+                return 0;
+            }
+        }
+        i as usize
+    }
+
+    fn at(&self, y: isize, x: isize) -> f64 {
+        let y = Self::reflect(y, self.height);
+        let x = Self::reflect(x, self.width);
+        self.samples[y * self.width + x] as f64
+    }
+
+    fn color(&self, y: isize, x: isize) -> usize {
+        self.pattern[y.rem_euclid(2) as usize][x.rem_euclid(2) as usize]
+    }
+
+    /// the mean of the neighbors within a 3x3 block that have the given color
+    fn neighbor_mean(&self, y: isize, x: isize, color: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut count = 0.0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dy, dx) != (0, 0) && self.color(y + dy, x + dx) == color {
+                    sum += self.at(y + dy, x + dx);
+                    count += 1.0;
+                }
+            }
+        }
+        sum / count
+    }
+
+    fn bilinear(&self, y: isize, x: isize) -> [f64; 3] {
+        let own = self.color(y, x);
+        let mut pixel = [0.0; 3];
+        for (color, value) in pixel.iter_mut().enumerate() {
+            *value = match color == own {
+                true => self.at(y, x),
+                false => self.neighbor_mean(y, x, color),
+            };
+        }
+        pixel
+    }
+
+    /// how much the frame changes around `y`, `x` in the direction `dy`, `dx`
+    fn gradient(&self, y: isize, x: isize, (dy, dx): (isize, isize)) -> f64 {
+        let (py, px) = (dx, -dy);
+        (self.at(y + dy, x + dx) - self.at(y - dy, x - dx)).abs()
+            + (self.at(y + 2 * dy, x + 2 * dx) - self.at(y, x)).abs()
+            + ((self.at(y + dy + py, x + dx + px) - self.at(y - dy + py, x - dx + px)).abs()
+                + (self.at(y + dy - py, x + dx - px) - self.at(y - dy - py, x - dx - px)).abs())
+                / 2.0
+    }
+
+    fn vng(&self, y: isize, x: isize) -> [f64; 3] {
+        let own = self.color(y, x);
+        let gradients = DIRECTIONS.map(|direction| self.gradient(y, x, direction));
+        let min = gradients.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = gradients.iter().copied().fold(0.0, f64::max);
+        let threshold = 1.5 * min + 0.5 * (max - min);
+        let mut differences = [0.0; 3];
+        let mut count = 0.0;
+        for ((dy, dx), gradient) in DIRECTIONS.iter().zip(gradients) {
+            if gradient > threshold {
+                continue;
+            }
+            let neighbor = self.bilinear(y + dy, x + dx);
+            for (color, difference) in differences.iter_mut().enumerate() {
+                *difference += neighbor[color] - neighbor[own];
+            }
+            count += 1.0;
+        }
+        let value = self.at(y, x);
+        // the minimum gradient always passes the threshold, so count is at least one
+        differences.map(|difference| value + difference / count)
+    }
+}
+
+/// the colors of the 2x2 filter pattern in rows
+fn pattern(bayer_mode: BayerMode) -> [[usize; 2]; 2] {
+    match bayer_mode {
+        BayerMode::RGGB => [[RED, GREEN], [GREEN, BLUE]],
+        BayerMode::BGGR => [[BLUE, GREEN], [GREEN, RED]],
+        BayerMode::GRBG => [[GREEN, RED], [BLUE, GREEN]],
+        BayerMode::GBRG => [[GREEN, BLUE], [RED, GREEN]],
+    }
+}
+
+impl ImageData {
+    /// Converts a raw frame with the filter pattern `bayer_mode` into a frame with three
+    /// channels in BGR order and the same bit depth, using the given algorithm. Fails with
+    /// `ChannelCountMismatchError` if the frame has more than one channel.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{BayerMode, DemosaicAlgorithm, ImageData};
+    /// let raw = ImageData { data: vec![200, 100, 100, 50], width: 2, height: 2, bits_per_pixel: 8, channels: 1 };
+    /// let color = raw.demosaic(BayerMode::RGGB, DemosaicAlgorithm::Superpixel).expect("demosaic failed");
+    /// assert_eq!(color.data, vec![50, 100, 200]);
+    /// ```
+    pub fn demosaic(
+        &self,
+        bayer_mode: BayerMode,
+        algorithm: DemosaicAlgorithm,
+    ) -> Result<ImageData> {
+        if self.channels != 1 {
+            let error = ChannelCountMismatchError {
+                expected: 1,
+                actual: self.channels,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let max = ((1_u128 << (bytes_per_sample(self.bits_per_pixel)? * 8)) - 1) as f64;
+        let mut samples = read_samples(self)?;
+        let (width, height) = (self.width as usize, self.height as usize);
+        samples.resize(width * height, 0);
+        let mosaic = Mosaic {
+            samples,
+            width,
+            height,
+            pattern: pattern(bayer_mode),
+        };
+        let (out_width, out_height) = match algorithm {
+            DemosaicAlgorithm::Superpixel => (width / 2, height / 2),
+            _ => (width, height),
+        };
+        let mut result = Vec::with_capacity(out_width * out_height * 3);
+        for y in 0..out_height as isize {
+            for x in 0..out_width as isize {
+                let pixel = match algorithm {
+                    DemosaicAlgorithm::Superpixel => {
+                        let mut pixel = [0.0; 3];
+                        for (dy, dx) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                            let color = mosaic.color(dy, dx);
+                            // the two green samples are averaged
+                            let weight = if color == GREEN { 0.5 } else { 1.0 };
+                            pixel[color] += weight * mosaic.at(2 * y + dy, 2 * x + dx);
+                        }
+                        pixel
+                    }
+                    DemosaicAlgorithm::Bilinear => mosaic.bilinear(y, x),
+                    DemosaicAlgorithm::Vng => mosaic.vng(y, x),
+                };
+                result.extend(pixel.map(|value| value.round().clamp(0.0, max) as u64));
+            }
+        }
+        Ok(ImageData {
+            data: write_samples(&result, self.bits_per_pixel)?,
+            width: out_width as u32,
+            height: out_height as u32,
+            bits_per_pixel: self.bits_per_pixel,
+            channels: 3,
+        })
+    }
+}
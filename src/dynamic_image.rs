@@ -0,0 +1,92 @@
+//! Conversion from `ImageData` to the `image` crate's `DynamicImage`
+//!
+//! The SDK delivers the channels of color frames in BGR or BGRA order, they are reordered to the
+//! RGB or RGBA order the `image` crate expects.
+
+use image::{DynamicImage, ImageBuffer};
+
+use crate::QHYError::{
+    BufferTooSmallError, PixelFormatMismatchError, UnsupportedChannelCountError,
+};
+use crate::{ImageData, PixelBuffer, PixelFormat, Result};
+
+/// swaps the blue and red sample of every pixel
+fn bgr_to_rgb<T>(samples: &mut [T], channels: usize) {
+    if channels >= 3 {
+        samples
+            .chunks_exact_mut(channels)
+            .for_each(|pixel| pixel.swap(0, 2));
+    }
+}
+
+impl ImageData {
+    /// Converts an 8 or 16 bit frame with 1, 3 or 4 channels to a `DynamicImage`. Fails with
+    /// `UnsupportedChannelCountError` for other channel counts and with
+    /// `PixelFormatMismatchError` for 32 bit frames.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData { data: vec![0, 0, 255], width: 1, height: 1, bits_per_pixel: 8, channels: 3 };
+    /// let rgb = image.to_dynamic_image().expect("to_dynamic_image failed").into_rgb8();
+    /// assert_eq!(rgb.get_pixel(0, 0).0, [255, 0, 0]);
+    /// ```
+    pub fn to_dynamic_image(&self) -> Result<DynamicImage> {
+        let (width, height, channels) = (self.width, self.height, self.channels);
+        if !matches!(channels, 1 | 3 | 4) {
+            let error = UnsupportedChannelCountError { channels };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        // `from_raw` only fails if the buffer is too small for the given dimensions
+        let too_small = || {
+            let error = BufferTooSmallError {
+                required: width as usize
+                    * height as usize
+                    * channels as usize
+                    * ((self.bits_per_pixel as usize + 7) / 8),
+                actual: self.data.len(),
+            };
+            tracing::error!(error = ?error);
+            error
+        };
+        let image = match self.clone().into_pixel_buffer()? {
+            PixelBuffer::U8(mut samples) => {
+                bgr_to_rgb(&mut samples, channels as usize);
+                match channels {
+                    1 => DynamicImage::ImageLuma8(
+                        ImageBuffer::from_raw(width, height, samples).ok_or_else(too_small)?,
+                    ),
+                    3 => DynamicImage::ImageRgb8(
+                        ImageBuffer::from_raw(width, height, samples).ok_or_else(too_small)?,
+                    ),
+                    _ => DynamicImage::ImageRgba8(
+                        ImageBuffer::from_raw(width, height, samples).ok_or_else(too_small)?,
+                    ),
+                }
+            }
+            PixelBuffer::U16(mut samples) => {
+                bgr_to_rgb(&mut samples, channels as usize);
+                match channels {
+                    1 => DynamicImage::ImageLuma16(
+                        ImageBuffer::from_raw(width, height, samples).ok_or_else(too_small)?,
+                    ),
+                    3 => DynamicImage::ImageRgb16(
+                        ImageBuffer::from_raw(width, height, samples).ok_or_else(too_small)?,
+                    ),
+                    _ => DynamicImage::ImageRgba16(
+                        ImageBuffer::from_raw(width, height, samples).ok_or_else(too_small)?,
+                    ),
+                }
+            }
+            PixelBuffer::U32(_) => {
+                let error = PixelFormatMismatchError {
+                    expected: PixelFormat::U16,
+                    actual: PixelFormat::U32,
+                };
+                tracing::error!(error = ?error);
+                return Err(error);
+            }
+        };
+        Ok(image)
+    }
+}
@@ -0,0 +1,174 @@
+//! Names for the error codes returned by the SDK
+//!
+//! The errors of this crate keep the raw `error_code` the SDK returned. `SdkErrorCode` maps it
+//! to the constants of `qhyccderr.h`, and the `Display` output of the errors uses it to say
+//! what the code means. Almost every SDK function only ever returns `QHYCCD_ERROR`, so
+//! `Camera::set_parameter` additionally checks whether the control is available and the value
+//! within its limits before it reports a bare `SetParameterError`.
+//!
+//! `QHYError` has a variant per failing call, `QHYError::kind` sorts them into the few kinds of
+//! failure code handling errors usually cares about, e.g. to retry after a timeout without
+//! listing every timeout variant.
+
+use std::fmt;
+
+use crate::QHYError::{self, *};
+use crate::{QHYCCD_DELAY_200MS, QHYCCD_ERROR, QHYCCD_READ_DIRECTLY};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A known error code of the SDK, or the raw value of an unknown one
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::{Control, QHYError, Sdk, SdkErrorCode};
+/// let sdk = Sdk::new().expect("SDK::new failed");
+/// let camera = sdk.cameras().last().expect("no camera found");
+/// camera.open().expect("open failed");
+/// match camera.set_parameter(Control::Gain, 30.0) {
+///     Err(QHYError::SetParameterError { error_code }) => {
+///         println!("gain not set: {:?}", SdkErrorCode::from(error_code))
+///     }
+///     Err(error) => println!("gain not set: {}", error),
+///     Ok(()) => (),
+/// }
+/// ```
+pub enum SdkErrorCode {
+    /// `QHYCCD_ERROR`, the generic failure that carries no reason
+    Error,
+    /// `QHYCCD_READ_DIRECTLY`, the frame has to be read without waiting for the exposure
+    ReadDirectly,
+    /// `QHYCCD_DELAY_200MS`, the camera needs 200ms before it accepts the next call
+    Delay200Ms,
+    /// a code the SDK documentation does not list
+    Unknown(u32),
+}
+
+impl SdkErrorCode {
+    /// Returns the raw value of the code
+    pub fn code(&self) -> u32 {
+        match self {
+            SdkErrorCode::Error => QHYCCD_ERROR,
+            SdkErrorCode::ReadDirectly => QHYCCD_READ_DIRECTLY,
+            SdkErrorCode::Delay200Ms => QHYCCD_DELAY_200MS,
+            SdkErrorCode::Unknown(code) => *code,
+        }
+    }
+}
+
+impl From<u32> for SdkErrorCode {
+    fn from(code: u32) -> Self {
+        match code {
+            QHYCCD_ERROR => SdkErrorCode::Error,
+            QHYCCD_READ_DIRECTLY => SdkErrorCode::ReadDirectly,
+            QHYCCD_DELAY_200MS => SdkErrorCode::Delay200Ms,
+            code => SdkErrorCode::Unknown(code),
+        }
+    }
+}
+
+impl fmt::Display for SdkErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SdkErrorCode::Error => write!(
+                f,
+                "QHYCCD_ERROR, the SDK gives no reason; check the USB connection and the SDK log"
+            ),
+            SdkErrorCode::ReadDirectly => write!(
+                f,
+                "QHYCCD_READ_DIRECTLY, the frame has to be read without waiting"
+            ),
+            SdkErrorCode::Delay200Ms => write!(f, "QHYCCD_DELAY_200MS, retry after 200ms"),
+            SdkErrorCode::Unknown(code) => write!(f, "{}", code),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
+/// The kind of failure of a `QHYError`, returned from `QHYError::kind`
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use qhyccd_rs::{ErrorKind, QHYError};
+/// let error = QHYError::CaptureTimeoutError { timeout: Duration::from_secs(30) };
+/// assert_eq!(error.kind(), ErrorKind::Timeout);
+/// ```
+pub enum ErrorKind {
+    /// the camera is not open, or its handle is no longer valid
+    NotOpen,
+    /// the camera is open, but not in the state the call needs, e.g. still exposing
+    InvalidState,
+    /// something did not happen within the time it was given
+    Timeout,
+    /// the camera or the installed SDK does not support the control, feature or format
+    Unsupported,
+    /// a value passed in is outside of what the camera or the call accepts
+    InvalidArgument,
+    /// the camera with the given id is not connected
+    NotFound,
+    /// reading or writing a file or a network connection failed
+    Io,
+    /// a call into the SDK failed, the variant usually keeps the `error_code`
+    Sdk,
+    /// a failure of this crate or of another crate not covered by the kinds above
+    Other,
+}
+
+impl QHYError {
+    /// Returns the kind of failure of this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            CameraNotOpenError | CameraClosedError => ErrorKind::NotOpen,
+            CameraStateError { .. }
+            | SupersededWriteError { .. }
+            | ReadoutModeChangeError { .. } => ErrorKind::InvalidState,
+            TriggerTimeoutError { .. }
+            | FilterWheelHomeError { .. }
+            | FocuserTimeoutError { .. }
+            | FilterWheelMoveTimeoutError { .. }
+            | CaptureTimeoutError { .. }
+            | FpnCalibrationTimeoutError { .. } => ErrorKind::Timeout,
+            IsControlAvailableError { .. }
+            | UnsupportedSdkFeatureError { .. }
+            | UnsupportedBitDepthError { .. }
+            | UnsupportedBinModeError { .. }
+            | UnsupportedChannelCountError { .. }
+            | FlashConfigSectionError { .. } => ErrorKind::Unsupported,
+            ParameterOutOfRangeError { .. }
+            | UnknownControlError { .. }
+            | InvalidRampRateError { .. }
+            | InvalidRampSettingsError { .. }
+            | InvalidScreenStretchError { .. }
+            | InvalidActualBitsError { .. }
+            | UnknownDataAlignmentError { .. }
+            | InvalidFocuserPositionError { .. }
+            | InvalidRotatorAngleError { .. }
+            | InvalidWatchdogTimeoutError { .. }
+            | InvalidSlotConfigError { .. }
+            | ReadoutModeOutOfRangeError { .. }
+            | FilterSlotOutOfRangeError { .. }
+            | RoiError { .. }
+            | BufferTooSmallError { .. }
+            | ImageGeometryMismatchError
+            | PixelFormatMismatchError { .. }
+            | ChannelCountMismatchError { .. }
+            | GpsHeaderError { .. }
+            | ParseFloatError { .. }
+            | CameraIdNulError { .. } => ErrorKind::InvalidArgument,
+            CameraNotFoundError { .. } => ErrorKind::NotFound,
+            FileError { .. } | IndiIoError { .. } => ErrorKind::Io,
+            NoStarSignalError
+            | AutofocusFitError
+            | AsyncExecutorError
+            | ExposureThreadError
+            | CoolerThreadError
+            | HandleLockError
+            | InvalidUtf8Error { .. }
+            | SelfTestCheckError { .. }
+            | IndiProtocolError { .. }
+            | LoadLibraryError { .. }
+            | ExternalError(_) => ErrorKind::Other,
+            _ => ErrorKind::Sdk,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Exposures running in the background
+//!
+//! Downloading a single frame blocks until the exposure finished, so `Camera::start_exposure`
+//! starts the exposure, runs the download on a background thread and returns an
+//! `ExposureHandle` to watch, finish, cancel or abort it from the calling thread.
+
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::QHYError::ExposureThreadError;
+use crate::{Camera, Control, ImageData, Result};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// The progress of an exposure returned from `ExposureHandle::progress`
+pub struct ExposureProgress {
+    /// the exposure time left as reported by the camera
+    pub remaining: Duration,
+    /// the finished part of the exposure from 0.0 to 1.0
+    pub fraction: f64,
+}
+
+#[derive(Debug)]
+/// Handle to an exposure started with `Camera::start_exposure`. Dropping it leaves the exposure
+/// running, the frame is discarded once it was downloaded.
+pub struct ExposureHandle {
+    camera: Camera,
+    exposure: Duration,
+    thread: JoinHandle<Result<ImageData>>,
+}
+
+impl Camera {
+    /// Sets the exposure time and starts a single frame exposure, which is downloaded on a
+    /// background thread. The camera has to be open, initialized and in single frame mode, an
+    /// exposure the camera refuses to start fails right here.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// let exposure = camera.start_exposure(Duration::from_secs(120)).expect("start_exposure failed");
+    /// while !exposure.is_finished() {
+    ///     let progress = exposure.progress().expect("progress failed");
+    ///     println!("{:.0}% done", progress.fraction * 100.0);
+    ///     std::thread::sleep(Duration::from_secs(1));
+    /// }
+    /// let image = exposure.wait().expect("exposure failed");
+    /// ```
+    pub fn start_exposure(&self, exposure: Duration) -> Result<ExposureHandle> {
+        self.set_parameter(Control::Exposure, exposure.as_micros() as f64)?;
+        self.spawn_exposure(exposure)
+    }
+
+    /// starts the exposure with the exposure time already set on the camera and downloads it on
+    /// a background thread
+    pub(crate) fn spawn_exposure(&self, exposure: Duration) -> Result<ExposureHandle> {
+        self.start_single_frame_exposure()?;
+        let camera = self.clone();
+        let thread = thread::spawn(move || {
+            let buffer_size = camera.get_image_size()?;
+            camera.get_single_frame(buffer_size)
+        });
+        Ok(ExposureHandle {
+            camera: self.clone(),
+            exposure,
+            thread,
+        })
+    }
+}
+
+impl ExposureHandle {
+    /// Returns the exposure time this exposure was started with
+    pub fn exposure(&self) -> Duration {
+        self.exposure
+    }
+
+    /// Returns `true` once the frame was downloaded or the exposure failed, `wait` does not
+    /// block after that
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Returns the progress of the exposure based on the remaining time reported by the camera
+    pub fn progress(&self) -> Result<ExposureProgress> {
+        let remaining = Duration::from_micros(self.camera.get_remaining_exposure_us()? as u64);
+        let fraction = match self.exposure.is_zero() {
+            true => 1.0,
+            false => 1.0 - (remaining.as_secs_f64() / self.exposure.as_secs_f64()).min(1.0),
+        };
+        Ok(ExposureProgress {
+            remaining,
+            fraction,
+        })
+    }
+
+    /// Waits until the exposure finished and returns the downloaded frame
+    pub fn wait(self) -> Result<ImageData> {
+        self.thread.join().map_err(|_| {
+            let error = ExposureThreadError;
+            tracing::error!(error = ?error);
+            error
+        })?
+    }
+
+    /// Stops the exposure early and returns the frame exposed so far
+    pub fn cancel(self) -> Result<ImageData> {
+        self.camera.stop_exposure()?;
+        self.wait()
+    }
+
+    /// Stops the exposure and the readout and discards the frame
+    pub fn abort_and_discard(self) -> Result<()> {
+        self.camera.abort_exposure_and_readout()?;
+        // the download fails or returns garbage after an abort, either way it is not needed
+        if let Err(error) = self.wait() {
+            tracing::debug!(discarded = ?error);
+        }
+        Ok(())
+    }
+}
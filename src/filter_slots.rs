@@ -0,0 +1,209 @@
+//! Names and metadata of the filters in a filter wheel
+//!
+//! The SDK only knows slot numbers. `FilterWheel::set_slot_labels` and `FilterWheel::set_slot`
+//! attach a name, a focus offset and an exposure factor to every slot, so imaging applications
+//! can work with `"Ha"` instead of `4`. The slots can be saved to and loaded from a small tab
+//! separated file with one slot per line, tabs, line breaks and backslashes in names are escaped
+//! with a backslash.
+
+use std::path::Path;
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+use crate::numeric::{format_float, parse_float};
+use crate::QHYError::{FilterSlotOutOfRangeError, InvalidSlotConfigError};
+use crate::{file_error, FilterWheel, Result};
+
+/// the slots `set_slot` accepts if the filter wheel cannot report its number of filters, more
+/// than any QHYCCD filter wheel has
+const MAX_SLOTS: u32 = 32;
+
+#[derive(Debug, PartialEq, Clone)]
+/// The filter in a slot of a filter wheel
+pub struct FilterSlot {
+    /// the name of the filter, e.g. `"Ha"`
+    pub name: String,
+    /// the focuser steps to add when switching to this filter
+    pub focus_offset: i32,
+    /// the factor to multiply the exposure time with when using this filter
+    pub exposure_factor: f64,
+}
+
+impl FilterSlot {
+    /// Creates a slot with the given name, no focus offset and an exposure factor of 1
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            focus_offset: 0,
+            exposure_factor: 1.0,
+        }
+    }
+
+    /// Returns the name of the filter
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl FilterWheel {
+    fn slots_ref(&self) -> RwLockReadGuard<'_, Vec<FilterSlot>> {
+        self.slots
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn slots_mut(&self) -> RwLockWriteGuard<'_, Vec<FilterSlot>> {
+        self.slots
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Names the slots in order starting with slot 0, other metadata is reset
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let fw = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// fw.set_slot_labels(["L", "R", "G", "B", "Ha"]);
+    /// assert_eq!(fw.slot(4).expect("no slot 4").name(), "Ha");
+    /// assert_eq!(fw.find_slot("G"), Some(2));
+    /// ```
+    pub fn set_slot_labels<S: Into<String>>(&self, labels: impl IntoIterator<Item = S>) {
+        *self.slots_mut() = labels.into_iter().map(FilterSlot::new).collect();
+    }
+
+    /// Sets the metadata of slot `index`, missing slots before it are added with empty names.
+    /// Fails with `FilterSlotOutOfRangeError` if `index` is not below `get_number_of_filters`,
+    /// or below 32 while the filter wheel cannot report its number of filters, e.g. before it
+    /// was opened.
+    pub fn set_slot(&self, index: u32, slot: FilterSlot) -> Result<()> {
+        let count = self.get_number_of_filters().unwrap_or(MAX_SLOTS);
+        if index >= count {
+            let error = FilterSlotOutOfRangeError { index, count };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let mut slots = self.slots_mut();
+        let index = index as usize;
+        if slots.len() <= index {
+            slots.resize(index + 1, FilterSlot::new(""));
+        }
+        slots[index] = slot;
+        Ok(())
+    }
+
+    /// Returns the metadata of slot `index` if it was set
+    pub fn slot(&self, index: u32) -> Option<FilterSlot> {
+        self.slots_ref().get(index as usize).cloned()
+    }
+
+    /// Returns the metadata of all slots starting with slot 0
+    pub fn slots(&self) -> Vec<FilterSlot> {
+        self.slots_ref().clone()
+    }
+
+    /// Returns the index of the first slot with the given name
+    pub fn find_slot(&self, name: &str) -> Option<u32> {
+        self.slots_ref()
+            .iter()
+            .position(|slot| slot.name == name)
+            .map(|index| index as u32)
+    }
+
+    /// Saves the metadata of all slots to the file at `path`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{FilterSlot, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let fw = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// fw.set_slot(0, FilterSlot { name: "L".to_owned(), focus_offset: 0, exposure_factor: 1.0 }).expect("set_slot failed");
+    /// fw.set_slot(1, FilterSlot { name: "Ha".to_owned(), focus_offset: 35, exposure_factor: 6.0 }).expect("set_slot failed");
+    /// fw.save_slots("filters.tsv".as_ref()).expect("save_slots failed");
+    /// ```
+    pub fn save_slots(&self, path: &Path) -> Result<()> {
+        let content = self
+            .slots_ref()
+            .iter()
+            .map(|slot| {
+                format!(
+                    "{}\t{}\t{}\n",
+                    escape(&slot.name),
+                    slot.focus_offset,
+                    format_float(slot.exposure_factor)
+                )
+            })
+            .collect::<String>();
+        std::fs::write(path, content).map_err(|source| file_error(path, source))
+    }
+
+    /// Replaces the metadata of all slots with the one saved by `save_slots`. Fails with
+    /// `InvalidSlotConfigError` if a line can not be parsed, the slots are unchanged then.
+    pub fn load_slots(&self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path).map_err(|source| file_error(path, source))?;
+        let slots = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                parse_slot(line).ok_or_else(|| {
+                    let error = InvalidSlotConfigError { line: index + 1 };
+                    tracing::error!(error = ?error);
+                    error
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        *self.slots_mut() = slots;
+        Ok(())
+    }
+}
+
+/// parses a line written by `save_slots`
+fn parse_slot(line: &str) -> Option<FilterSlot> {
+    let mut fields = line.split('\t');
+    let slot = FilterSlot {
+        name: unescape(fields.next()?),
+        focus_offset: fields.next()?.parse().ok()?,
+        exposure_factor: parse_float(fields.next()?).ok()?,
+    };
+    match fields.next() {
+        Some(_) => None,
+        None => Some(slot),
+    }
+}
+
+/// escapes the characters that would break the line of a slot
+fn escape(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// reverts `escape`, unknown escapes are kept as they are
+fn unescape(name: &str) -> String {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,235 @@
+//! Minimal FITS writer for `ImageData`
+//!
+//! Writes a single primary HDU with the frame as image data. Unsigned 16 and 32 bit samples are
+//! stored as signed integers with the usual `BZERO` offset, frames with more than one channel
+//! are written as a cube with one plane per channel.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::arithmetic::read_samples;
+use crate::numeric::format_float;
+use crate::{file_error, ImageData, PixelFormat, Result};
+
+/// FITS files are organized in blocks of this many bytes
+const BLOCK_SIZE: usize = 2880;
+/// every header card has exactly this many bytes
+const CARD_SIZE: usize = 80;
+
+#[derive(Debug, PartialEq, Clone)]
+/// The value of a FITS header card
+pub(crate) enum HeaderValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl HeaderValue {
+    fn format(&self) -> String {
+        match self {
+            HeaderValue::Bool(value) => format!("{:>20}", if *value { "T" } else { "F" }),
+            HeaderValue::Int(value) => format!("{:>20}", value),
+            HeaderValue::Float(value) => format!("{:>20}", format_float(*value)),
+            HeaderValue::Str(value) => format!("'{:<8}'", value.replace('\'', "''")),
+        }
+    }
+}
+
+/// formats a header card, padded or truncated to `CARD_SIZE`
+fn card(key: &str, value: &HeaderValue, comment: &str) -> String {
+    let mut card = format!("{:<8}= {}", key, value.format());
+    if !comment.is_empty() {
+        card.push_str(" / ");
+        card.push_str(comment);
+    }
+    let mut card = card.chars().filter(|c| c.is_ascii()).collect::<String>();
+    card.truncate(CARD_SIZE);
+    format!("{:<80}", card)
+}
+
+/// pads `buffer` with `fill` to a multiple of `BLOCK_SIZE`
+fn pad_to_block(buffer: &mut Vec<u8>, fill: u8) {
+    let remainder = buffer.len() % BLOCK_SIZE;
+    if remainder != 0 {
+        buffer.resize(buffer.len() + BLOCK_SIZE - remainder, fill);
+    }
+}
+
+/// encodes `image` as a FITS file with the mandatory keywords followed by `cards`
+pub(crate) fn encode_fits(
+    image: &ImageData,
+    cards: &[(&str, HeaderValue, &str)],
+) -> Result<Vec<u8>> {
+    let format = PixelFormat::from_bits_per_pixel(image.bits_per_pixel)?;
+    let pixels = image.width as usize * image.height as usize;
+    let channels = image.channels.max(1) as usize;
+    let samples = read_samples(image)?;
+
+    let mut header = vec![
+        card(
+            "SIMPLE",
+            &HeaderValue::Bool(true),
+            "conforms to FITS standard",
+        ),
+        card(
+            "BITPIX",
+            &HeaderValue::Int(format.bits() as i64),
+            "bits per data value",
+        ),
+        card(
+            "NAXIS",
+            &HeaderValue::Int(if channels > 1 { 3 } else { 2 }),
+            "number of data axes",
+        ),
+        card(
+            "NAXIS1",
+            &HeaderValue::Int(image.width as i64),
+            "image width",
+        ),
+        card(
+            "NAXIS2",
+            &HeaderValue::Int(image.height as i64),
+            "image height",
+        ),
+    ];
+    if channels > 1 {
+        header.push(card(
+            "NAXIS3",
+            &HeaderValue::Int(channels as i64),
+            "number of channels",
+        ));
+    }
+    let bzero = match format {
+        PixelFormat::U8 => 0,
+        PixelFormat::U16 => 1_i64 << 15,
+        PixelFormat::U32 => 1_i64 << 31,
+    };
+    if bzero != 0 {
+        header.push(card(
+            "BZERO",
+            &HeaderValue::Int(bzero),
+            "offset for unsigned data",
+        ));
+        header.push(card(
+            "BSCALE",
+            &HeaderValue::Int(1),
+            "default scaling factor",
+        ));
+    }
+    header.extend(
+        cards
+            .iter()
+            .map(|(key, value, comment)| card(key, value, comment)),
+    );
+    header.push(format!("{:<80}", "END"));
+
+    let mut buffer = header.concat().into_bytes();
+    pad_to_block(&mut buffer, b' ');
+    // SDK frames interleave the channels per pixel, FITS stores one plane after the other
+    for channel in 0..channels {
+        for pixel in 0..pixels {
+            let sample = samples
+                .get(pixel * channels + channel)
+                .copied()
+                .unwrap_or(0) as i64;
+            let stored = sample - bzero;
+            match format {
+                PixelFormat::U8 => buffer.push(stored as u8),
+                PixelFormat::U16 => buffer.extend_from_slice(&(stored as i16).to_be_bytes()),
+                PixelFormat::U32 => buffer.extend_from_slice(&(stored as i32).to_be_bytes()),
+            }
+        }
+    }
+    pad_to_block(&mut buffer, 0);
+    Ok(buffer)
+}
+
+/// writes `image` as a FITS file to `path`, see `encode_fits`
+pub(crate) fn write_fits(
+    image: &ImageData,
+    path: &Path,
+    cards: &[(&str, HeaderValue, &str)],
+) -> Result<()> {
+    let buffer = encode_fits(image, cards)?;
+    let file = File::create(path).map_err(|source| file_error(path, source))?;
+    let mut writer = BufWriter::new(file);
+    writer
+        .write_all(&buffer)
+        .and_then(|_| writer.flush())
+        .map_err(|source| file_error(path, source))
+}
+
+#[cfg(feature = "fits")]
+impl ImageData {
+    /// Writes the frame as FITS file to `path` with header cards for all values set in
+    /// `metadata`. Unsigned samples are stored with the usual `BZERO` offset, frames with more
+    /// than one channel are written as a cube with one plane per channel.
+    /// # Example
+    /// ```no_run
+    /// use std::path::Path;
+    /// use qhyccd_rs::{Control, Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.set_parameter(Control::Exposure, 2000000.0).expect("set_param failed");
+    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+    /// let buffer_size = camera.get_image_size().expect("get_image_size failed");
+    /// let image = camera.get_single_frame(buffer_size).expect("get_single_frame failed");
+    /// let metadata = camera.frame_metadata();
+    /// image.write_fits(Path::new("light.fits"), &metadata).expect("write_fits failed");
+    /// ```
+    pub fn write_fits(&self, path: &Path, metadata: &crate::FrameMetadata) -> Result<()> {
+        write_fits(self, path, &metadata_cards(metadata))
+    }
+}
+
+/// the header cards for all values set in `metadata`
+#[cfg(feature = "fits")]
+pub(crate) fn metadata_cards(
+    metadata: &crate::FrameMetadata,
+) -> Vec<(&'static str, HeaderValue, &'static str)> {
+    let mut cards = Vec::new();
+    if let Some(instrument) = &metadata.instrument {
+        cards.push((
+            "INSTRUME",
+            HeaderValue::Str(instrument.clone()),
+            "camera model",
+        ));
+    }
+    if let Some(exposure) = metadata.exposure {
+        cards.push((
+            "EXPTIME",
+            HeaderValue::Float(exposure.as_secs_f64()),
+            "exposure time in seconds",
+        ));
+    }
+    if let Some(gain) = metadata.gain {
+        cards.push(("GAIN", HeaderValue::Float(gain), "camera gain"));
+    }
+    if let Some(offset) = metadata.offset {
+        cards.push(("OFFSET", HeaderValue::Float(offset), "camera offset"));
+    }
+    if let Some(temperature) = metadata.ccd_temperature {
+        cards.push((
+            "CCD-TEMP",
+            HeaderValue::Float(temperature),
+            "sensor temperature in C",
+        ));
+    }
+    if let Some((x, y)) = metadata.binning {
+        cards.push(("XBINNING", HeaderValue::Int(x as i64), "horizontal binning"));
+        cards.push(("YBINNING", HeaderValue::Int(y as i64), "vertical binning"));
+    }
+    if let Some(bayer_mode) = metadata.bayer_mode {
+        cards.push((
+            "BAYERPAT",
+            HeaderValue::Str(format!("{:?}", bayer_mode)),
+            "bayer pattern",
+        ));
+    }
+    cards
+}
@@ -0,0 +1,75 @@
+//! The startup configuration some cameras keep in flash
+//!
+//! Cameras reporting `Control::CamInitConfigFromFlash` store a block of `FLASH_CONFIG_LEN` bytes
+//! they apply at power up. `Camera::read_flash_config` returns it as a `FlashConfig`, which
+//! decodes the fields the SDK knows about and gives access to the raw bytes for everything else.
+//! The SDK has no call to write the block as a whole, only the single fields, see
+//! `Camera::set_flash_init_pwm` and `Camera::reset_flash_ulvo_error`.
+
+/// the size of the flash configuration block in bytes
+pub const FLASH_CONFIG_LEN: usize = 64;
+
+/// marks an initialized block
+const CONFIG_HEADER: &[u8] = b"cfg";
+/// marks the error section, the undervoltage lockout flag follows at `ULVO_ERROR`
+const ERROR_HEADER: &[u8] = b"err";
+const ERROR_OFFSET: usize = 8;
+const ULVO_ERROR: usize = 15;
+/// marks the PWM section, `PWM_SET` is `+` once a PWM was written to `INIT_PWM`
+const PWM_HEADER: &[u8] = b"pwm";
+const PWM_OFFSET: usize = 16;
+const PWM_SET: usize = 19;
+const INIT_PWM: usize = 23;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The startup configuration stored in the flash of a camera, returned from
+/// `Camera::read_flash_config`
+pub struct FlashConfig {
+    raw: [u8; FLASH_CONFIG_LEN],
+}
+
+impl FlashConfig {
+    /// Wraps a raw configuration block
+    pub fn from_bytes(raw: [u8; FLASH_CONFIG_LEN]) -> FlashConfig {
+        FlashConfig { raw }
+    }
+
+    /// Returns the raw configuration block, e.g. for fields this crate does not decode
+    pub fn as_bytes(&self) -> &[u8; FLASH_CONFIG_LEN] {
+        &self.raw
+    }
+
+    /// Returns `true` if the camera has initialized the block, an erased block has no fields
+    pub fn is_initialized(&self) -> bool {
+        self.raw.starts_with(CONFIG_HEADER)
+    }
+
+    /// Returns the cooler PWM, 0 to 255, the camera applies at power up, `None` if none was set
+    pub fn init_pwm(&self) -> Option<u8> {
+        match self.has_section(PWM_OFFSET, PWM_HEADER) && self.raw[PWM_SET] == b'+' {
+            true => Some(self.raw[INIT_PWM]),
+            false => None,
+        }
+    }
+
+    /// Returns `true` if the camera recorded an undervoltage lockout, `None` if the block has no
+    /// error section
+    pub fn ulvo_error(&self) -> Option<bool> {
+        match self.has_section(ERROR_OFFSET, ERROR_HEADER) {
+            true => Some(self.raw[ULVO_ERROR] != 0),
+            false => None,
+        }
+    }
+
+    pub(crate) fn has_pwm_section(&self) -> bool {
+        self.has_section(PWM_OFFSET, PWM_HEADER)
+    }
+
+    pub(crate) fn has_error_section(&self) -> bool {
+        self.has_section(ERROR_OFFSET, ERROR_HEADER)
+    }
+
+    fn has_section(&self, offset: usize, header: &[u8]) -> bool {
+        self.is_initialized() && self.raw[offset..].starts_with(header)
+    }
+}
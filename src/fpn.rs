@@ -0,0 +1,85 @@
+//! Fixed pattern noise calibration
+//!
+//! Some CCD cameras can measure their fixed pattern noise, e.g. vertical stripes, and subtract it
+//! from later frames. Cameras supporting this report `Control::CamCalibrateFpnInterface`.
+//! Setting the control to 1 starts the calibration and it reads non zero until the camera is
+//! done. `Camera::calibrate_fpn` runs that sequence and reports its status along the way.
+
+use std::time::{Duration, Instant};
+
+use crate::QHYError::{FpnCalibrationTimeoutError, IsControlAvailableError};
+use crate::{Camera, Control, Result};
+
+/// how often `Camera::calibrate_fpn` asks the camera whether it is done
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// The status of a calibration run by `Camera::calibrate_fpn`
+pub enum FpnCalibrationStatus {
+    /// the camera accepted the request to calibrate
+    Started,
+    /// the camera is still calibrating
+    Running {
+        /// the time since the calibration started
+        elapsed: Duration,
+    },
+    /// the camera finished the calibration
+    Finished {
+        /// the time the calibration took
+        elapsed: Duration,
+    },
+}
+
+impl Camera {
+    /// Runs the fixed pattern noise calibration of the camera and calls `on_status` whenever
+    /// there is news. Fails with `IsControlAvailableError` for cameras without FPN calibration
+    /// and with `FpnCalibrationTimeoutError` if the camera is not done within `timeout`.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{FpnCalibrationStatus, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.init().expect("init failed");
+    /// camera
+    ///     .calibrate_fpn(Duration::from_secs(120), |status| match status {
+    ///         FpnCalibrationStatus::Running { elapsed } => println!("calibrating for {:?}", elapsed),
+    ///         status => println!("{:?}", status),
+    ///     })
+    ///     .expect("calibrate_fpn failed");
+    /// ```
+    pub fn calibrate_fpn(
+        &self,
+        timeout: Duration,
+        mut on_status: impl FnMut(FpnCalibrationStatus),
+    ) -> Result<()> {
+        if self
+            .is_control_available(Control::CamCalibrateFpnInterface)
+            .is_none()
+        {
+            let error = IsControlAvailableError {
+                control: Control::CamCalibrateFpnInterface,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let start = Instant::now();
+        self.set_parameter(Control::CamCalibrateFpnInterface, 1.0)?;
+        on_status(FpnCalibrationStatus::Started);
+        loop {
+            let elapsed = start.elapsed();
+            if self.get_parameter(Control::CamCalibrateFpnInterface)? == 0.0 {
+                on_status(FpnCalibrationStatus::Finished { elapsed });
+                return Ok(());
+            }
+            if elapsed >= timeout {
+                let error = FpnCalibrationTimeoutError { timeout };
+                tracing::error!(error = ?error);
+                return Err(error);
+            }
+            on_status(FpnCalibrationStatus::Running { elapsed });
+            std::thread::sleep(POLL_INTERVAL.min(timeout - elapsed));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Recycled frame buffers for live mode
+//!
+//! At high frame rates allocating a new multi-megabyte buffer for every frame adds up.
+//! `Camera::get_live_frame_pooled` downloads into a buffer taken from a `FramePool` and returns
+//! a `PooledFrame`, which hands the buffer back to the pool when it is dropped.
+
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use crate::{Camera, ImageData, Result};
+
+#[derive(Debug, Clone)]
+/// A pool of frame buffers, clones share the same buffers
+pub struct FramePool {
+    buffers: Arc<Mutex<Vec<Vec<u8>>>>,
+    max_buffers: usize,
+}
+
+impl FramePool {
+    /// Creates an empty pool that keeps at most `max_buffers` unused buffers around, buffers
+    /// returned to a full pool are freed
+    pub fn new(max_buffers: usize) -> Self {
+        Self {
+            buffers: Arc::new(Mutex::new(Vec::with_capacity(max_buffers))),
+            max_buffers,
+        }
+    }
+
+    /// Returns the number of unused buffers in the pool
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Vec<u8>>> {
+        self.buffers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// takes a buffer of `len` bytes from the pool or allocates a new one if the pool is empty
+    fn take(&self, len: usize) -> Vec<u8> {
+        let mut buffer = self.lock().pop().unwrap_or_default();
+        buffer.resize(len, 0);
+        buffer
+    }
+
+    fn give_back(&self, buffer: Vec<u8>) {
+        let mut buffers = self.lock();
+        if buffers.len() < self.max_buffers {
+            buffers.push(buffer);
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A frame whose buffer goes back to its `FramePool` when it is dropped, dereferences to
+/// `ImageData`
+pub struct PooledFrame {
+    image: ImageData,
+    pool: FramePool,
+}
+
+impl PooledFrame {
+    /// Detaches the frame from the pool, its buffer is not returned to the pool then
+    pub fn into_image(mut self) -> ImageData {
+        std::mem::replace(
+            &mut self.image,
+            ImageData {
+                data: Vec::new(),
+                width: 0,
+                height: 0,
+                bits_per_pixel: 0,
+                channels: 0,
+            },
+        )
+    }
+}
+
+impl Deref for PooledFrame {
+    type Target = ImageData;
+
+    fn deref(&self) -> &ImageData {
+        &self.image
+    }
+}
+
+impl Drop for PooledFrame {
+    fn drop(&mut self) {
+        let buffer = std::mem::take(&mut self.image.data);
+        if buffer.capacity() > 0 {
+            self.pool.give_back(buffer);
+        }
+    }
+}
+
+impl Camera {
+    /// Same as `get_live_frame`, but downloads into a buffer from `pool` instead of allocating
+    /// a new one for every frame
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{FramePool, Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// let pool = FramePool::new(4);
+    /// for _ in 0..1000 {
+    ///     if let Ok(frame) = camera.get_live_frame_pooled(&pool) {
+    ///         println!("{}x{} pixels", frame.width, frame.height);
+    ///     } // the buffer goes back to the pool here
+    /// }
+    /// camera.end_live().expect("end_live failed");
+    /// ```
+    pub fn get_live_frame_pooled(&self, pool: &FramePool) -> Result<PooledFrame> {
+        let mut buffer = pool.take(self.get_image_size()?);
+        match self.get_live_frame_into(&mut buffer) {
+            Ok(info) => Ok(PooledFrame {
+                image: info.into_image(buffer),
+                pool: pool.clone(),
+            }),
+            Err(error) => {
+                pool.give_back(buffer);
+                Err(error)
+            }
+        }
+    }
+}
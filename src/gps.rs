@@ -0,0 +1,125 @@
+//! Decoding of the GPS header of cameras like the QHY174M-GPS
+//!
+//! With GPS switched on through `Camera::set_gps` the camera overwrites the first bytes of every
+//! frame with the frame sequence number, the position and the GPS timestamps of the start and
+//! the end of the exposure. `ImageData::gps_info` decodes them into a `GpsInfo`.
+
+use std::time::{Duration, SystemTime};
+
+use crate::ImageData;
+use crate::QHYError::GpsHeaderError;
+use crate::Result;
+
+/// the number of bytes of the GPS header at the start of the frame
+const HEADER_LEN: usize = 44;
+/// the GPS seconds count from JD 2450000.5, that is 9413 days after the unix epoch
+const GPS_EPOCH_OFFSET: u64 = 9413 * 86_400;
+
+#[derive(Debug, PartialEq, Clone)]
+/// The GPS header of a frame, returned from `ImageData::gps_info`
+pub struct GpsInfo {
+    /// the sequence number of the frame
+    pub sequence: u32,
+    /// the width of the frame as written by the camera
+    pub width: u16,
+    /// the height of the frame as written by the camera
+    pub height: u16,
+    /// the latitude in degrees, negative values are south
+    pub latitude: f64,
+    /// the longitude in degrees, negative values are west
+    pub longitude: f64,
+    /// the status flag of the start timestamp
+    pub start_flag: u8,
+    /// the GPS time the exposure started
+    pub start_time: SystemTime,
+    /// the status flag of the end timestamp
+    pub end_flag: u8,
+    /// the GPS time the exposure ended
+    pub end_time: SystemTime,
+    /// the status flag of the current timestamp
+    pub now_flag: u8,
+    /// the GPS time the header was written
+    pub now_time: SystemTime,
+    /// the oscillator count between the last two PPS pulses, nominally 10 MHz
+    pub pps_counter: u32,
+}
+
+impl GpsInfo {
+    /// Returns the exposure time measured by the GPS module
+    pub fn exposure(&self) -> Duration {
+        self.end_time
+            .duration_since(self.start_time)
+            .unwrap_or_default()
+    }
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |value, &byte| value << 8 | byte as u32)
+}
+
+/// decodes seconds since the GPS epoch and a 24 bit count of 100ns
+fn timestamp(seconds: &[u8], ticks: &[u8]) -> SystemTime {
+    SystemTime::UNIX_EPOCH
+        + Duration::from_secs(be_u32(seconds) as u64 + GPS_EPOCH_OFFSET)
+        + Duration::from_nanos(be_u32(ticks) as u64 * 100)
+}
+
+/// decodes a coordinate stored as sign flag, degrees, minutes and fractional minutes
+fn coordinate(raw: u32, degree_divisor: u32) -> f64 {
+    let negative = raw > 1_000_000_000;
+    let raw = raw % 1_000_000_000;
+    let degrees = (raw / degree_divisor) as f64;
+    let minutes = (raw % degree_divisor) as f64 / (degree_divisor / 100) as f64;
+    let value = degrees + minutes / 60.0;
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+impl ImageData {
+    /// Decodes the GPS header the camera wrote into the first bytes of this frame. The values
+    /// are only meaningful for frames captured with GPS switched on.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_gps(true).expect("set_gps failed");
+    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+    /// let buffer_size = camera.get_image_size().expect("get_image_size failed");
+    /// let image = camera.get_single_frame(buffer_size).expect("get_single_frame failed");
+    /// let gps = image.gps_info().expect("gps_info failed");
+    /// println!("exposure started at {:?} and took {:?}", gps.start_time, gps.exposure());
+    /// ```
+    pub fn gps_info(&self) -> Result<GpsInfo> {
+        let header = match self.data.get(..HEADER_LEN) {
+            Some(header) => header,
+            None => {
+                let error = GpsHeaderError {
+                    len: self.data.len(),
+                };
+                tracing::error!(error = ?error);
+                return Err(error);
+            }
+        };
+        Ok(GpsInfo {
+            sequence: be_u32(&header[0..4]),
+            width: be_u32(&header[5..7]) as u16,
+            height: be_u32(&header[7..9]) as u16,
+            latitude: coordinate(be_u32(&header[9..13]), 10_000_000),
+            longitude: coordinate(be_u32(&header[13..17]), 1_000_000),
+            start_flag: header[17],
+            start_time: timestamp(&header[18..22], &header[22..25]),
+            end_flag: header[25],
+            end_time: timestamp(&header[26..30], &header[30..33]),
+            now_flag: header[33],
+            now_time: timestamp(&header[34..38], &header[38..41]),
+            pps_counter: be_u32(&header[41..44]),
+        })
+    }
+}
@@ -0,0 +1,134 @@
+//! Homing and position persistence for filter wheels
+//!
+//! `FilterWheel::home` drives the wheel to slot 0 with `FilterWheel::move_to_position` and
+//! remembers that it succeeded until the wheel is closed, see `FilterWheel::is_homed`.
+//! `FilterWheel::save_position` and `FilterWheel::check_saved_position` keep the last known
+//! position in a small file, so an application can notice at startup that the wheel was moved
+//! while it was not running and warn before imaging with the wrong filter.
+
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crate::QHYError::{FilterWheelHomeError, FilterWheelMoveTimeoutError};
+use crate::{file_error, FilterWheel, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The result of `FilterWheel::check_saved_position`
+pub enum SavedPosition {
+    /// there is no saved position for this filter wheel
+    Unknown,
+    /// the wheel is still at the saved position
+    Matches(u32),
+    /// the wheel is at a different position than the saved one
+    Moved {
+        /// the position that was saved
+        saved: u32,
+        /// the position the wheel reports now
+        actual: u32,
+    },
+}
+
+impl FilterWheel {
+    /// Drives the wheel to slot 0 and waits until it stopped there, see `move_to_position`.
+    /// Fails with `FilterWheelHomeError` if the wheel did not arrive within `timeout`.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let filter_wheel = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// filter_wheel.open().expect("open failed");
+    /// filter_wheel.home(Duration::from_secs(30)).expect("home failed");
+    /// ```
+    pub fn home(&self, timeout: Duration) -> Result<()> {
+        self.move_to_position(0, timeout)
+            .map_err(|error| match error {
+                FilterWheelMoveTimeoutError { timeout, .. } => FilterWheelHomeError { timeout },
+                error => error,
+            })?;
+        self.homed.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Returns `true` if `home` succeeded since the filter wheel was last closed
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let filter_wheel = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// filter_wheel.open().expect("open failed");
+    /// filter_wheel.home(Duration::from_secs(30)).expect("home failed");
+    /// assert!(filter_wheel.is_homed());
+    /// ```
+    pub fn is_homed(&self) -> bool {
+        self.homed.load(Ordering::SeqCst)
+    }
+
+    /// Reads the current position and stores it together with the id of the filter wheel in
+    /// the file at `path`. Returns the stored position.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let filter_wheel = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// filter_wheel.open().expect("open failed");
+    /// filter_wheel.set_fw_position(2).expect("set_fw_position failed");
+    /// filter_wheel.save_position("filter_wheel.pos".as_ref()).expect("save_position failed");
+    /// ```
+    pub fn save_position(&self, path: &Path) -> Result<u32> {
+        let position = self.get_fw_position()?;
+        std::fs::write(path, format!("{}\t{}\n", self.id(), position))
+            .map_err(|source| file_error(path, source))?;
+        Ok(position)
+    }
+
+    /// Compares the current position with the one saved by `save_position` and logs a warning
+    /// if the wheel was moved. A missing file or a file saved for another filter wheel results
+    /// in `SavedPosition::Unknown`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{SavedPosition, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let filter_wheel = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// filter_wheel.open().expect("open failed");
+    /// let check = filter_wheel
+    ///     .check_saved_position("filter_wheel.pos".as_ref())
+    ///     .expect("check_saved_position failed");
+    /// if let SavedPosition::Moved { saved, actual } = check {
+    ///     println!("filter wheel moved from {} to {}", saved, actual);
+    /// }
+    /// ```
+    pub fn check_saved_position(&self, path: &Path) -> Result<SavedPosition> {
+        let saved = match std::fs::read_to_string(path) {
+            Ok(content) => parse_saved_position(&content, self.id()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(source) => return Err(file_error(path, source)),
+        };
+        let saved = match saved {
+            Some(saved) => saved,
+            None => return Ok(SavedPosition::Unknown),
+        };
+        let actual = self.get_fw_position()?;
+        if actual == saved {
+            return Ok(SavedPosition::Matches(actual));
+        }
+        tracing::warn!(
+            saved,
+            actual,
+            "filter wheel {} is not at its saved position",
+            self.id()
+        );
+        Ok(SavedPosition::Moved { saved, actual })
+    }
+}
+
+/// returns the position from a file written by `save_position` if it belongs to `id`
+fn parse_saved_position(content: &str, id: &str) -> Option<u32> {
+    let (saved_id, position) = content.trim_end().split_once('\t')?;
+    match saved_id == id {
+        true => position.parse().ok(),
+        false => None,
+    }
+}
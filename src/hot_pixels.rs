@@ -0,0 +1,144 @@
+//! Hot pixel detection and correction
+//!
+//! `HotPixelMap::detect_from_dark` flags every pixel of a dark frame that is more than a given
+//! number of standard deviations above the median. `ImageData::remove_hot_pixels` replaces the
+//! flagged pixels of a frame with the median of their neighbors that are not flagged themselves.
+
+use crate::arithmetic::{read_samples, write_samples};
+use crate::ImageData;
+use crate::QHYError::ImageGeometryMismatchError;
+use crate::Result;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+/// The positions of the hot pixels of a sensor, detected from a dark frame
+pub struct HotPixelMap {
+    width: u32,
+    height: u32,
+    pixels: Vec<(u32, u32)>,
+}
+
+impl HotPixelMap {
+    /// Flags all pixels of `dark` with a sample more than `sigma` standard deviations above the
+    /// median of the frame, a pixel is hot if any of its channels is
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{HotPixelMap, ImageData};
+    /// let dark = ImageData { data: vec![10, 11, 9, 10, 250, 10, 11, 9, 10], width: 3, height: 3, bits_per_pixel: 8, channels: 1 };
+    /// let map = HotPixelMap::detect_from_dark(&dark, 2.0).expect("detect_from_dark failed");
+    /// assert_eq!(map.pixels(), &[(1, 1)]);
+    /// ```
+    pub fn detect_from_dark(dark: &ImageData, sigma: f64) -> Result<HotPixelMap> {
+        let statistics = dark.statistics()?;
+        let threshold = statistics.median + sigma * statistics.stddev;
+        let samples = read_samples(dark)?;
+        let channels = dark.channels.max(1) as usize;
+        let pixels = samples
+            .chunks_exact(channels)
+            .take(dark.width as usize * dark.height as usize)
+            .enumerate()
+            .filter(|(_, pixel)| pixel.iter().any(|&sample| sample as f64 > threshold))
+            .map(|(index, _)| {
+                (
+                    (index % dark.width as usize) as u32,
+                    (index / dark.width as usize) as u32,
+                )
+            })
+            .collect();
+        Ok(HotPixelMap {
+            width: dark.width,
+            height: dark.height,
+            pixels,
+        })
+    }
+
+    /// Returns the `(x, y)` positions of all hot pixels ordered by row
+    pub fn pixels(&self) -> &[(u32, u32)] {
+        &self.pixels
+    }
+
+    /// Returns the number of hot pixels
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// Returns `true` if no hot pixel was found
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// Returns `true` if the pixel at `x`, `y` is hot
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        self.pixels
+            .binary_search_by_key(&(y, x), |&(x, y)| (y, x))
+            .is_ok()
+    }
+}
+
+impl ImageData {
+    /// Returns a copy of the frame with every pixel in `map` replaced by the median of its up to
+    /// eight neighbors that are not hot themselves, channel by channel. Pixels without such
+    /// neighbors are left as they are. Fails with `ImageGeometryMismatchError` if the map was
+    /// detected on a frame of a different size.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{HotPixelMap, ImageData};
+    /// let dark = ImageData { data: vec![10, 11, 9, 10, 250, 10, 11, 9, 10], width: 3, height: 3, bits_per_pixel: 8, channels: 1 };
+    /// let map = HotPixelMap::detect_from_dark(&dark, 2.0).expect("detect_from_dark failed");
+    /// let light = ImageData { data: vec![50, 52, 51, 50, 255, 49, 50, 51, 52], ..dark };
+    /// let corrected = light.remove_hot_pixels(&map).expect("remove_hot_pixels failed");
+    /// assert_eq!(corrected.data[4], 50);
+    /// ```
+    pub fn remove_hot_pixels(&self, map: &HotPixelMap) -> Result<ImageData> {
+        if self.width != map.width || self.height != map.height {
+            let error = ImageGeometryMismatchError;
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let channels = self.channels.max(1) as usize;
+        let original = read_samples(self)?;
+        let mut samples = original.clone();
+        let sample = |x: u32, y: u32, channel: usize| {
+            original.get((y as usize * self.width as usize + x as usize) * channels + channel)
+        };
+        for &(x, y) in map.pixels() {
+            let neighbors = (-1_i64..=1)
+                .flat_map(|dy| (-1_i64..=1).map(move |dx| (dx, dy)))
+                .filter(|&offset| offset != (0, 0))
+                .map(|(dx, dy)| (x as i64 + dx, y as i64 + dy))
+                .filter(|&(nx, ny)| {
+                    nx >= 0 && ny >= 0 && nx < self.width as i64 && ny < self.height as i64
+                })
+                .map(|(nx, ny)| (nx as u32, ny as u32))
+                .filter(|&(nx, ny)| !map.contains(nx, ny))
+                .collect::<Vec<_>>();
+            if neighbors.is_empty() {
+                continue;
+            }
+            for channel in 0..channels {
+                let mut values = neighbors
+                    .iter()
+                    .filter_map(|&(nx, ny)| sample(nx, ny, channel).copied())
+                    .collect::<Vec<_>>();
+                if values.is_empty() {
+                    continue;
+                }
+                values.sort_unstable();
+                let median = match values.len() % 2 {
+                    0 => (values[values.len() / 2 - 1] + values[values.len() / 2]) / 2,
+                    _ => values[values.len() / 2],
+                };
+                let index = (y as usize * self.width as usize + x as usize) * channels + channel;
+                if let Some(target) = samples.get_mut(index) {
+                    *target = median;
+                }
+            }
+        }
+        Ok(ImageData {
+            data: write_samples(&samples, self.bits_per_pixel)?,
+            width: self.width,
+            height: self.height,
+            bits_per_pixel: self.bits_per_pixel,
+            channels: self.channels,
+        })
+    }
+}
@@ -0,0 +1,92 @@
+//! Notifications about cameras being attached and detached
+//!
+//! The SDK reports hot-plugging through two C callbacks that only take the camera id.
+//! `Sdk::subscribe_hotplug` registers them with the first subscriber and returns a channel, every
+//! subscriber gets every event until its receiver is dropped.
+
+use std::ffi::{c_char, CStr};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, Once};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A camera being attached or detached, received from `Sdk::subscribe_hotplug`
+pub enum HotplugEvent {
+    /// a camera with the given id was attached, open it with `Camera::new`
+    Attached(String),
+    /// the camera with the given id was detached
+    Detached(String),
+}
+
+#[derive(Debug)]
+/// the senders of all subscribers
+pub(crate) struct Subscribers {
+    senders: Mutex<Vec<Sender<HotplugEvent>>>,
+    /// the SDK callbacks are registered once for all subscribers
+    registered: Once,
+}
+
+impl Subscribers {
+    pub(crate) const fn new() -> Self {
+        Self {
+            senders: Mutex::new(Vec::new()),
+            registered: Once::new(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Sender<HotplugEvent>>> {
+        self.senders
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// adds a subscriber, the SDK callbacks have to be registered with `register` separately
+    pub(crate) fn subscribe(&self) -> Receiver<HotplugEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.lock().push(sender);
+        receiver
+    }
+
+    /// registers the SDK callbacks with `register` unless that was done before
+    pub(crate) fn register(&self, register: impl FnOnce()) {
+        self.registered.call_once(register);
+    }
+
+    /// sends `event` to all subscribers and forgets the ones that dropped their receiver
+    pub(crate) fn dispatch(&self, event: HotplugEvent) {
+        tracing::debug!(event = ?event);
+        self.lock()
+            .retain(|sender| sender.send(event.clone()).is_ok());
+    }
+}
+
+/// the subscribers the SDK callbacks dispatch to
+pub(crate) static SUBSCRIBERS: Subscribers = Subscribers::new();
+
+fn id_from_ptr(id: *const c_char) -> Option<String> {
+    if id.is_null() {
+        tracing::error!("hotplug callback without camera id");
+        return None;
+    }
+    match unsafe { CStr::from_ptr(id) }.to_str() {
+        Ok(id) => Some(id.to_owned()),
+        Err(error) => {
+            tracing::error!(error = ?error);
+            None
+        }
+    }
+}
+
+/// registered with `RegisterPnpEventIn`
+pub(crate) extern "C" fn on_attached(id: *mut c_char) {
+    if let Some(id) = id_from_ptr(id) {
+        SUBSCRIBERS.dispatch(HotplugEvent::Attached(id));
+    }
+}
+
+/// registered with `RegisterPnpEventOut`
+pub(crate) extern "C" fn on_detached(id: *mut c_char) {
+    if let Some(id) = id_from_ptr(id) {
+        SUBSCRIBERS.dispatch(HotplugEvent::Detached(id));
+    }
+}
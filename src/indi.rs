@@ -0,0 +1,1244 @@
+//! INDI driver for cameras and filter wheels
+//!
+//! `IndiDriver` speaks the XML protocol of INDI over TCP, so INDI clients and the imaging suites
+//! built on them can use a camera through this crate instead of the C++ driver shipped with INDI.
+//! The camera is offered with the standard properties `CONNECTION`, `CCD_EXPOSURE`,
+//! `CCD_ABORT_EXPOSURE`, `CCD_TEMPERATURE` and `CCD_FRAME`, a filter wheel added with
+//! `IndiDriver::with_filter_wheel` as `FILTER_SLOT`. Frames are sent as FITS files in the `CCD1`
+//! BLOB to clients that enabled BLOBs with `enableBLOB`.
+//!
+//! Every client connection is served by its own session on a thread of its own. The sessions
+//! share the camera, it is only initialized for the first client to connect and only closed when
+//! the last connected client sends `DISCONNECT`. Exposures run in the background through
+//! `Camera::start_exposure`, the session checks on them between the messages of the client and
+//! reports the progress of the exposure, the cooler and the filter wheel once a second.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::fits::{encode_fits, metadata_cards};
+use crate::QHYError::{CameraStateError, IndiIoError, IndiProtocolError};
+use crate::{
+    format_float, parse_float, CCDChipArea, Camera, CameraState, Control, ExposureHandle,
+    FilterWheel, FilterWheelStatus, QHYError, Result, StreamMode,
+};
+
+/// The port INDI servers listen on by default
+pub const INDI_PORT: u16 = 7624;
+
+/// how long a session waits for a message of the client before it checks on running operations
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// how often the progress of running operations is reported to the client
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// the sensor counts as settled within this many °C of the set-point
+const TEMPERATURE_TOLERANCE: f64 = 0.5;
+/// the exposure limits in seconds used when the camera does not report them
+const DEFAULT_EXPOSURE_LIMITS: (f64, f64, f64) = (0.0, 3600.0, 0.001);
+/// the set-point limits in °C used when the camera does not report them
+const DEFAULT_TEMPERATURE_LIMITS: (f64, f64, f64) = (-50.0, 50.0, 0.1);
+/// the deepest nesting of elements a message of the client may have
+const MAX_DEPTH: usize = 16;
+/// the most bytes of a message of the client that are buffered before it is dropped
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+/// the properties that only exist while the camera is connected
+const DEVICE_PROPERTIES: [&str; 6] = [
+    "CCD_EXPOSURE",
+    "CCD_ABORT_EXPOSURE",
+    "CCD_TEMPERATURE",
+    "CCD_FRAME",
+    "FILTER_SLOT",
+    "CCD1",
+];
+
+#[derive(Debug, Clone)]
+/// Serves a camera, and optionally the filter wheel plugged into it, to INDI clients
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::{IndiDriver, Sdk, INDI_PORT};
+/// let sdk = Sdk::new().expect("SDK::new failed");
+/// let camera = sdk.cameras().last().expect("no camera found");
+/// let mut driver = IndiDriver::new(camera.clone());
+/// if let Some(fw) = sdk.filter_wheels().last() {
+///     driver = driver.with_filter_wheel(fw.clone());
+/// }
+/// driver.serve(("0.0.0.0", INDI_PORT)).expect("serve failed");
+/// ```
+pub struct IndiDriver {
+    device: String,
+    camera: Camera,
+    filter_wheel: Option<FilterWheel>,
+    /// the number of sessions connected to the camera, shared by all clones
+    connections: Arc<Mutex<usize>>,
+}
+
+impl IndiDriver {
+    /// Creates a driver for `camera`, the INDI device is named after the id of the camera
+    pub fn new(camera: Camera) -> Self {
+        Self {
+            device: format!("QHY CCD {}", camera.id()),
+            camera,
+            filter_wheel: None,
+            connections: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    /// Adds `filter_wheel` to the device as `FILTER_SLOT`
+    pub fn with_filter_wheel(mut self, filter_wheel: FilterWheel) -> Self {
+        self.filter_wheel = Some(filter_wheel);
+        self
+    }
+
+    /// Replaces the name of the INDI device
+    pub fn with_device_name(mut self, device: &str) -> Self {
+        self.device = device.to_owned();
+        self
+    }
+
+    /// Returns the name of the INDI device
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+
+    /// Listens on `address` and serves every client that connects on a thread of its own. Only
+    /// returns if the address cannot be bound, failed client connections are logged.
+    pub fn serve(&self, address: impl ToSocketAddrs) -> Result<()> {
+        let listener = TcpListener::bind(address).map_err(io_error)?;
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    tracing::warn!(indi_accept = ?error);
+                    continue;
+                }
+            };
+            let driver = self.clone();
+            thread::spawn(move || {
+                if let Err(error) = driver.handle_client(stream) {
+                    tracing::warn!(indi_client = ?error);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// held while the camera is opened or closed, so no session connects while another one
+    /// closes the camera
+    fn connections(&self) -> MutexGuard<'_, usize> {
+        self.connections
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Serves one client until it disconnects
+    pub fn handle_client(&self, stream: TcpStream) -> Result<()> {
+        stream
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .map_err(io_error)?;
+        let mut reader = stream.try_clone().map_err(io_error)?;
+        let mut session = IndiSession::new(self.clone(), stream);
+        let mut chunk = [0_u8; 4096];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => return Ok(()),
+                Ok(len) => session.receive(&chunk[..len])?,
+                Err(error)
+                    if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(error) => return Err(io_error(error)),
+            }
+            session.poll()?;
+        }
+    }
+}
+
+impl<W: Write> Drop for IndiSession<W> {
+    fn drop(&mut self) {
+        // a client that goes away without `DISCONNECT` leaves the camera open, as before
+        if self.connected {
+            let mut connections = self.driver.connections();
+            *connections = connections.saturating_sub(1);
+        }
+    }
+}
+
+/// logs an I/O error on the client connection and returns it as `IndiIoError`
+fn io_error(source: std::io::Error) -> QHYError {
+    let error = IndiIoError { source };
+    tracing::error!(error = ?error);
+    error
+}
+
+/// logs and returns an `IndiProtocolError`
+fn protocol_error(reason: String) -> QHYError {
+    let error = IndiProtocolError { reason };
+    tracing::error!(error = ?error);
+    error
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// the state INDI attaches to every property
+enum PropertyState {
+    Idle,
+    Ok,
+    Busy,
+    Alert,
+}
+
+impl PropertyState {
+    fn as_str(self) -> &'static str {
+        match self {
+            PropertyState::Idle => "Idle",
+            PropertyState::Ok => "Ok",
+            PropertyState::Busy => "Busy",
+            PropertyState::Alert => "Alert",
+        }
+    }
+}
+
+/// a member of a number property as it is defined to the client
+struct NumberMember<'a> {
+    name: &'a str,
+    label: &'a str,
+    format: &'a str,
+    limits: (f64, f64, f64),
+    value: f64,
+}
+
+/// The connection to one client, `receive` handles its messages and `poll` checks on the
+/// operations they started
+pub(crate) struct IndiSession<W: Write> {
+    driver: IndiDriver,
+    writer: W,
+    buffer: Vec<u8>,
+    exposure: Option<ExposureHandle>,
+    has_cooler: bool,
+    temperature_target: Option<f64>,
+    filter_target: Option<u32>,
+    /// whether the client asked for frames with `enableBLOB`
+    blobs_enabled: bool,
+    /// whether this session counts as one of the `IndiDriver::connections`
+    connected: bool,
+    last_report: Instant,
+}
+
+impl<W: Write> IndiSession<W> {
+    pub(crate) fn new(driver: IndiDriver, writer: W) -> Self {
+        // a camera opened by another session or before serving counts as connected here too
+        let connected = {
+            let mut connections = driver.connections();
+            let connected = matches!(driver.camera.is_open(), Ok(true));
+            *connections += connected as usize;
+            connected
+        };
+        let has_cooler = connected
+            && driver
+                .camera
+                .is_control_available(Control::Cooler)
+                .is_some();
+        Self {
+            driver,
+            writer,
+            buffer: Vec::new(),
+            exposure: None,
+            has_cooler,
+            temperature_target: None,
+            filter_target: None,
+            blobs_enabled: false,
+            connected,
+            last_report: Instant::now(),
+        }
+    }
+
+    /// Handles all complete messages in `bytes` and whatever was left over from the last call.
+    /// Malformed messages are logged and dropped, only failing to answer is an error.
+    pub(crate) fn receive(&mut self, bytes: &[u8]) -> Result<()> {
+        self.buffer.extend_from_slice(bytes);
+        loop {
+            match parse_element(&self.buffer) {
+                Ok(Some((message, len))) => {
+                    self.buffer.drain(..len);
+                    self.dispatch(&message)?;
+                }
+                Ok(None) => return Ok(()),
+                Err(error) => {
+                    // skip to the next element, the messages after the broken one are fine,
+                    // unless the broken one is too long to tell where it ends
+                    let next = match self.buffer.len() > MAX_MESSAGE_LEN {
+                        true => self.buffer.len(),
+                        false => self.buffer[1..]
+                            .iter()
+                            .position(|byte| *byte == b'<')
+                            .map_or(self.buffer.len(), |offset| offset + 1),
+                    };
+                    let dropped = self.buffer.drain(..next).count();
+                    tracing::warn!(indi_dropped = ?error, bytes = dropped);
+                }
+            }
+        }
+    }
+
+    /// Sends the frame of a finished exposure and, once a second, the progress of the exposure,
+    /// the cooler and the filter wheel
+    pub(crate) fn poll(&mut self) -> Result<()> {
+        if self
+            .exposure
+            .as_ref()
+            .map_or(false, ExposureHandle::is_finished)
+        {
+            if let Some(exposure) = self.exposure.take() {
+                self.finish_exposure(exposure)?;
+            }
+        }
+        if self.last_report.elapsed() >= REPORT_INTERVAL {
+            self.report()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_exposing(&self) -> bool {
+        self.exposure.is_some()
+    }
+
+    /// reports the progress of the running operations right away
+    pub(crate) fn report(&mut self) -> Result<()> {
+        self.last_report = Instant::now();
+        if !self.is_connected() {
+            return Ok(());
+        }
+        if let Some(Ok(progress)) = self.exposure.as_ref().map(ExposureHandle::progress) {
+            self.set_numbers(
+                "CCD_EXPOSURE",
+                PropertyState::Busy,
+                None,
+                &[("CCD_EXPOSURE_VALUE", progress.remaining.as_secs_f64())],
+            )?;
+        }
+        // the download holds the camera until the frame arrived, reading the cooler or the
+        // filter wheel now would stall the session and with it an abort from the client
+        if self.exposure.is_some() {
+            return Ok(());
+        }
+        if self.has_cooler {
+            self.report_temperature()?;
+        }
+        if let Some(slot) = self.filter_target {
+            self.report_filter_slot(slot)?;
+        }
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected && matches!(self.driver.camera.is_open(), Ok(true))
+    }
+
+    fn dispatch(&mut self, message: &Element) -> Result<()> {
+        if message
+            .attribute("device")
+            .map_or(false, |device| device != self.driver.device)
+        {
+            return Ok(());
+        }
+        let name = message.attribute("name").unwrap_or_default();
+        let kind = match message.name.as_str() {
+            "getProperties" => return self.define_properties(message.attribute("name")),
+            "enableBLOB" => {
+                self.blobs_enabled = matches!(message.text.trim(), "Also" | "Only");
+                return Ok(());
+            }
+            "newNumberVector" => "Number",
+            "newSwitchVector" => "Switch",
+            other => {
+                tracing::trace!(indi_ignored = other);
+                return Ok(());
+            }
+        };
+        match self.update(name, message) {
+            Err(error @ IndiIoError { .. }) => Err(error),
+            Err(error) => self.send_set(kind, name, PropertyState::Alert, Some(&error), ""),
+            Ok(()) => Ok(()),
+        }
+    }
+
+    /// applies a new value the client sent for the property `name`
+    fn update(&mut self, name: &str, message: &Element) -> Result<()> {
+        match name {
+            "CONNECTION" if switch_on(message, "CONNECT") => self.connect(),
+            "CONNECTION" if switch_on(message, "DISCONNECT") => self.disconnect(),
+            "CCD_EXPOSURE" => self.start_exposure(number(message, "CCD_EXPOSURE_VALUE")?),
+            "CCD_ABORT_EXPOSURE" if switch_on(message, "ABORT") => self.abort_exposure(),
+            "CCD_TEMPERATURE" if self.has_cooler => {
+                self.set_temperature(number(message, "CCD_TEMPERATURE_VALUE")?)
+            }
+            "CCD_FRAME" => self.set_frame(CCDChipArea {
+                start_x: unsigned(message, "X")?,
+                start_y: unsigned(message, "Y")?,
+                width: unsigned(message, "WIDTH")?,
+                height: unsigned(message, "HEIGHT")?,
+            }),
+            "FILTER_SLOT" if self.driver.filter_wheel.is_some() => {
+                self.set_filter_slot(unsigned(message, "FILTER_SLOT_VALUE")?)
+            }
+            _ => {
+                tracing::trace!(indi_ignored = name);
+                Ok(())
+            }
+        }
+    }
+
+    fn connect(&mut self) -> Result<()> {
+        let mut connections = self.driver.connections();
+        let camera = &self.driver.camera;
+        if !camera.is_open()? {
+            camera.open()?;
+        }
+        // the camera is shared, a client connecting later must not reset it under the others
+        if !camera.handle.is_initialized_in(StreamMode::SingleFrameMode) {
+            camera.set_stream_mode(StreamMode::SingleFrameMode)?;
+            camera.init()?;
+        }
+        if let Some(filter_wheel) = &self.driver.filter_wheel {
+            if !filter_wheel.is_open()? {
+                filter_wheel.open()?;
+            }
+        }
+        if !self.connected {
+            self.connected = true;
+            *connections += 1;
+        }
+        drop(connections);
+        self.has_cooler = camera.is_control_available(Control::Cooler).is_some();
+        self.set_switches(
+            "CONNECTION",
+            PropertyState::Ok,
+            &[("CONNECT", true), ("DISCONNECT", false)],
+        )?;
+        self.define_device_properties(None)
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        if let Some(exposure) = self.exposure.take() {
+            exposure.abort_and_discard()?;
+        }
+        self.temperature_target = None;
+        self.filter_target = None;
+        if self.connected {
+            let mut connections = self.driver.connections();
+            self.connected = false;
+            *connections = connections.saturating_sub(1);
+            // the other connected clients still use the camera and the filter wheel
+            if *connections == 0 {
+                if let Some(filter_wheel) = &self.driver.filter_wheel {
+                    if filter_wheel.is_open()? {
+                        filter_wheel.close()?;
+                    }
+                }
+                if self.driver.camera.is_open()? {
+                    self.driver.camera.close()?;
+                }
+            }
+        }
+        for name in DEVICE_PROPERTIES {
+            let device = self.driver.device.clone();
+            self.send(&xml(
+                "delProperty",
+                &[("device", &device), ("name", name)],
+                None,
+            ))?;
+        }
+        self.set_switches(
+            "CONNECTION",
+            PropertyState::Idle,
+            &[("CONNECT", false), ("DISCONNECT", true)],
+        )
+    }
+
+    fn start_exposure(&mut self, seconds: f64) -> Result<()> {
+        if self.is_exposing() {
+            let error = CameraStateError {
+                state: CameraState::Exposing,
+                expected: CameraState::Open,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        if !(0.0..u64::MAX as f64).contains(&seconds) {
+            return Err(protocol_error(format!("invalid exposure time {}", seconds)));
+        }
+        let exposure = Duration::from_secs_f64(seconds);
+        self.exposure = Some(self.driver.camera.start_exposure(exposure)?);
+        self.set_numbers(
+            "CCD_EXPOSURE",
+            PropertyState::Busy,
+            None,
+            &[("CCD_EXPOSURE_VALUE", seconds)],
+        )
+    }
+
+    fn finish_exposure(&mut self, exposure: ExposureHandle) -> Result<()> {
+        let camera = self.driver.camera.clone();
+        let fits = exposure
+            .wait()
+            .and_then(|image| encode_fits(&image, &metadata_cards(&camera.frame_metadata())));
+        match fits {
+            Ok(fits) => {
+                // clients that did not enable BLOBs do not want frames sent to them
+                if self.blobs_enabled {
+                    let blob = xml(
+                        "oneBLOB",
+                        &[
+                            ("name", "CCD1"),
+                            ("size", &fits.len().to_string()),
+                            ("format", ".fits"),
+                        ],
+                        Some(&base64(&fits)),
+                    );
+                    self.send_set("BLOB", "CCD1", PropertyState::Ok, None, &blob)?;
+                }
+                self.set_numbers(
+                    "CCD_EXPOSURE",
+                    PropertyState::Ok,
+                    None,
+                    &[("CCD_EXPOSURE_VALUE", 0.0)],
+                )
+            }
+            Err(error) => self.set_numbers(
+                "CCD_EXPOSURE",
+                PropertyState::Alert,
+                Some(&error),
+                &[("CCD_EXPOSURE_VALUE", 0.0)],
+            ),
+        }
+    }
+
+    fn abort_exposure(&mut self) -> Result<()> {
+        if let Some(exposure) = self.exposure.take() {
+            exposure.abort_and_discard()?;
+            self.set_numbers(
+                "CCD_EXPOSURE",
+                PropertyState::Idle,
+                None,
+                &[("CCD_EXPOSURE_VALUE", 0.0)],
+            )?;
+        }
+        self.set_switches("CCD_ABORT_EXPOSURE", PropertyState::Ok, &[("ABORT", false)])
+    }
+
+    fn set_temperature(&mut self, target: f64) -> Result<()> {
+        self.driver.camera.set_parameter(Control::Cooler, target)?;
+        self.temperature_target = Some(target);
+        self.report_temperature()
+    }
+
+    fn report_temperature(&mut self) -> Result<()> {
+        let current = match self.driver.camera.get_parameter(Control::CurTemp) {
+            Ok(current) => current,
+            Err(error) => {
+                return self.send_set(
+                    "Number",
+                    "CCD_TEMPERATURE",
+                    PropertyState::Alert,
+                    Some(&error),
+                    "",
+                )
+            }
+        };
+        let state = match self.temperature_target {
+            Some(target) if (current - target).abs() > TEMPERATURE_TOLERANCE => PropertyState::Busy,
+            _ => {
+                self.temperature_target = None;
+                PropertyState::Ok
+            }
+        };
+        self.set_numbers(
+            "CCD_TEMPERATURE",
+            state,
+            None,
+            &[("CCD_TEMPERATURE_VALUE", current)],
+        )
+    }
+
+    fn set_frame(&mut self, roi: CCDChipArea) -> Result<()> {
+        self.driver.camera.set_roi(roi)?;
+        self.set_numbers(
+            "CCD_FRAME",
+            PropertyState::Ok,
+            None,
+            &[
+                ("X", roi.start_x as f64),
+                ("Y", roi.start_y as f64),
+                ("WIDTH", roi.width as f64),
+                ("HEIGHT", roi.height as f64),
+            ],
+        )
+    }
+
+    /// moves the filter wheel to `slot`, INDI counts the slots from 1
+    fn set_filter_slot(&mut self, slot: u32) -> Result<()> {
+        let Some(filter_wheel) = &self.driver.filter_wheel else {
+            return Ok(());
+        };
+        if slot == 0 {
+            return Err(protocol_error("filter slots start at 1".to_owned()));
+        }
+        filter_wheel.set_fw_position(slot - 1)?;
+        self.filter_target = Some(slot);
+        self.set_numbers(
+            "FILTER_SLOT",
+            PropertyState::Busy,
+            None,
+            &[("FILTER_SLOT_VALUE", slot as f64)],
+        )
+    }
+
+    fn report_filter_slot(&mut self, slot: u32) -> Result<()> {
+        let Some(filter_wheel) = &self.driver.filter_wheel else {
+            return Ok(());
+        };
+        // wheels without status support are only checked by their position
+        let moving = matches!(filter_wheel.status(), Ok(FilterWheelStatus::Moving));
+        let state = match filter_wheel.get_fw_position() {
+            Ok(position) if !moving && position + 1 == slot => {
+                self.filter_target = None;
+                PropertyState::Ok
+            }
+            Ok(_) => PropertyState::Busy,
+            Err(error) => {
+                self.filter_target = None;
+                return self.send_set(
+                    "Number",
+                    "FILTER_SLOT",
+                    PropertyState::Alert,
+                    Some(&error),
+                    "",
+                );
+            }
+        };
+        self.set_numbers(
+            "FILTER_SLOT",
+            state,
+            None,
+            &[("FILTER_SLOT_VALUE", slot as f64)],
+        )
+    }
+
+    /// defines `CONNECTION` and, while connected, the properties of the device, all of them or
+    /// only the one called `name`
+    fn define_properties(&mut self, name: Option<&str>) -> Result<()> {
+        if name.map_or(true, |name| name == "CONNECTION") {
+            let connected = self.is_connected();
+            self.define_switches(
+                "CONNECTION",
+                "Connection",
+                "Main Control",
+                "OneOfMany",
+                &[
+                    ("CONNECT", "Connect", connected),
+                    ("DISCONNECT", "Disconnect", !connected),
+                ],
+            )?;
+        }
+        match self.is_connected() {
+            true => self.define_device_properties(name),
+            false => Ok(()),
+        }
+    }
+
+    fn define_device_properties(&mut self, name: Option<&str>) -> Result<()> {
+        let wanted = |property: &str| name.map_or(true, |name| name == property);
+        let camera = self.driver.camera.clone();
+        if wanted("CCD_EXPOSURE") {
+            let limits = camera
+                .get_parameter_min_max_step(Control::Exposure)
+                .map(|(min, max, step)| (min / 1e6, max / 1e6, step / 1e6))
+                .unwrap_or(DEFAULT_EXPOSURE_LIMITS);
+            self.define_numbers(
+                "CCD_EXPOSURE",
+                "Expose",
+                "Main Control",
+                &[NumberMember {
+                    name: "CCD_EXPOSURE_VALUE",
+                    label: "Duration (s)",
+                    format: "%5.3f",
+                    limits,
+                    value: 0.0,
+                }],
+            )?;
+        }
+        if wanted("CCD_ABORT_EXPOSURE") {
+            self.define_switches(
+                "CCD_ABORT_EXPOSURE",
+                "Abort",
+                "Main Control",
+                "AtMostOne",
+                &[("ABORT", "Abort", false)],
+            )?;
+        }
+        if self.has_cooler && wanted("CCD_TEMPERATURE") {
+            let limits = camera
+                .get_parameter_min_max_step(Control::Cooler)
+                .unwrap_or(DEFAULT_TEMPERATURE_LIMITS);
+            self.define_numbers(
+                "CCD_TEMPERATURE",
+                "Temperature",
+                "Main Control",
+                &[NumberMember {
+                    name: "CCD_TEMPERATURE_VALUE",
+                    label: "Temperature (C)",
+                    format: "%5.2f",
+                    limits,
+                    value: camera.get_parameter(Control::CurTemp).unwrap_or_default(),
+                }],
+            )?;
+        }
+        if wanted("CCD_FRAME") {
+            // the limits are the ones `set_roi` checks against, an empty area leaves no frame
+            // to choose, so the property is not defined for it
+            let binning = camera.frame_geometry().binning.unwrap_or((1, 1));
+            let area = camera
+                .binned_effective_area(binning)
+                .ok()
+                .filter(|area| area.width > 0 && area.height > 0);
+            if let (Some(area), Ok(roi)) = (area, camera.get_roi()) {
+                let member = |name, label, (min, max): (u32, u32), value: u32| NumberMember {
+                    name,
+                    label,
+                    format: "%4.0f",
+                    limits: (min as f64, max as f64, 1.0),
+                    value: value as f64,
+                };
+                self.define_numbers(
+                    "CCD_FRAME",
+                    "Frame",
+                    "Image Settings",
+                    &[
+                        member(
+                            "X",
+                            "Left",
+                            (area.start_x, area.start_x.saturating_add(area.width - 1)),
+                            roi.start_x,
+                        ),
+                        member(
+                            "Y",
+                            "Top",
+                            (area.start_y, area.start_y.saturating_add(area.height - 1)),
+                            roi.start_y,
+                        ),
+                        member("WIDTH", "Width", (1, area.width), roi.width),
+                        member("HEIGHT", "Height", (1, area.height), roi.height),
+                    ],
+                )?;
+            }
+        }
+        if let Some(filter_wheel) = self.driver.filter_wheel.clone() {
+            if wanted("FILTER_SLOT") {
+                if let Ok(slots) = filter_wheel.get_number_of_filters() {
+                    let position = filter_wheel.get_fw_position().unwrap_or_default();
+                    self.define_numbers(
+                        "FILTER_SLOT",
+                        "Filter Slot",
+                        "Filter Wheel",
+                        &[NumberMember {
+                            name: "FILTER_SLOT_VALUE",
+                            label: "Filter",
+                            format: "%3.0f",
+                            limits: (1.0, slots as f64, 1.0),
+                            value: (position + 1) as f64,
+                        }],
+                    )?;
+                }
+            }
+        }
+        if wanted("CCD1") {
+            let device = self.driver.device.clone();
+            let blob = xml("defBLOB", &[("name", "CCD1"), ("label", "Image")], None);
+            self.send(&xml(
+                "defBLOBVector",
+                &[
+                    ("device", &device),
+                    ("name", "CCD1"),
+                    ("label", "Image Data"),
+                    ("group", "Image Info"),
+                    ("state", PropertyState::Idle.as_str()),
+                    ("perm", "ro"),
+                ],
+                Some(&blob),
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn define_numbers(
+        &mut self,
+        name: &str,
+        label: &str,
+        group: &str,
+        members: &[NumberMember<'_>],
+    ) -> Result<()> {
+        let content = members
+            .iter()
+            .map(|member| {
+                let (min, max, step) = member.limits;
+                xml(
+                    "defNumber",
+                    &[
+                        ("name", member.name),
+                        ("label", member.label),
+                        ("format", member.format),
+                        ("min", &format_float(min)),
+                        ("max", &format_float(max)),
+                        ("step", &format_float(step)),
+                    ],
+                    Some(&format_float(member.value)),
+                )
+            })
+            .collect::<String>();
+        let device = self.driver.device.clone();
+        self.send(&xml(
+            "defNumberVector",
+            &[
+                ("device", &device),
+                ("name", name),
+                ("label", label),
+                ("group", group),
+                ("state", PropertyState::Idle.as_str()),
+                ("perm", "rw"),
+                ("timeout", "60"),
+            ],
+            Some(&content),
+        ))
+    }
+
+    fn define_switches(
+        &mut self,
+        name: &str,
+        label: &str,
+        group: &str,
+        rule: &str,
+        members: &[(&str, &str, bool)],
+    ) -> Result<()> {
+        let content = members
+            .iter()
+            .map(|(name, label, on)| {
+                xml(
+                    "defSwitch",
+                    &[("name", name), ("label", label)],
+                    Some(switch_value(*on)),
+                )
+            })
+            .collect::<String>();
+        let device = self.driver.device.clone();
+        self.send(&xml(
+            "defSwitchVector",
+            &[
+                ("device", &device),
+                ("name", name),
+                ("label", label),
+                ("group", group),
+                ("state", PropertyState::Idle.as_str()),
+                ("perm", "rw"),
+                ("rule", rule),
+                ("timeout", "60"),
+            ],
+            Some(&content),
+        ))
+    }
+
+    fn set_numbers(
+        &mut self,
+        name: &str,
+        state: PropertyState,
+        error: Option<&QHYError>,
+        members: &[(&str, f64)],
+    ) -> Result<()> {
+        let content = members
+            .iter()
+            .map(|(name, value)| xml("oneNumber", &[("name", name)], Some(&format_float(*value))))
+            .collect::<String>();
+        self.send_set("Number", name, state, error, &content)
+    }
+
+    fn set_switches(
+        &mut self,
+        name: &str,
+        state: PropertyState,
+        members: &[(&str, bool)],
+    ) -> Result<()> {
+        let content = members
+            .iter()
+            .map(|(name, on)| xml("oneSwitch", &[("name", name)], Some(switch_value(*on))))
+            .collect::<String>();
+        self.send_set("Switch", name, state, None, &content)
+    }
+
+    /// sends a `set<kind>Vector`, `error` becomes the message shown by the client
+    fn send_set(
+        &mut self,
+        kind: &str,
+        name: &str,
+        state: PropertyState,
+        error: Option<&QHYError>,
+        content: &str,
+    ) -> Result<()> {
+        let device = self.driver.device.clone();
+        let message = error.map(ToString::to_string);
+        let mut attributes = vec![
+            ("device", device.as_str()),
+            ("name", name),
+            ("state", state.as_str()),
+        ];
+        if let Some(message) = &message {
+            attributes.push(("message", message));
+        }
+        self.send(&xml(
+            &format!("set{}Vector", kind),
+            &attributes,
+            Some(content),
+        ))
+    }
+
+    /// sends one message, each on a line of its own
+    fn send(&mut self, xml: &str) -> Result<()> {
+        self.writer
+            .write_all(xml.as_bytes())
+            .and_then(|_| self.writer.write_all(b"\n"))
+            .and_then(|_| self.writer.flush())
+            .map_err(io_error)
+    }
+}
+
+/// returns the text of the member `name` of a new vector sent by the client
+fn member<'a>(message: &'a Element, name: &str) -> Option<&'a str> {
+    message
+        .children
+        .iter()
+        .find(|child| child.attribute("name") == Some(name))
+        .map(|child| child.text.trim())
+}
+
+fn switch_on(message: &Element, name: &str) -> bool {
+    member(message, name) == Some("On")
+}
+
+fn switch_value(on: bool) -> &'static str {
+    match on {
+        true => "On",
+        false => "Off",
+    }
+}
+
+fn number(message: &Element, name: &str) -> Result<f64> {
+    match member(message, name) {
+        Some(value) => parse_float(value),
+        None => Err(protocol_error(format!(
+            "{} has no member {}",
+            message.attribute("name").unwrap_or_default(),
+            name
+        ))),
+    }
+}
+
+/// reads the member `name` of a property holding a position, size or slot, which INDI sends as
+/// a number like any other
+fn unsigned(message: &Element, name: &str) -> Result<u32> {
+    match number(message, name)? {
+        value if (0.0..=u32::MAX as f64).contains(&value) => Ok(value as u32),
+        value => Err(protocol_error(format!("invalid {} {}", name, value))),
+    }
+}
+
+/// formats the element `name`, `content` has to be escaped already, `None` closes it right away
+fn xml(name: &str, attributes: &[(&str, &str)], content: Option<&str>) -> String {
+    let mut xml = format!("<{}", name);
+    for (key, value) in attributes {
+        xml.push_str(&format!(" {}=\"{}\"", key, escape(value)));
+    }
+    match content {
+        Some(content) => xml.push_str(&format!(">{}</{}>", content, name)),
+        None => xml.push_str("/>"),
+    }
+    xml
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// encodes `data` as base64 with padding, the encoding INDI uses for BLOBs
+pub(crate) fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let triple = chunk
+            .iter()
+            .chain([0, 0].iter())
+            .take(3)
+            .fold(0_u32, |triple, byte| triple << 8 | *byte as u32);
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => {
+                    encoded.push(ALPHABET[(triple >> (18 - 6 * index) & 0x3f) as usize] as char)
+                }
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+/// an XML element of an INDI message
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) text: String,
+    pub(crate) children: Vec<Element>,
+}
+
+impl Element {
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// why an element could not be parsed
+enum ParseError {
+    /// the input ends before the element does
+    Incomplete,
+    Malformed(String),
+}
+
+type Parsed<T> = std::result::Result<T, ParseError>;
+
+/// Parses the first element of `input` and returns it with the number of bytes it took up,
+/// `None` if it is not complete yet. Whitespace, XML declarations and comments before it are
+/// skipped. Elements nested deeper than `MAX_DEPTH` and incomplete elements longer than
+/// `MAX_MESSAGE_LEN` bytes are errors.
+pub(crate) fn parse_element(input: &[u8]) -> Result<Option<(Element, usize)>> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        depth: 0,
+    };
+    match parser.skip_misc().and_then(|_| parser.element()) {
+        Ok(element) => Ok(Some((element, parser.pos))),
+        Err(ParseError::Incomplete) if input.len() > MAX_MESSAGE_LEN => Err(protocol_error(
+            format!("message longer than {} bytes", MAX_MESSAGE_LEN),
+        )),
+        Err(ParseError::Incomplete) => Ok(None),
+        Err(ParseError::Malformed(reason)) => Err(protocol_error(reason)),
+    }
+}
+
+/// a parser for the subset of XML used by INDI, no DTDs, CDATA or namespaces
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    /// how many elements enclose the one being parsed
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Parsed<u8> {
+        self.input
+            .get(self.pos)
+            .copied()
+            .ok_or(ParseError::Incomplete)
+    }
+
+    fn starts_with(&self, prefix: &[u8]) -> Parsed<bool> {
+        let rest = &self.input[self.pos..];
+        match rest.len() < prefix.len() && prefix.starts_with(rest) {
+            true => Err(ParseError::Incomplete),
+            false => Ok(rest.starts_with(prefix)),
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Parsed<()> {
+        match self.peek()? {
+            found if found == byte => {
+                self.pos += 1;
+                Ok(())
+            }
+            found => Err(ParseError::Malformed(format!(
+                "expected '{}' but found '{}' at byte {}",
+                byte as char, found as char, self.pos
+            ))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Parsed<()> {
+        while self.peek()?.is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn skip_past(&mut self, end: &[u8]) -> Parsed<()> {
+        match self.input[self.pos..]
+            .windows(end.len())
+            .position(|window| window == end)
+        {
+            Some(offset) => {
+                self.pos += offset + end.len();
+                Ok(())
+            }
+            None => Err(ParseError::Incomplete),
+        }
+    }
+
+    /// skips whitespace, XML declarations and comments
+    fn skip_misc(&mut self) -> Parsed<()> {
+        loop {
+            self.skip_whitespace()?;
+            if self.starts_with(b"<?")? {
+                self.skip_past(b"?>")?;
+            } else if self.starts_with(b"<!--")? {
+                self.skip_past(b"-->")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Parsed<String> {
+        let start = self.pos;
+        while !matches!(self.peek()?, b'/' | b'>' | b'=' | b'<')
+            && !self.peek()?.is_ascii_whitespace()
+        {
+            self.pos += 1;
+        }
+        match start == self.pos {
+            true => Err(ParseError::Malformed(format!(
+                "missing name at byte {}",
+                start
+            ))),
+            false => Ok(String::from_utf8_lossy(&self.input[start..self.pos]).into_owned()),
+        }
+    }
+
+    fn element(&mut self) -> Parsed<Element> {
+        self.expect(b'<')?;
+        let mut element = Element {
+            name: self.name()?,
+            ..Default::default()
+        };
+        loop {
+            self.skip_whitespace()?;
+            match self.peek()? {
+                b'/' => {
+                    self.pos += 1;
+                    self.expect(b'>')?;
+                    return Ok(element);
+                }
+                b'>' => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => {
+                    let key = self.name()?;
+                    self.skip_whitespace()?;
+                    self.expect(b'=')?;
+                    self.skip_whitespace()?;
+                    let quote = self.peek()?;
+                    if quote != b'"' && quote != b'\'' {
+                        return Err(ParseError::Malformed(format!(
+                            "unquoted value of {} at byte {}",
+                            key, self.pos
+                        )));
+                    }
+                    self.pos += 1;
+                    let start = self.pos;
+                    self.skip_past(&[quote])?;
+                    let value = unescape(&self.input[start..self.pos - 1]);
+                    element.attributes.push((key, value));
+                }
+            }
+        }
+        loop {
+            if self.starts_with(b"</")? {
+                self.pos += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(ParseError::Malformed(format!(
+                        "<{}> closed by </{}>",
+                        element.name, name
+                    )));
+                }
+                self.skip_whitespace()?;
+                self.expect(b'>')?;
+                return Ok(element);
+            } else if self.starts_with(b"<!--")? {
+                self.skip_past(b"-->")?;
+            } else if self.peek()? == b'<' {
+                if self.depth == MAX_DEPTH {
+                    return Err(ParseError::Malformed(format!(
+                        "elements nested deeper than {} at byte {}",
+                        MAX_DEPTH, self.pos
+                    )));
+                }
+                self.depth += 1;
+                let child = self.element();
+                self.depth -= 1;
+                element.children.push(child?);
+            } else {
+                let start = self.pos;
+                while self.peek()? != b'<' {
+                    self.pos += 1;
+                }
+                element
+                    .text
+                    .push_str(&unescape(&self.input[start..self.pos]));
+            }
+        }
+    }
+}
+
+/// replaces the predefined and numeric character references in `raw`
+fn unescape(raw: &[u8]) -> String {
+    let raw = String::from_utf8_lossy(raw);
+    let mut text = String::with_capacity(raw.len());
+    let mut rest = raw.as_ref();
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let reference = &rest[1..end];
+        let decoded = match reference {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => reference
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| reference.strip_prefix('#').map(str::parse))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(decoded) => {
+                text.push(decoded);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                text.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
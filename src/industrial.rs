@@ -0,0 +1,175 @@
+//! Signal alarm and FPGA watchdog of customized cameras
+//!
+//! Cameras built for industrial deployments can have a speaker and LED alarm,
+//! `Control::CamSpeakerLedAlarm`, and a watchdog in the FPGA that resets the camera when the host
+//! stops talking to it, `Control::CamWatchDogFpga`. `SignalAlarm` and `FpgaWatchdog` give typed
+//! access to both, `Camera` implements them through the controls and fails with
+//! `IsControlAvailableError` on cameras without them. `SimulatedSignalAlarm` and
+//! `SimulatedFpgaWatchdog` keep the state in memory, so integration code can be tested without
+//! such a camera.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::QHYError::InvalidWatchdogTimeoutError;
+use crate::{Camera, Control, Result};
+
+/// A speaker and LED alarm
+pub trait SignalAlarm {
+    /// Turns the alarm on or off
+    fn set_alarm(&self, on: bool) -> Result<()>;
+    /// Returns `true` while the alarm is on
+    fn is_alarm_on(&self) -> Result<bool>;
+}
+
+/// A watchdog that resets the camera if the host is silent for longer than its timeout
+pub trait FpgaWatchdog {
+    /// Enables the watchdog with `timeout`, which is rounded down to whole seconds and has to be
+    /// at least a second
+    fn enable_watchdog(&self, timeout: Duration) -> Result<()>;
+    /// Disables the watchdog
+    fn disable_watchdog(&self) -> Result<()>;
+    /// Returns the timeout of the enabled watchdog, `None` while it is disabled
+    fn watchdog_timeout(&self) -> Result<Option<Duration>>;
+}
+
+/// the value of `Control::CamWatchDogFpga` for `timeout`, 0 disables the watchdog
+fn watchdog_seconds(timeout: Duration) -> Result<u64> {
+    match timeout.as_secs() {
+        0 => {
+            let error = InvalidWatchdogTimeoutError { timeout };
+            tracing::error!(error = ?error);
+            Err(error)
+        }
+        seconds => Ok(seconds),
+    }
+}
+
+impl SignalAlarm for Camera {
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk, SignalAlarm};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_alarm(true).expect("set_alarm failed");
+    /// ```
+    fn set_alarm(&self, on: bool) -> Result<()> {
+        self.set_if_available(Control::CamSpeakerLedAlarm, on as u8 as f64)
+    }
+
+    fn is_alarm_on(&self) -> Result<bool> {
+        Ok(self.get_if_available(Control::CamSpeakerLedAlarm)? != 0.0)
+    }
+}
+
+impl FpgaWatchdog for Camera {
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{FpgaWatchdog, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.enable_watchdog(Duration::from_secs(30)).expect("enable_watchdog failed");
+    /// ```
+    fn enable_watchdog(&self, timeout: Duration) -> Result<()> {
+        let seconds = watchdog_seconds(timeout)?;
+        self.set_if_available(Control::CamWatchDogFpga, seconds as f64)
+    }
+
+    fn disable_watchdog(&self) -> Result<()> {
+        self.set_if_available(Control::CamWatchDogFpga, 0.0)
+    }
+
+    fn watchdog_timeout(&self) -> Result<Option<Duration>> {
+        match self.get_if_available(Control::CamWatchDogFpga)? as u64 {
+            0 => Ok(None),
+            seconds => Ok(Some(Duration::from_secs(seconds))),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// A `SignalAlarm` that only remembers whether it is on
+pub struct SimulatedSignalAlarm {
+    on: Mutex<bool>,
+}
+
+impl SimulatedSignalAlarm {
+    /// Creates an alarm that is off
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{SignalAlarm, SimulatedSignalAlarm};
+    /// let alarm = SimulatedSignalAlarm::new();
+    /// alarm.set_alarm(true).expect("set_alarm failed");
+    /// assert!(alarm.is_alarm_on().unwrap());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SignalAlarm for SimulatedSignalAlarm {
+    fn set_alarm(&self, on: bool) -> Result<()> {
+        *self
+            .on
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = on;
+        Ok(())
+    }
+
+    fn is_alarm_on(&self) -> Result<bool> {
+        Ok(*self
+            .on
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+}
+
+#[derive(Debug, Default)]
+/// A `FpgaWatchdog` that only remembers its timeout, it never resets anything
+pub struct SimulatedFpgaWatchdog {
+    timeout: Mutex<Option<Duration>>,
+}
+
+impl SimulatedFpgaWatchdog {
+    /// Creates a disabled watchdog
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{FpgaWatchdog, SimulatedFpgaWatchdog};
+    /// let watchdog = SimulatedFpgaWatchdog::new();
+    /// watchdog.enable_watchdog(Duration::from_millis(2500)).expect("enable_watchdog failed");
+    /// assert_eq!(watchdog.watchdog_timeout().unwrap(), Some(Duration::from_secs(2)));
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FpgaWatchdog for SimulatedFpgaWatchdog {
+    fn enable_watchdog(&self, timeout: Duration) -> Result<()> {
+        let seconds = watchdog_seconds(timeout)?;
+        *self
+            .timeout
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Duration::from_secs(seconds));
+        Ok(())
+    }
+
+    fn disable_watchdog(&self) -> Result<()> {
+        *self
+            .timeout
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        Ok(())
+    }
+
+    fn watchdog_timeout(&self) -> Result<Option<Duration>> {
+        Ok(*self
+            .timeout
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+}
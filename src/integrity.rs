@@ -0,0 +1,103 @@
+//! Software side validation of downloaded frames
+//!
+//! Besides the on-camera frame detection (`Control::FrameDetect`), `FrameValidator` checks every
+//! frame for a plausible geometry, enough data for that geometry and, for streams of frames,
+//! whether the camera delivered the exact same frame again, which happens when the USB
+//! transfer stalls.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::{Camera, Control, ImageData, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The result of validating a frame with `FrameValidator::validate`
+pub enum FrameIntegrity {
+    /// the frame passed all checks
+    Valid,
+    /// the frame has no pixels or an unsupported bit depth
+    InvalidGeometry,
+    /// the frame contains less data than its geometry requires
+    Truncated {
+        /// the number of bytes needed for the geometry
+        expected: usize,
+        /// the number of bytes received
+        actual: usize,
+    },
+    /// the frame is identical to the previous one
+    Frozen,
+}
+
+impl FrameIntegrity {
+    /// Returns `true` for `FrameIntegrity::Valid`
+    pub fn is_valid(&self) -> bool {
+        *self == FrameIntegrity::Valid
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+/// Validates frames, keeps the hash of the last valid frame to detect frozen frames
+pub struct FrameValidator {
+    last_hash: Option<u64>,
+}
+
+impl FrameValidator {
+    /// Creates a validator that has not seen any frame yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates `image` and remembers it for the frozen frame check of the next call
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{FrameValidator, Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// let size = camera.get_image_size().expect("get_image_size failed");
+    /// let mut validator = FrameValidator::new();
+    /// while let Ok(image) = camera.get_live_frame(size) {
+    ///     let integrity = validator.validate(&image);
+    ///     if !integrity.is_valid() {
+    ///         println!("dropping frame: {:?}", integrity);
+    ///     }
+    /// }
+    /// ```
+    pub fn validate(&mut self, image: &ImageData) -> FrameIntegrity {
+        let expected = match image.expected_len() {
+            Ok(0) | Err(_) => return FrameIntegrity::InvalidGeometry,
+            Ok(expected) => expected,
+        };
+        if image.data.len() < expected {
+            return FrameIntegrity::Truncated {
+                expected,
+                actual: image.data.len(),
+            };
+        }
+        let mut hasher = DefaultHasher::new();
+        image.data[..expected].hash(&mut hasher);
+        let hash = hasher.finish();
+        match self.last_hash.replace(hash) {
+            Some(last_hash) if last_hash == hash => FrameIntegrity::Frozen,
+            _ => FrameIntegrity::Valid,
+        }
+    }
+}
+
+impl Camera {
+    /// Enables or disables the on-camera frame detection, `Control::FrameDetect`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_frame_detect(true).expect("set_frame_detect failed");
+    /// ```
+    pub fn set_frame_detect(&self, enabled: bool) -> Result<()> {
+        self.set_if_available(Control::FrameDetect, enabled as u8 as f64)
+    }
+}
@@ -0,0 +1,72 @@
+//! Keep-alive for cameras that reset their USB interface when idle
+//!
+//! Some models reset their USB interface if no command arrives for a long time, which shows up
+//! as a camera that suddenly stops responding after a long pause between sequences.
+//! `Camera::start_keep_alive` issues a benign `Control::CurTemp` read at a fixed interval on a
+//! background thread for as long as the returned `KeepAlive` lives.
+
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{Camera, CameraState, Control};
+
+#[derive(Debug)]
+/// Guard returned from `Camera::start_keep_alive`, the keep-alive stops when it is dropped
+pub struct KeepAlive {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl KeepAlive {
+    /// Stops the keep-alive and waits for the background thread to finish, same as dropping it
+    pub fn stop(self) {}
+}
+
+impl Drop for KeepAlive {
+    fn drop(&mut self) {
+        // dropping the sender wakes the thread up with a disconnect
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                tracing::error!("keep-alive thread panicked");
+            }
+        }
+    }
+}
+
+impl Camera {
+    /// Starts reading `Control::CurTemp` every `interval` on a background thread, as long as the
+    /// returned `KeepAlive` lives. Reads are skipped unless the camera is open and idle, an
+    /// exposure or a live stream keeps it busy anyway. Failed reads are only logged.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let keep_alive = camera.start_keep_alive(Duration::from_secs(60));
+    /// /* wait for the next sequence */
+    /// keep_alive.stop();
+    /// ```
+    pub fn start_keep_alive(&self, interval: Duration) -> KeepAlive {
+        let camera = self.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                if camera.state() != CameraState::Open {
+                    continue;
+                }
+                match camera.get_parameter(Control::CurTemp) {
+                    Ok(temperature) => tracing::trace!(keep_alive = ?temperature),
+                    Err(error) => tracing::warn!(keep_alive = ?error),
+                }
+            }
+        });
+        KeepAlive {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
@@ -1,7 +1,11 @@
 //! # QHYCCD SDK bindings for Rust
 //!
 //! This crate provides a safe interface to the QHYCCD SDK for controlling QHYCCD cameras, filter wheels and focusers.
-//! The libqhyccd-sys crate provides the raw FFI bindings. It uses tracing for logging and eyre for error handling.
+//! The libqhyccd-sys crate provides the raw FFI bindings. It uses tracing for logging.
+//! All fallible functions return `Result<T, QHYError>`, so callers can match on specific failures.
+//! With the `eyre` feature an `eyre::Report` converts into `QHYError::ExternalError`.
+//! With the `dlopen` feature libqhyccd is loaded at runtime, so an application can start on a
+//! machine without the SDK and gets `QHYError::LoadLibraryError` from `Sdk::new` or `Camera::open`.
 //!
 //! # Example
 //! ```no_run
@@ -14,11 +18,16 @@
 
 use std::ffi::{c_char, CStr};
 use std::fmt::Debug;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
-use eyre::{eyre, Result, WrapErr};
 use tracing::error;
 
+use crate::call_lock::{CallGuard, CallLock};
+use crate::metadata::FrameGeometry;
+use crate::rate_limit::RateLimiter;
+use crate::telemetry::CaptureMode;
 use crate::QHYError::*;
 #[macro_use]
 extern crate educe;
@@ -26,32 +35,147 @@ extern crate educe;
 #[cfg(test)]
 pub mod mocks;
 
+mod accessory;
+pub mod analysis;
+mod arithmetic;
+#[cfg(feature = "ndarray")]
+mod array;
+#[cfg(feature = "async")]
+mod async_api;
+mod autofocus;
+mod bit_depth;
+mod call_lock;
+mod camera_id;
+mod capabilities;
+mod capture;
+mod chamber;
+mod cooling;
+mod demosaic;
+#[cfg(feature = "image")]
+mod dynamic_image;
+mod error_code;
+mod exposure;
+mod filter_slots;
+mod fits;
+mod flash_config;
+mod fpn;
+mod frame_pool;
+mod gps;
+mod homing;
+mod hot_pixels;
+mod hotplug;
+#[cfg(feature = "indi")]
+mod indi;
+mod industrial;
+mod integrity;
+mod keep_alive;
+mod metadata;
+mod numeric;
+mod parameters;
+mod pixel;
+mod preflight;
+#[cfg(feature = "provenance")]
+mod provenance;
+pub mod quick;
+mod rate_limit;
+mod roi;
+mod sdk_features;
+mod self_test;
+mod statistics;
+mod stream_modes;
+mod stretch;
+mod telemetry;
+pub use crate::accessory::{FocusMotor, Rotator, SimulatedFocusMotor, SimulatedRotator};
+pub use crate::arithmetic::OverflowPolicy;
+#[cfg(feature = "async")]
+pub use crate::async_api::{AsyncCamera, AsyncFilterWheel};
+pub use crate::autofocus::{
+    autofocus, half_flux_diameter, run_autofocus, AutofocusResult, AutofocusSettings,
+};
+pub use crate::bit_depth::{DataAlignment, SampleDepth};
+pub use crate::camera_id::CameraId;
+pub use crate::capabilities::CameraCapabilities;
+pub use crate::capture::CaptureSettings;
+pub use crate::chamber::{Chamber, ChamberStatus, Pump};
+pub use crate::cooling::{
+    BackgroundRamp, Cooler, CoolerStatus, HeadroomEstimate, RampEvent, RampSettings,
+    TemperatureRamp, ThermalModel,
+};
+pub use crate::demosaic::DemosaicAlgorithm;
+pub use crate::error_code::{ErrorKind, SdkErrorCode};
+pub use crate::exposure::{ExposureHandle, ExposureProgress};
+pub use crate::filter_slots::FilterSlot;
+pub use crate::flash_config::{FlashConfig, FLASH_CONFIG_LEN};
+pub use crate::fpn::FpnCalibrationStatus;
+pub use crate::frame_pool::{FramePool, PooledFrame};
+pub use crate::gps::GpsInfo;
+pub use crate::homing::SavedPosition;
+pub use crate::hot_pixels::HotPixelMap;
+pub use crate::hotplug::HotplugEvent;
+#[cfg(feature = "indi")]
+pub use crate::indi::{IndiDriver, INDI_PORT};
+pub use crate::industrial::{
+    FpgaWatchdog, SignalAlarm, SimulatedFpgaWatchdog, SimulatedSignalAlarm,
+};
+pub use crate::integrity::{FrameIntegrity, FrameValidator};
+pub use crate::keep_alive::KeepAlive;
+pub use crate::metadata::FrameMetadata;
+pub use crate::numeric::{format_float, parse_float};
+pub use crate::pixel::{PixelBuffer, PixelFormat};
+pub use crate::preflight::{
+    preflight, PreflightCheck, PreflightOutcome, PreflightPlan, PreflightReport,
+};
+#[cfg(feature = "provenance")]
+pub use crate::provenance::{FrameProvenance, ProvenanceChain};
+pub use crate::roi::RoiConstraint;
+pub use crate::sdk_features::{SdkFeature, SdkFeatures};
+pub use crate::self_test::{SelfTestOptions, SelfTestOutcome, SelfTestReport, SelfTestStep};
+pub use crate::statistics::FrameStatistics;
+pub use crate::stream_modes::{LiveCamera, SingleFrameCamera};
+pub use crate::stretch::{ScreenStretch, StretchMode};
+
 #[cfg(not(test))]
 use libqhyccd_sys::{
     BeginQHYCCDLive, CancelQHYCCDExposing, CancelQHYCCDExposingAndReadout, CloseQHYCCD,
-    ExpQHYCCDSingleFrame, GetQHYCCDChipInfo, GetQHYCCDEffectiveArea, GetQHYCCDExposureRemaining,
-    GetQHYCCDFWVersion, GetQHYCCDId, GetQHYCCDLiveFrame, GetQHYCCDMemLength, GetQHYCCDModel,
-    GetQHYCCDNumberOfReadModes, GetQHYCCDOverScanArea, GetQHYCCDParam, GetQHYCCDParamMinMaxStep,
-    GetQHYCCDReadMode, GetQHYCCDReadModeName, GetQHYCCDReadModeResolution, GetQHYCCDSDKVersion,
-    GetQHYCCDSingleFrame, GetQHYCCDType, InitQHYCCD, InitQHYCCDResource, IsQHYCCDCFWPlugged,
-    IsQHYCCDControlAvailable, OpenQHYCCD, ReleaseQHYCCDResource, ScanQHYCCD, SetQHYCCDBinMode,
-    SetQHYCCDBitsMode, SetQHYCCDDebayerOnOff, SetQHYCCDParam, SetQHYCCDReadMode,
-    SetQHYCCDResolution, SetQHYCCDStreamMode, StopQHYCCDLive, QHYCCD_ERROR, QHYCCD_ERROR_F64,
-    QHYCCD_SUCCESS,
+    EnableQHYCCDLogFile, EnableQHYCCDMessage, EnableQHYCCDTrigerOut, ExpQHYCCDSingleFrame,
+    GetQHYCCDCFWStatus, GetQHYCCDChipInfo, GetQHYCCDEffectiveArea, GetQHYCCDExposureRemaining,
+    GetQHYCCDFPGAVersion, GetQHYCCDFWVersion, GetQHYCCDHumidity, GetQHYCCDId, GetQHYCCDLiveFrame,
+    GetQHYCCDMemLength, GetQHYCCDModel, GetQHYCCDNumberOfReadModes, GetQHYCCDOverScanArea,
+    GetQHYCCDParam, GetQHYCCDParamMinMaxStep, GetQHYCCDPreciseExposureInfo, GetQHYCCDPressure,
+    GetQHYCCDReadMode, GetQHYCCDReadModeName, GetQHYCCDReadModeResolution,
+    GetQHYCCDRollingShutterEndOffset, GetQHYCCDSDKVersion, GetQHYCCDSensorName,
+    GetQHYCCDSingleFrame, GetQHYCCDTrigerInterfaceName, GetQHYCCDTrigerInterfaceNumber,
+    GetQHYCCDType, InitQHYCCD, InitQHYCCDResource, IsQHYCCDCFWPlugged, IsQHYCCDControlAvailable,
+    OpenQHYCCD, QHYCCDEraseInitConfigFlash, QHYCCDReadInitConfigFlash, QHYCCDResetFlashULVOError,
+    QHYCCDSensorPhaseReTrain, QHYCCDSetFlashInitPWM, RegisterPnpEventIn, RegisterPnpEventOut,
+    ReleaseQHYCCDResource, ScanQHYCCD, SetQHYCCDBinMode, SetQHYCCDBitsMode, SetQHYCCDDebayerOnOff,
+    SetQHYCCDGPSLedCal, SetQHYCCDGPSLedCalMode, SetQHYCCDGPSPOSA, SetQHYCCDGPSPOSB,
+    SetQHYCCDGPSVCOXFreq, SetQHYCCDLogLevel, SetQHYCCDParam, SetQHYCCDReadMode,
+    SetQHYCCDResolution, SetQHYCCDStreamMode, SetQHYCCDTrigerFunction, SetQHYCCDTrigerInterface,
+    SetQHYCCDTrigerMode, StopQHYCCDLive, QHYCCD_DELAY_200MS, QHYCCD_ERROR, QHYCCD_ERROR_F64,
+    QHYCCD_READ_DIRECTLY, QHYCCD_SUCCESS,
 };
 
 #[cfg(test)]
 use crate::mocks::mock_libqhyccd_sys::{
     BeginQHYCCDLive, CancelQHYCCDExposing, CancelQHYCCDExposingAndReadout, CloseQHYCCD,
-    ExpQHYCCDSingleFrame, GetQHYCCDChipInfo, GetQHYCCDEffectiveArea, GetQHYCCDExposureRemaining,
-    GetQHYCCDFWVersion, GetQHYCCDId, GetQHYCCDLiveFrame, GetQHYCCDMemLength, GetQHYCCDModel,
-    GetQHYCCDNumberOfReadModes, GetQHYCCDOverScanArea, GetQHYCCDParam, GetQHYCCDParamMinMaxStep,
-    GetQHYCCDReadMode, GetQHYCCDReadModeName, GetQHYCCDReadModeResolution, GetQHYCCDSDKVersion,
-    GetQHYCCDSingleFrame, GetQHYCCDType, InitQHYCCD, InitQHYCCDResource, IsQHYCCDCFWPlugged,
-    IsQHYCCDControlAvailable, OpenQHYCCD, ReleaseQHYCCDResource, ScanQHYCCD, SetQHYCCDBinMode,
-    SetQHYCCDBitsMode, SetQHYCCDDebayerOnOff, SetQHYCCDParam, SetQHYCCDReadMode,
-    SetQHYCCDResolution, SetQHYCCDStreamMode, StopQHYCCDLive, QHYCCD_ERROR, QHYCCD_ERROR_F64,
-    QHYCCD_SUCCESS,
+    EnableQHYCCDLogFile, EnableQHYCCDMessage, EnableQHYCCDTrigerOut, ExpQHYCCDSingleFrame,
+    GetQHYCCDCFWStatus, GetQHYCCDChipInfo, GetQHYCCDEffectiveArea, GetQHYCCDExposureRemaining,
+    GetQHYCCDFPGAVersion, GetQHYCCDFWVersion, GetQHYCCDHumidity, GetQHYCCDId, GetQHYCCDLiveFrame,
+    GetQHYCCDMemLength, GetQHYCCDModel, GetQHYCCDNumberOfReadModes, GetQHYCCDOverScanArea,
+    GetQHYCCDParam, GetQHYCCDParamMinMaxStep, GetQHYCCDPreciseExposureInfo, GetQHYCCDPressure,
+    GetQHYCCDReadMode, GetQHYCCDReadModeName, GetQHYCCDReadModeResolution,
+    GetQHYCCDRollingShutterEndOffset, GetQHYCCDSDKVersion, GetQHYCCDSensorName,
+    GetQHYCCDSingleFrame, GetQHYCCDTrigerInterfaceName, GetQHYCCDTrigerInterfaceNumber,
+    GetQHYCCDType, InitQHYCCD, InitQHYCCDResource, IsQHYCCDCFWPlugged, IsQHYCCDControlAvailable,
+    OpenQHYCCD, QHYCCDEraseInitConfigFlash, QHYCCDReadInitConfigFlash, QHYCCDResetFlashULVOError,
+    QHYCCDSensorPhaseReTrain, QHYCCDSetFlashInitPWM, RegisterPnpEventIn, RegisterPnpEventOut,
+    ReleaseQHYCCDResource, ScanQHYCCD, SetQHYCCDBinMode, SetQHYCCDBitsMode, SetQHYCCDDebayerOnOff,
+    SetQHYCCDGPSLedCal, SetQHYCCDGPSLedCalMode, SetQHYCCDGPSPOSA, SetQHYCCDGPSPOSB,
+    SetQHYCCDGPSVCOXFreq, SetQHYCCDLogLevel, SetQHYCCDParam, SetQHYCCDReadMode,
+    SetQHYCCDResolution, SetQHYCCDStreamMode, SetQHYCCDTrigerFunction, SetQHYCCDTrigerInterface,
+    SetQHYCCDTrigerMode, StopQHYCCDLive, QHYCCD_DELAY_200MS, QHYCCD_ERROR, QHYCCD_ERROR_F64,
+    QHYCCD_READ_DIRECTLY, QHYCCD_SUCCESS,
 };
 
 use thiserror::Error;
@@ -60,64 +184,69 @@ use thiserror::Error;
 /// Errors that can occur when interacting with the QHYCCD SDK
 /// most functions from the SDK return `u32::MAX` on error
 /// where it is different, is is noted in the documentation
+///
+/// The enum is `#[non_exhaustive]`, new features bring new errors, so matches outside of this
+/// crate need a wildcard arm. `QHYError::kind` sorts the errors into kinds like
+/// `ErrorKind::Timeout` or `ErrorKind::Unsupported` for code that does not care which call failed.
 #[allow(missing_docs)]
+#[non_exhaustive]
 pub enum QHYError {
-    #[error("Error initializing QHYCCD SDK, error code {}", error_code)]
+    #[error("Error initializing QHYCCD SDK, error code {}", SdkErrorCode::from(*error_code))]
     InitSDKError { error_code: u32 },
-    #[error("Error closing QHYCCD SDK, error code {}", error_code)]
+    #[error("Error closing QHYCCD SDK, error code {}", SdkErrorCode::from(*error_code))]
     CloseSDKError { error_code: u32 },
-    #[error("Error getting QHYCCD SDK version, error code {:?}", error_code)]
+    #[error("Error getting QHYCCD SDK version, error code {}", SdkErrorCode::from(*error_code))]
     GetSDKVersionError { error_code: u32 },
     #[error("Error scanning QHYCCD cameras")]
     ScanQHYCCDError,
     #[error("Error opening camera")]
     OpenCameraError,
-    #[error("Error camera id, error code {:?}", error_code)]
+    #[error("Error camera id, error code {}", SdkErrorCode::from(*error_code))]
     GetCameraIdError { error_code: u32 },
-    #[error("Error getting firmware version, error code {:?}", error_code)]
+    #[error("Error getting firmware version, error code {}", SdkErrorCode::from(*error_code))]
     GetFirmwareVersionError { error_code: u32 },
-    #[error("Error setting camera read mode, error code {:?}", error_code)]
+    #[error("Error setting camera read mode, error code {}", SdkErrorCode::from(*error_code))]
     SetReadoutModeError { error_code: u32 },
-    #[error("Error setting camera stream mode, error code {:?}", error_code)]
+    #[error("Error setting camera stream mode, error code {}", SdkErrorCode::from(*error_code))]
     SetStreamModeError { error_code: u32 },
-    #[error("Error initializing camera {:?}", error_code)]
+    #[error("Error initializing camera, error code {}", SdkErrorCode::from(*error_code))]
     InitCameraError { error_code: u32 },
-    #[error("Error getting camera CCD info, error code {:?}", error_code)]
+    #[error("Error getting camera CCD info, error code {}", SdkErrorCode::from(*error_code))]
     GetCCDInfoError { error_code: u32 },
-    #[error("Error setting camera bit mode, error code {:?}", error_code)]
+    #[error("Error setting camera bit mode, error code {}", SdkErrorCode::from(*error_code))]
     SetBitModeError { error_code: u32 },
-    #[error("Error setting camera debayer on/off, error code {:?}", error_code)]
+    #[error("Error setting camera debayer on/off, error code {}", SdkErrorCode::from(*error_code))]
     SetDebayerError { error_code: u32 },
-    #[error("Error setting camera bin mode, error code {:?}", error_code)]
+    #[error("Error setting camera bin mode, error code {}", SdkErrorCode::from(*error_code))]
     SetBinModeError { error_code: u32 },
-    #[error("Error setting camera sub frame, error code {:?}", error_code)]
+    #[error("Error setting camera sub frame, error code {}", SdkErrorCode::from(*error_code))]
     SetRoiError { error_code: u32 },
     #[error("Error getting camera parameter, error code {:?}", control)]
     GetParameterError {
         /// here the control field has the `Control` enum variant we tried to get the value for
         control: Control,
     },
-    #[error("Error setting camera parameter, error code {:?}", error_code)]
+    #[error("Error setting camera parameter, error code {}", SdkErrorCode::from(*error_code))]
     SetParameterError { error_code: u32 },
-    #[error("Error starting camera live mode, error code {:?}", error_code)]
+    #[error("Error starting camera live mode, error code {}", SdkErrorCode::from(*error_code))]
     BeginLiveError { error_code: u32 },
-    #[error("Error stopping camera live mode, error code {:?}", error_code)]
+    #[error("Error stopping camera live mode, error code {}", SdkErrorCode::from(*error_code))]
     EndLiveError { error_code: u32 },
     #[error("Error getting image size, error code")]
     GetImageSizeError,
-    #[error("Error getting camera live frame, error code {:?}", error_code)]
+    #[error("Error getting camera live frame, error code {}", SdkErrorCode::from(*error_code))]
     GetLiveFrameError { error_code: u32 },
-    #[error("Error getting camera single frame, error code {:?}", error_code)]
+    #[error("Error getting camera single frame, error code {}", SdkErrorCode::from(*error_code))]
     GetSingleFrameError { error_code: u32 },
-    #[error("Error closing camera, error code {:?}", error_code)]
+    #[error("Error closing camera, error code {}", SdkErrorCode::from(*error_code))]
     CloseCameraError { error_code: u32 },
-    #[error("Error getting camera overscan area, error code {:?}", error_code)]
+    #[error("Error getting camera overscan area, error code {}", SdkErrorCode::from(*error_code))]
     GetOverscanAreaError { error_code: u32 },
-    #[error("Error getting camera effective area, error code {:?}", error_code)]
+    #[error("Error getting camera effective area, error code {}", SdkErrorCode::from(*error_code))]
     GetEffectiveAreaError { error_code: u32 },
     #[error("Error getting determining support for camera feature {:?}", control)]
     IsControlAvailableError { control: Control },
-    #[error("Error starting single frame exposure, error code {:?}", error_code)]
+    #[error("Error starting single frame exposure, error code {}", SdkErrorCode::from(*error_code))]
     StartSingleFrameExposureError { error_code: u32 },
     #[error("Error getting camera number of read modes")]
     GetNumberOfReadoutModesError,
@@ -127,15 +256,15 @@ pub enum QHYError {
     GetReadoutModeResolutionError,
     #[error("Error getting camera readout mode")]
     GetReadoutModeError,
-    #[error("Error getting model of camera {:?}", error_code)]
+    #[error("Error getting model of camera, error code {}", SdkErrorCode::from(*error_code))]
     GetCameraModelError { error_code: u32 },
     #[error("Error getting type of camera")]
     GetCameraTypeError,
     #[error("Error getting remaining exposure time")]
     GetExposureRemainingError,
-    #[error("Error stopping exposure {:?}", error_code)]
+    #[error("Error stopping exposure, error code {}", SdkErrorCode::from(*error_code))]
     StopExposureError { error_code: u32 },
-    #[error("Error canceling exposure and readout {:?}", error_code)]
+    #[error("Error canceling exposure and readout, error code {}", SdkErrorCode::from(*error_code))]
     AbortExposureAndReadoutError { error_code: u32 },
     #[error("Error getting camera CFW plugged status")]
     IsCfwPluggedInError,
@@ -155,16 +284,247 @@ pub enum QHYError {
     SetCfwPositionError,
     #[error("Error opening the filter wheel")]
     OpenFilterWheelError,
-    #[error("Error closing the filter wheel error code {:?}", error_code)]
+    #[error("Error closing the filter wheel, error code {}", SdkErrorCode::from(*error_code))]
     CloseFilterWheelError { error_code: u32 },
     #[error("Error getting the number of filters")]
     GetNumberOfFiltersError,
+    #[error("Error images differ in width, height or number of channels")]
+    ImageGeometryMismatchError,
+    #[error("Error unsupported bit depth {:?}", bits_per_pixel)]
+    UnsupportedBitDepthError { bits_per_pixel: u32 },
+    #[error("Error setting camera trigger function, error code {}", SdkErrorCode::from(*error_code))]
+    SetTriggerFunctionError { error_code: u32 },
+    #[error("Error no triggered frame arrived within {:?}", timeout)]
+    TriggerTimeoutError { timeout: Duration },
+    #[error("Error invalid temperature ramp rate {:?}°C/min", rate)]
+    InvalidRampRateError { rate: f64 },
+    #[error(
+        "Error invalid temperature ramp settings, step interval {:?} and {:?} stable steps have to be above zero",
+        step_interval,
+        stable_steps
+    )]
+    InvalidRampSettingsError {
+        step_interval: Duration,
+        stable_steps: u32,
+    },
+    #[error("Error invalid screen stretch, black {:?} white {:?}", black, white)]
+    InvalidScreenStretchError { black: f64, white: f64 },
+    #[error(
+        "Error invalid number of significant bits {:?}, it has to be 1 to 16",
+        bits
+    )]
+    InvalidActualBitsError { bits: u32 },
+    #[error("Error unknown output data alignment {:?}", value)]
+    UnknownDataAlignmentError { value: u32 },
+    #[error("Error unknown control id {:?}", id)]
+    UnknownControlError { id: u32 },
+    #[error("Error camera {:?} not found", id)]
+    CameraNotFoundError { id: Option<String> },
+    #[error(
+        "Error value {:?} for {:?} is outside of {:?}..={:?}",
+        value,
+        control,
+        min,
+        max
+    )]
+    ParameterOutOfRangeError {
+        control: Control,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    #[error("Error setting GPS parameter, error code {}", SdkErrorCode::from(*error_code))]
+    SetGpsError { error_code: u32 },
+    #[error("Error frame of {:?} bytes is too short for a GPS header", len)]
+    GpsHeaderError { len: usize },
+    #[error("Error getting humidity, error code {}", SdkErrorCode::from(*error_code))]
+    GetHumidityError { error_code: u32 },
+    #[error("Error getting pressure, error code {}", SdkErrorCode::from(*error_code))]
+    GetPressureError { error_code: u32 },
+    #[error("Error setting camera trigger mode, error code {}", SdkErrorCode::from(*error_code))]
+    SetTriggerModeError { error_code: u32 },
+    #[error("Error enabling camera trigger out, error code {}", SdkErrorCode::from(*error_code))]
+    EnableTriggerOutError { error_code: u32 },
+    #[error("Error setting camera trigger interface, error code {}", SdkErrorCode::from(*error_code))]
+    SetTriggerInterfaceError { error_code: u32 },
+    #[error("Error getting camera trigger interfaces, error code {}", SdkErrorCode::from(*error_code))]
+    GetTriggerInterfacesError { error_code: u32 },
+    #[error("Error parsing {:?} as floating point value", value)]
+    ParseFloatError { value: String },
+    #[error("Error filter wheel did not reach slot 0 within {:?}", timeout)]
+    FilterWheelHomeError { timeout: Duration },
+    #[error("Error focuser position {:?} is outside of 0..={:?}", position, max)]
+    InvalidFocuserPositionError { position: i32, max: i32 },
+    #[error("Error invalid rotator angle {:?}", angle)]
+    InvalidRotatorAngleError { angle: f64 },
+    #[error(
+        "Error invalid watchdog timeout {:?}, it has to be at least a second",
+        timeout
+    )]
+    InvalidWatchdogTimeoutError { timeout: Duration },
+    #[error("Error getting filter wheel status, error code {}", SdkErrorCode::from(*error_code))]
+    GetCfwStatusError { error_code: u32 },
+    #[error("Error no star signal above the background")]
+    NoStarSignalError,
+    #[error("Error fitting the V-curve, the samples have no minimum")]
+    AutofocusFitError,
+    #[error("Error focuser still moving after {:?}", timeout)]
+    FocuserTimeoutError { timeout: Duration },
+    #[error(
+        "Error filter wheel did not settle at position {:?} within {:?}",
+        position,
+        timeout
+    )]
+    FilterWheelMoveTimeoutError { position: u32, timeout: Duration },
+    #[error("Error invalid filter slot configuration in line {:?}", line)]
+    InvalidSlotConfigError { line: usize },
+    #[error("Error the async executor thread is gone")]
+    AsyncExecutorError,
+    #[error("Error the exposure thread panicked")]
+    ExposureThreadError,
+    #[error(
+        "Error expected {:?} samples, but the frame has {:?} samples",
+        expected,
+        actual
+    )]
+    PixelFormatMismatchError {
+        expected: PixelFormat,
+        actual: PixelFormat,
+    },
+    #[error(
+        "Error expected {:?} channels, but the frame has {:?} channels",
+        expected,
+        actual
+    )]
+    ChannelCountMismatchError { expected: u32, actual: u32 },
+    #[error("Error unsupported channel count {:?}", channels)]
+    UnsupportedChannelCountError { channels: u32 },
+    #[error(
+        "Error buffer of {:?} bytes is too small, {:?} bytes are required",
+        actual,
+        required
+    )]
+    BufferTooSmallError { required: usize, actual: usize },
+    #[error("Error no frame arrived within {:?} after the exposure", timeout)]
+    CaptureTimeoutError { timeout: Duration },
+    #[error(
+        "Error readout mode {:?} does not exist, the camera has {:?}",
+        mode,
+        count
+    )]
+    ReadoutModeOutOfRangeError { mode: u32, count: u32 },
+    #[error(
+        "Error readout mode {:?} differs from the current mode {:?}, it can only be changed before init",
+        mode,
+        current
+    )]
+    ReadoutModeChangeError { mode: u32, current: u32 },
+    #[error(
+        "Error filter slot {:?} does not exist, the filter wheel has {:?}",
+        index,
+        count
+    )]
+    FilterSlotOutOfRangeError { index: u32, count: u32 },
+    #[error("Error unsupported bin mode {:?}x{:?}", bin_x, bin_y)]
+    UnsupportedBinModeError { bin_x: u32, bin_y: u32 },
+    #[error("Error ROI {:?} violates {:?}", roi, constraint)]
+    RoiError {
+        roi: CCDChipArea,
+        constraint: RoiConstraint,
+    },
+    #[error("Error the cooler ramp thread panicked")]
+    CoolerThreadError,
+    #[error("Error getting sensor name, error code {}", SdkErrorCode::from(*error_code))]
+    GetSensorNameError { error_code: u32 },
+    #[error("Error getting FPGA version, error code {}", SdkErrorCode::from(*error_code))]
+    GetFpgaVersionError { error_code: u32 },
+    #[error("Error getting precise exposure info, error code {}", SdkErrorCode::from(*error_code))]
+    GetPreciseExposureInfoError { error_code: u32 },
+    #[error("Error getting rolling shutter end offset, error code {}", SdkErrorCode::from(*error_code))]
+    GetRollingShutterEndOffsetError { error_code: u32 },
+    #[error("Error camera handle is no longer valid, the SDK was released since it was opened")]
+    CameraClosedError,
+    #[error("Error camera is {:?}, but has to be {:?}", state, expected)]
+    CameraStateError {
+        state: CameraState,
+        expected: CameraState,
+    },
+    #[error("Error could not acquire the lock on the camera handle")]
+    HandleLockError,
+    #[error("Error accessing file {:?}: {}", path, source)]
+    FileError {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Error the SDK returned a string that is not valid UTF-8: {}", source)]
+    InvalidUtf8Error {
+        #[from]
+        source: std::str::Utf8Error,
+    },
+    #[error("Error camera id contains a NUL byte: {}", source)]
+    CameraIdNulError {
+        #[from]
+        source: std::ffi::NulError,
+    },
+    #[error("Error self test check failed: {}", reason)]
+    SelfTestCheckError { reason: String },
+    #[error("Error talking to the INDI client: {}", source)]
+    IndiIoError { source: std::io::Error },
+    #[error("Error malformed INDI message: {}", reason)]
+    IndiProtocolError { reason: String },
+    #[error("Error loading libqhyccd: {}", reason)]
+    LoadLibraryError { reason: String },
+    #[error("Error the installed QHYCCD SDK does not support {:?}", feature)]
+    UnsupportedSdkFeatureError { feature: SdkFeature },
+    #[error(
+        "Error the flash configuration of the camera has no {} section",
+        section
+    )]
+    FlashConfigSectionError { section: &'static str },
+    #[error("Error FPN calibration not finished after {:?}", timeout)]
+    FpnCalibrationTimeoutError { timeout: Duration },
+    #[error(
+        "Error write of {:?} was dropped, a newer write took over its rate limited slot",
+        control
+    )]
+    SupersededWriteError { control: Control },
+    /// errors of other crates, e.g. `ndarray` or returned by a `FocusMotor` implementation
+    #[error(transparent)]
+    ExternalError(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[cfg(feature = "eyre")]
+impl From<eyre::Report> for QHYError {
+    fn from(report: eyre::Report) -> Self {
+        ExternalError(report.into())
+    }
+}
+
+/// logs an I/O error on `path` and returns it as `FileError`
+pub(crate) fn file_error(path: &std::path::Path, source: std::io::Error) -> QHYError {
+    let error = FileError {
+        path: path.to_owned(),
+        source,
+    };
+    tracing::error!(error = ?error);
+    error
 }
 
+/// The result of all fallible functions of this crate, the error can be matched on to handle
+/// specific failures
+pub type Result<T, E = QHYError> = std::result::Result<T, E>;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 /// Controls used in `is_control_available` and `set_parameter` nad `get_parameter`
 /// documentation is taken from the QHYCCD SDK
 /// here <https://www.qhyccd.cn/file/repository/publish/SDK/code/QHYCCD%20SDK_API_EN_V2.3.pdf>
+///
+/// The enum is `#[non_exhaustive]`, new SDK releases add controls and they will be added here
+/// in minor releases, so matches outside of this crate need a wildcard arm. Raw control ids
+/// that are not known yet can still be checked with `Control::try_from`, which returns
+/// `UnknownControlError` for them.
 pub enum Control {
     /// Check if support brightness
     Brightness = 0,
@@ -398,7 +758,153 @@ pub enum Control {
     GaindB = 1029,
 }
 
-#[derive(Debug, PartialEq)]
+/// every `Control` in the order of the SDK control ids
+const ALL_CONTROLS: [Control; 92] = [
+    Control::Brightness,
+    Control::Contrast,
+    Control::Wbr,
+    Control::Wbb,
+    Control::Wbg,
+    Control::Gamma,
+    Control::Gain,
+    Control::Offset,
+    Control::Exposure,
+    Control::Speed,
+    Control::TransferBit,
+    Control::Channels,
+    Control::UsbTraffic,
+    Control::RowDeNoise,
+    Control::CurTemp,
+    Control::CurPWM,
+    Control::ManualPWM,
+    Control::CfwPort,
+    Control::Cooler,
+    Control::St4Port,
+    Control::CamColor,
+    Control::CamBin1x1mode,
+    Control::CamBin2x2mode,
+    Control::CamBin3x3mode,
+    Control::CamBin4x4mode,
+    Control::CamMechanicalShutter,
+    Control::CamTrigerInterface,
+    Control::CamTecoverprotectInterface,
+    Control::CamSignalClampInterface,
+    Control::CamFinetoneInterface,
+    Control::CamShutterMotorHeatingInterface,
+    Control::CamCalibrateFpnInterface,
+    Control::CamChipTemperatureSensorInterface,
+    Control::CamUsbReadoutSlowestInterface,
+    Control::Cam8bits,
+    Control::Cam16bits,
+    Control::CamGps,
+    Control::CamIgnoreOverscanInterface,
+    Control::Qhyccd3aAutoexposure,
+    Control::Qhyccd3aAutofocus,
+    Control::Ampv,
+    Control::Vcam,
+    Control::CamViewMode,
+    Control::CfwSlotsNum,
+    Control::IsExposingDone,
+    Control::ScreenStretchB,
+    Control::ScreenStretchW,
+    Control::DDR,
+    Control::CamLightPerformanceMode,
+    Control::CamQhy5IIGuideMode,
+    Control::DDRBufferCapacity,
+    Control::DDRBufferReadThreshold,
+    Control::DefaultGain,
+    Control::DefaultOffset,
+    Control::OutputDataActualBits,
+    Control::OutputDataAlignment,
+    Control::CamSingleFrameMode,
+    Control::CamLiveVideoMode,
+    Control::CamIsColor,
+    Control::HasHardwareFrameCounter,
+    Control::MaxIdError,
+    Control::CamHumidity,
+    Control::CamPressure,
+    Control::VacuumPump,
+    Control::SensorChamberCyclePump,
+    Control::Cam32bits,
+    Control::CamSensorUlvoStatus,
+    Control::CamSensorPhaseReTrain,
+    Control::CamInitConfigFromFlash,
+    Control::CamTriggerMode,
+    Control::CamTriggerOut,
+    Control::CamBurstMode,
+    Control::CamSpeakerLedAlarm,
+    Control::CamWatchDogFpga,
+    Control::CamBin6x6mode,
+    Control::CamBin8x8mode,
+    Control::CamGlobalSensorGpsLED,
+    Control::ImgProc,
+    Control::RemoveRbi,
+    Control::GlobalReset,
+    Control::FrameDetect,
+    Control::CamGainDbConversion,
+    Control::CamCurveSystemGain,
+    Control::CamCurveFullWell,
+    Control::CamCurveReadoutNoise,
+    Control::MaxId,
+    Control::Autowhitebalance,
+    Control::Autoexposure,
+    Control::AutoexpMessureValue,
+    Control::AutoexpMessureMethod,
+    Control::ImageStabilization,
+    Control::GaindB,
+];
+
+impl Control {
+    /// Returns every known control in the order of the SDK control ids
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Control, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// for control in Control::all() {
+    ///     println!("{:?}: {:?}", control, camera.is_control_available(*control));
+    /// }
+    /// ```
+    pub fn all() -> &'static [Control] {
+        &ALL_CONTROLS
+    }
+}
+
+impl TryFrom<u32> for Control {
+    type Error = QHYError;
+
+    /// Returns the control with the given SDK control id
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Control;
+    /// assert_eq!(Control::try_from(8).unwrap(), Control::Exposure);
+    /// assert!(Control::try_from(4711).is_err());
+    /// ```
+    fn try_from(id: u32) -> std::result::Result<Self, Self::Error> {
+        ALL_CONTROLS
+            .iter()
+            .find(|control| **control as u32 == id)
+            .copied()
+            .ok_or(UnknownControlError { id })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// What a camera is doing, returned from `Camera::state`
+pub enum CameraState {
+    /// the camera is not open
+    Closed,
+    /// the camera is open and idle, settings can be changed and exposures or live mode started
+    Open,
+    /// the camera is streaming in live mode, between `begin_live` and `end_live`
+    Live,
+    /// a single frame exposure was started and its frame not downloaded yet
+    Exposing,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 /// Stream mode used in `set_stream_mode`
 pub enum StreamMode {
     /// Long exposure mode
@@ -407,6 +913,49 @@ pub enum StreamMode {
     LiveMode = 1,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// Conversion gain used in `set_conversion_gain`, switched through `Control::CamLightPerformanceMode`
+pub enum ConversionGain {
+    /// low conversion gain (LGC), larger full well
+    Low = 0,
+    /// high conversion gain (HGC), lower read noise
+    High = 1,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// Shutter readout mode used in `set_shutter_readout_mode`, switched through `Control::GlobalReset`
+pub enum ShutterReadoutMode {
+    /// rows start and end their exposure one after the other, the exposure of the last row
+    /// starts later than the one of the first row by the readout time of the sensor
+    Rolling = 0,
+    /// all rows start their exposure at the same time, but are read out one after the other,
+    /// so rows read out later are exposed longer, use a mechanical shutter or flash to end the
+    /// exposure for all rows at once
+    GlobalReset = 1,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The trigger mode of the camera used with `set_trigger_mode`
+pub enum TriggerMode {
+    /// the trigger function is off, exposures start immediately
+    Off,
+    /// exposures wait for a pulse at the trigger input, the number selects one of the camera
+    /// specific trigger modes, e.g. edge or level triggered
+    External(u32),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The undervoltage lockout (ULVO) status of the sensor as returned by `ulvo_status`
+pub enum UlvoStatus {
+    /// the sensor supply is fine, the SDK reports 0
+    Normal,
+    /// the sensor saw an undervoltage event, `code` is the non zero value the SDK reports
+    Undervoltage {
+        /// the camera specific status code
+        code: u32,
+    },
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 /// Camera sensor info
 pub struct CCDChipInfo {
@@ -426,7 +975,50 @@ pub struct CCDChipInfo {
     pub bits_per_pixel: u32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The version of an FPGA in the camera as returned by `get_fpga_version`
+pub struct FpgaVersion {
+    /// the year of the build, two digits
+    pub year: u8,
+    /// the month of the build
+    pub month: u8,
+    /// the day of the build
+    pub day: u8,
+    /// the revision on that day
+    pub revision: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The sensor timing of the current settings as returned by `get_precise_exposure_info`
+pub struct PreciseExposureInfo {
+    /// the duration of one pixel clock in ps
+    pub pixel_period_ps: u32,
+    /// the duration of one line in ns
+    pub line_period_ns: u32,
+    /// the duration of one frame in us
+    pub frame_period_us: u32,
+    /// the number of pixel clocks per line
+    pub clocks_per_line: u32,
+    /// the number of lines per frame
+    pub lines_per_frame: u32,
+    /// the exposure time the sensor actually uses, rounded to whole lines
+    pub actual_exposure: Duration,
+    /// whether the sensor is in long exposure mode
+    pub long_exposure_mode: bool,
+}
+
+impl std::fmt::Display for FpgaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "20{:02}_{}_{}_{}",
+            self.year, self.month, self.day, self.revision
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 /// the image data coming from the camera in `get_live_frame` and `get_single_frame`
 pub struct ImageData {
     /// the image data
@@ -441,7 +1033,33 @@ pub struct ImageData {
     pub channels: u32,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// the geometry of a frame downloaded with `get_single_frame_into` and `get_live_frame_into`
+pub struct FrameInfo {
+    /// the width of the image in pixels
+    pub width: u32,
+    /// the height of the image in pixels
+    pub height: u32,
+    /// the number of bits per pixel
+    pub bits_per_pixel: u32,
+    /// the number of channels 1 or 4 most of the time
+    pub channels: u32,
+}
+
+impl FrameInfo {
+    fn into_image(self, data: Vec<u8>) -> ImageData {
+        ImageData {
+            data,
+            width: self.width,
+            height: self.height,
+            bits_per_pixel: self.bits_per_pixel,
+            channels: self.channels,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// this struct is used in `get_overscan_area`, `get_effective_area`, `set_roi` and `get_roi`
 pub struct CCDChipArea {
     /// the x coordinate of the top left corner of the area
@@ -454,7 +1072,8 @@ pub struct CCDChipArea {
     pub height: u32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 /// this struct is returned from `is_control_available` when used with `Control::CamColor`
 pub enum BayerMode {
@@ -487,6 +1106,19 @@ pub struct ReadoutMode {
     pub name: String,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+/// a readout mode together with its resolution, returned from `readout_modes`
+pub struct ReadoutModeInfo {
+    /// the number of the mode staring with 0
+    pub id: u32,
+    /// the name of the mode e.g., `"STANDARD MODE"`
+    pub name: String,
+    /// the width of the image in this mode in pixels
+    pub width: u32,
+    /// the height of the image in this mode in pixels
+    pub height: u32,
+}
+
 #[derive(Debug, PartialEq)]
 /// returned from `SDK::version`
 pub struct SDKVersion {
@@ -499,10 +1131,66 @@ pub struct SDKVersion {
     /// the subday of the SDK version
     pub subday: u32,
 }
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// How much the SDK logs, used in `Sdk::set_log_level`. The SDK prints its messages up to the
+/// given level, `Off` silences it.
+pub enum QhyLogLevel {
+    /// no messages at all
+    Off = 0,
+    /// errors only
+    Error = 1,
+    /// errors and warnings
+    Warning = 2,
+    /// informational messages
+    Info = 3,
+    /// every call into the SDK
+    Debug = 4,
+    /// everything the SDK has to say
+    Trace = 5,
+}
+
+/// calls the SDK function `$function` inside a `sdk_call` debug span and records how long it
+/// took, see `telemetry`
+///
+/// The span carries the function name, the id of the camera if one is given as first argument,
+/// and the arguments. The duration and the return value are logged as a debug event in the span,
+/// so running with e.g. `RUST_LOG=qhyccd_rs=debug` traces every call into the SDK. Each argument
+/// is evaluated once, in order, and bound to a name so it can be both logged and passed on.
+macro_rules! sdk_call {
+    (@bind [$($camera:tt)*] $function:ident [$($bound:ident)*] [$($unused:ident)*] []) => {{
+        let _span = tracing::debug_span!(
+            "sdk_call",
+            function = stringify!($function),
+            $($camera)*
+            args = ?($(&$bound,)*)
+        )
+        .entered();
+        let start = std::time::Instant::now();
+        let result = $function($($bound),*);
+        let duration = start.elapsed();
+        tracing::debug!(?duration, ?result);
+        $crate::telemetry::sdk_call(stringify!($function), duration);
+        result
+    }};
+    (@bind $camera:tt $function:ident [$($bound:ident)*] [$name:ident $($names:ident)*]
+        [$arg:expr $(, $rest:expr)*]) => {
+        match $arg {
+            $name => sdk_call!(@bind $camera $function [$($bound)* $name] [$($names)*] [$($rest),*]),
+        }
+    };
+    ($function:ident($($arg:expr),* $(,)?)) => {
+        sdk_call!(@bind [] $function [] [a0 a1 a2 a3 a4 a5 a6 a7 a8 a9] [$($arg),*])
+    };
+    ($camera:expr, $function:ident($($arg:expr),* $(,)?)) => {
+        sdk_call!(@bind [camera = %$camera,] $function [] [a0 a1 a2 a3 a4 a5 a6 a7 a8 a9] [$($arg),*])
+    };
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
 /// The representation of the SDK. It automatically allocates the SDK when constructed
-/// and automatically frees resource when deconstructed.
+/// and automatically frees resource when deconstructed. All `Sdk` values and their clones share
+/// the same resources, they are freed when the last one is dropped.
 ///
 /// # Example
 /// ```no_run
@@ -519,6 +1207,7 @@ pub struct SDKVersion {
 pub struct Sdk {
     cameras: Vec<Camera>,
     filter_wheels: Vec<FilterWheel>,
+    resources: SdkResources,
 }
 
 #[allow(unused_unsafe)]
@@ -531,86 +1220,79 @@ impl Sdk {
     /// assert!(sdk.is_ok());
     /// ```
     pub fn new() -> Result<Self> {
-        match unsafe { InitQHYCCDResource() } {
-            QHYCCD_SUCCESS => {
-                let num_cameras = match unsafe { ScanQHYCCD() } {
-                    QHYCCD_ERROR => {
-                        let error = ScanQHYCCDError;
-                        tracing::error!(error = ?error);
-                        Err(eyre!(error))
-                    }
-                    num => Ok(num),
-                }?;
-
-                let mut cameras = Vec::with_capacity(num_cameras as usize);
-                let mut filter_wheels = Vec::with_capacity(num_cameras as usize);
-                for index in 0..num_cameras {
-                    let id = {
-                        let mut c_id: [c_char; 32] = [0; 32];
-                        unsafe {
-                            match GetQHYCCDId(index, c_id.as_mut_ptr()) {
-                                QHYCCD_SUCCESS => {
-                                    let id = match CStr::from_ptr(c_id.as_ptr()).to_str() {
-                                        Ok(id) => id,
-                                        Err(error) => {
-                                            tracing::error!(error = ?error);
-                                            return Err(eyre!(error));
-                                        }
-                                    };
-                                    Ok(id.to_owned())
-                                }
-                                error_code => {
-                                    let error = GetCameraIdError { error_code };
+        let resources = SdkResources::acquire()?;
+        let num_cameras = match unsafe { sdk_call!(ScanQHYCCD()) } {
+            QHYCCD_ERROR => {
+                let error = ScanQHYCCDError;
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+            num => Ok(num),
+        }?;
+
+        let mut cameras = Vec::with_capacity(num_cameras as usize);
+        let mut filter_wheels = Vec::with_capacity(num_cameras as usize);
+        for index in 0..num_cameras {
+            let id = {
+                let mut c_id: [c_char; 32] = [0; 32];
+                unsafe {
+                    match sdk_call!(GetQHYCCDId(index, c_id.as_mut_ptr())) {
+                        QHYCCD_SUCCESS => {
+                            let id = match CStr::from_ptr(c_id.as_ptr()).to_str() {
+                                Ok(id) => id,
+                                Err(error) => {
                                     tracing::error!(error = ?error);
-                                    Err(eyre!(error))
+                                    return Err(error.into());
                                 }
-                            }
+                            };
+                            Ok(id.to_owned())
                         }
-                    }?;
-                    let camera = Camera::new(id.clone());
-                    let mut has_filter_wheel = false;
-                    match camera.open() {
-                        Ok(_) => match camera.is_cfw_plugged_in() {
-                            Ok(true) => {
-                                tracing::trace!("Camera {} reporting a filter wheel", id);
-                                has_filter_wheel = true;
-                            }
-                            Ok(false) => {
-                                tracing::trace!("Camera {} has no filter wheel", id)
-                            }
-                            Err(error) => {
-                                tracing::error!(error = ?error);
-                            }
-                        },
-                        Err(error) => {
+                        error_code => {
+                            let error = GetCameraIdError { error_code };
                             tracing::error!(error = ?error);
-                            continue;
+                            Err(error)
                         }
                     }
-                    match camera.close() {
-                        Ok(_) => (),
-                        Err(error) => {
-                            tracing::error!(error = ?error);
-                            continue;
-                        }
+                }
+            }?;
+            let camera = Camera::new(id.clone());
+            let mut has_filter_wheel = false;
+            match camera.open() {
+                Ok(_) => match camera.is_cfw_plugged_in() {
+                    Ok(true) => {
+                        tracing::trace!("Camera {} reporting a filter wheel", id);
+                        has_filter_wheel = true;
+                    }
+                    Ok(false) => {
+                        tracing::trace!("Camera {} has no filter wheel", id)
+                    }
+                    Err(error) => {
+                        tracing::error!(error = ?error);
                     }
-                    if has_filter_wheel {
-                        filter_wheels.push(FilterWheel::new(Camera::new(id)))
-                    };
-                    cameras.push(camera);
+                },
+                Err(error) => {
+                    tracing::error!(error = ?error);
+                    continue;
                 }
-
-                Ok(Sdk {
-                    cameras,
-                    filter_wheels,
-                })
             }
-            error_code => {
-                let error = InitSDKError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
+            match camera.close() {
+                Ok(_) => (),
+                Err(error) => {
+                    tracing::error!(error = ?error);
+                    continue;
+                }
             }
+            if has_filter_wheel {
+                filter_wheels.push(FilterWheel::new(camera.clone()))
+            };
+            cameras.push(camera);
         }
+
+        Ok(Sdk {
+            cameras,
+            filter_wheels,
+            resources,
+        })
     }
     /// Returns an iterator over all cameras found by the SDK
     /// # Example
@@ -650,7 +1332,14 @@ impl Sdk {
         let mut month: u32 = 0;
         let mut day: u32 = 0;
         let mut subday: u32 = 0;
-        match unsafe { GetQHYCCDSDKVersion(&mut year, &mut month, &mut day, &mut subday) } {
+        match unsafe {
+            sdk_call!(GetQHYCCDSDKVersion(
+                &mut year,
+                &mut month,
+                &mut day,
+                &mut subday
+            ))
+        } {
             QHYCCD_SUCCESS => Ok(SDKVersion {
                 year,
                 month,
@@ -660,800 +1349,2135 @@ impl Sdk {
             error_code => {
                 let error = GetSDKVersionError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
-        }
-    }
-}
-
-#[allow(unused_unsafe)]
-impl Drop for Sdk {
-    fn drop(&mut self) {
-        match unsafe { ReleaseQHYCCDResource() } {
-            QHYCCD_SUCCESS => (),
-            error_code => {
-                let error = CloseSDKError { error_code };
-                tracing::error!(error = ?error);
+                Err(error)
             }
         }
     }
-}
-
-#[derive(Debug, PartialEq, Copy, Clone)]
-struct QHYCCDHandle {
-    pub ptr: *const std::ffi::c_void,
-}
-
-//Safety: QHYCCDHandle is only used in Camera and Camera is Send and Sync
-unsafe impl Send for QHYCCDHandle {}
-unsafe impl Sync for QHYCCDHandle {}
-
-#[derive(Educe)]
-#[educe(Debug, Clone, PartialEq)]
-/// The representation of a camera. It is constructed by the SDK and can be used to
-/// interact with the camera.
-pub struct Camera {
-    id: String,
-    #[educe(PartialEq(ignore))]
-    handle: Arc<RwLock<Option<QHYCCDHandle>>>,
-}
-
-macro_rules! read_lock {
-    ($var:expr, $wrap:expr) => {
-        $var.read().map_err(|err| {
-            tracing::error!(error=?err);
-            eyre!("Could not acquire read lock on camera handle")
-        }).and_then(|lock|{match *lock {
-            Some(handle) => Ok(handle.ptr),
-            None => {
-                tracing::error!(error = ?CameraNotOpenError);
-                Err(eyre!(CameraNotOpenError))
-            }
-        }}).wrap_err($wrap)
-    }
-}
 
-#[allow(unused_unsafe)]
-impl Camera {
-    /// Creates a new instance of the camera. The Sdk automatically finds all cameras and provides them in it's cameras() iterator. Creating
-    /// a camera manually should only be needed for rare cases.
+    /// Sets how much the SDK prints to stdout and its log file. Can be called before `Sdk::new`
+    /// to also silence the scan for cameras.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk, Camera};
-    /// let camera = Camera::new("camera id from sdk".to_string());
-    /// println!("Camera: {:?}", camera);
+    /// use qhyccd_rs::{QhyLogLevel, Sdk};
+    /// Sdk::set_log_level(QhyLogLevel::Error);
+    /// let sdk = Sdk::new().expect("SDK::new failed");
     /// ```
-    pub fn new(id: String) -> Self {
-        Self {
-            id: id.clone(),
-            handle: Arc::new(RwLock::new(None)),
-        }
+    pub fn set_log_level(level: QhyLogLevel) {
+        unsafe { sdk_call!(SetQHYCCDLogLevel(level as u8)) }
     }
 
-    /// Returns the id of the camera
+    /// Enables or disables the messages the SDK prints to stdout
     /// # Example
     /// ```no_run
     /// use qhyccd_rs::Sdk;
+    /// Sdk::enable_messages(false);
     /// let sdk = Sdk::new().expect("SDK::new failed");
-    /// let camera = sdk.cameras().last().expect("no camera found");
-    /// println!("Camera id: {}", camera.id());
     /// ```
-    pub fn id(&self) -> &str {
-        self.id.as_str()
+    pub fn enable_messages(enable: bool) {
+        unsafe { sdk_call!(EnableQHYCCDMessage(enable)) }
     }
 
-    /// Sets the stream mode of the camera
+    /// Enables or disables writing the SDK messages to its log file. The SDK does not take a
+    /// path, it decides where the file goes.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk, StreamMode};
+    /// use qhyccd_rs::Sdk;
+    /// Sdk::enable_messages(false);
+    /// Sdk::enable_log_file(true);
     /// let sdk = Sdk::new().expect("SDK::new failed");
-    /// let camera = sdk.cameras().last().expect("no camera found");
-    /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
     /// ```
-    pub fn set_stream_mode(&self, mode: StreamMode) -> Result<()> {
-        let handle = read_lock!(self.handle, SetStreamModeError { error_code: 0 })?;
-        match unsafe { SetQHYCCDStreamMode(handle, mode as u8) } {
-            QHYCCD_SUCCESS => Ok(()),
-            error_code => {
-                let error = SetStreamModeError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
-        }
+    pub fn enable_log_file(enable: bool) {
+        unsafe { sdk_call!(EnableQHYCCDLogFile(enable)) }
     }
 
-    /// Sets the readout mode of the camera with the id of the `ReadoutMode` between 0 and the value
-    /// returned by `get_number_of_readout_modes`
+    /// Returns a channel that receives a `HotplugEvent` whenever a camera is attached or
+    /// detached. The SDK callbacks are registered with the first subscription, later ones only
+    /// add another channel. `cameras` is not updated, attached cameras are opened through
+    /// `Camera::new` with the id from the event.
+    /// If the installed SDK lacks `SdkFeature::Hotplug` no events arrive.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk};
+    /// use qhyccd_rs::{HotplugEvent, Sdk};
     /// let sdk = Sdk::new().expect("SDK::new failed");
-    /// let camera = sdk.cameras().last().expect("no camera found");
-    /// camera.open().expect("open failed");
-    /// camera.set_readout_mode(0).expect("set_readout_mode failed");
+    /// let events = sdk.subscribe_hotplug();
+    /// for event in events {
+    ///     match event {
+    ///         HotplugEvent::Attached(id) => println!("camera {} attached", id),
+    ///         HotplugEvent::Detached(id) => println!("camera {} detached", id),
+    ///     }
+    /// }
     /// ```
-    pub fn set_readout_mode(&self, mode: u32) -> Result<()> {
-        let handle = read_lock!(self.handle, SetReadoutModeError { error_code: 0 })?;
-        match unsafe { SetQHYCCDReadMode(handle, mode) } {
-            QHYCCD_SUCCESS => Ok(()),
-            error_code => {
-                let error = SetReadoutModeError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
+    pub fn subscribe_hotplug(&self) -> std::sync::mpsc::Receiver<HotplugEvent> {
+        let receiver = hotplug::SUBSCRIBERS.subscribe();
+        if !self.features().contains(SdkFeature::Hotplug) {
+            tracing::warn!("the installed SDK does not report hotplug events");
+            return receiver;
         }
+        hotplug::SUBSCRIBERS.register(|| unsafe {
+            sdk_call!(RegisterPnpEventIn(
+                hotplug::on_attached as extern "C" fn(*mut c_char)
+            ));
+            sdk_call!(RegisterPnpEventOut(
+                hotplug::on_detached as extern "C" fn(*mut c_char)
+            ));
+        });
+        receiver
     }
+}
 
-    /// retunrs the model or short description of the camera - this does not work for all cameras
-    /// # Example
-    /// ```no_run
-    /// use qhyccd_rs::Sdk;
-    /// let sdk = Sdk::new().expect("SDK::new failed");
-    /// let camera = sdk.cameras().last().expect("no camera found");
-    /// camera.open().expect("open failed");
-    /// let model = camera.get_model().expect("get_model failed");
-    /// println!("Camera model: {}", model);
-    /// ```
-    pub fn get_model(&self) -> Result<String> {
-        let handle = read_lock!(self.handle, GetCameraModelError { error_code: 0 })?;
-        let mut model: [c_char; 80] = [0; 80];
-        match unsafe { GetQHYCCDModel(handle, model.as_mut_ptr()) } {
-            QHYCCD_SUCCESS => {
-                let model = match unsafe { CStr::from_ptr(model.as_ptr()) }.to_str() {
-                    Ok(model) => model,
-                    Err(error) => {
-                        tracing::error!(error = ?error);
-                        return Err(eyre!(error));
-                    }
-                };
-                Ok(model.to_string())
-            }
-            error_code => {
-                let error = GetCameraModelError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
+/// loads libqhyccd if it is not linked at build time, see the `dlopen` feature
+fn load_library() -> Result<()> {
+    #[cfg(all(feature = "dlopen", not(test)))]
+    if let Err(error) = libqhyccd_sys::load() {
+        let error = LoadLibraryError {
+            reason: error.to_string(),
+        };
+        tracing::error!(error = ?error);
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+/// A share of the resources of the SDK. The first share initializes them, they are released
+/// when the last share is dropped, so all `Sdk` values can use the same resources.
+struct SdkResources;
+
+/// the number of `SdkResources` alive
+static SDK_RESOURCE_USERS: Mutex<usize> = Mutex::new(0);
+
+#[derive(Debug)]
+/// what camera handles need to know about the SDK resources
+struct SdkState {
+    /// incremented whenever the SDK resources are released, camera handles opened before are
+    /// invalid
+    generation: u64,
+    /// the number of `SdkCall`s alive
+    calls: usize,
+}
+
+static SDK_STATE: Mutex<SdkState> = Mutex::new(SdkState {
+    generation: 0,
+    calls: 0,
+});
+
+/// notified when the last `SdkCall` ends
+static SDK_IDLE: Condvar = Condvar::new();
+
+fn sdk_state() -> std::sync::MutexGuard<'static, SdkState> {
+    SDK_STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A call on a camera handle in progress, the SDK resources are not released while it lives
+struct SdkCall;
+
+impl SdkCall {
+    /// starts a call on a handle opened in `generation`, `None` if the resources it was opened
+    /// with were released since
+    fn begin(generation: u64) -> Option<Self> {
+        let mut state = sdk_state();
+        if state.generation != generation {
+            return None;
+        }
+        state.calls += 1;
+        Some(SdkCall)
+    }
+}
+
+impl Drop for SdkCall {
+    fn drop(&mut self) {
+        let mut state = sdk_state();
+        state.calls -= 1;
+        if state.calls == 0 {
+            SDK_IDLE.notify_all();
+        }
+    }
+}
+
+#[allow(unused_unsafe)]
+impl SdkResources {
+    fn users() -> std::sync::MutexGuard<'static, usize> {
+        SDK_RESOURCE_USERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn acquire() -> Result<Self> {
+        load_library()?;
+        let mut users = Self::users();
+        if *users == 0 {
+            match unsafe { sdk_call!(InitQHYCCDResource()) } {
+                QHYCCD_SUCCESS => (),
+                error_code => {
+                    let error = InitSDKError { error_code };
+                    tracing::error!(error = ?error);
+                    return Err(error);
+                }
+            }
+        }
+        *users += 1;
+        Ok(SdkResources)
+    }
+}
+
+impl Clone for SdkResources {
+    fn clone(&self) -> Self {
+        *Self::users() += 1;
+        SdkResources
+    }
+}
+
+#[allow(unused_unsafe)]
+impl Drop for SdkResources {
+    fn drop(&mut self) {
+        let mut users = Self::users();
+        *users = users.saturating_sub(1);
+        if *users == 0 {
+            // the calls running on camera handles still use the resources
+            let mut state = sdk_state();
+            while state.calls > 0 {
+                state = SDK_IDLE
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+            match unsafe { sdk_call!(ReleaseQHYCCDResource()) } {
+                QHYCCD_SUCCESS => (),
+                error_code => {
+                    let error = CloseSDKError { error_code };
+                    tracing::error!(error = ?error);
+                }
+            }
+            state.generation += 1;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct QHYCCDHandle {
+    pub ptr: *const std::ffi::c_void,
+    /// the `SdkState::generation` the handle was opened in
+    pub generation: u64,
+    /// what the camera is doing, never `CameraState::Closed`
+    pub state: CameraState,
+    /// the stream mode last set through `Camera::set_stream_mode`
+    pub stream_mode: Option<StreamMode>,
+    /// whether `Camera::init` succeeded since the stream mode was changed
+    pub initialized: bool,
+}
+
+//Safety: the SDK accepts the handle from any thread, `CallLock` keeps two threads from using it
+//at the same time
+unsafe impl Send for QHYCCDHandle {}
+unsafe impl Sync for QHYCCDHandle {}
+
+impl QHYCCDHandle {
+    fn new(ptr: *const std::ffi::c_void) -> Self {
+        Self {
+            ptr,
+            generation: sdk_state().generation,
+            state: CameraState::Open,
+            stream_mode: None,
+            initialized: false,
+        }
+    }
+
+    /// the handle is dangling once the SDK resources it was opened with were released
+    fn is_valid(&self) -> bool {
+        self.generation == sdk_state().generation
+    }
+}
+
+#[derive(Debug, Default)]
+/// The handle shared by all clones of a camera, it is closed when the last clone is dropped
+struct SharedHandle {
+    handle: RwLock<Option<QHYCCDHandle>>,
+    /// serializes the SDK calls made with `handle`
+    calls: CallLock,
+}
+
+impl SharedHandle {
+    fn lock_mut(&self) -> std::sync::RwLockWriteGuard<'_, Option<QHYCCDHandle>> {
+        self.handle
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn state(&self) -> CameraState {
+        match *self
+            .handle
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+        {
+            Some(handle) if handle.is_valid() => handle.state,
+            _ => CameraState::Closed,
+        }
+    }
+
+    /// fails with `CameraStateError` if the camera is open but not in `expected`, a closed
+    /// camera is left to `read_lock!` to report
+    fn require_state(&self, expected: CameraState) -> Result<()> {
+        match self.state() {
+            CameraState::Closed => Ok(()),
+            state if state == expected => Ok(()),
+            state => {
+                let error = CameraStateError { state, expected };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// moves an open camera from `from` to `to`, see `require_state`
+    fn transition(&self, from: CameraState, to: CameraState) -> Result<()> {
+        let mut lock = self.lock_mut();
+        match lock.as_mut() {
+            Some(handle) if handle.is_valid() && handle.state != from => {
+                let error = CameraStateError {
+                    state: handle.state,
+                    expected: from,
+                };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+            Some(handle) => {
+                handle.state = to;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn set_state(&self, state: CameraState) {
+        if let Some(handle) = self.lock_mut().as_mut() {
+            handle.state = state;
+        }
+    }
+
+    /// records the stream mode set on the camera, a new mode needs a new `Camera::init`
+    fn set_stream_mode(&self, mode: StreamMode) {
+        if let Some(handle) = self.lock_mut().as_mut() {
+            handle.initialized &= handle.stream_mode == Some(mode);
+            handle.stream_mode = Some(mode);
+        }
+    }
+
+    fn set_initialized(&self) {
+        if let Some(handle) = self.lock_mut().as_mut() {
+            handle.initialized = true;
+        }
+    }
+
+    /// whether `Camera::init` succeeded since the stream mode was last changed
+    fn is_initialized(&self) -> bool {
+        match *self
+            .handle
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+        {
+            Some(handle) if handle.is_valid() => handle.initialized,
+            _ => false,
+        }
+    }
+
+    /// whether the camera was initialized after switching to `mode`
+    fn is_initialized_in(&self, mode: StreamMode) -> bool {
+        match *self
+            .handle
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+        {
+            Some(handle) if handle.is_valid() => {
+                handle.initialized && handle.stream_mode == Some(mode)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::ops::Deref for SharedHandle {
+    type Target = RwLock<Option<QHYCCDHandle>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+/// The pointer of an open camera returned by `read_lock!`, the `CallLock` of the camera is held
+/// until it is dropped
+struct HandleGuard<'a> {
+    ptr: *const std::ffi::c_void,
+    _call: Option<CallGuard<'a>>,
+    _sdk: SdkCall,
+}
+
+impl std::ops::Deref for HandleGuard<'_> {
+    type Target = *const std::ffi::c_void;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ptr
+    }
+}
+
+#[allow(unused_unsafe)]
+impl Drop for SharedHandle {
+    fn drop(&mut self) {
+        let handle = self
+            .handle
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        let Some(handle) = handle else {
+            return;
+        };
+        if let Some(_sdk) = SdkCall::begin(handle.generation) {
+            match unsafe { sdk_call!(CloseQHYCCD(handle.ptr)) } {
+                QHYCCD_SUCCESS => (),
+                error_code => {
+                    let error = CloseCameraError { error_code };
+                    tracing::error!(error = ?error);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Educe)]
+#[educe(Debug, Clone, PartialEq)]
+/// The representation of a camera. It is constructed by the SDK and can be used to
+/// interact with the camera. Clones share the same handle, it is closed when the last clone is
+/// dropped.
+///
+/// `Camera` is `Send` and `Sync`, clones can be used from different threads. Calls into the SDK
+/// are serialized per camera. `get_single_frame` and `get_single_frame_into` hold the lock for
+/// the whole exposure, only `get_remaining_exposure_us`, `stop_exposure` and
+/// `abort_exposure_and_readout` bypass it to be called while they block. Everything else on the
+/// camera, like the cooler ramp, the keep-alive reads and the calls of an attached `FilterWheel`,
+/// stalls until the frame arrived.
+pub struct Camera {
+    id: String,
+    #[educe(PartialEq(ignore))]
+    handle: Arc<SharedHandle>,
+    #[educe(PartialEq(ignore))]
+    rate_limits: Arc<Mutex<RateLimiter>>,
+    #[educe(PartialEq(ignore))]
+    geometry: Arc<Mutex<FrameGeometry>>,
+    #[educe(PartialEq(ignore))]
+    last_frame_counter: Arc<Mutex<Option<u64>>>,
+}
+
+macro_rules! read_lock {
+    // for the calls that must not wait for a blocking download, see `call_lock`
+    (unserialized $var:expr) => {
+        read_lock!(@handle $var, None)
+    };
+    // the state is checked under the call lock, so no other call can change it in between
+    ($var:expr, $state:expr) => {
+        read_lock!($var).and_then(|guard| $var.require_state($state).map(|_| guard))
+    };
+    ($var:expr) => {
+        read_lock!(@handle $var, Some($var.calls.lock()))
+    };
+    (@handle $var:expr, $call:expr) => {{
+        // the call lock is taken first, `open` and `close` hold it while they write the handle
+        let call = $call;
+        $var.read().map_err(|err| {
+            tracing::error!(error=?err);
+            HandleLockError
+        }).and_then(|lock|{match (*lock, (*lock).and_then(|handle| SdkCall::begin(handle.generation))) {
+            (Some(handle), Some(sdk)) => Ok(HandleGuard {
+                ptr: handle.ptr,
+                _call: call,
+                _sdk: sdk,
+            }),
+            (Some(_), None) => {
+                tracing::error!(error = ?CameraClosedError);
+                Err(CameraClosedError)
+            }
+            (None, _) => {
+                tracing::error!(error = ?CameraNotOpenError);
+                Err(CameraNotOpenError)
+            }
+        }})
+    }};
+}
+
+#[allow(unused_unsafe)]
+impl Camera {
+    /// Creates a new instance of the camera. The Sdk automatically finds all cameras and provides them in it's cameras() iterator. Creating
+    /// a camera manually should only be needed for rare cases.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk, Camera};
+    /// let camera = Camera::new("camera id from sdk".to_string());
+    /// println!("Camera: {:?}", camera);
+    /// ```
+    pub fn new(id: String) -> Self {
+        Self {
+            id: id.clone(),
+            handle: Arc::new(SharedHandle::default()),
+            rate_limits: Arc::new(Mutex::new(RateLimiter::default())),
+            geometry: Arc::new(Mutex::new(FrameGeometry::default())),
+            last_frame_counter: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Returns the id of the camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// println!("Camera id: {}", camera.id());
+    /// ```
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    /// Sets the stream mode of the camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// ```
+    pub fn set_stream_mode(&self, mode: StreamMode) -> Result<()> {
+        let handle = read_lock!(self.handle, CameraState::Open)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDStreamMode(*handle, mode as u8)) } {
+            QHYCCD_SUCCESS => {
+                self.handle.set_stream_mode(mode);
+                Ok(())
+            }
+            error_code => {
+                let error = SetStreamModeError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Sets the readout mode of the camera with the id of the `ReadoutMode` between 0 and the value
+    /// returned by `get_number_of_readout_modes`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_readout_mode(0).expect("set_readout_mode failed");
+    /// ```
+    pub fn set_readout_mode(&self, mode: u32) -> Result<()> {
+        sdk_features::require(SdkFeature::ReadoutModes)?;
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDReadMode(*handle, mode)) } {
+            QHYCCD_SUCCESS => Ok(()),
+            error_code => {
+                let error = SetReadoutModeError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// retunrs the model or short description of the camera - this does not work for all cameras
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let model = camera.get_model().expect("get_model failed");
+    /// println!("Camera model: {}", model);
+    /// ```
+    pub fn get_model(&self) -> Result<String> {
+        let handle = read_lock!(self.handle)?;
+        let mut model: [c_char; 80] = [0; 80];
+        match unsafe { sdk_call!(self.id, GetQHYCCDModel(*handle, model.as_mut_ptr())) } {
+            QHYCCD_SUCCESS => {
+                let model = match unsafe { CStr::from_ptr(model.as_ptr()) }.to_str() {
+                    Ok(model) => model,
+                    Err(error) => {
+                        tracing::error!(error = ?error);
+                        return Err(error.into());
+                    }
+                };
+                Ok(model.to_string())
+            }
+            error_code => {
+                let error = GetCameraModelError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// initializes the camera to a new session - use this to change from LiveMode to SingleFrameMode for instance
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// ```
+    pub fn init(&self) -> Result<()> {
+        let handle = read_lock!(self.handle, CameraState::Open)?;
+
+        match unsafe { sdk_call!(self.id, InitQHYCCD(*handle)) } {
+            QHYCCD_SUCCESS => {
+                self.handle.set_initialized();
+                Ok(())
+            }
+            error_code => {
+                let error = InitCameraError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// returns the firmware version of the camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let firmware_version = camera.get_firmware_version().expect("get_firmware_version failed");
+    /// println!("Firmware version: {}", firmware_version);
+    /// ```
+    pub fn get_firmware_version(&self) -> Result<String> {
+        let handle = read_lock!(self.handle)?;
+        let mut version = [0u8; 32];
+        match unsafe { sdk_call!(self.id, GetQHYCCDFWVersion(*handle, version.as_mut_ptr())) } {
+            QHYCCD_SUCCESS => {
+                if version[0] >> 4 <= 9 {
+                    Ok(format!(
+                        "Firmware version: 20{}_{}_{}",
+                        (((version[0] >> 4) + 0x10) as u32),
+                        version[0] & 0x0F,
+                        version[1]
+                    ))
+                } else {
+                    Ok(format!(
+                        "Firmware version: 20{}_{}_{}",
+                        ((version[0] >> 4) as u32),
+                        version[0] & 0x0F,
+                        version[1]
+                    ))
+                }
+            }
+            error_code => {
+                let error = GetFirmwareVersionError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// returns the name of the image sensor, e.g. `IMX571`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let sensor = camera.get_sensor_name().expect("get_sensor_name failed");
+    /// println!("Sensor: {}", sensor);
+    /// ```
+    pub fn get_sensor_name(&self) -> Result<String> {
+        sdk_features::require(SdkFeature::SensorName)?;
+        let handle = read_lock!(self.handle)?;
+        let mut name: [c_char; 80] = [0; 80];
+        match unsafe { sdk_call!(self.id, GetQHYCCDSensorName(*handle, name.as_mut_ptr())) } {
+            QHYCCD_SUCCESS => {
+                let name = match unsafe { CStr::from_ptr(name.as_ptr()) }.to_str() {
+                    Ok(name) => name,
+                    Err(error) => {
+                        tracing::error!(error = ?error);
+                        return Err(error.into());
+                    }
+                };
+                Ok(name.to_string())
+            }
+            error_code => {
+                let error = GetSensorNameError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// returns the version of the FPGA with the given index, most cameras only have FPGA 0
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let version = camera.get_fpga_version(0).expect("get_fpga_version failed");
+    /// println!("FPGA version: {}", version);
+    /// ```
+    pub fn get_fpga_version(&self, fpga_index: u8) -> Result<FpgaVersion> {
+        sdk_features::require(SdkFeature::FpgaVersion)?;
+        let handle = read_lock!(self.handle)?;
+        let mut version = [0u8; 32];
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDFPGAVersion(*handle, fpga_index, version.as_mut_ptr())
+            )
+        } {
+            QHYCCD_SUCCESS => Ok(FpgaVersion {
+                year: version[0],
+                month: version[1],
+                day: version[2],
+                revision: version[3],
+            }),
+            error_code => {
+                let error = GetFpgaVersionError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns the number of readout modes of the camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let num_readout_modes = camera.get_number_of_readout_modes().expect("get_number_of_readout_modes failed");
+    /// println!("Number of readout modes: {}", num_readout_modes);
+    /// ```
+    pub fn get_number_of_readout_modes(&self) -> Result<u32> {
+        sdk_features::require(SdkFeature::ReadoutModes)?;
+        let handle = read_lock!(self.handle)?;
+
+        let mut num: u32 = 0;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDNumberOfReadModes(*handle, &mut num as *mut u32)
+            )
+        } {
+            QHYCCD_ERROR => {
+                let error = GetNumberOfReadoutModesError;
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+            _ => Ok(num),
+        }
+    }
+
+    /// Returns the readout mode name with the given index. Make sure to check the number of readout modes.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let num_readout_modes = camera.get_number_of_readout_modes().expect("get_number_of_readout_modes failed");
+    /// for index in 0..num_readout_modes {
+    ///    let readout_mode_name = camera.get_readout_mode_name(index).expect("get_readout_mode_name failed");
+    ///   println!("Readout mode {}: {}", index, readout_mode_name);
+    /// }
+    /// ```
+    pub fn get_readout_mode_name(&self, index: u32) -> Result<String> {
+        sdk_features::require(SdkFeature::ReadoutModes)?;
+        let handle = read_lock!(self.handle)?;
+        let mut name: [c_char; 80] = [0; 80];
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDReadModeName(*handle, index, name.as_mut_ptr())
+            )
+        } {
+            QHYCCD_ERROR => {
+                let error = GetReadoutModeNameError;
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+            _ => {
+                let name = match unsafe { CStr::from_ptr(name.as_ptr()) }.to_str() {
+                    Ok(name) => name,
+                    Err(error) => {
+                        tracing::error!(error = ?error);
+                        return Err(error.into());
+                    }
+                };
+                Ok(name.to_string())
+            }
+        }
+    }
+
+    /// Returns the resolution of the readout mode with the given index. Make sure to check the number of readout modes.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let num_readout_modes = camera.get_number_of_readout_modes().expect("get_number_of_readout_modes failed");
+    /// for index in 0..num_readout_modes {
+    ///   let readout_mode_resolution = camera.get_readout_mode_resolution(index).expect("get_readout_mode_resolution failed");
+    ///  println!("Readout mode {}: {:?}", index, readout_mode_resolution);
+    /// }
+    /// ```
+    pub fn get_readout_mode_resolution(&self, index: u32) -> Result<(u32, u32)> {
+        sdk_features::require(SdkFeature::ReadoutModes)?;
+        let handle = read_lock!(self.handle)?;
+
+        let mut width: u32 = 0;
+        let mut height: u32 = 0;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDReadModeResolution(
+                    *handle,
+                    index,
+                    &mut width as *mut u32,
+                    &mut height as *mut u32,
+                )
+            )
+        } {
+            QHYCCD_SUCCESS => Ok((width, height)),
+            _ => {
+                let error = GetReadoutModeResolutionError;
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns all readout modes of the camera with their names and resolutions
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// for mode in camera.readout_modes().expect("readout_modes failed") {
+    ///     println!("{}: {} {}x{}", mode.id, mode.name, mode.width, mode.height);
+    /// }
+    /// ```
+    pub fn readout_modes(&self) -> Result<Vec<ReadoutModeInfo>> {
+        (0..self.get_number_of_readout_modes()?)
+            .map(|id| {
+                let name = self.get_readout_mode_name(id)?;
+                let (width, height) = self.get_readout_mode_resolution(id)?;
+                Ok(ReadoutModeInfo {
+                    id,
+                    name,
+                    width,
+                    height,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the current readout mode of the camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::Camera;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let readout_mode = camera.get_readout_mode().expect("get_readout_mode failed");
+    /// println!("Readout mode: {}", readout_mode);
+    /// ```
+    pub fn get_readout_mode(&self) -> Result<u32> {
+        sdk_features::require(SdkFeature::ReadoutModes)?;
+        let handle = read_lock!(self.handle)?;
+        let mut mode: u32 = 0;
+        match unsafe { sdk_call!(self.id, GetQHYCCDReadMode(*handle, &mut mode as *mut u32)) } {
+            QHYCCD_SUCCESS => Ok(mode),
+            _ => {
+                let error = GetReadoutModeError;
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Not sure what this does, for a QHY178M Cool it returns 4010
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::Camera;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let tipe = camera.get_type().expect("get_type failed");
+    /// println!("Type: {}", tipe);
+    /// ```
+    pub fn get_type(&self) -> Result<u32> {
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, GetQHYCCDType(*handle)) } {
+            QHYCCD_ERROR => {
+                let error = GetCameraTypeError;
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+            camera_type => Ok(camera_type),
+        }
+    }
+
+    /// Sets the binning mode of the camera
+    /// Only symmetric binnings are supported
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::Camera;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_bin_mode(2, 2).expect("set_bin_mode failed");
+    /// ```
+    pub fn set_bin_mode(&self, bin_x: u32, bin_y: u32) -> Result<()> {
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDBinMode(*handle, bin_x, bin_y)) } {
+            QHYCCD_SUCCESS => {
+                self.update_frame_geometry(|geometry| geometry.binning = Some((bin_x, bin_y)));
+                Ok(())
+            }
+            error_code => {
+                let error = SetBinModeError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// According to c-cod ethis does not work for all cameras
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::Camera;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_debayer(false).expect("set_debayer failed");
+    ///```
+    pub fn set_debayer(&self, on: bool) -> Result<()> {
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDDebayerOnOff(*handle, on)) } {
+            QHYCCD_SUCCESS => Ok(()),
+            error_code => {
+                let error = SetDebayerError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Sets the Region of interest of the camera in binned pixels. Fails with `RoiError` if the
+    /// ROI violates a `RoiConstraint`, the binning has to be set before.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::Camera;
+    /// use qhyccd_rs::CCDChipArea;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let roi = CCDChipArea {
+    ///     start_x: 0,
+    ///     start_y: 0,
+    ///     width: 1000,
+    ///     height: 1000,
+    /// };
+    /// camera.set_roi(roi).expect("set_roi failed");
+    /// ```
+    pub fn set_roi(&self, roi: CCDChipArea) -> Result<()> {
+        self.check_roi(roi, self.frame_geometry().binning.unwrap_or((1, 1)))?;
+        self.write_roi(roi)
+    }
+
+    /// sets the ROI without checking it
+    pub(crate) fn write_roi(&self, roi: CCDChipArea) -> Result<()> {
+        let handle = read_lock!(self.handle)?;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                SetQHYCCDResolution(*handle, roi.start_x, roi.start_y, roi.width, roi.height)
+            )
+        } {
+            QHYCCD_SUCCESS => {
+                self.update_frame_geometry(|geometry| geometry.roi = Some(roi));
+                Ok(())
+            }
+            error_code => {
+                let error = SetRoiError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Starts Live Video Mode on the camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// ```
+    pub fn begin_live(&self) -> Result<()> {
+        let handle = read_lock!(self.handle, CameraState::Open)?;
+        match unsafe { sdk_call!(self.id, BeginQHYCCDLive(*handle)) } {
+            QHYCCD_SUCCESS => {
+                self.handle.set_state(CameraState::Live);
+                self.reset_frame_counter();
+                Ok(())
+            }
+            error_code => {
+                let error = BeginLiveError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Stops Live Video Mode on the camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// /* Download images in between */
+    /// camera.end_live().expect("end_live failed");
+    /// ```
+    pub fn end_live(&self) -> Result<()> {
+        let handle = read_lock!(self.handle, CameraState::Live)?;
+        match unsafe { sdk_call!(self.id, StopQHYCCDLive(*handle)) } {
+            QHYCCD_SUCCESS => {
+                self.handle.set_state(CameraState::Open);
+                Ok(())
+            }
+            error_code => {
+                let error = EndLiveError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns the number of bytes needed to retrieve the image stored in the camera. In 32 bit
+    /// mode it is at least 4 bytes for every pixel of the sensor.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.set_parameter(Control::Exposure, 2000000.0).expect("set_param failed"); // this is in micro seconds
+    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+    /// // wait for exposure to finish
+    /// let buffer_size = camera.get_image_size().expect("get_camera_image_size failed");
+    /// let image = camera.get_single_frame(buffer_size).expect("get_camera_single_frame failed");
+    /// ```
+    pub fn get_image_size(&self) -> Result<usize> {
+        let size = {
+            let handle = read_lock!(self.handle)?;
+            match unsafe { sdk_call!(self.id, GetQHYCCDMemLength(*handle)) } {
+                QHYCCD_ERROR => {
+                    let error = GetImageSizeError;
+                    tracing::error!(error = ?error);
+                    return Err(error);
+                }
+                size => size as usize,
+            }
+        };
+        match self.frame_geometry().bits_per_pixel {
+            // not every model accounts for 4 bytes per sample in the length it reports
+            Some(bits_per_pixel) if bits_per_pixel == PixelFormat::U32.bits() => {
+                let info = self.get_ccd_info()?;
+                Ok(size.max(
+                    info.image_width as usize
+                        * info.image_height as usize
+                        * PixelFormat::U32.bytes_per_sample(),
+                ))
+            }
+            _ => Ok(size),
+        }
+    }
+
+    /// Returns the image stored in the camera as `ImageData` struct if the camera is in Live Video Mode
+    /// # Example
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.set_parameter(Control::Exposure, 10000.0).expect("set_param failed"); // this is in micro seconds
+    /// camera.begin_live().expect("begin_live failed");
+    /// let size = camera.get_image_size().expect("get_camera_image_size failed");
+    /// for _ in 0..1000 {
+    ///     let result = camera.get_live_frame(size);
+    ///     if result.is_err() {
+    ///         thread::sleep(Duration::from_millis(100));
+    ///         continue;
+    ///     }
+    ///     let image = result.unwrap();
+    ///    /* Do something with the image */
+    ///     break;
+    /// }
+    /// camera.end_live().expect("end_camera_live failed");
+    /// ```
+    pub fn get_live_frame(&self, buffer_size: usize) -> Result<ImageData> {
+        let handle = read_lock!(self.handle, CameraState::Live)?;
+        read_live_frame(&self.id, *handle, buffer_size).map_err(|error_code| {
+            let error = GetLiveFrameError { error_code };
+            tracing::error!(error = ?error);
+            error
+        })
+    }
+
+    /// Returns a `LiveFrameIter` over the frames buffered in the camera while it is in Live Video
+    /// Mode. Use `LiveFrameIter::next_batch` to drain many frames at once for high frame rates.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// let size = camera.get_image_size().expect("get_camera_image_size failed");
+    /// let mut frames = camera.live_frames(size);
+    /// for _ in 0..100 {
+    ///     let batch = frames.next_batch(32, Duration::from_millis(100)).expect("next_batch failed");
+    ///     println!("received {} frames", batch.len());
+    /// }
+    /// camera.end_live().expect("end_camera_live failed");
+    /// ```
+    pub fn live_frames(&self, buffer_size: usize) -> LiveFrameIter {
+        LiveFrameIter {
+            camera: self.clone(),
+            buffer_size,
+        }
+    }
+
+    /// Returns the image stored in the camera as `ImageData` struct if the camera is in Single Frame Mode
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.set_parameter(Control::Exposure, 10000.0).expect("set_param failed"); // this is in micro seconds
+    /// camera.start_single_frame_exposure().expect("start_camera_single_frame_exposure failed");
+    /// let buffer_size = camera.get_image_size().expect("get_camera_image_size failed");
+    /// let image = camera.get_single_frame(buffer_size).expect("get_camera_single_frame failed");
+    /// ```
+    pub fn get_single_frame(&self, buffer_size: usize) -> Result<ImageData> {
+        let handle = read_lock!(self.handle, CameraState::Exposing)?;
+        let mut buffer = vec![0u8; buffer_size];
+        read_single_frame_into(&self.id, *handle, &mut buffer)
+            .map(|info| {
+                self.handle.set_state(CameraState::Open);
+                info.into_image(buffer)
+            })
+            .map_err(|error_code| {
+                let error = GetSingleFrameError { error_code };
+                tracing::error!(error = ?error);
+                error
+            })
+    }
+
+    /// Downloads the image stored in the camera in Single Frame Mode into `buffer` instead of
+    /// allocating a new one and returns its geometry. Fails with `BufferTooSmallError` if
+    /// `buffer` is smaller than `get_image_size`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// let mut buffer = vec![0u8; camera.get_image_size().expect("get_image_size failed")];
+    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+    /// let info = camera.get_single_frame_into(&mut buffer).expect("get_single_frame_into failed");
+    /// println!("{}x{} pixels", info.width, info.height);
+    /// ```
+    pub fn get_single_frame_into(&self, buffer: &mut [u8]) -> Result<FrameInfo> {
+        let handle = read_lock!(self.handle, CameraState::Exposing)?;
+        self.check_buffer_size(buffer)?;
+        read_single_frame_into(&self.id, *handle, buffer)
+            .map(|info| {
+                self.handle.set_state(CameraState::Open);
+                info
+            })
+            .map_err(|error_code| {
+                let error = GetSingleFrameError { error_code };
+                tracing::error!(error = ?error);
+                error
+            })
+    }
+
+    /// Downloads the next frame in Live Video Mode into `buffer` instead of allocating a new
+    /// one and returns its geometry, so the same buffer can be reused for every frame. Fails
+    /// with `BufferTooSmallError` if `buffer` is smaller than `get_image_size`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// let mut buffer = vec![0u8; camera.get_image_size().expect("get_image_size failed")];
+    /// for _ in 0..1000 {
+    ///     if let Ok(info) = camera.get_live_frame_into(&mut buffer) {
+    ///         /* process the frame in buffer */
+    ///     }
+    /// }
+    /// camera.end_live().expect("end_live failed");
+    /// ```
+    pub fn get_live_frame_into(&self, buffer: &mut [u8]) -> Result<FrameInfo> {
+        let handle = read_lock!(self.handle, CameraState::Live)?;
+        self.check_buffer_size(buffer)?;
+        read_live_frame_into(&self.id, *handle, buffer).map_err(|error_code| {
+            let error = GetLiveFrameError { error_code };
+            tracing::error!(error = ?error);
+            error
+        })
+    }
+
+    fn check_buffer_size(&self, buffer: &[u8]) -> Result<()> {
+        let required = self.get_image_size()?;
+        if buffer.len() < required {
+            let error = BufferTooSmallError {
+                required,
+                actual: buffer.len(),
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Get the chip area including overscan area
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,CCDChipArea};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let chip_area = camera.get_overscan_area().expect("get_overscan_area failed");
+    /// println!("Chip area: {:?}", chip_area);
+    /// ```
+    pub fn get_overscan_area(&self) -> Result<CCDChipArea> {
+        let handle = read_lock!(self.handle)?;
+        let mut start_x: u32 = 0;
+        let mut start_y: u32 = 0;
+        let mut width: u32 = 0;
+        let mut height: u32 = 0;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDOverScanArea(
+                    *handle,
+                    &mut start_x as *mut u32,
+                    &mut start_y as *mut u32,
+                    &mut width as *mut u32,
+                    &mut height as *mut u32,
+                )
+            )
+        } {
+            QHYCCD_SUCCESS => Ok(CCDChipArea {
+                start_x,
+                start_y,
+                width,
+                height,
+            }),
+            error_code => {
+                let error = GetOverscanAreaError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Get the effective imaging chip area
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,CCDChipArea};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let chip_area = camera.get_effective_area().expect("get_overscan_area failed");
+    /// println!("Chip area: {:?}", chip_area);
+    /// ```
+    pub fn get_effective_area(&self) -> Result<CCDChipArea> {
+        let handle = read_lock!(self.handle)?;
+        let mut start_x: u32 = 0;
+        let mut start_y: u32 = 0;
+        let mut width: u32 = 0;
+        let mut height: u32 = 0;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDEffectiveArea(
+                    *handle,
+                    &mut start_x as *mut u32,
+                    &mut start_y as *mut u32,
+                    &mut width as *mut u32,
+                    &mut height as *mut u32,
+                )
+            )
+        } {
+            QHYCCD_SUCCESS => Ok(CCDChipArea {
+                start_x,
+                start_y,
+                width,
+                height,
+            }),
+            error_code => {
+                let error = GetEffectiveAreaError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
             }
         }
     }
 
-    /// initializes the camera to a new session - use this to change from LiveMode to SingleFrameMode for instance
+    /// Returns the sensor timing for the current settings, only supported by some cameras
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk, StreamMode};
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
-    /// camera.init().expect("init failed");
+    /// let info = camera.get_precise_exposure_info().expect("get_precise_exposure_info failed");
+    /// println!("actual exposure: {:?}", info.actual_exposure);
     /// ```
-    pub fn init(&self) -> Result<()> {
-        let handle = read_lock!(self.handle, InitCameraError { error_code: 0 })?;
-
-        match unsafe { InitQHYCCD(handle) } {
-            QHYCCD_SUCCESS => Ok(()),
+    pub fn get_precise_exposure_info(&self) -> Result<PreciseExposureInfo> {
+        sdk_features::require(SdkFeature::PreciseExposureInfo)?;
+        let handle = read_lock!(self.handle)?;
+        let mut pixel_period_ps: u32 = 0;
+        let mut line_period_ns: u32 = 0;
+        let mut frame_period_us: u32 = 0;
+        let mut clocks_per_line: u32 = 0;
+        let mut lines_per_frame: u32 = 0;
+        let mut actual_exposure_us: u32 = 0;
+        let mut long_exposure_mode: u8 = 0;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDPreciseExposureInfo(
+                    *handle,
+                    &mut pixel_period_ps as *mut u32,
+                    &mut line_period_ns as *mut u32,
+                    &mut frame_period_us as *mut u32,
+                    &mut clocks_per_line as *mut u32,
+                    &mut lines_per_frame as *mut u32,
+                    &mut actual_exposure_us as *mut u32,
+                    &mut long_exposure_mode as *mut u8,
+                )
+            )
+        } {
+            QHYCCD_SUCCESS => Ok(PreciseExposureInfo {
+                pixel_period_ps,
+                line_period_ns,
+                frame_period_us,
+                clocks_per_line,
+                lines_per_frame,
+                actual_exposure: Duration::from_micros(actual_exposure_us as u64),
+                long_exposure_mode: long_exposure_mode != 0,
+            }),
             error_code => {
-                let error = InitCameraError { error_code };
+                let error = GetPreciseExposureInfoError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// returns the firmware version of the camera
+    /// Returns how much later the exposure of `row` ends than the one of the first row on
+    /// rolling shutter sensors, only supported by some cameras
     /// # Example
     /// ```no_run
     /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let firmware_version = camera.get_firmware_version().expect("get_firmware_version failed");
-    /// println!("Firmware version: {}", firmware_version);
+    /// let offset = camera.get_rolling_shutter_end_offset(1000).expect("get_rolling_shutter_end_offset failed");
+    /// println!("row 1000 ends {:?} after row 0", offset);
     /// ```
-    pub fn get_firmware_version(&self) -> Result<String> {
-        let handle = read_lock!(self.handle, GetFirmwareVersionError { error_code: 0 })?;
-        let mut version = [0u8; 32];
-        match unsafe { GetQHYCCDFWVersion(handle, version.as_mut_ptr()) } {
-            QHYCCD_SUCCESS => {
-                if version[0] >> 4 <= 9 {
-                    Ok(format!(
-                        "Firmware version: 20{}_{}_{}",
-                        (((version[0] >> 4) + 0x10) as u32),
-                        version[0] & 0x0F,
-                        version[1]
-                    ))
-                } else {
-                    Ok(format!(
-                        "Firmware version: 20{}_{}_{}",
-                        ((version[0] >> 4) as u32),
-                        version[0] & 0x0F,
-                        version[1]
-                    ))
-                }
-            }
+    pub fn get_rolling_shutter_end_offset(&self, row: u32) -> Result<Duration> {
+        sdk_features::require(SdkFeature::RollingShutterEndOffset)?;
+        let handle = read_lock!(self.handle)?;
+        let mut offset_us: f64 = 0.0;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDRollingShutterEndOffset(*handle, row, &mut offset_us as *mut f64)
+            )
+        } {
+            QHYCCD_SUCCESS => Ok(Duration::from_secs_f64(offset_us.max(0.0) / 1_000_000.0)),
             error_code => {
-                let error = GetFirmwareVersionError { error_code };
+                let error = GetRollingShutterEndOffsetError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Returns the number of readout modes of the camera
+    /// Start a long exposure
+    /// Make sure to set the exposure time before calling this function
+    /// this function blocks the current thread and only returns when the exposure is finished
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
+    ///
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let num_readout_modes = camera.get_number_of_readout_modes().expect("get_number_of_readout_modes failed");
-    /// println!("Number of readout modes: {}", num_readout_modes);
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.set_parameter(Control::Exposure, 2000000.0).expect("set_param failed"); // this is in micro seconds
+    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
     /// ```
-    pub fn get_number_of_readout_modes(&self) -> Result<u32> {
-        let handle = read_lock!(self.handle, GetNumberOfReadoutModesError)?;
-
-        let mut num: u32 = 0;
-        match unsafe { GetQHYCCDNumberOfReadModes(handle, &mut num as *mut u32) } {
-            QHYCCD_ERROR => {
-                let error = GetNumberOfReadoutModesError;
+    pub fn start_single_frame_exposure(&self) -> Result<()> {
+        let handle = read_lock!(self.handle)?;
+        self.handle
+            .transition(CameraState::Open, CameraState::Exposing)?;
+        match unsafe { sdk_call!(self.id, ExpQHYCCDSingleFrame(*handle)) } {
+            QHYCCD_SUCCESS => Ok(()),
+            error_code => {
+                self.handle.set_state(CameraState::Open);
+                let error = StartSingleFrameExposureError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
-            _ => Ok(num),
         }
     }
 
-    /// Returns the readout mode name with the given index. Make sure to check the number of readout modes.
+    /// Gets the remaining exposure time
+    /// it needs to be called from a different thread than the one that called `start_single_frame_exposure`
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::{Sdk,Camera};
+    ///
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let num_readout_modes = camera.get_number_of_readout_modes().expect("get_number_of_readout_modes failed");
-    /// for index in 0..num_readout_modes {
-    ///    let readout_mode_name = camera.get_readout_mode_name(index).expect("get_readout_mode_name failed");
-    ///   println!("Readout mode {}: {}", index, readout_mode_name);
-    /// }
+    /// /* start exposure on a different thread*/
+    /// let remaining_exposure = camera.get_remaining_exposure_us().expect("get_remaining_exposure_us failed");
+    /// println!("Remaining exposure: {}", remaining_exposure);
     /// ```
-    pub fn get_readout_mode_name(&self, index: u32) -> Result<String> {
-        let handle = read_lock!(self.handle, GetReadoutModeNameError)?;
-        let mut name: [c_char; 80] = [0; 80];
-        match unsafe { GetQHYCCDReadModeName(handle, index, name.as_mut_ptr()) } {
+    pub fn get_remaining_exposure_us(&self) -> Result<u32> {
+        let handle = read_lock!(unserialized self.handle)?;
+        match unsafe { sdk_call!(self.id, GetQHYCCDExposureRemaining(*handle)) } {
             QHYCCD_ERROR => {
-                let error = GetReadoutModeNameError;
+                let error = GetExposureRemainingError;
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
-            _ => {
-                let name = match unsafe { CStr::from_ptr(name.as_ptr()) }.to_str() {
-                    Ok(name) => name,
-                    Err(error) => {
-                        tracing::error!(error = ?error);
-                        return Err(eyre!(error));
-                    }
-                };
-                Ok(name.to_string())
+                Err(error)
             }
+            remaining if { remaining <= 100 } => Ok(0),
+            remaining => Ok(remaining),
         }
     }
 
-    /// Returns the resolution of the readout mode with the given index. Make sure to check the number of readout modes.
+    /// Stops the current exposure
+    /// the image data stays in the camera and must be retrieved with `get_single_frame`
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::Sdk;
+    /// use qhyccd_rs::{Sdk,Camera};
+    ///
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let num_readout_modes = camera.get_number_of_readout_modes().expect("get_number_of_readout_modes failed");
-    /// for index in 0..num_readout_modes {
-    ///   let readout_mode_resolution = camera.get_readout_mode_resolution(index).expect("get_readout_mode_resolution failed");
-    ///  println!("Readout mode {}: {:?}", index, readout_mode_resolution);
-    /// }
+    /// /* start exposure on a different thread*/
+    /// camera.stop_exposure().expect("stop_exposure failed");
+    /// /* retrieve image data */
     /// ```
-    pub fn get_readout_mode_resolution(&self, index: u32) -> Result<(u32, u32)> {
-        let handle = read_lock!(self.handle, GetReadoutModeResolutionError)?;
-
-        let mut width: u32 = 0;
-        let mut height: u32 = 0;
-        match unsafe {
-            GetQHYCCDReadModeResolution(
-                handle,
-                index,
-                &mut width as *mut u32,
-                &mut height as *mut u32,
-            )
-        } {
-            QHYCCD_SUCCESS => Ok((width, height)),
-            _ => {
-                let error = GetReadoutModeResolutionError;
+    pub fn stop_exposure(&self) -> Result<()> {
+        let handle = read_lock!(unserialized self.handle)?;
+        match unsafe { sdk_call!(self.id, CancelQHYCCDExposing(*handle)) } {
+            QHYCCD_SUCCESS => Ok(()),
+            error_code => {
+                let error = StopExposureError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Returns the current readout mode of the camera
+    /// Stops the current exposure and discards the image data in the camera
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::Sdk;
-    /// use qhyccd_rs::Camera;
+    /// use qhyccd_rs::{Sdk,Camera};
+    ///
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let readout_mode = camera.get_readout_mode().expect("get_readout_mode failed");
-    /// println!("Readout mode: {}", readout_mode);
+    /// /* start exposure on a different thread*/
+    /// camera.abort_exposure_and_readout().expect("abort_exposure failed");
     /// ```
-    pub fn get_readout_mode(&self) -> Result<u32> {
-        let handle = read_lock!(self.handle, GetReadoutModeError)?;
-        let mut mode: u32 = 0;
-        match unsafe { GetQHYCCDReadMode(handle, &mut mode as *mut u32) } {
-            QHYCCD_SUCCESS => Ok(mode),
-            _ => {
-                let error = GetReadoutModeError;
+    pub fn abort_exposure_and_readout(&self) -> Result<()> {
+        sdk_features::require(SdkFeature::AbortReadout)?;
+        let handle = read_lock!(unserialized self.handle)?;
+        match unsafe { sdk_call!(self.id, CancelQHYCCDExposingAndReadout(*handle)) } {
+            QHYCCD_SUCCESS => {
+                self.handle.set_state(CameraState::Open);
+                Ok(())
+            }
+            error_code => {
+                let error = AbortExposureAndReadoutError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Not sure what this does, for a QHY178M Cool it returns 4010
+    /// Takes `flushes` frames with the minimum exposure time and discards them, to clear the
+    /// residual bulk image (RBI) of CCDs before the next frame. Use it together with
+    /// `set_rbi_removal` on cameras that support an infrared pre-flash. The exposure time is
+    /// restored afterwards, the camera has to be in single frame mode. If a flush fails, its
+    /// frame is aborted with `abort_exposure_and_readout` and the error of the flush is returned.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::Sdk;
-    /// use qhyccd_rs::Camera;
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control};
+    ///
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let tipe = camera.get_type().expect("get_type failed");
-    /// println!("Type: {}", tipe);
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.flush_residual_image(3).expect("flush_residual_image failed");
+    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
     /// ```
-    pub fn get_type(&self) -> Result<u32> {
-        let handle = read_lock!(self.handle, GetCameraTypeError)?;
-        match unsafe { GetQHYCCDType(handle) } {
-            QHYCCD_ERROR => {
-                let error = GetCameraTypeError;
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
+    pub fn flush_residual_image(&self, flushes: u32) -> Result<()> {
+        let exposure = self.get_parameter(Control::Exposure)?;
+        let (min, _, _) = self.get_parameter_min_max_step(Control::Exposure)?;
+        self.set_parameter(Control::Exposure, min)?;
+        let flushed = (0..flushes).try_for_each(|_| {
+            self.start_single_frame_exposure()?;
+            let buffer_size = self.get_image_size()?;
+            self.get_single_frame(buffer_size).map(|_| ())
+        });
+        if let Err(error) = flushed {
+            // a failed download leaves the flush frame exposing, which would block `close`
+            if self.state() == CameraState::Exposing {
+                if let Err(abort_error) = self.abort_exposure_and_readout() {
+                    tracing::warn!(error = ?abort_error, "could not abort the flush frame");
+                }
             }
-            camera_type => Ok(camera_type),
+            if let Err(restore_error) = self.set_parameter(Control::Exposure, exposure) {
+                tracing::warn!(error = ?restore_error, "could not restore the exposure time");
+            }
+            return Err(error);
         }
+        self.set_parameter(Control::Exposure, exposure)
     }
 
-    /// Sets the binning mode of the camera
-    /// Only symmetric binnings are supported
+    /// Enables or disables the RBI removal of the camera, `Control::RemoveRbi`, which pre-flashes
+    /// the sensor before every frame on CCDs prone to residual bulk images
     /// # Example
     /// ```no_run
     /// use qhyccd_rs::Sdk;
-    /// use qhyccd_rs::Camera;
+    ///
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_bin_mode(2, 2).expect("set_bin_mode failed");
+    /// camera.set_rbi_removal(true).expect("set_rbi_removal failed");
     /// ```
-    pub fn set_bin_mode(&self, bin_x: u32, bin_y: u32) -> Result<()> {
-        let handle = read_lock!(self.handle, SetBinModeError { error_code: 0 })?;
-        match unsafe { SetQHYCCDBinMode(handle, bin_x, bin_y) } {
-            QHYCCD_SUCCESS => Ok(()),
-            error_code => {
-                let error = SetBinModeError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
-        }
+    pub fn set_rbi_removal(&self, enabled: bool) -> Result<()> {
+        self.set_if_available(Control::RemoveRbi, enabled as u8 as f64)
     }
 
-    /// According to c-cod ethis does not work for all cameras
+    /// Returns `true` if the RBI removal of the camera is enabled, see `set_rbi_removal`
+    pub fn is_rbi_removal_enabled(&self) -> Result<bool> {
+        Ok(self.get_if_available(Control::RemoveRbi)? != 0.0)
+    }
+
+    /// Arms the camera for a single frame that is started by an external trigger pulse. This
+    /// enables the trigger function of the camera and starts a single frame exposure, which
+    /// then waits for the pulse. Collect the frame with `wait_for_triggered_frame`.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::Sdk;
-    /// use qhyccd_rs::Camera;
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control};
+    ///
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_debayer(false).expect("set_debayer failed");
-    ///```
-    pub fn set_debayer(&self, on: bool) -> Result<()> {
-        let handle = read_lock!(self.handle, SetDebayerError { error_code: 0 })?;
-        match unsafe { SetQHYCCDDebayerOnOff(handle, on) } {
-            QHYCCD_SUCCESS => Ok(()),
-            error_code => {
-                let error = SetDebayerError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
+    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.set_parameter(Control::Exposure, 10000.0).expect("set_param failed"); // this is in micro seconds
+    /// camera.arm_external_trigger().expect("arm_external_trigger failed");
+    /// let image = camera.wait_for_triggered_frame(Duration::from_secs(60)).expect("no triggered frame");
+    /// camera.disarm_external_trigger().expect("disarm_external_trigger failed");
+    /// ```
+    pub fn arm_external_trigger(&self) -> Result<()> {
+        if self
+            .is_control_available(Control::CamTrigerInterface)
+            .is_none()
+        {
+            let error = IsControlAvailableError {
+                control: Control::CamTrigerInterface,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        self.set_trigger_function(true)?;
+        self.start_single_frame_exposure().map_err(|error| {
+            // leave the camera as it was, with exposures starting immediately
+            if let Err(disarm_error) = self.set_trigger_function(false) {
+                tracing::warn!(error = ?disarm_error, "could not switch the trigger function off");
+            }
+            error
+        })
+    }
+
+    /// Waits for the frame of an exposure started with `arm_external_trigger` and downloads it.
+    /// Returns `TriggerTimeoutError` if no frame arrived within `timeout`. Any other error of the
+    /// SDK ends the wait with `GetSingleFrameError`. If the wait fails the pending exposure is
+    /// cancelled with `abort_exposure_and_readout`, so the camera is `CameraState::Open` again
+    /// and ready for the next `arm_external_trigger`. If the cancel fails, its error is returned
+    /// instead.
+    /// # Example
+    /// see `arm_external_trigger`
+    pub fn wait_for_triggered_frame(&self, timeout: Duration) -> Result<ImageData> {
+        let deadline = Instant::now() + timeout;
+        let buffer_size = self.get_image_size()?;
+        let mut width: u32 = 0;
+        let mut height: u32 = 0;
+        let mut bpp: u32 = 0;
+        let mut channels: u32 = 0;
+        let mut buffer = vec![0u8; buffer_size];
+        loop {
+            // locked per attempt, so other calls on the camera go on while it waits for the pulse
+            let handle = read_lock!(self.handle, CameraState::Exposing)?;
+            let attempt = Instant::now();
+            match unsafe {
+                sdk_call!(
+                    self.id,
+                    GetQHYCCDSingleFrame(
+                        *handle,
+                        &mut width as *mut u32,
+                        &mut height as *mut u32,
+                        &mut bpp as *mut u32,
+                        &mut channels as *mut u32,
+                        buffer.as_mut_ptr(),
+                    )
+                )
+            } {
+                QHYCCD_SUCCESS => {
+                    telemetry::frame_captured(&self.id, CaptureMode::Triggered, attempt.elapsed());
+                    self.handle.set_state(CameraState::Open);
+                    return Ok(ImageData {
+                        data: buffer,
+                        width,
+                        height,
+                        bits_per_pixel: bpp,
+                        channels,
+                    });
+                }
+                NO_FRAME_YET => {
+                    drop(handle);
+                    if Instant::now() >= deadline {
+                        self.abort_exposure_and_readout()?;
+                        let error = TriggerTimeoutError { timeout };
+                        tracing::error!(error = ?error);
+                        return Err(error);
+                    }
+                    tracing::trace!("waiting for triggered frame");
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                error_code => {
+                    drop(handle);
+                    let error = GetSingleFrameError { error_code };
+                    tracing::error!(error = ?error);
+                    self.abort_exposure_and_readout()?;
+                    return Err(error);
+                }
             }
         }
     }
 
-    /// Sets the Region of interest of the camera
+    /// Switches the trigger function of the camera off again, so exposures start immediately. The
+    /// exposure of a camera still waiting for the trigger is cancelled first with
+    /// `abort_exposure_and_readout`, the camera is `CameraState::Open` again afterwards.
+    /// # Example
+    /// see `arm_external_trigger`
+    pub fn disarm_external_trigger(&self) -> Result<()> {
+        if self.state() == CameraState::Exposing {
+            self.abort_exposure_and_readout()?;
+        }
+        self.set_trigger_function(false)
+    }
+
+    /// Sets the trigger mode of the camera. `TriggerMode::External` fails with
+    /// `IsControlAvailableError` for cameras without `Control::CamTriggerMode`.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::Sdk;
-    /// use qhyccd_rs::Camera;
-    /// use qhyccd_rs::CCDChipArea;
+    /// use qhyccd_rs::{Sdk, TriggerMode};
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let roi = CCDChipArea {
-    ///     start_x: 0,
-    ///     start_y: 0,
-    ///     width: 1000,
-    ///     height: 1000,
-    /// };
-    /// camera.set_roi(roi).expect("set_roi failed");
+    /// camera.set_trigger_mode(TriggerMode::External(0)).expect("set_trigger_mode failed");
+    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
     /// ```
-    pub fn set_roi(&self, roi: CCDChipArea) -> Result<()> {
-        let handle = read_lock!(self.handle, SetRoiError { error_code: 0 })?;
-        match unsafe {
-            SetQHYCCDResolution(handle, roi.start_x, roi.start_y, roi.width, roi.height)
-        } {
-            QHYCCD_SUCCESS => Ok(()),
+    pub fn set_trigger_mode(&self, mode: TriggerMode) -> Result<()> {
+        let mode = match mode {
+            TriggerMode::Off => return self.set_trigger_function(false),
+            TriggerMode::External(mode) => mode,
+        };
+        if self.is_control_available(Control::CamTriggerMode).is_none() {
+            let error = IsControlAvailableError {
+                control: Control::CamTriggerMode,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDTrigerMode(*handle, mode)) } {
+            QHYCCD_SUCCESS => self.set_trigger_function(true),
             error_code => {
-                let error = SetRoiError { error_code };
+                let error = SetTriggerModeError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Starts Live Video Mode on the camera
+    /// Enables the trigger output of the camera, which signals the exposure of each frame to
+    /// other devices. Fails with `IsControlAvailableError` for cameras without
+    /// `Control::CamTriggerOut`.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera,StreamMode};
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
-    /// camera.init().expect("init failed");
-    /// camera.begin_live().expect("begin_live failed");
+    /// camera.enable_trigger_out().expect("enable_trigger_out failed");
     /// ```
-    pub fn begin_live(&self) -> Result<()> {
-        let handle = read_lock!(self.handle, BeginLiveError { error_code: 0 })?;
-        match unsafe { BeginQHYCCDLive(handle) } {
+    pub fn enable_trigger_out(&self) -> Result<()> {
+        if self.is_control_available(Control::CamTriggerOut).is_none() {
+            let error = IsControlAvailableError {
+                control: Control::CamTriggerOut,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, EnableQHYCCDTrigerOut(*handle)) } {
             QHYCCD_SUCCESS => Ok(()),
             error_code => {
-                let error = BeginLiveError { error_code };
+                let error = EnableTriggerOutError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Stops Live Video Mode on the camera
+    /// Returns the names of the trigger interfaces of the camera, the index of a name is the
+    /// number to use with `set_trigger_interface`
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera,StreamMode};
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
-    /// camera.init().expect("init failed");
-    /// camera.begin_live().expect("begin_live failed");
-    /// /* Download images in between */
-    /// camera.end_live().expect("end_live failed");
+    /// let interfaces = camera.get_trigger_interfaces().expect("get_trigger_interfaces failed");
+    /// if let Some(index) = interfaces.iter().position(|name| name.contains("GPIO")) {
+    ///     camera.set_trigger_interface(index as u32).expect("set_trigger_interface failed");
+    /// }
     /// ```
-    pub fn end_live(&self) -> Result<()> {
-        let handle = read_lock!(self.handle, EndLiveError { error_code: 0 })?;
-        match unsafe { StopQHYCCDLive(handle) } {
+    pub fn get_trigger_interfaces(&self) -> Result<Vec<String>> {
+        sdk_features::require(SdkFeature::TriggerInterfaces)?;
+        let handle = read_lock!(self.handle)?;
+        let mut number: u32 = 0;
+        match unsafe {
+            sdk_call!(
+                self.id,
+                GetQHYCCDTrigerInterfaceNumber(*handle, &mut number as *mut u32)
+            )
+        } {
+            QHYCCD_SUCCESS => (),
+            error_code => {
+                let error = GetTriggerInterfacesError { error_code };
+                tracing::error!(error = ?error);
+                return Err(error);
+            }
+        }
+        (0..number)
+            .map(|index| {
+                let mut name: [c_char; 80] = [0; 80];
+                match unsafe {
+                    sdk_call!(
+                        self.id,
+                        GetQHYCCDTrigerInterfaceName(*handle, index, name.as_mut_ptr())
+                    )
+                } {
+                    QHYCCD_SUCCESS => Ok(unsafe { CStr::from_ptr(name.as_ptr()) }
+                        .to_string_lossy()
+                        .into_owned()),
+                    error_code => {
+                        let error = GetTriggerInterfacesError { error_code };
+                        tracing::error!(error = ?error);
+                        Err(error)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Selects the trigger interface by its index in `get_trigger_interfaces`
+    pub fn set_trigger_interface(&self, index: u32) -> Result<()> {
+        sdk_features::require(SdkFeature::TriggerInterfaces)?;
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDTrigerInterface(*handle, index)) } {
             QHYCCD_SUCCESS => Ok(()),
             error_code => {
-                let error = EndLiveError { error_code };
+                let error = SetTriggerInterfaceError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    fn set_trigger_function(&self, on: bool) -> Result<()> {
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDTrigerFunction(*handle, on)) } {
+            QHYCCD_SUCCESS => Ok(()),
+            error_code => {
+                let error = SetTriggerFunctionError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Returns the number of bytes needed to retrieve the image stored in the camera
+    /// Switches the GPS module of cameras like the QHY174M-GPS on or off. With GPS on the camera
+    /// writes a timing header into the first bytes of every frame, see `ImageData::gps_info`.
+    /// Fails with `IsControlAvailableError` for cameras without GPS.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
-    ///
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
-    /// camera.init().expect("init failed");
-    /// camera.set_parameter(Control::Exposure, 2000000.0).expect("set_param failed"); // this is in micro seconds
-    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
-    /// // wait for exposure to finish
-    /// let buffer_size = camera.get_image_size().expect("get_camera_image_size failed");
-    /// let image = camera.get_single_frame(buffer_size).expect("get_camera_single_frame failed");
+    /// camera.set_gps(true).expect("set_gps failed");
     /// ```
-    pub fn get_image_size(&self) -> Result<usize> {
-        let handle = read_lock!(self.handle, GetImageSizeError)?;
-        match unsafe { GetQHYCCDMemLength(handle) } {
-            QHYCCD_ERROR => {
-                let error = GetImageSizeError;
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
-            size => Ok(size as usize),
-        }
+    pub fn set_gps(&self, on: bool) -> Result<()> {
+        self.set_if_available(Control::CamGps, if on { 1.0 } else { 0.0 })
     }
 
-    /// Returns the image stored in the camera as `ImageData` struct if the camera is in Live Video Mode
+    /// Switches the LED calibration mode of the GPS module on or off. In calibration mode the
+    /// LED flashes at the positions set with `set_gps_pos_a` and `set_gps_pos_b`, which allows
+    /// measuring the delay between the GPS time and the actual start of the exposure.
+    pub fn set_gps_led_calibration_mode(&self, on: bool) -> Result<()> {
+        sdk_features::require(SdkFeature::Gps)?;
+        let handle = read_lock!(self.handle)?;
+        gps_result(unsafe { sdk_call!(self.id, SetQHYCCDGPSLedCalMode(*handle, on as u8)) })
+    }
+
+    /// Sets the position and the width of the calibration LED pulse, the SDK reports no result
+    pub fn set_gps_led_calibration(&self, position: u32, width: u8) -> Result<()> {
+        sdk_features::require(SdkFeature::Gps)?;
+        let handle = read_lock!(self.handle)?;
+        unsafe { sdk_call!(self.id, SetQHYCCDGPSLedCal(*handle, position, width)) };
+        Ok(())
+    }
+
+    /// Sets the position and the width of the calibration LED pulse at the start of the exposure,
+    /// the SDK reports no result
+    pub fn set_gps_pos_a(&self, is_slave: bool, position: u32, width: u8) -> Result<()> {
+        sdk_features::require(SdkFeature::Gps)?;
+        let handle = read_lock!(self.handle)?;
+        unsafe {
+            sdk_call!(
+                self.id,
+                SetQHYCCDGPSPOSA(*handle, is_slave as u8, position, width)
+            )
+        };
+        Ok(())
+    }
+
+    /// Sets the position and the width of the calibration LED pulse at the end of the exposure,
+    /// the SDK reports no result
+    pub fn set_gps_pos_b(&self, is_slave: bool, position: u32, width: u8) -> Result<()> {
+        sdk_features::require(SdkFeature::Gps)?;
+        let handle = read_lock!(self.handle)?;
+        unsafe {
+            sdk_call!(
+                self.id,
+                SetQHYCCDGPSPOSB(*handle, is_slave as u8, position, width)
+            )
+        };
+        Ok(())
+    }
+
+    /// Trims the frequency of the voltage controlled oscillator the GPS module uses for timing
+    pub fn set_gps_vcox_frequency(&self, frequency: u16) -> Result<()> {
+        sdk_features::require(SdkFeature::Gps)?;
+        let handle = read_lock!(self.handle)?;
+        gps_result(unsafe { sdk_call!(self.id, SetQHYCCDGPSVCOXFreq(*handle, frequency)) })
+    }
+
+    /// Returns the relative humidity inside the sensor chamber in percent. Fails with
+    /// `IsControlAvailableError` for cameras without humidity sensor.
     /// # Example
     /// ```no_run
-    /// use std::{thread, time::Duration};
-    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
-    ///
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
-    /// camera.init().expect("init failed");
-    /// camera.set_parameter(Control::Exposure, 10000.0).expect("set_param failed"); // this is in micro seconds
-    /// camera.begin_live().expect("begin_live failed");
-    /// let size = camera.get_image_size().expect("get_camera_image_size failed");
-    /// for _ in 0..1000 {
-    ///     let result = camera.get_live_frame(size);
-    ///     if result.is_err() {
-    ///         thread::sleep(Duration::from_millis(100));
-    ///         continue;
-    ///     }
-    ///     let image = result.unwrap();
-    ///    /* Do something with the image */
-    ///     break;
-    /// }
-    /// camera.end_live().expect("end_camera_live failed");
+    /// let humidity = camera.get_humidity().expect("get_humidity failed");
+    /// println!("humidity: {:.1}%", humidity);
     /// ```
-    pub fn get_live_frame(&self, buffer_size: usize) -> Result<ImageData> {
-        let handle = read_lock!(self.handle, GetLiveFrameError { error_code: 0 })?;
-        let mut width: u32 = 0;
-        let mut height: u32 = 0;
-        let mut bpp: u32 = 0;
-        let mut channels: u32 = 0;
-        let mut buffer = vec![0u8; buffer_size];
+    pub fn get_humidity(&self) -> Result<f64> {
+        sdk_features::require(SdkFeature::Humidity)?;
+        if self.is_control_available(Control::CamHumidity).is_none() {
+            let error = IsControlAvailableError {
+                control: Control::CamHumidity,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let handle = read_lock!(self.handle)?;
+        let mut humidity: f64 = 0.0;
         match unsafe {
-            GetQHYCCDLiveFrame(
-                handle,
-                &mut width as *mut u32,
-                &mut height as *mut u32,
-                &mut bpp as *mut u32,
-                &mut channels as *mut u32,
-                buffer.as_mut_ptr(),
+            sdk_call!(
+                self.id,
+                GetQHYCCDHumidity(*handle, &mut humidity as *mut f64)
             )
         } {
-            QHYCCD_SUCCESS => Ok(ImageData {
-                data: buffer,
-                width,
-                height,
-                bits_per_pixel: bpp,
-                channels,
-            }),
+            QHYCCD_SUCCESS => Ok(humidity),
             error_code => {
-                let error = GetLiveFrameError { error_code };
+                let error = GetHumidityError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Returns the image stored in the camera as `ImageData` struct if the camera is in Single Frame Mode
+    /// Returns the pressure inside the sensor chamber in mbar. Fails with
+    /// `IsControlAvailableError` for cameras without pressure sensor.
     /// # Example
-    ///
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
-    ///
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
-    /// camera.init().expect("init failed");
-    /// camera.set_parameter(Control::Exposure, 10000.0).expect("set_param failed"); // this is in micro seconds
-    /// camera.start_single_frame_exposure().expect("start_camera_single_frame_exposure failed");
-    /// let buffer_size = camera.get_image_size().expect("get_camera_image_size failed");
-    /// let image = camera.get_single_frame(buffer_size).expect("get_camera_single_frame failed");
+    /// let pressure = camera.get_pressure().expect("get_pressure failed");
+    /// println!("pressure: {:.1}mbar", pressure);
     /// ```
-    pub fn get_single_frame(&self, buffer_size: usize) -> Result<ImageData> {
-        let handle = read_lock!(self.handle, GetSingleFrameError { error_code: 0 })?;
-        let mut width: u32 = 0;
-        let mut height: u32 = 0;
-        let mut bpp: u32 = 0;
-        let mut channels: u32 = 0;
-        let mut buffer = vec![0u8; buffer_size];
+    pub fn get_pressure(&self) -> Result<f64> {
+        sdk_features::require(SdkFeature::Pressure)?;
+        if self.is_control_available(Control::CamPressure).is_none() {
+            let error = IsControlAvailableError {
+                control: Control::CamPressure,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let handle = read_lock!(self.handle)?;
+        let mut pressure: f64 = 0.0;
         match unsafe {
-            GetQHYCCDSingleFrame(
-                handle,
-                &mut width as *mut u32,
-                &mut height as *mut u32,
-                &mut bpp as *mut u32,
-                &mut channels as *mut u32,
-                buffer.as_mut_ptr(),
+            sdk_call!(
+                self.id,
+                GetQHYCCDPressure(*handle, &mut pressure as *mut f64)
             )
         } {
-            QHYCCD_SUCCESS => Ok(ImageData {
-                data: buffer,
-                width,
-                height,
-                bits_per_pixel: bpp,
-                channels,
-            }),
+            QHYCCD_SUCCESS => Ok(pressure),
             error_code => {
-                let error = GetSingleFrameError { error_code };
+                let error = GetPressureError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Get the chip area including overscan area
+    /// fails with `IsControlAvailableError` for cameras without flash configuration
+    fn require_flash_config(&self) -> Result<()> {
+        sdk_features::require(SdkFeature::FlashConfig)?;
+        if self
+            .is_control_available(Control::CamInitConfigFromFlash)
+            .is_none()
+        {
+            let error = IsControlAvailableError {
+                control: Control::CamInitConfigFromFlash,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Reads the startup configuration from the flash of the camera. Fails with
+    /// `IsControlAvailableError` for cameras without flash configuration.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera,CCDChipArea};
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let chip_area = camera.get_overscan_area().expect("get_overscan_area failed");
-    /// println!("Chip area: {:?}", chip_area);
+    /// let config = camera.read_flash_config().expect("read_flash_config failed");
+    /// println!("init PWM: {:?}, ULVO error: {:?}", config.init_pwm(), config.ulvo_error());
     /// ```
-    pub fn get_overscan_area(&self) -> Result<CCDChipArea> {
-        let handle = read_lock!(self.handle, GetOverscanAreaError { error_code: 0 })?;
-        let mut start_x: u32 = 0;
-        let mut start_y: u32 = 0;
-        let mut width: u32 = 0;
-        let mut height: u32 = 0;
-        match unsafe {
-            GetQHYCCDOverScanArea(
-                handle,
-                &mut start_x as *mut u32,
-                &mut start_y as *mut u32,
-                &mut width as *mut u32,
-                &mut height as *mut u32,
+    pub fn read_flash_config(&self) -> Result<FlashConfig> {
+        self.require_flash_config()?;
+        let handle = read_lock!(self.handle)?;
+        let mut raw = [0u8; FLASH_CONFIG_LEN];
+        unsafe {
+            sdk_call!(
+                self.id,
+                QHYCCDReadInitConfigFlash(*handle, raw.as_mut_ptr())
             )
-        } {
-            QHYCCD_SUCCESS => Ok(CCDChipArea {
-                start_x,
-                start_y,
-                width,
-                height,
-            }),
-            error_code => {
-                let error = GetOverscanAreaError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
-        }
+        };
+        Ok(FlashConfig::from_bytes(raw))
     }
 
-    /// Get the effective imaging chip area
+    /// Sets the cooler PWM, 0 to 255, the camera applies at power up. Fails with
+    /// `FlashConfigSectionError` if the flash configuration has no PWM section, the SDK would
+    /// silently ignore the call then.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera,CCDChipArea};
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// let chip_area = camera.get_effective_area().expect("get_overscan_area failed");
-    /// println!("Chip area: {:?}", chip_area);
+    /// camera.set_flash_init_pwm(0).expect("set_flash_init_pwm failed");
     /// ```
-    pub fn get_effective_area(&self) -> Result<CCDChipArea> {
-        let handle = read_lock!(self.handle, GetEffectiveAreaError { error_code: 0 })?;
-        let mut start_x: u32 = 0;
-        let mut start_y: u32 = 0;
-        let mut width: u32 = 0;
-        let mut height: u32 = 0;
-        match unsafe {
-            GetQHYCCDEffectiveArea(
-                handle,
-                &mut start_x as *mut u32,
-                &mut start_y as *mut u32,
-                &mut width as *mut u32,
-                &mut height as *mut u32,
-            )
-        } {
-            QHYCCD_SUCCESS => Ok(CCDChipArea {
-                start_x,
-                start_y,
-                width,
-                height,
-            }),
-            error_code => {
-                let error = GetEffectiveAreaError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
+    pub fn set_flash_init_pwm(&self, pwm: u8) -> Result<()> {
+        if !self.read_flash_config()?.has_pwm_section() {
+            let error = FlashConfigSectionError { section: "pwm" };
+            tracing::error!(error = ?error);
+            return Err(error);
         }
+        let handle = read_lock!(self.handle)?;
+        unsafe { sdk_call!(self.id, QHYCCDSetFlashInitPWM(*handle, pwm)) };
+        Ok(())
     }
 
-    /// Start a long exposure
-    /// Make sure to set the exposure time before calling this function
-    /// this function blocks the current thread and only returns when the exposure is finished
+    /// Clears the undervoltage lockout flag in the flash configuration. Fails with
+    /// `FlashConfigSectionError` if the flash configuration has no error section.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera,StreamMode,Control, ImageData};
-    ///
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// camera.set_stream_mode(StreamMode::SingleFrameMode).expect("set_stream_mode failed");
-    /// camera.init().expect("init failed");
-    /// camera.set_parameter(Control::Exposure, 2000000.0).expect("set_param failed"); // this is in micro seconds
-    /// camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+    /// camera.reset_flash_ulvo_error().expect("reset_flash_ulvo_error failed");
     /// ```
-    pub fn start_single_frame_exposure(&self) -> Result<()> {
-        let handle = read_lock!(self.handle, StartSingleFrameExposureError { error_code: 0 })?;
-        match unsafe { ExpQHYCCDSingleFrame(handle) } {
-            QHYCCD_SUCCESS => Ok(()),
-            error_code => {
-                let error = StartSingleFrameExposureError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
+    pub fn reset_flash_ulvo_error(&self) -> Result<()> {
+        if !self.read_flash_config()?.has_error_section() {
+            let error = FlashConfigSectionError { section: "err" };
+            tracing::error!(error = ?error);
+            return Err(error);
         }
+        let handle = read_lock!(self.handle)?;
+        unsafe { sdk_call!(self.id, QHYCCDResetFlashULVOError(*handle)) };
+        Ok(())
     }
 
-    /// Gets the remaining exposure time
-    /// it needs to be called from a different thread than the one that called `start_single_frame_exposure`
+    /// Erases the flash configuration. Fails with `IsControlAvailableError` for cameras without
+    /// flash configuration.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera};
-    ///
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// /* start exposure on a different thread*/
-    /// let remaining_exposure = camera.get_remaining_exposure_us().expect("get_remaining_exposure_us failed");
-    /// println!("Remaining exposure: {}", remaining_exposure);
+    /// camera.erase_flash_config().expect("erase_flash_config failed");
     /// ```
-    pub fn get_remaining_exposure_us(&self) -> Result<u32> {
-        let handle = read_lock!(self.handle, GetExposureRemainingError)?;
-        match unsafe { GetQHYCCDExposureRemaining(handle) } {
-            QHYCCD_ERROR => {
-                let error = GetExposureRemainingError;
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
-            remaining if { remaining <= 100 } => Ok(0),
-            remaining => Ok(remaining),
-        }
+    pub fn erase_flash_config(&self) -> Result<()> {
+        self.require_flash_config()?;
+        let handle = read_lock!(self.handle)?;
+        unsafe { sdk_call!(self.id, QHYCCDEraseInitConfigFlash(*handle)) };
+        Ok(())
     }
 
-    /// Stops the current exposure
-    /// the image data stays in the camera and must be retrieved with `get_single_frame`
+    /// Retrains the phase of the sensor data lines, which recovers large format CMOS sensors
+    /// from banding. Fails with `IsControlAvailableError` for cameras without phase retraining.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera};
-    ///
+    /// use qhyccd_rs::Sdk;
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// /* start exposure on a different thread*/
-    /// camera.stop_exposure().expect("stop_exposure failed");
-    /// /* retrieve image data */
+    /// camera.retrain_sensor_phase().expect("retrain_sensor_phase failed");
     /// ```
-    pub fn stop_exposure(&self) -> Result<()> {
-        let handle = read_lock!(self.handle, StopExposureError { error_code: 0 })?;
-        match unsafe { CancelQHYCCDExposing(handle) } {
-            QHYCCD_SUCCESS => Ok(()),
-            error_code => {
-                let error = StopExposureError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
+    pub fn retrain_sensor_phase(&self) -> Result<()> {
+        sdk_features::require(SdkFeature::SensorPhaseReTrain)?;
+        if self
+            .is_control_available(Control::CamSensorPhaseReTrain)
+            .is_none()
+        {
+            let error = IsControlAvailableError {
+                control: Control::CamSensorPhaseReTrain,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
         }
+        let handle = read_lock!(self.handle)?;
+        unsafe { sdk_call!(self.id, QHYCCDSensorPhaseReTrain(*handle)) };
+        Ok(())
     }
 
-    /// Stops the current exposure and discards the image data in the camera
+    /// Returns whether the sensor saw an undervoltage event. Fails with
+    /// `IsControlAvailableError` for cameras without ULVO status.
     /// # Example
     /// ```no_run
-    /// use qhyccd_rs::{Sdk,Camera};
-    ///
+    /// use qhyccd_rs::{Sdk, UlvoStatus};
     /// let sdk = Sdk::new().expect("SDK::new failed");
     /// let camera = sdk.cameras().last().expect("no camera found");
     /// camera.open().expect("open failed");
-    /// /* start exposure on a different thread*/
-    /// camera.abort_exposure_and_readout().expect("abort_exposure failed");
+    /// if let UlvoStatus::Undervoltage { code } = camera.ulvo_status().expect("ulvo_status failed") {
+    ///     println!("undervoltage event, status {}", code);
+    /// }
     /// ```
-    pub fn abort_exposure_and_readout(&self) -> Result<()> {
-        let handle = read_lock!(self.handle, AbortExposureAndReadoutError { error_code: 0 })?;
-        match unsafe { CancelQHYCCDExposingAndReadout(handle) } {
-            QHYCCD_SUCCESS => Ok(()),
-            error_code => {
-                let error = AbortExposureAndReadoutError { error_code };
-                tracing::error!(error = ?error);
-                Err(eyre!(error))
-            }
+    pub fn ulvo_status(&self) -> Result<UlvoStatus> {
+        if self
+            .is_control_available(Control::CamSensorUlvoStatus)
+            .is_none()
+        {
+            let error = IsControlAvailableError {
+                control: Control::CamSensorUlvoStatus,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        match self.get_parameter(Control::CamSensorUlvoStatus)? as u32 {
+            0 => Ok(UlvoStatus::Normal),
+            code => Ok(UlvoStatus::Undervoltage { code }),
         }
     }
 
@@ -1475,11 +3499,11 @@ impl Camera {
     /// let camera_is_color = camera.is_control_available(Control::CamColor).is_some(); //this returns a `BayerID` if it is a color camera
     /// ```
     pub fn is_control_available(&self, control: Control) -> Option<u32> {
-        let handle = match read_lock!(self.handle, IsControlAvailableError { control }) {
+        let handle = match read_lock!(self.handle) {
             Ok(handle) => handle,
             Err(_) => return None,
         };
-        match unsafe { IsQHYCCDControlAvailable(handle, control as u32) } {
+        match unsafe { sdk_call!(self.id, IsQHYCCDControlAvailable(*handle, control as u32)) } {
             QHYCCD_ERROR => {
                 let error = IsControlAvailableError { control };
                 tracing::debug!(control = ?error);
@@ -1489,6 +3513,24 @@ impl Camera {
         }
     }
 
+    /// Returns all controls from `Control::all` the camera reports as available
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// println!("Available controls: {:?}", camera.available_controls());
+    /// ```
+    pub fn available_controls(&self) -> Vec<Control> {
+        Control::all()
+            .iter()
+            .copied()
+            .filter(|control| self.is_control_available(*control).is_some())
+            .collect()
+    }
+
     /// Returns information about the chip in the camera
     /// # Example
     /// ```no_run
@@ -1500,7 +3542,7 @@ impl Camera {
     /// println!("Chip info: {:?}", chip_info);
     /// ```
     pub fn get_ccd_info(&self) -> Result<CCDChipInfo> {
-        let handle = read_lock!(self.handle, GetCCDInfoError { error_code: 0 })?;
+        let handle = read_lock!(self.handle)?;
         let mut chipw: f64 = 0.0;
         let mut chiph: f64 = 0.0;
         let mut imagew: u32 = 0;
@@ -1509,15 +3551,18 @@ impl Camera {
         let mut pixelh: f64 = 0.0;
         let mut bpp: u32 = 0;
         match unsafe {
-            GetQHYCCDChipInfo(
-                handle,
-                &mut chipw as *mut f64,
-                &mut chiph as *mut f64,
-                &mut imagew as *mut u32,
-                &mut imageh as *mut u32,
-                &mut pixelw as *mut f64,
-                &mut pixelh as *mut f64,
-                &mut bpp as *mut u32,
+            sdk_call!(
+                self.id,
+                GetQHYCCDChipInfo(
+                    *handle,
+                    &mut chipw as *mut f64,
+                    &mut chiph as *mut f64,
+                    &mut imagew as *mut u32,
+                    &mut imageh as *mut u32,
+                    &mut pixelw as *mut f64,
+                    &mut pixelh as *mut f64,
+                    &mut bpp as *mut u32,
+                )
             )
         } {
             QHYCCD_SUCCESS => Ok(CCDChipInfo {
@@ -1532,12 +3577,13 @@ impl Camera {
             error_code => {
                 let error = GetCCDInfoError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Sets the USB transfer mode to either 8 or 16 bit
+    /// Sets the USB transfer mode to 8, 16 or, on cameras supporting `PixelFormat::U32`, 32 bit.
+    /// Fails with `UnsupportedBitDepthError` when asked for 32 bit on other cameras.
     ///
     /// # Example
     /// ```no_run
@@ -1548,13 +3594,27 @@ impl Camera {
     /// camera.set_bit_mode(8).expect("set_bit_mode failed");
     /// ```
     pub fn set_bit_mode(&self, mode: u32) -> Result<()> {
-        let handle = read_lock!(self.handle, SetBitModeError { error_code: 0 })?;
-        match unsafe { SetQHYCCDBitsMode(handle, mode) } {
-            QHYCCD_SUCCESS => Ok(()),
+        if mode == PixelFormat::U32.bits()
+            && self
+                .is_control_available(PixelFormat::U32.control())
+                .is_none()
+        {
+            let error = UnsupportedBitDepthError {
+                bits_per_pixel: mode,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDBitsMode(*handle, mode)) } {
+            QHYCCD_SUCCESS => {
+                self.update_frame_geometry(|geometry| geometry.bits_per_pixel = Some(mode));
+                Ok(())
+            }
             error_code => {
                 let error = SetBitModeError { error_code };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
@@ -1572,13 +3632,18 @@ impl Camera {
     /// };
     /// ```
     pub fn get_parameter(&self, control: Control) -> Result<f64> {
-        let handle = read_lock!(self.handle, GetParameterError { control })?;
-        let res = unsafe { GetQHYCCDParam(handle, control as u32) };
+        let handle = read_lock!(self.handle)?;
+        let res = unsafe { sdk_call!(self.id, GetQHYCCDParam(*handle, control as u32)) };
         if (res - QHYCCD_ERROR_F64).abs() < f64::EPSILON {
             let error = GetParameterError { control };
             tracing::error!(error = ?error);
-            Err(eyre!(error))
+            Err(error)
         } else {
+            match control {
+                Control::CurTemp => telemetry::sensor_temperature(&self.id, res),
+                Control::CurPWM => telemetry::cooler_power(&self.id, cooling::pwm_to_percent(res)),
+                _ => (),
+            }
             Ok(res)
         }
     }
@@ -1593,29 +3658,36 @@ impl Camera {
     /// let (min_exposure, max_exposure, exposure_resolution) = camera.get_parameter_min_max_step(Control::Exposure).expect("getting min,max,step failed");
     /// ```
     pub fn get_parameter_min_max_step(&self, control: Control) -> Result<(f64, f64, f64)> {
-        let handle = read_lock!(self.handle, GetMinMaxStepError { control })?;
+        let handle = read_lock!(self.handle)?;
         let mut min: f64 = 0.0;
         let mut max: f64 = 0.0;
         let mut step: f64 = 0.0;
         match unsafe {
-            GetQHYCCDParamMinMaxStep(
-                handle,
-                control as u32,
-                &mut min as *mut f64,
-                &mut max as *mut f64,
-                &mut step as *mut f64,
+            sdk_call!(
+                self.id,
+                GetQHYCCDParamMinMaxStep(
+                    *handle,
+                    control as u32,
+                    &mut min as *mut f64,
+                    &mut max as *mut f64,
+                    &mut step as *mut f64,
+                )
             )
         } {
             QHYCCD_SUCCESS => Ok((min, max, step)),
             _ => {
                 let error = GetMinMaxStepError { control };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
 
-    /// Sets the value for a given control
+    /// Sets the value for a given control, the write is delayed if it comes in earlier than the
+    /// rate limit of the control allows and dropped with `SupersededWriteError` if a newer write
+    /// of the control comes in while it waits, see `set_rate_limit`. If the SDK rejects the value,
+    /// the error is `IsControlAvailableError` for a control the camera does not have,
+    /// `ParameterOutOfRangeError` for a value outside its limits and `SetParameterError` otherwise.
     /// # Example
     /// ```no_run
     /// use qhyccd_rs::{Sdk,Camera,Control};
@@ -1625,13 +3697,34 @@ impl Camera {
     /// camera.set_parameter(Control::Exposure, 2000000.0).expect("set_parameter failed");
     /// ```
     pub fn set_parameter(&self, control: Control, value: f64) -> Result<()> {
-        let handle = read_lock!(self.handle, SetParameterError { error_code: 0 })?;
-        match unsafe { SetQHYCCDParam(handle, control as u32, value) } {
+        // wait before locking, other calls on the camera go on while a write is delayed
+        if !self.wait_for_rate_limit(control) {
+            let error = SupersededWriteError { control };
+            tracing::debug!(error = ?error);
+            return Err(error);
+        }
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, SetQHYCCDParam(*handle, control as u32, value)) } {
             QHYCCD_SUCCESS => Ok(()),
             error_code => {
-                let error = SetParameterError { error_code };
+                // the SDK hardly ever says more than QHYCCD_ERROR, so look for the reason
+                let error = if self.is_control_available(control).is_none() {
+                    IsControlAvailableError { control }
+                } else {
+                    match self.get_parameter_min_max_step(control) {
+                        Ok((min, max, _)) if !(min..=max).contains(&value) => {
+                            ParameterOutOfRangeError {
+                                control,
+                                value,
+                                min,
+                                max,
+                            }
+                        }
+                        _ => SetParameterError { error_code },
+                    }
+                };
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
@@ -1649,10 +3742,215 @@ impl Camera {
     pub fn set_if_available(&self, control: Control, value: f64) -> Result<()> {
         match self.is_control_available(control) {
             Some(_) => self.set_parameter(control, value),
-            None => Err(eyre!(IsControlAvailableError { control })),
+            None => Err(IsControlAvailableError { control }),
+        }
+    }
+
+    /// Convinience function that returns the value for a given control if it is available
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk,Camera,Control};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let gain = camera.get_if_available(Control::Gain).expect("failed to get gain");
+    /// ```
+    pub fn get_if_available(&self, control: Control) -> Result<f64> {
+        match self.is_control_available(control) {
+            Some(_) => self.get_parameter(control),
+            None => Err(IsControlAvailableError { control }),
+        }
+    }
+
+    /// Enables or disables the on-camera image processing, `Control::ImgProc`. Disable it to get
+    /// unaltered raw data for calibration and science frames, and enable it for good looking
+    /// live views. Frames processed on the camera should not be calibrated again with the
+    /// arithmetic of `ImageData`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_image_processing(false).expect("set_image_processing failed");
+    /// ```
+    pub fn set_image_processing(&self, enabled: bool) -> Result<()> {
+        self.set_if_available(Control::ImgProc, enabled as u8 as f64)
+    }
+
+    /// Returns `true` if the on-camera image processing is enabled, see `set_image_processing`.
+    /// Cameras without `Control::ImgProc` never process images, so this returns `false` for them.
+    pub fn is_image_processing_enabled(&self) -> Result<bool> {
+        match self.is_control_available(Control::ImgProc) {
+            Some(_) => Ok(self.get_parameter(Control::ImgProc)? != 0.0),
+            None => Ok(false),
+        }
+    }
+
+    /// Switches CMOS sensors between rolling shutter and global reset readout, see
+    /// `ShutterReadoutMode` for the timing implications. Cameras without `Control::GlobalReset`
+    /// return `IsControlAvailableError`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk, ShutterReadoutMode};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera
+    ///     .set_shutter_readout_mode(ShutterReadoutMode::GlobalReset)
+    ///     .expect("set_shutter_readout_mode failed");
+    /// ```
+    pub fn set_shutter_readout_mode(&self, mode: ShutterReadoutMode) -> Result<()> {
+        self.set_if_available(Control::GlobalReset, mode as u8 as f64)
+    }
+
+    /// Returns the current shutter readout mode, see `set_shutter_readout_mode`. Cameras without
+    /// `Control::GlobalReset` always use `ShutterReadoutMode::Rolling`.
+    pub fn get_shutter_readout_mode(&self) -> Result<ShutterReadoutMode> {
+        match self.is_control_available(Control::GlobalReset) {
+            Some(_) if self.get_parameter(Control::GlobalReset)? != 0.0 => {
+                Ok(ShutterReadoutMode::GlobalReset)
+            }
+            _ => Ok(ShutterReadoutMode::Rolling),
+        }
+    }
+
+    /// Switches the sensor between high and low conversion gain. The gain curve changes with the
+    /// conversion gain, so set it before characterizing the camera. Cameras without
+    /// `Control::CamLightPerformanceMode` return `IsControlAvailableError`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{ConversionGain, Sdk};
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_conversion_gain(ConversionGain::High).expect("set_conversion_gain failed");
+    /// ```
+    pub fn set_conversion_gain(&self, gain: ConversionGain) -> Result<()> {
+        self.set_if_available(Control::CamLightPerformanceMode, gain as u8 as f64)
+    }
+
+    /// Returns the current conversion gain, see `set_conversion_gain`
+    pub fn get_conversion_gain(&self) -> Result<ConversionGain> {
+        match self.get_if_available(Control::CamLightPerformanceMode)? != 0.0 {
+            true => Ok(ConversionGain::High),
+            false => Ok(ConversionGain::Low),
         }
     }
 
+    /// Sets the shutter motor / anti-dew heater to `target`. The SDK exposes the heater through
+    /// `Control::CamShutterMotorHeatingInterface`, cameras without it return `IsControlAvailableError`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_heater(1.0).expect("set_heater failed");
+    /// ```
+    pub fn set_heater(&self, target: f64) -> Result<()> {
+        self.set_if_available(Control::CamShutterMotorHeatingInterface, target)
+    }
+
+    /// Returns the current shutter motor / anti-dew heater setting, see `set_heater`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// println!("Heater: {}", camera.get_heater().expect("get_heater failed"));
+    /// ```
+    pub fn get_heater(&self) -> Result<f64> {
+        self.get_if_available(Control::CamShutterMotorHeatingInterface)
+    }
+
+    /// Enables or disables the row denoise of the camera, `Control::RowDeNoise`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_row_denoise(true).expect("set_row_denoise failed");
+    /// ```
+    pub fn set_row_denoise(&self, enabled: bool) -> Result<()> {
+        self.set_if_available(Control::RowDeNoise, enabled as u8 as f64)
+    }
+
+    /// Returns `true` if the row denoise of the camera is enabled, see `set_row_denoise`
+    pub fn is_row_denoise_enabled(&self) -> Result<bool> {
+        Ok(self.get_if_available(Control::RowDeNoise)? != 0.0)
+    }
+
+    /// Enables or disables buffering of frames in the DDR memory of the camera, `Control::DDR`.
+    /// The buffer evens out USB hiccups in live mode, which would otherwise drop frames.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_ddr_enabled(true).expect("set_ddr_enabled failed");
+    /// camera.set_ddr_read_threshold(2).expect("set_ddr_read_threshold failed");
+    /// ```
+    pub fn set_ddr_enabled(&self, enabled: bool) -> Result<()> {
+        self.set_if_available(Control::DDR, enabled as u8 as f64)
+    }
+
+    /// Returns `true` if DDR buffering is enabled, see `set_ddr_enabled`
+    pub fn is_ddr_enabled(&self) -> Result<bool> {
+        Ok(self.get_if_available(Control::DDR)? != 0.0)
+    }
+
+    /// Returns the amount of data currently held in the DDR buffer as reported by the camera,
+    /// `Control::DDRBufferCapacity`. A level that keeps growing in live mode means frames are
+    /// not read fast enough and will be dropped once the buffer is full.
+    pub fn get_ddr_fill_level(&self) -> Result<u64> {
+        Ok(self.get_if_available(Control::DDRBufferCapacity)?.max(0.0) as u64)
+    }
+
+    /// Sets the amount of buffered data at which the camera starts sending frames from the DDR
+    /// buffer, `Control::DDRBufferReadThreshold`
+    pub fn set_ddr_read_threshold(&self, threshold: u32) -> Result<()> {
+        self.set_if_available(Control::DDRBufferReadThreshold, threshold as f64)
+    }
+
+    /// Returns the DDR read threshold, see `set_ddr_read_threshold`
+    pub fn get_ddr_read_threshold(&self) -> Result<u32> {
+        Ok(self
+            .get_if_available(Control::DDRBufferReadThreshold)?
+            .max(0.0) as u32)
+    }
+
+    /// Enables or disables the amp glow suppression of the camera, `Control::Ampv`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    ///
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_amp_glow_suppression(true).expect("set_amp_glow_suppression failed");
+    /// ```
+    pub fn set_amp_glow_suppression(&self, enabled: bool) -> Result<()> {
+        self.set_if_available(Control::Ampv, enabled as u8 as f64)
+    }
+
+    /// Returns `true` if the amp glow suppression of the camera is enabled, see
+    /// `set_amp_glow_suppression`
+    pub fn is_amp_glow_suppression_enabled(&self) -> Result<bool> {
+        Ok(self.get_if_available(Control::Ampv)? != 0.0)
+    }
+
     /// Returns `true` if a filter wheel is plugged into the given camera
     /// # Example
     /// ```no_run
@@ -1665,14 +3963,14 @@ impl Camera {
     /// println!("Is filter wheel plugged in: {}", is_cfw_plugged_in);
     /// ```
     pub fn is_cfw_plugged_in(&self) -> Result<bool> {
-        let handle = read_lock!(self.handle, IsCfwPluggedInError)?;
-        match unsafe { IsQHYCCDCFWPlugged(handle) } {
+        let handle = read_lock!(self.handle)?;
+        match unsafe { sdk_call!(self.id, IsQHYCCDCFWPlugged(*handle)) } {
             QHYCCD_SUCCESS => Ok(true),
             QHYCCD_ERROR => Ok(false),
             _ => {
                 let error = IsCfwPluggedInError;
                 tracing::error!(error = ?error);
-                Err(eyre!(error))
+                Err(error)
             }
         }
     }
@@ -1691,33 +3989,40 @@ impl Camera {
         if self.is_open()? {
             return Ok(());
         }
-        // read and see if the handle is already Some(_)
+        load_library()?;
+        let _call = self.handle.calls.lock();
         let mut lock = self.handle.write().map_err(|err| {
             tracing::error!(error=?err);
-            eyre!("Could not acquire write lock on camera handle")
+            HandleLockError
         })?;
+        // another clone may have opened the camera while this one waited for the locks
+        if matches!(*lock, Some(handle) if handle.is_valid()) {
+            return Ok(());
+        }
         unsafe {
             match std::ffi::CString::new(self.id.clone()) {
                 Ok(c_id) => {
-                    let handle = OpenQHYCCD(c_id.as_ptr());
+                    let handle = sdk_call!(self.id, OpenQHYCCD(c_id.as_ptr()));
                     if handle.is_null() {
                         let error = OpenCameraError;
                         tracing::error!(error = ?error);
-                        return Err(eyre!(error));
+                        return Err(error);
                     }
-                    *lock = Some(QHYCCDHandle { ptr: handle });
+                    *lock = Some(QHYCCDHandle::new(handle));
                     Ok(())
                 }
                 Err(error) => {
                     tracing::error!(error = ?error);
-                    Err(eyre!(error))
+                    Err(error.into())
                 }
             }
         }
     }
 
     /// Closes the camera. If you have to call this function, you can then open the camera again by
-    /// calling `open`. Calling close on a camera that is not open does not do anything.
+    /// calling `open`. Calling close on a camera that is not open does not do anything. A running
+    /// download is waited for, a camera still `CameraState::Exposing` or `CameraState::Live`
+    /// fails with `CameraStateError`, abort the exposure or end live mode first.
     /// # Example
     /// ```no_run
     /// use qhyccd_rs::{Sdk,Camera};
@@ -1727,16 +4032,27 @@ impl Camera {
     /// camera.close().expect("close failed");
     /// ```
     pub fn close(&self) -> Result<()> {
-        if !self.is_open()? {
-            return Ok(());
-        }
+        let _call = self.handle.calls.lock();
         let mut lock = self.handle.write().map_err(|err| {
             tracing::error!(error=?err);
-            eyre!("Could not acquire write lock on camera handle")
+            HandleLockError
         })?;
 
+        let sdk = (*lock).and_then(|handle| SdkCall::begin(handle.generation));
         match *lock {
-            Some(handle) => match unsafe { CloseQHYCCD(handle.ptr) } {
+            Some(_) if sdk.is_none() => {
+                lock.take();
+                Ok(())
+            }
+            Some(handle) if matches!(handle.state, CameraState::Exposing | CameraState::Live) => {
+                let error = CameraStateError {
+                    state: handle.state,
+                    expected: CameraState::Open,
+                };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+            Some(handle) => match unsafe { sdk_call!(self.id, CloseQHYCCD(handle.ptr)) } {
                 QHYCCD_SUCCESS => {
                     lock.take();
                     Ok(())
@@ -1744,7 +4060,7 @@ impl Camera {
                 error_code => {
                     let error = CloseCameraError { error_code };
                     tracing::error!(error = ?error);
-                    Err(eyre!(error))
+                    Err(error)
                 }
             },
             None => Ok(()),
@@ -1764,21 +4080,201 @@ impl Camera {
     pub fn is_open(&self) -> Result<bool> {
         let lock = self.handle.read().map_err(|err| {
             tracing::error!(error=?err);
-            eyre!("Could not acquire read lock on camera handle")
+            HandleLockError
         })?;
-        Ok((*lock).is_some())
+        Ok(matches!(*lock, Some(handle) if handle.is_valid()))
+    }
+
+    /// Returns what the camera is doing. Methods that only work in one state, like
+    /// `get_live_frame` in `CameraState::Live`, fail with `CameraStateError` in the others.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{CameraState, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// assert_eq!(camera.state(), CameraState::Closed);
+    /// camera.open().expect("open failed");
+    /// assert_eq!(camera.state(), CameraState::Open);
+    /// ```
+    pub fn state(&self) -> CameraState {
+        self.handle.state()
+    }
+}
+
+/// maps the result of the GPS functions of the SDK
+fn gps_result(error_code: u32) -> Result<()> {
+    match error_code {
+        QHYCCD_SUCCESS => Ok(()),
+        error_code => {
+            let error = SetGpsError { error_code };
+            tracing::error!(error = ?error);
+            Err(error)
+        }
+    }
+}
+
+/// the SDK has no dedicated code for a frame that has not arrived yet, polling for a live or a
+/// triggered frame returns its generic error code until the frame is there
+pub(crate) const NO_FRAME_YET: u32 = QHYCCD_ERROR;
+
+/// downloads a live frame, returns the SDK error code if no frame is available
+fn read_live_frame(
+    camera: &str,
+    handle: *const std::ffi::c_void,
+    buffer_size: usize,
+) -> std::result::Result<ImageData, u32> {
+    let mut buffer = vec![0u8; buffer_size];
+    read_live_frame_into(camera, handle, &mut buffer).map(|info| info.into_image(buffer))
+}
+
+/// downloads a live frame into `buffer`, which has to hold at least `get_image_size` bytes
+#[allow(unused_unsafe)]
+fn read_live_frame_into(
+    camera: &str,
+    handle: *const std::ffi::c_void,
+    buffer: &mut [u8],
+) -> std::result::Result<FrameInfo, u32> {
+    let mut info = FrameInfo {
+        width: 0,
+        height: 0,
+        bits_per_pixel: 0,
+        channels: 0,
+    };
+    let start = Instant::now();
+    match unsafe {
+        sdk_call!(
+            camera,
+            GetQHYCCDLiveFrame(
+                handle,
+                &mut info.width as *mut u32,
+                &mut info.height as *mut u32,
+                &mut info.bits_per_pixel as *mut u32,
+                &mut info.channels as *mut u32,
+                buffer.as_mut_ptr(),
+            )
+        )
+    } {
+        QHYCCD_SUCCESS => {
+            telemetry::frame_captured(camera, CaptureMode::Live, start.elapsed());
+            Ok(info)
+        }
+        error_code => Err(error_code),
+    }
+}
+
+/// downloads a single frame into `buffer`, which has to hold at least `get_image_size` bytes
+#[allow(unused_unsafe)]
+fn read_single_frame_into(
+    camera: &str,
+    handle: *const std::ffi::c_void,
+    buffer: &mut [u8],
+) -> std::result::Result<FrameInfo, u32> {
+    let mut info = FrameInfo {
+        width: 0,
+        height: 0,
+        bits_per_pixel: 0,
+        channels: 0,
+    };
+    let start = Instant::now();
+    match unsafe {
+        sdk_call!(
+            camera,
+            GetQHYCCDSingleFrame(
+                handle,
+                &mut info.width as *mut u32,
+                &mut info.height as *mut u32,
+                &mut info.bits_per_pixel as *mut u32,
+                &mut info.channels as *mut u32,
+                buffer.as_mut_ptr(),
+            )
+        )
+    } {
+        QHYCCD_SUCCESS => {
+            telemetry::frame_captured(camera, CaptureMode::Single, start.elapsed());
+            Ok(info)
+        }
+        error_code => Err(error_code),
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Iterator over the frames buffered in the camera in Live Video Mode, returned from
+/// `Camera::live_frames`. `next` never waits, it returns `None` if no frame is buffered right
+/// now, so the iterator can continue to yield frames after returning `None`.
+pub struct LiveFrameIter {
+    camera: Camera,
+    buffer_size: usize,
+}
+
+impl LiveFrameIter {
+    /// Drains up to `max_n` frames buffered in the camera or its DDR memory. Waits up to
+    /// `max_wait` for the first frame and returns as soon as the buffer is empty after that, so
+    /// the result is empty if no frame arrived within `max_wait`. The camera handle is only held
+    /// while reading a frame, other calls can run while waiting. Any other error of the SDK fails
+    /// with `GetLiveFrameError`, unless frames were already drained, those are returned and the
+    /// error is logged.
+    pub fn next_batch(&mut self, max_n: usize, max_wait: Duration) -> Result<Vec<ImageData>> {
+        let deadline = Instant::now() + max_wait;
+        let mut frames = Vec::with_capacity(max_n);
+        while frames.len() < max_n {
+            let frame = match read_lock!(self.camera.handle, CameraState::Live) {
+                Ok(handle) => read_live_frame(&self.camera.id, *handle, self.buffer_size),
+                Err(error) if frames.is_empty() => return Err(error),
+                // live mode ended while draining, the frames read so far are still good
+                Err(_) => break,
+            };
+            match frame {
+                Ok(image) => frames.push(image),
+                Err(NO_FRAME_YET) if !frames.is_empty() || Instant::now() >= deadline => break,
+                Err(NO_FRAME_YET) => std::thread::sleep(Duration::from_millis(1)),
+                Err(error_code) if frames.is_empty() => {
+                    let error = GetLiveFrameError { error_code };
+                    tracing::error!(error = ?error);
+                    return Err(error);
+                }
+                Err(error_code) => {
+                    let error = GetLiveFrameError { error_code };
+                    tracing::warn!(error = ?error, frames = frames.len(), "returning partial batch");
+                    break;
+                }
+            }
+        }
+        Ok(frames)
     }
 }
 
-unsafe impl Send for Camera {}
-unsafe impl Sync for Camera {}
+impl Iterator for LiveFrameIter {
+    type Item = ImageData;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let handle = read_lock!(self.camera.handle, CameraState::Live).ok()?;
+        read_live_frame(&self.camera.id, *handle, self.buffer_size).ok()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The state of a filter wheel returned from `FilterWheel::status`
+pub enum FilterWheelStatus {
+    /// the wheel is standing at a slot
+    Idle,
+    /// the wheel is moving to a new slot
+    Moving,
+    /// the wheel reported something that is neither a slot nor moving
+    Error,
+}
 
 #[derive(Educe)]
 #[educe(Debug, Clone, PartialEq)]
 /// The representation of a filter wheel. It is constructed by the SDK and can be used to
-/// interact with the filter wheel - every filter wheel is always plugged into a camera.
+/// interact with the filter wheel - every filter wheel is always plugged into a camera. Its
+/// calls into the SDK are serialized with the ones of that camera.
 pub struct FilterWheel {
     camera: Camera,
+    #[educe(PartialEq(ignore))]
+    slots: Arc<RwLock<Vec<FilterSlot>>>,
+    /// whether `home` succeeded since the filter wheel was last closed
+    #[educe(PartialEq(ignore))]
+    homed: Arc<AtomicBool>,
 }
 
 /// Filter wheels are directly connected to the QHY camera and can be controlled through the camera
@@ -1793,7 +4289,11 @@ impl FilterWheel {
     /// println!("FilterWheel: {:?}", fw);
     /// ```
     pub fn new(camera: Camera) -> Self {
-        Self { camera }
+        Self {
+            camera,
+            slots: Arc::new(RwLock::new(Vec::new())),
+            homed: Arc::new(AtomicBool::new(false)),
+        }
     }
 
     /// Returns the id of the filter wheel
@@ -1858,6 +4358,7 @@ impl FilterWheel {
     /// fw.close().expect("close failed");
     /// ```
     pub fn close(&self) -> Result<()> {
+        self.homed.store(false, Ordering::SeqCst);
         self.camera.close()
     }
 
@@ -1882,7 +4383,44 @@ impl FilterWheel {
             ),
             None => {
                 tracing::debug!("I'm a filter wheel without filters. :(");
-                Err(eyre!(GetNumberOfFiltersError))
+                Err(GetNumberOfFiltersError)
+            }
+        }
+    }
+
+    /// Returns whether the filter wheel is moving, so clients can poll for the end of a move
+    /// started with `set_fw_position` instead of waiting a fixed time
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{FilterWheelStatus, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let fw = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// fw.open().expect("open failed");
+    /// fw.set_fw_position(3).expect("set_fw_position failed");
+    /// while fw.status().expect("status failed") == FilterWheelStatus::Moving {
+    ///     std::thread::sleep(Duration::from_millis(100));
+    /// }
+    /// ```
+    pub fn status(&self) -> Result<FilterWheelStatus> {
+        let handle = read_lock!(self.camera.handle)?;
+        let mut status: [c_char; 64] = [0; 64];
+        match unsafe {
+            sdk_call!(
+                self.camera.id,
+                GetQHYCCDCFWStatus(*handle, status.as_mut_ptr())
+            )
+        } {
+            //the wheel reports the ASCII value of the slot or 'N' while it is moving
+            QHYCCD_SUCCESS => Ok(match status[0] as u8 {
+                b'N' => FilterWheelStatus::Moving,
+                b'0'..=b'~' => FilterWheelStatus::Idle,
+                _ => FilterWheelStatus::Error,
+            }),
+            error_code => {
+                let error = GetCfwStatusError { error_code };
+                tracing::error!(error = ?error);
+                Err(error)
             }
         }
     }
@@ -1904,12 +4442,12 @@ impl FilterWheel {
                 Ok(position) => Ok((position - 48_f64) as u32), //removing ASCII offset
                 Err(error) => {
                     tracing::error!(error = ?error);
-                    Err(eyre!(error))
+                    Err(error)
                 }
             },
             None => {
                 tracing::debug!("No filter wheel plugged in.");
-                Err(eyre!(GetCfwPositionError))
+                Err(GetCfwPositionError)
             }
         }
     }
@@ -1932,19 +4470,144 @@ impl FilterWheel {
                 .map_err(|_| {
                     let error = SetCfwPositionError;
                     tracing::error!(error = ?error);
-                    eyre!(error)
+                    error
                 }),
             None => {
                 tracing::debug!("No filter wheel plugged in.");
-                Err(eyre!(SetCfwPositionError))
+                Err(SetCfwPositionError)
+            }
+        }
+    }
+
+    /// Moves the filter wheel to `position` and waits until it stopped there. Fails with
+    /// `FilterWheelMoveTimeoutError` if the wheel did not settle within `timeout`.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let fw = sdk.filter_wheels().last().expect("no filter wheel found");
+    /// fw.open().expect("open failed");
+    /// fw.move_to_position(3, Duration::from_secs(30)).expect("move_to_position failed");
+    /// ```
+    pub fn move_to_position(&self, position: u32, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        self.set_fw_position(position)?;
+        loop {
+            // wheels without status support are only checked by their position
+            let moving = matches!(self.status(), Ok(FilterWheelStatus::Moving));
+            if !moving && matches!(self.get_fw_position(), Ok(current) if current == position) {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                let error = FilterWheelMoveTimeoutError { position, timeout };
+                tracing::error!(error = ?error);
+                return Err(error);
             }
+            std::thread::sleep(
+                Duration::from_millis(100).min(deadline.saturating_duration_since(Instant::now())),
+            );
         }
     }
 }
 
+#[cfg(test)]
+mod test_accessory;
+#[cfg(test)]
+mod test_analysis;
+#[cfg(test)]
+mod test_arithmetic;
+#[cfg(all(test, feature = "ndarray"))]
+mod test_array;
+#[cfg(all(test, feature = "async"))]
+mod test_async_api;
+#[cfg(test)]
+mod test_autofocus;
+#[cfg(test)]
+mod test_bit_depth;
+#[cfg(test)]
+mod test_call_lock;
 #[cfg(test)]
 mod test_camera;
 #[cfg(test)]
+mod test_camera_id;
+#[cfg(test)]
+mod test_capabilities;
+#[cfg(test)]
+mod test_capture;
+#[cfg(test)]
+mod test_chamber;
+#[cfg(test)]
+mod test_control;
+#[cfg(test)]
+mod test_cooling;
+#[cfg(test)]
+mod test_demosaic;
+#[cfg(all(test, feature = "image"))]
+mod test_dynamic_image;
+#[cfg(test)]
+mod test_error_code;
+#[cfg(test)]
+mod test_exposure;
+#[cfg(test)]
+mod test_filter_slots;
+#[cfg(test)]
 mod test_filter_wheel;
 #[cfg(test)]
+mod test_fits;
+#[cfg(test)]
+mod test_flash_config;
+#[cfg(test)]
+mod test_fpn;
+#[cfg(test)]
+mod test_frame_pool;
+#[cfg(test)]
+mod test_gps;
+#[cfg(test)]
+mod test_hot_pixels;
+#[cfg(test)]
+mod test_hotplug;
+#[cfg(all(test, feature = "indi"))]
+mod test_indi;
+#[cfg(test)]
+mod test_industrial;
+#[cfg(test)]
+mod test_integrity;
+#[cfg(test)]
+mod test_keep_alive;
+#[cfg(test)]
+mod test_metadata;
+#[cfg(test)]
+mod test_numeric;
+#[cfg(test)]
+mod test_parameters;
+#[cfg(test)]
+mod test_pixel;
+#[cfg(test)]
+mod test_preflight;
+#[cfg(all(test, feature = "provenance"))]
+mod test_provenance;
+#[cfg(test)]
+mod test_quick;
+#[cfg(test)]
+mod test_rate_limit;
+#[cfg(test)]
+mod test_roi;
+#[cfg(test)]
 mod test_sdk;
+#[cfg(test)]
+mod test_sdk_features;
+#[cfg(test)]
+mod test_self_test;
+#[cfg(test)]
+mod test_sensor_status;
+#[cfg(test)]
+mod test_statistics;
+#[cfg(test)]
+mod test_stream_modes;
+#[cfg(test)]
+mod test_stretch;
+#[cfg(all(test, feature = "metrics"))]
+mod test_telemetry;
+#[cfg(test)]
+mod test_tracing;
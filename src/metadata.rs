@@ -0,0 +1,169 @@
+//! Metadata describing how a frame was captured
+//!
+//! `Camera::frame_metadata` reads the current settings from the camera, it should be called
+//! right after the exposure finished, while the settings still match the frame.
+//! `Camera::get_live_frame_with_meta` does that for every live frame. The camera does not report
+//! its binning and ROI, so the last values passed to `set_bin_mode` and `set_roi` are used.
+
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    telemetry, BayerMode, CCDChipArea, Camera, Control, ImageData, PreciseExposureInfo, Result,
+    SampleDepth,
+};
+
+#[derive(Debug, Default, Clone, Copy)]
+/// the binning, ROI and bit mode last set on the camera, they cannot be read back
+pub(crate) struct FrameGeometry {
+    pub(crate) binning: Option<(u32, u32)>,
+    pub(crate) roi: Option<CCDChipArea>,
+    pub(crate) bits_per_pixel: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The settings a frame was captured with, every value is optional because not every camera
+/// supports every setting
+pub struct FrameMetadata {
+    /// the camera model
+    pub instrument: Option<String>,
+    /// the exposure time
+    pub exposure: Option<Duration>,
+    /// the gain
+    pub gain: Option<f64>,
+    /// the offset
+    pub offset: Option<f64>,
+    /// the sensor temperature in °C
+    pub ccd_temperature: Option<f64>,
+    /// the horizontal and vertical binning
+    pub binning: Option<(u32, u32)>,
+    /// the bayer pattern of color sensors
+    pub bayer_mode: Option<BayerMode>,
+    /// when the exposure of the frame started
+    pub capture_start: Option<SystemTime>,
+    /// the readout mode
+    pub readout_mode: Option<u32>,
+    /// the region of interest
+    pub roi: Option<CCDChipArea>,
+    /// the frame counter of cameras with `Control::HasHardwareFrameCounter`
+    pub frame_counter: Option<u64>,
+    /// the sensor timing of cameras supporting `get_precise_exposure_info`
+    pub precise_exposure: Option<PreciseExposureInfo>,
+    /// the significant bits of the samples, see `ImageData::normalize_to_16bit`
+    pub sample_depth: Option<SampleDepth>,
+}
+
+impl Camera {
+    /// Reads the current settings of the camera as `FrameMetadata`. Settings the camera does not
+    /// support or fails to report are left as `None`, so are binning and ROI until they were set
+    /// through `set_bin_mode` and `set_roi`. The start of the capture is not known here and left
+    /// as `None`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// /* capture a frame */
+    /// let metadata = camera.frame_metadata();
+    /// println!("Metadata: {:?}", metadata);
+    /// ```
+    pub fn frame_metadata(&self) -> FrameMetadata {
+        let available = |control: Control| self.is_control_available(control);
+        let geometry = self.frame_geometry();
+        FrameMetadata {
+            instrument: self.get_model().ok(),
+            exposure: self.get_exposure().ok(),
+            gain: available(Control::Gain).and_then(|_| self.get_gain().ok()),
+            offset: available(Control::Offset).and_then(|_| self.get_offset().ok()),
+            ccd_temperature: available(Control::CurTemp)
+                .and_then(|_| self.get_parameter(Control::CurTemp).ok()),
+            binning: geometry.binning,
+            bayer_mode: available(Control::CamColor)
+                .and_then(|value| BayerMode::try_from(value).ok()),
+            capture_start: None,
+            readout_mode: self.get_readout_mode().ok(),
+            roi: geometry.roi,
+            frame_counter: available(Control::HasHardwareFrameCounter)
+                .and_then(|_| self.get_parameter(Control::HasHardwareFrameCounter).ok())
+                .map(|counter| counter as u64),
+            precise_exposure: self.get_precise_exposure_info().ok(),
+            sample_depth: self.get_sample_depth().ok(),
+        }
+    }
+
+    /// Same as `get_live_frame`, but also returns the metadata of the frame. The start of the
+    /// capture is estimated as the time the frame arrived minus the exposure time, the one the
+    /// sensor actually uses if the camera reports its precise exposure info.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk, StreamMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_stream_mode(StreamMode::LiveMode).expect("set_stream_mode failed");
+    /// camera.init().expect("init failed");
+    /// camera.begin_live().expect("begin_live failed");
+    /// let size = camera.get_image_size().expect("get_image_size failed");
+    /// let (image, metadata) = camera.get_live_frame_with_meta(size).expect("get_live_frame_with_meta failed");
+    /// println!("frame {:?} started at {:?}", metadata.frame_counter, metadata.capture_start);
+    /// ```
+    pub fn get_live_frame_with_meta(
+        &self,
+        buffer_size: usize,
+    ) -> Result<(ImageData, FrameMetadata)> {
+        let image = self.get_live_frame(buffer_size)?;
+        let received = SystemTime::now();
+        let mut metadata = self.frame_metadata();
+        metadata.capture_start = metadata
+            .precise_exposure
+            .map(|precise| precise.actual_exposure)
+            .or(metadata.exposure)
+            .and_then(|exposure| received.checked_sub(exposure));
+        if let Some(counter) = metadata.frame_counter {
+            self.count_dropped_frames(counter);
+        }
+        Ok((image, metadata))
+    }
+
+    /// counts the frames missing between `counter` and the last frame, a counter that went
+    /// backwards was reset and does not count
+    pub(crate) fn count_dropped_frames(&self, counter: u64) -> u64 {
+        let previous = self
+            .last_frame_counter
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .replace(counter);
+        let dropped = previous.map_or(0, |previous| {
+            counter.saturating_sub(previous).saturating_sub(1)
+        });
+        if dropped > 0 {
+            tracing::warn!(dropped_frames = dropped, counter);
+            telemetry::live_frames_dropped(&self.id, dropped);
+        }
+        dropped
+    }
+
+    pub(crate) fn reset_frame_counter(&self) {
+        self.last_frame_counter
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+    }
+
+    pub(crate) fn frame_geometry(&self) -> FrameGeometry {
+        *self
+            .geometry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn update_frame_geometry(&self, update: impl FnOnce(&mut FrameGeometry)) {
+        update(
+            &mut self
+                .geometry
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+    }
+}
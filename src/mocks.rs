@@ -3,6 +3,8 @@
 //This file duplicates the libqhyccd-sys bindings, but with mockable functions.
 //These bindings are activated by the import config for the test target.
 
+use std::cell::RefCell;
+
 use mockall::automock;
 
 pub const QHYCCD_PCIE: u32 = 9;
@@ -15,10 +17,49 @@ pub const QHYCCD_MONO: u32 = 3;
 pub const QHYCCD_COOL: u32 = 2;
 pub const QHYCCD_NOTCOO: u32 = 1;
 
+thread_local! {
+    static MISSING_FUNCTIONS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Stands in for `libqhyccd_sys::is_available`. Every function is available unless the test
+/// removed it with `set_missing_functions`, which only affects the calling thread.
+pub fn is_available(name: &str) -> bool {
+    MISSING_FUNCTIONS.with(|missing| !missing.borrow().contains(&name))
+}
+
+/// makes `is_available` report `names` as missing on the calling thread
+pub fn set_missing_functions(names: &[&'static str]) {
+    MISSING_FUNCTIONS.with(|missing| *missing.borrow_mut() = names.to_vec());
+}
+
+/// Keeps a clone of `camera` alive for the rest of the test run. Dropping the last clone of an
+/// open camera calls `CloseQHYCCD`, which most tests do not expect, so they keep their cameras
+/// open with this.
+pub fn keep_open<T: Clone>(camera: &T) {
+    std::mem::forget(camera.clone());
+}
+
+/// the handle `OpenQHYCCD` returns for the cameras made by `new_camera`
+pub const TEST_HANDLE: *const core::ffi::c_void = 0xdeadbeef as *const core::ffi::c_void;
+
+/// Returns a camera opened with `TEST_HANDLE` and kept open with `keep_open`. It sets up the
+/// `OpenQHYCCD` expectation itself, so tests must not hold their own `OpenQHYCCD_context` while
+/// calling it.
+pub fn new_camera() -> crate::Camera {
+    let ctx_open = mock_libqhyccd_sys::OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let camera = crate::Camera::new("test_camera".to_owned());
+    camera.open().unwrap();
+    keep_open(&camera);
+    camera
+}
+
 #[cfg_attr(test, automock)]
 pub mod libqhyccd_sys {
     use core::ffi::c_char;
 
+    pub const QHYCCD_READ_DIRECTLY: u32 = 0x2001;
+    pub const QHYCCD_DELAY_200MS: u32 = 0x2000;
     pub const QHYCCD_SUCCESS: u32 = 0;
     pub const QHYCCD_ERROR: u32 = u32::MAX;
     pub const QHYCCD_ERROR_F64: f64 = u32::MAX as f64;
@@ -30,6 +71,21 @@ pub mod libqhyccd_sys {
     pub fn ScanQHYCCD() -> u32 {
         unimplemented!()
     }
+    pub fn SetQHYCCDLogLevel(log_level: u8) {
+        unimplemented!()
+    }
+    pub fn EnableQHYCCDMessage(enable: bool) {
+        unimplemented!()
+    }
+    pub fn EnableQHYCCDLogFile(enable: bool) {
+        unimplemented!()
+    }
+    pub fn RegisterPnpEventIn(in_callback: extern "C" fn(id: *mut c_char)) {
+        unimplemented!()
+    }
+    pub fn RegisterPnpEventOut(out_callback: extern "C" fn(id: *mut c_char)) {
+        unimplemented!()
+    }
     pub fn GetQHYCCDSDKVersion(
         _year: *mut u32,
         _month: *mut u32,
@@ -180,6 +236,31 @@ pub mod libqhyccd_sys {
     pub fn GetQHYCCDModel(handle: QhyccdHandle, model: *mut c_char) -> u32 {
         unimplemented!()
     }
+    pub fn GetQHYCCDSensorName(handle: QhyccdHandle, name: *mut c_char) -> u32 {
+        unimplemented!()
+    }
+    pub fn GetQHYCCDFPGAVersion(handle: QhyccdHandle, fpga_index: u8, buf: *mut u8) -> u32 {
+        unimplemented!()
+    }
+    pub fn GetQHYCCDPreciseExposureInfo(
+        handle: QhyccdHandle,
+        pixel_period_ps: *mut u32,
+        line_period_ns: *mut u32,
+        frame_period_us: *mut u32,
+        clocks_per_line: *mut u32,
+        lines_per_frame: *mut u32,
+        actual_exposure_time: *mut u32,
+        is_long_exposure_mode: *mut u8,
+    ) -> u32 {
+        unimplemented!()
+    }
+    pub fn GetQHYCCDRollingShutterEndOffset(
+        handle: QhyccdHandle,
+        row: u32,
+        offset: *mut f64,
+    ) -> u32 {
+        unimplemented!()
+    }
     pub fn GetQHYCCDType(handle: QhyccdHandle) -> u32 {
         unimplemented!()
     }
@@ -201,4 +282,62 @@ pub mod libqhyccd_sys {
     pub fn SendOrder2QHYCCDCFW(handle: QhyccdHandle, order: *const c_char, length: u32) -> u32 {
         unimplemented!()
     }
+    pub fn SetQHYCCDTrigerFunction(handle: QhyccdHandle, value: bool) -> u32 {
+        unimplemented!()
+    }
+    pub fn SetQHYCCDGPSVCOXFreq(handle: QhyccdHandle, i: u16) -> u32 {
+        unimplemented!()
+    }
+    pub fn SetQHYCCDGPSLedCalMode(handle: QhyccdHandle, i: u8) -> u32 {
+        unimplemented!()
+    }
+    pub fn SetQHYCCDGPSLedCal(handle: QhyccdHandle, pos: u32, width: u8) {
+        unimplemented!()
+    }
+    pub fn SetQHYCCDGPSPOSA(handle: QhyccdHandle, is_slave: u8, pos: u32, width: u8) {
+        unimplemented!()
+    }
+    pub fn SetQHYCCDGPSPOSB(handle: QhyccdHandle, is_slave: u8, pos: u32, width: u8) {
+        unimplemented!()
+    }
+    pub fn GetQHYCCDHumidity(handle: QhyccdHandle, hd: *mut f64) -> u32 {
+        unimplemented!()
+    }
+    pub fn GetQHYCCDPressure(handle: QhyccdHandle, pressure: *mut f64) -> u32 {
+        unimplemented!()
+    }
+    pub fn SetQHYCCDTrigerMode(handle: QhyccdHandle, trigger_mode: u32) -> u32 {
+        unimplemented!()
+    }
+    pub fn EnableQHYCCDTrigerOut(handle: QhyccdHandle) -> u32 {
+        unimplemented!()
+    }
+    pub fn GetQHYCCDTrigerInterfaceNumber(handle: QhyccdHandle, mode_number: *mut u32) -> u32 {
+        unimplemented!()
+    }
+    pub fn GetQHYCCDTrigerInterfaceName(
+        handle: QhyccdHandle,
+        mode_number: u32,
+        name: *mut c_char,
+    ) -> u32 {
+        unimplemented!()
+    }
+    pub fn SetQHYCCDTrigerInterface(handle: QhyccdHandle, trigger_interface: u32) -> u32 {
+        unimplemented!()
+    }
+    pub fn QHYCCDReadInitConfigFlash(handle: QhyccdHandle, config: *mut u8) {
+        unimplemented!()
+    }
+    pub fn QHYCCDEraseInitConfigFlash(handle: QhyccdHandle) {
+        unimplemented!()
+    }
+    pub fn QHYCCDSetFlashInitPWM(handle: QhyccdHandle, pwm: u8) {
+        unimplemented!()
+    }
+    pub fn QHYCCDResetFlashULVOError(handle: QhyccdHandle) {
+        unimplemented!()
+    }
+    pub fn QHYCCDSensorPhaseReTrain(handle: QhyccdHandle) {
+        unimplemented!()
+    }
 }
@@ -0,0 +1,64 @@
+//! Locale independent formatting and parsing of floating point values
+//!
+//! Everything the crate writes to files, e.g. FITS header values, uses `format_float`. The
+//! output always uses `.` as decimal separator, never groups digits and parses back to exactly
+//! the same value with `parse_float`, no matter the locale of the capture host.
+
+use crate::QHYError::ParseFloatError;
+use crate::Result;
+
+/// values with longer decimal representations are written in exponent notation
+const MAX_DECIMAL_LEN: usize = 20;
+
+/// Formats `value` with the shortest representation that parses back to the same value. The
+/// result always contains a decimal point or an exponent, so it can not be mistaken for an
+/// integer, and uses exponent notation like `1.5E-20` if the plain decimal would be too long.
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::format_float;
+/// assert_eq!(format_float(300.0), "300.0");
+/// assert_eq!(format_float(0.1), "0.1");
+/// assert_eq!(format_float(1.5e-20), "1.5E-20");
+/// ```
+pub fn format_float(value: f64) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let decimal = value.to_string();
+    if decimal.len() <= MAX_DECIMAL_LEN {
+        return match decimal.contains('.') {
+            true => decimal,
+            false => format!("{}.0", decimal),
+        };
+    }
+    let exponent = format!("{:E}", value);
+    match exponent.split_once('E') {
+        Some((mantissa, exponent)) if !mantissa.contains('.') => {
+            format!("{}.0E{}", mantissa, exponent)
+        }
+        _ => exponent,
+    }
+}
+
+/// Parses a value written by `format_float`. Also accepts the FITS style `D` exponent and
+/// surrounding whitespace, but rejects locale specific formats like `1,5`.
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::parse_float;
+/// assert_eq!(parse_float("1.5E-12").unwrap(), 1.5e-12);
+/// assert_eq!(parse_float(" 2.0D3 ").unwrap(), 2000.0);
+/// assert!(parse_float("1,5").is_err());
+/// ```
+pub fn parse_float(value: &str) -> Result<f64> {
+    value
+        .trim()
+        .replace(['D', 'd'], "E")
+        .parse::<f64>()
+        .map_err(|_| {
+            let error = ParseFloatError {
+                value: value.to_owned(),
+            };
+            tracing::error!(error = ?error);
+            error
+        })
+}
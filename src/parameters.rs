@@ -0,0 +1,119 @@
+//! Typed setters and getters for the most common controls
+//!
+//! `set_parameter` takes raw `f64` values in whatever unit the SDK uses for a control. The
+//! methods here take proper types instead, e.g. a `Duration` for the exposure time, and check
+//! the value against `get_parameter_min_max_step` before handing it to the camera.
+
+use std::time::Duration;
+
+use crate::QHYError::ParameterOutOfRangeError;
+use crate::{Camera, Control, Result};
+
+impl Camera {
+    /// sets `control` to `value` after checking it is within the limits the camera reports
+    fn set_within_limits(&self, control: Control, value: f64) -> Result<()> {
+        self.check_within_limits(control, value)?;
+        self.set_parameter(control, value)
+    }
+
+    /// fails with `ParameterOutOfRangeError` if `value` is outside the limits the camera reports
+    /// for `control`
+    pub(crate) fn check_within_limits(&self, control: Control, value: f64) -> Result<()> {
+        let (min, max, _) = self.get_parameter_min_max_step(control)?;
+        if !(min..=max).contains(&value) {
+            let error = ParameterOutOfRangeError {
+                control,
+                value,
+                min,
+                max,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Sets the exposure time, the SDK resolution is one microsecond
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_exposure(Duration::from_secs(2)).expect("set_exposure failed");
+    /// ```
+    pub fn set_exposure(&self, exposure: Duration) -> Result<()> {
+        self.set_within_limits(Control::Exposure, exposure.as_micros() as f64)
+    }
+
+    /// Returns the exposure time
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let exposure = camera.get_exposure().expect("get_exposure failed");
+    /// println!("exposure time: {:?}", exposure);
+    /// ```
+    pub fn get_exposure(&self) -> Result<Duration> {
+        let exposure = self.get_parameter(Control::Exposure)?;
+        Ok(Duration::from_micros(exposure.max(0.0) as u64))
+    }
+
+    /// Sets the gain, the range depends on the camera model
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_gain(26.0).expect("set_gain failed");
+    /// ```
+    pub fn set_gain(&self, gain: f64) -> Result<()> {
+        self.set_within_limits(Control::Gain, gain)
+    }
+
+    /// Returns the gain
+    pub fn get_gain(&self) -> Result<f64> {
+        self.get_parameter(Control::Gain)
+    }
+
+    /// Sets the offset, the range depends on the camera model
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_offset(30.0).expect("set_offset failed");
+    /// ```
+    pub fn set_offset(&self, offset: f64) -> Result<()> {
+        self.set_within_limits(Control::Offset, offset)
+    }
+
+    /// Returns the offset
+    pub fn get_offset(&self) -> Result<f64> {
+        self.get_parameter(Control::Offset)
+    }
+
+    /// Sets the USB traffic, higher values lower the frame rate but make the transfer more
+    /// reliable on slow or long connections
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// camera.set_usb_traffic(30).expect("set_usb_traffic failed");
+    /// ```
+    pub fn set_usb_traffic(&self, usb_traffic: u32) -> Result<()> {
+        self.set_within_limits(Control::UsbTraffic, usb_traffic as f64)
+    }
+
+    /// Returns the USB traffic
+    pub fn get_usb_traffic(&self) -> Result<u32> {
+        Ok(self.get_parameter(Control::UsbTraffic)? as u32)
+    }
+}
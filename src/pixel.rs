@@ -0,0 +1,294 @@
+//! Sample formats of the image data delivered by the SDK
+//!
+//! Depending on the transfer bit mode the SDK delivers 8, 16 or, on deep-well cameras reporting
+//! `Control::Cam32bits`, 32 bits per sample. Samples wider than 8 bits are stored little endian.
+//! Frames with more than one channel store the channels of a pixel next to each other.
+
+use std::borrow::Cow;
+
+use crate::QHYError::{PixelFormatMismatchError, UnsupportedBitDepthError};
+use crate::{Camera, Control, ImageData, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The storage format of a single sample in `ImageData::data`
+pub enum PixelFormat {
+    /// one byte per sample, used for bit depths up to 8
+    U8,
+    /// two bytes per sample, used for bit depths from 9 to 16
+    U16,
+    /// four bytes per sample, used for bit depths from 17 to 32
+    U32,
+}
+
+impl PixelFormat {
+    /// Returns the format used to store samples of the given bit depth, fails with
+    /// `UnsupportedBitDepthError` for bit depths of 0 or above 32
+    pub fn from_bits_per_pixel(bits_per_pixel: u32) -> Result<Self> {
+        match bits_per_pixel {
+            1..=8 => Ok(PixelFormat::U8),
+            9..=16 => Ok(PixelFormat::U16),
+            17..=32 => Ok(PixelFormat::U32),
+            _ => {
+                let error = UnsupportedBitDepthError { bits_per_pixel };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns the number of bits of a stored sample
+    pub fn bits(self) -> u32 {
+        self.bytes_per_sample() as u32 * 8
+    }
+
+    /// Returns the number of bytes of a stored sample
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            PixelFormat::U8 => 1,
+            PixelFormat::U16 => 2,
+            PixelFormat::U32 => 4,
+        }
+    }
+
+    /// the control the camera uses to report support for this format
+    pub(crate) fn control(self) -> Control {
+        match self {
+            PixelFormat::U8 => Control::Cam8bits,
+            PixelFormat::U16 => Control::Cam16bits,
+            PixelFormat::U32 => Control::Cam32bits,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// The samples of a frame in their native width, returned from `ImageData::into_pixel_buffer`
+pub enum PixelBuffer {
+    /// samples of frames with up to 8 bits per pixel
+    U8(Vec<u8>),
+    /// samples of frames with 9 to 16 bits per pixel
+    U16(Vec<u16>),
+    /// samples of frames with 17 to 32 bits per pixel
+    U32(Vec<u32>),
+}
+
+impl PixelBuffer {
+    /// Returns the storage format of the samples
+    pub fn pixel_format(&self) -> PixelFormat {
+        match self {
+            PixelBuffer::U8(_) => PixelFormat::U8,
+            PixelBuffer::U16(_) => PixelFormat::U16,
+            PixelBuffer::U32(_) => PixelFormat::U32,
+        }
+    }
+
+    /// Returns the number of samples
+    pub fn len(&self) -> usize {
+        match self {
+            PixelBuffer::U8(samples) => samples.len(),
+            PixelBuffer::U16(samples) => samples.len(),
+            PixelBuffer::U32(samples) => samples.len(),
+        }
+    }
+
+    /// Returns `true` if there are no samples
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// a sample type wider than a byte, stored little endian in `ImageData::data`
+trait WideSample: Copy {
+    const FORMAT: PixelFormat;
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+
+impl WideSample for u16 {
+    const FORMAT: PixelFormat = PixelFormat::U16;
+    fn from_le_slice(bytes: &[u8]) -> Self {
+        u16::from_le_bytes([bytes[0], bytes[1]])
+    }
+}
+
+impl WideSample for u32 {
+    const FORMAT: PixelFormat = PixelFormat::U32;
+    fn from_le_slice(bytes: &[u8]) -> Self {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+impl ImageData {
+    /// the bytes holding the pixels, without the padding the SDK may add to the buffer
+    fn pixel_bytes(&self) -> Result<&[u8]> {
+        let len = self.expected_len()?.min(self.data.len());
+        Ok(&self.data[..len])
+    }
+
+    fn check_format(&self, expected: PixelFormat) -> Result<()> {
+        match self.pixel_format()? {
+            actual if actual == expected => Ok(()),
+            actual => {
+                let error = PixelFormatMismatchError { expected, actual };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+        }
+    }
+
+    fn as_wide_slice<T: WideSample>(&self) -> Result<Cow<'_, [T]>> {
+        self.check_format(T::FORMAT)?;
+        let bytes = self.pixel_bytes()?;
+        if cfg!(target_endian = "little") {
+            // every bit pattern is a valid u16 or u32, so reinterpreting is fine as long as the
+            // bytes happen to be aligned
+            let (prefix, samples, suffix) = unsafe { bytes.align_to::<T>() };
+            if prefix.is_empty() && suffix.is_empty() {
+                return Ok(Cow::Borrowed(samples));
+            }
+        }
+        Ok(Cow::Owned(
+            bytes
+                .chunks_exact(T::FORMAT.bytes_per_sample())
+                .map(T::from_le_slice)
+                .collect(),
+        ))
+    }
+
+    /// Returns the samples of a frame with 9 to 16 bits per pixel. The samples are borrowed from
+    /// `data` where its alignment and the byte order of the host allow it and copied otherwise.
+    /// Fails with `PixelFormatMismatchError` for other bit depths.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData { data: vec![1, 0, 0, 1], width: 2, height: 1, bits_per_pixel: 16, channels: 1 };
+    /// assert_eq!(*image.as_u16_slice().expect("not a 16 bit frame"), [1, 256]);
+    /// ```
+    pub fn as_u16_slice(&self) -> Result<Cow<'_, [u16]>> {
+        self.as_wide_slice()
+    }
+
+    /// Returns the samples of a frame with 17 to 32 bits per pixel, see `as_u16_slice`
+    pub fn as_u32_slice(&self) -> Result<Cow<'_, [u32]>> {
+        self.as_wide_slice()
+    }
+
+    /// Returns the samples of a frame with up to 8 bits per pixel. Fails with
+    /// `PixelFormatMismatchError` for other bit depths.
+    pub fn as_u8_slice(&self) -> Result<&[u8]> {
+        self.check_format(PixelFormat::U8)?;
+        self.pixel_bytes()
+    }
+
+    /// Consumes the frame and returns the samples of a frame with 9 to 16 bits per pixel
+    pub fn into_u16_vec(self) -> Result<Vec<u16>> {
+        Ok(self.as_u16_slice()?.into_owned())
+    }
+
+    /// Consumes the frame and returns the samples of a frame with 17 to 32 bits per pixel
+    pub fn into_u32_vec(self) -> Result<Vec<u32>> {
+        Ok(self.as_u32_slice()?.into_owned())
+    }
+
+    /// Consumes the frame and returns its samples in their native width
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{ImageData, PixelBuffer};
+    /// let image = ImageData { data: vec![1, 0, 0, 1], width: 2, height: 1, bits_per_pixel: 12, channels: 1 };
+    /// assert_eq!(image.into_pixel_buffer().expect("unsupported bit depth"), PixelBuffer::U16(vec![1, 256]));
+    /// ```
+    pub fn into_pixel_buffer(self) -> Result<PixelBuffer> {
+        match self.pixel_format()? {
+            PixelFormat::U8 => {
+                let mut data = self.data;
+                data.truncate(self.width as usize * self.height as usize * self.channels as usize);
+                Ok(PixelBuffer::U8(data))
+            }
+            PixelFormat::U16 => Ok(PixelBuffer::U16(self.into_u16_vec()?)),
+            PixelFormat::U32 => Ok(PixelBuffer::U32(self.into_u32_vec()?)),
+        }
+    }
+
+    /// Returns the sample of `channel` at the pixel `x`, `y` widened to `u32`, or `None` if the
+    /// position or channel is outside of the frame
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData { data: vec![10, 20, 30, 11, 21, 31], width: 2, height: 1, bits_per_pixel: 8, channels: 3 };
+    /// assert_eq!(image.pixel(1, 0, 2), Some(31));
+    /// ```
+    pub fn pixel(&self, x: u32, y: u32, channel: u32) -> Option<u32> {
+        if x >= self.width || y >= self.height || channel >= self.channels {
+            return None;
+        }
+        let bytes_per_sample = self.pixel_format().ok()?.bytes_per_sample();
+        let index = ((y as usize * self.width as usize + x as usize) * self.channels as usize
+            + channel as usize)
+            * bytes_per_sample;
+        let bytes = self.data.get(index..index + bytes_per_sample)?;
+        match bytes_per_sample {
+            1 => Some(bytes[0] as u32),
+            2 => Some(u16::from_le_slice(bytes) as u32),
+            _ => Some(u32::from_le_slice(bytes)),
+        }
+    }
+
+    /// Returns a simulated monochrome frame in `format`, a horizontal ramp from 0 in the first
+    /// column to the largest sample of the format in the last, e.g. to test code handling
+    /// frames of deep-well cameras without such a camera
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{ImageData, PixelFormat};
+    /// let image = ImageData::simulated(3, 2, PixelFormat::U32);
+    /// assert_eq!(image.pixel(1, 1, 0), Some(u32::MAX / 2));
+    /// assert_eq!(image.pixel(2, 0, 0), Some(u32::MAX));
+    /// ```
+    pub fn simulated(width: u32, height: u32, format: PixelFormat) -> ImageData {
+        let max = u64::MAX >> (64 - format.bits());
+        let row = (0..width as u64)
+            .map(|x| max * x / (width as u64).saturating_sub(1).max(1))
+            .flat_map(|sample| sample.to_le_bytes()[..format.bytes_per_sample()].to_vec())
+            .collect::<Vec<_>>();
+        ImageData {
+            data: row.repeat(height as usize),
+            width,
+            height,
+            bits_per_pixel: format.bits(),
+            channels: 1,
+        }
+    }
+
+    /// Returns the storage format of the samples of this frame
+    pub fn pixel_format(&self) -> Result<PixelFormat> {
+        PixelFormat::from_bits_per_pixel(self.bits_per_pixel)
+    }
+
+    /// Returns the number of bytes needed for the pixels of this frame, the buffer returned by
+    /// the SDK can be larger than this
+    pub fn expected_len(&self) -> Result<usize> {
+        Ok(self.width as usize
+            * self.height as usize
+            * self.channels as usize
+            * self.pixel_format()?.bytes_per_sample())
+    }
+}
+
+impl Camera {
+    /// Returns the pixel formats the camera can deliver, based on `Control::Cam8bits`,
+    /// `Control::Cam16bits` and `Control::Cam32bits`
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{PixelFormat, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// if camera.supported_pixel_formats().contains(&PixelFormat::U32) {
+    ///     camera.set_bit_mode(32).expect("set_bit_mode failed");
+    /// }
+    /// ```
+    pub fn supported_pixel_formats(&self) -> Vec<PixelFormat> {
+        [PixelFormat::U8, PixelFormat::U16, PixelFormat::U32]
+            .into_iter()
+            .filter(|format| self.is_control_available(format.control()).is_some())
+            .collect()
+    }
+}
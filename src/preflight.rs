@@ -0,0 +1,274 @@
+//! Pre-flight checks to run before starting a series of exposures
+//!
+//! Instead of failing somewhere in the middle of a long run, `preflight` validates the camera,
+//! the cooler, an optional filter wheel, the free disk space and the planned exposure time up
+//! front and returns a report with the outcome of every single check.
+
+use std::path::PathBuf;
+
+use crate::{Camera, Control, FilterWheel};
+
+#[derive(Debug, PartialEq, Clone)]
+/// Describes what is about to be captured, used as input for `preflight`
+pub struct PreflightPlan {
+    /// the exposure time in microseconds every frame will use
+    pub exposure_us: f64,
+    /// the number of frames that will be captured
+    pub frame_count: u32,
+    /// the cooler set-point in °C, `None` skips the cooler check
+    pub target_temperature: Option<f64>,
+    /// the maximum allowed difference between the sensor temperature and `target_temperature` in °C
+    pub temperature_tolerance: f64,
+    /// the directory the frames will be written to, `None` skips the disk space check, as do
+    /// platforms other than unix
+    pub output_directory: Option<PathBuf>,
+}
+
+impl Default for PreflightPlan {
+    fn default() -> Self {
+        Self {
+            exposure_us: 1_000_000.0,
+            frame_count: 1,
+            target_temperature: None,
+            temperature_tolerance: 1.0,
+            output_directory: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The individual checks that make up a pre-flight run
+pub enum PreflightCheck {
+    /// the camera has been opened
+    CameraOpen,
+    /// `Camera::init` succeeded since the stream mode was last changed
+    CameraInitialized,
+    /// the sensor temperature is within the tolerance of the set-point
+    CoolerAtSetPoint,
+    /// the filter wheel is open, was homed with `FilterWheel::home` and reports a position
+    FilterWheelReady,
+    /// the output directory has enough free space for all planned frames
+    DiskSpace,
+    /// the exposure time is within the limits reported by the camera
+    ExposureWithinLimits,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// The outcome of a single `PreflightCheck`
+pub enum PreflightOutcome {
+    /// the check passed
+    Passed,
+    /// the check failed, the string describes why
+    Failed(String),
+    /// the check was not run, the string describes why
+    Skipped(String),
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+/// The report returned from `preflight`
+pub struct PreflightReport {
+    /// the outcome of every check in the order they were run
+    pub results: Vec<(PreflightCheck, PreflightOutcome)>,
+}
+
+impl PreflightReport {
+    /// Returns `true` if no check failed, skipped checks do not count as failures
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Returns an iterator over all checks that failed together with the reason
+    pub fn failures(&self) -> impl Iterator<Item = (PreflightCheck, &str)> {
+        self.results
+            .iter()
+            .filter_map(|(check, outcome)| match outcome {
+                PreflightOutcome::Failed(reason) => Some((*check, reason.as_str())),
+                _ => None,
+            })
+    }
+
+    /// Returns the outcome of the given check if it was part of the run
+    pub fn outcome(&self, check: PreflightCheck) -> Option<&PreflightOutcome> {
+        self.results
+            .iter()
+            .find(|(c, _)| *c == check)
+            .map(|(_, outcome)| outcome)
+    }
+}
+
+/// Runs all pre-flight checks for the given camera, optional filter wheel and plan. The checks
+/// never return early, so the report always contains an outcome for every check.
+/// # Example
+/// ```no_run
+/// use qhyccd_rs::{preflight, PreflightPlan, Sdk};
+/// let sdk = Sdk::new().expect("SDK::new failed");
+/// let camera = sdk.cameras().last().expect("no camera found");
+/// camera.open().expect("open failed");
+/// let plan = PreflightPlan {
+///     exposure_us: 300_000_000.0,
+///     frame_count: 20,
+///     target_temperature: Some(-10.0),
+///     output_directory: Some("/data/lights".into()),
+///     ..Default::default()
+/// };
+/// let report = preflight(camera, sdk.filter_wheels().next(), &plan);
+/// for (check, reason) in report.failures() {
+///     println!("{:?} failed: {}", check, reason);
+/// }
+/// ```
+pub fn preflight(
+    camera: &Camera,
+    filter_wheel: Option<&FilterWheel>,
+    plan: &PreflightPlan,
+) -> PreflightReport {
+    let mut report = PreflightReport::default();
+    let camera_open = matches!(camera.is_open(), Ok(true));
+    report.results.push((
+        PreflightCheck::CameraOpen,
+        match camera_open {
+            true => PreflightOutcome::Passed,
+            false => PreflightOutcome::Failed("camera is not open".to_owned()),
+        },
+    ));
+    if !camera_open {
+        for check in [
+            PreflightCheck::CameraInitialized,
+            PreflightCheck::CoolerAtSetPoint,
+            PreflightCheck::FilterWheelReady,
+            PreflightCheck::DiskSpace,
+            PreflightCheck::ExposureWithinLimits,
+        ] {
+            report.results.push((
+                check,
+                PreflightOutcome::Skipped("camera is not open".to_owned()),
+            ));
+        }
+        return report;
+    }
+    report
+        .results
+        .push((PreflightCheck::CameraInitialized, check_initialized(camera)));
+    report
+        .results
+        .push((PreflightCheck::CoolerAtSetPoint, check_cooler(camera, plan)));
+    report.results.push((
+        PreflightCheck::FilterWheelReady,
+        check_filter_wheel(filter_wheel),
+    ));
+    report
+        .results
+        .push((PreflightCheck::DiskSpace, check_disk_space(camera, plan)));
+    report.results.push((
+        PreflightCheck::ExposureWithinLimits,
+        check_exposure(camera, plan),
+    ));
+    report
+}
+
+fn check_initialized(camera: &Camera) -> PreflightOutcome {
+    match camera.handle.is_initialized() {
+        true => PreflightOutcome::Passed,
+        false => PreflightOutcome::Failed("camera has not been initialized".to_owned()),
+    }
+}
+
+fn check_cooler(camera: &Camera, plan: &PreflightPlan) -> PreflightOutcome {
+    let target = match plan.target_temperature {
+        Some(target) => target,
+        None => return PreflightOutcome::Skipped("no target temperature planned".to_owned()),
+    };
+    if camera.is_control_available(Control::Cooler).is_none() {
+        return PreflightOutcome::Failed("camera has no cooler".to_owned());
+    }
+    match camera.get_parameter(Control::CurTemp) {
+        Ok(current) if (current - target).abs() <= plan.temperature_tolerance => {
+            PreflightOutcome::Passed
+        }
+        Ok(current) => PreflightOutcome::Failed(format!(
+            "sensor is at {:.1}°C, set-point is {:.1}°C ± {:.1}°C",
+            current, target, plan.temperature_tolerance
+        )),
+        Err(error) => PreflightOutcome::Failed(format!("could not read temperature: {}", error)),
+    }
+}
+
+fn check_filter_wheel(filter_wheel: Option<&FilterWheel>) -> PreflightOutcome {
+    let filter_wheel = match filter_wheel {
+        Some(filter_wheel) => filter_wheel,
+        None => return PreflightOutcome::Skipped("no filter wheel in use".to_owned()),
+    };
+    if !matches!(filter_wheel.is_open(), Ok(true)) {
+        return PreflightOutcome::Failed("filter wheel is not open".to_owned());
+    }
+    if !filter_wheel.is_homed() {
+        return PreflightOutcome::Failed("filter wheel has not been homed".to_owned());
+    }
+    match filter_wheel.get_fw_position() {
+        Ok(_) => PreflightOutcome::Passed,
+        Err(error) => PreflightOutcome::Failed(format!("could not read position: {}", error)),
+    }
+}
+
+fn check_disk_space(camera: &Camera, plan: &PreflightPlan) -> PreflightOutcome {
+    let directory = match &plan.output_directory {
+        Some(directory) => directory,
+        None => return PreflightOutcome::Skipped("no output directory planned".to_owned()),
+    };
+    if cfg!(not(unix)) {
+        return PreflightOutcome::Skipped("free space unknown on this platform".to_owned());
+    }
+    let frame_size = match camera.get_image_size() {
+        Ok(size) => size as u64,
+        Err(error) => {
+            return PreflightOutcome::Failed(format!("could not get image size: {}", error))
+        }
+    };
+    let required = frame_size * plan.frame_count as u64;
+    match available_space(directory) {
+        Some(available) if available >= required => PreflightOutcome::Passed,
+        Some(available) => PreflightOutcome::Failed(format!(
+            "{} bytes needed, but only {} bytes available in {}",
+            required,
+            available,
+            directory.display()
+        )),
+        None => PreflightOutcome::Failed(format!(
+            "could not determine free space in {}",
+            directory.display()
+        )),
+    }
+}
+
+fn check_exposure(camera: &Camera, plan: &PreflightPlan) -> PreflightOutcome {
+    match camera.get_parameter_min_max_step(Control::Exposure) {
+        Ok((min, max, _)) if plan.exposure_us >= min && plan.exposure_us <= max => {
+            PreflightOutcome::Passed
+        }
+        Ok((min, max, _)) => PreflightOutcome::Failed(format!(
+            "exposure of {}us is outside of {}us..={}us",
+            plan.exposure_us, min, max
+        )),
+        Err(error) => {
+            PreflightOutcome::Failed(format!("could not read exposure limits: {}", error))
+        }
+    }
+}
+
+/// returns the number of bytes available to unprivileged users on the file system of `path`
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn available_space(path: &std::path::Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    match unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } {
+        0 => Some(stat.f_bavail as u64 * stat.f_frsize as u64),
+        _ => None,
+    }
+}
+
+/// the free space is only known on unix, `check_disk_space` skips the check elsewhere
+#[cfg(not(unix))]
+pub(crate) fn available_space(_path: &std::path::Path) -> Option<u64> {
+    None
+}
@@ -0,0 +1,116 @@
+//! Provenance hash chain for captured frames
+//!
+//! `ProvenanceChain` hashes every frame with SHA-256 and links the hash with the one of the
+//! previous frame. Storing the resulting `FrameProvenance` next to the archived frames makes it
+//! possible to detect later modifications, removals or reordering of frames in a series.
+
+use sha2::{Digest, Sha256};
+
+use crate::ImageData;
+
+/// the previous hash of the first frame in a chain
+const GENESIS: [u8; 32] = [0; 32];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// The provenance record of a single frame, returned from `ProvenanceChain::record`
+pub struct FrameProvenance {
+    /// the position of the frame in the chain starting with 0
+    pub sequence: u64,
+    /// the SHA-256 hash of the frame geometry and data
+    pub frame_hash: [u8; 32],
+    /// the `chain_hash` of the previous frame, all zeros for the first frame
+    pub previous_hash: [u8; 32],
+    /// the SHA-256 hash of `sequence`, `previous_hash` and `frame_hash`
+    pub chain_hash: [u8; 32],
+}
+
+impl FrameProvenance {
+    fn new(sequence: u64, frame_hash: [u8; 32], previous_hash: [u8; 32]) -> Self {
+        let chain_hash = Sha256::new()
+            .chain_update(sequence.to_le_bytes())
+            .chain_update(previous_hash)
+            .chain_update(frame_hash)
+            .finalize()
+            .into();
+        Self {
+            sequence,
+            frame_hash,
+            previous_hash,
+            chain_hash,
+        }
+    }
+
+    /// Returns `true` if `image` is the frame this record was created for and the record
+    /// follows `previous`, pass `None` for the first frame of a chain
+    pub fn verify(&self, image: &ImageData, previous: Option<&FrameProvenance>) -> bool {
+        let (sequence, previous_hash) = match previous {
+            Some(previous) => (previous.sequence + 1, previous.chain_hash),
+            None => (0, GENESIS),
+        };
+        *self == FrameProvenance::new(sequence, hash_frame(image), previous_hash)
+    }
+
+    /// Returns `chain_hash` as lower case hex string, e.g. to store it in a FITS header
+    pub fn chain_hash_hex(&self) -> String {
+        self.chain_hash
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+/// hashes the geometry and the data of a frame
+fn hash_frame(image: &ImageData) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(image.width.to_le_bytes())
+        .chain_update(image.height.to_le_bytes())
+        .chain_update(image.bits_per_pixel.to_le_bytes())
+        .chain_update(image.channels.to_le_bytes())
+        .chain_update(&image.data)
+        .finalize()
+        .into()
+}
+
+#[derive(Debug, Default)]
+/// Links the hashes of consecutive frames, needs the `provenance` feature
+pub struct ProvenanceChain {
+    last: Option<FrameProvenance>,
+}
+
+impl ProvenanceChain {
+    /// Creates a new, empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hashes `image`, links it to the previously recorded frame and returns the record
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{ProvenanceChain, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let mut chain = ProvenanceChain::new();
+    /// for _ in 0..10 {
+    ///     camera.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+    ///     let buffer_size = camera.get_image_size().expect("get_image_size failed");
+    ///     let image = camera.get_single_frame(buffer_size).expect("get_single_frame failed");
+    ///     let provenance = chain.record(&image);
+    ///     println!("frame {} {}", provenance.sequence, provenance.chain_hash_hex());
+    /// }
+    /// ```
+    pub fn record(&mut self, image: &ImageData) -> FrameProvenance {
+        let (sequence, previous_hash) = match &self.last {
+            Some(last) => (last.sequence + 1, last.chain_hash),
+            None => (0, GENESIS),
+        };
+        let provenance = FrameProvenance::new(sequence, hash_frame(image), previous_hash);
+        self.last = Some(provenance.clone());
+        provenance
+    }
+
+    /// Returns the record of the last frame, `None` if no frame was recorded yet
+    pub fn last(&self) -> Option<&FrameProvenance> {
+        self.last.as_ref()
+    }
+}
@@ -0,0 +1,75 @@
+//! One-shot capture for getting started
+//!
+//! `capture` runs the whole open, configure, expose, download and save flow with sensible
+//! defaults. It only uses the public API of the crate, so its source doubles as an example of
+//! how the individual steps fit together.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::fits::{write_fits, HeaderValue};
+use crate::QHYError::CameraNotFoundError;
+use crate::{Control, ImageData, Result, Sdk, StreamMode};
+
+/// Captures a single frame with the given exposure time and saves it as FITS file to `path`.
+/// Uses the camera with the id `id_or_first` or the first camera found if it is `None`. The
+/// camera is put into single frame mode with 16 bit transfer where supported and closed again
+/// afterwards. Returns the captured frame.
+/// # Example
+/// ```no_run
+/// use std::path::Path;
+/// use std::time::Duration;
+///
+/// let image = qhyccd_rs::quick::capture(None, Duration::from_secs(2), Path::new("first.fits"))
+///     .expect("capture failed");
+/// println!("captured {}x{} pixels", image.width, image.height);
+/// ```
+pub fn capture(id_or_first: Option<&str>, exposure: Duration, path: &Path) -> Result<ImageData> {
+    let sdk = Sdk::new()?;
+    let camera = match id_or_first {
+        Some(id) => sdk.camera_by_id(id),
+        None => sdk.cameras().next(),
+    };
+    let camera = camera.ok_or_else(|| {
+        let error = CameraNotFoundError {
+            id: id_or_first.map(str::to_owned),
+        };
+        tracing::error!(error = ?error);
+        error
+    })?;
+
+    camera.open()?;
+    let image = (|| {
+        camera.set_stream_mode(StreamMode::SingleFrameMode)?;
+        camera.init()?;
+        if camera.is_control_available(Control::TransferBit).is_some() {
+            camera.set_parameter(Control::TransferBit, 16.0)?;
+        }
+        camera.set_parameter(Control::Exposure, exposure.as_micros() as f64)?;
+        camera.start_single_frame_exposure()?;
+        let buffer_size = camera.get_image_size()?;
+        camera.get_single_frame(buffer_size)
+    })();
+    if let Err(error) = camera.close() {
+        tracing::warn!(close = ?error);
+    }
+    let image = image?;
+
+    write_fits(
+        &image,
+        path,
+        &[
+            (
+                "EXPTIME",
+                HeaderValue::Float(exposure.as_secs_f64()),
+                "exposure time in seconds",
+            ),
+            (
+                "INSTRUME",
+                HeaderValue::Str(camera.id().to_owned()),
+                "camera id",
+            ),
+        ],
+    )?;
+    Ok(image)
+}
@@ -0,0 +1,132 @@
+//! Rate limiting of parameter writes
+//!
+//! Some firmware misbehaves when a control is written many times per second, e.g. while a GUI
+//! slider for the cooler set-point is dragged. `Camera::set_rate_limit` sets a minimum interval
+//! between two writes of a control, `Camera::set_parameter` delays writes that come in earlier.
+//! Writes waiting for the same slot are coalesced, only the last one reaches the camera, so a
+//! burst of writes never replays stale values. The dropped ones fail with
+//! `SupersededWriteError`, so callers can tell that their value was not written.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{Camera, Control};
+
+#[derive(Debug, Default)]
+/// the configured minimum intervals, the time of the last write and the newest pending write per
+/// control
+pub(crate) struct RateLimiter {
+    limits: HashMap<u32, Duration>,
+    last_write: HashMap<u32, Instant>,
+    pending: HashMap<u32, u64>,
+    tickets: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// a write slot handed out by `RateLimiter::reserve`
+pub(crate) struct Reservation {
+    /// how long to wait for the slot
+    pub(crate) wait: Duration,
+    /// identifies the write among the ones waiting for the same slot, `None` without a limit
+    ticket: Option<u64>,
+}
+
+impl RateLimiter {
+    /// reserves the next write slot for `control`, a write still waiting for its slot is
+    /// superseded and shares it with the new one
+    pub(crate) fn reserve(&mut self, control: Control) -> Reservation {
+        let now = Instant::now();
+        let Some(limit) = self.limits.get(&(control as u32)) else {
+            return Reservation {
+                wait: Duration::ZERO,
+                ticket: None,
+            };
+        };
+        let slot = match self.last_write.get(&(control as u32)) {
+            Some(last) if *last > now => *last,
+            Some(last) => (*last + *limit).max(now),
+            None => now,
+        };
+        self.last_write.insert(control as u32, slot);
+        self.tickets += 1;
+        self.pending.insert(control as u32, self.tickets);
+        Reservation {
+            wait: slot - now,
+            ticket: Some(self.tickets),
+        }
+    }
+
+    /// returns `true` if a newer write of `control` took over the slot of `reservation`
+    pub(crate) fn is_superseded(&self, control: Control, reservation: &Reservation) -> bool {
+        reservation.ticket.map_or(false, |ticket| {
+            self.pending.get(&(control as u32)) != Some(&ticket)
+        })
+    }
+}
+
+impl Camera {
+    /// Sets the minimum interval between two writes of `control` through `set_parameter`, writes
+    /// that come in earlier are delayed. A delayed write is dropped with `SupersededWriteError` if
+    /// a newer write of the same control comes in while it waits, only the newest value is
+    /// written. `None` removes the
+    /// limit. Limits are shared by all clones of the camera.
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use qhyccd_rs::{Control, Sdk};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.set_rate_limit(Control::Cooler, Some(Duration::from_secs(2)));
+    /// camera.set_rate_limit(Control::UsbTraffic, Some(Duration::from_millis(500)));
+    /// ```
+    pub fn set_rate_limit(&self, control: Control, interval: Option<Duration>) {
+        let mut limiter = self
+            .rate_limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match interval {
+            Some(interval) => {
+                limiter.limits.insert(control as u32, interval);
+            }
+            None => {
+                limiter.limits.remove(&(control as u32));
+                limiter.last_write.remove(&(control as u32));
+                limiter.pending.remove(&(control as u32));
+            }
+        }
+    }
+
+    /// Returns the minimum interval between two writes of `control`, see `set_rate_limit`
+    pub fn rate_limit(&self, control: Control) -> Option<Duration> {
+        self.rate_limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .limits
+            .get(&(control as u32))
+            .copied()
+    }
+
+    /// waits until `control` may be written again, returns `false` if a newer write of `control`
+    /// came in meanwhile and this one has to be dropped
+    pub(crate) fn wait_for_rate_limit(&self, control: Control) -> bool {
+        let reservation = self
+            .rate_limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .reserve(control);
+        if reservation.wait.is_zero() {
+            return true;
+        }
+        tracing::debug!(?control, wait = ?reservation.wait, "delaying rate limited write");
+        std::thread::sleep(reservation.wait);
+        let superseded = self
+            .rate_limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_superseded(control, &reservation);
+        if superseded {
+            tracing::debug!(?control, "dropping superseded rate limited write");
+        }
+        !superseded
+    }
+}
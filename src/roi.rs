@@ -0,0 +1,90 @@
+//! Validation of the region of interest
+//!
+//! The SDK rejects an invalid ROI with an error code that does not say what is wrong with it.
+//! `Camera::set_roi` therefore checks the ROI first and fails with a `RoiError` naming the
+//! `RoiConstraint` it violates. The camera cannot report the active ROI, `Camera::get_roi`
+//! returns the one last set through this crate. Both work on the effective area of the sensor,
+//! so the ROI `get_roi` returns can always be set again.
+
+use crate::QHYError::RoiError;
+use crate::{CCDChipArea, Camera, Control, Result};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// A rule the ROI passed to `Camera::set_roi` has to follow
+pub enum RoiConstraint {
+    /// the ROI must be at least one pixel wide and high
+    NotEmpty,
+    /// the ROI must lie within the effective area of the sensor, given here in binned pixels
+    WithinEffectiveArea(CCDChipArea),
+    /// on unbinned color sensors the ROI must start on an even pixel to keep the bayer pattern
+    BayerAligned,
+}
+
+impl Camera {
+    /// Returns the active region of interest in binned pixels. That is the ROI last set through
+    /// `set_roi`, or the effective area at the current binning if none was set.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let roi = camera.get_roi().expect("get_roi failed");
+    /// println!("ROI: {:?}", roi);
+    /// ```
+    pub fn get_roi(&self) -> Result<CCDChipArea> {
+        let geometry = self.frame_geometry();
+        if let Some(roi) = geometry.roi {
+            return Ok(roi);
+        }
+        self.binned_effective_area(geometry.binning.unwrap_or((1, 1)))
+    }
+
+    /// the binned pixels entirely within the effective area of the sensor at `binning`
+    pub(crate) fn binned_effective_area(&self, (bin_x, bin_y): (u32, u32)) -> Result<CCDChipArea> {
+        let (bin_x, bin_y) = (bin_x.max(1), bin_y.max(1));
+        let effective = self.get_effective_area()?;
+        let (start_x, start_y) = (
+            (effective.start_x + bin_x - 1) / bin_x,
+            (effective.start_y + bin_y - 1) / bin_y,
+        );
+        Ok(CCDChipArea {
+            start_x,
+            start_y,
+            // an area narrower than one bin holds no whole binned pixel
+            width: ((effective.start_x + effective.width) / bin_x).saturating_sub(start_x),
+            height: ((effective.start_y + effective.height) / bin_y).saturating_sub(start_y),
+        })
+    }
+
+    /// checks `roi` in pixels binned by `binning` against all `RoiConstraint`s
+    pub(crate) fn check_roi(&self, roi: CCDChipArea, binning: (u32, u32)) -> Result<()> {
+        let binned = self.binned_effective_area(binning)?;
+        let within = |start: u32, len: u32, area_start: u32, area_len: u32| {
+            start >= area_start && start as u64 + len as u64 <= area_start as u64 + area_len as u64
+        };
+        let constraint = if roi.width == 0 || roi.height == 0 {
+            Some(RoiConstraint::NotEmpty)
+        } else if !within(roi.start_x, roi.width, binned.start_x, binned.width)
+            || !within(roi.start_y, roi.height, binned.start_y, binned.height)
+        {
+            Some(RoiConstraint::WithinEffectiveArea(binned))
+        } else if binning.0 <= 1
+            && binning.1 <= 1
+            && (roi.start_x % 2 != 0 || roi.start_y % 2 != 0)
+            && self.is_control_available(Control::CamColor).is_some()
+        {
+            Some(RoiConstraint::BayerAligned)
+        } else {
+            None
+        };
+        match constraint {
+            Some(constraint) => {
+                let error = RoiError { roi, constraint };
+                tracing::error!(error = ?error);
+                Err(error)
+            }
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Functions of the SDK that older releases lack
+//!
+//! `Sdk::features` reports which of the optional parts of the SDK the installed libqhyccd
+//! provides. Linked at build time every part is there, the build fails otherwise. With the
+//! `dlopen` feature a part is missing if the loaded library lacks one of its functions, and the
+//! methods using it fail with `UnsupportedSdkFeatureError` instead of calling into the library.
+
+#[cfg(not(test))]
+use libqhyccd_sys::is_available;
+
+#[cfg(test)]
+use crate::mocks::is_available;
+
+use crate::QHYError::UnsupportedSdkFeatureError;
+use crate::{Result, Sdk};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An optional part of the SDK, see `Sdk::features`
+pub enum SdkFeature {
+    /// `Sdk::subscribe_hotplug`
+    Hotplug,
+    /// the readout mode functions of `Camera`, e.g. `Camera::set_readout_mode`
+    ReadoutModes,
+    /// `Camera::get_sensor_name`
+    SensorName,
+    /// `Camera::get_fpga_version`
+    FpgaVersion,
+    /// `Camera::get_precise_exposure_info`
+    PreciseExposureInfo,
+    /// `Camera::get_rolling_shutter_end_offset`
+    RollingShutterEndOffset,
+    /// `Camera::abort_exposure_and_readout`
+    AbortReadout,
+    /// `Camera::get_trigger_interfaces` and `Camera::set_trigger_interface`
+    TriggerInterfaces,
+    /// the GPS functions of `Camera`, e.g. `Camera::set_gps_vcox_frequency`
+    Gps,
+    /// `Camera::get_humidity`
+    Humidity,
+    /// `Camera::get_pressure`
+    Pressure,
+    /// the flash configuration functions of `Camera`, e.g. `Camera::read_flash_config`
+    FlashConfig,
+    /// `Camera::retrain_sensor_phase`
+    SensorPhaseReTrain,
+}
+
+impl SdkFeature {
+    /// all optional parts of the SDK
+    pub const ALL: [SdkFeature; 13] = [
+        SdkFeature::Hotplug,
+        SdkFeature::ReadoutModes,
+        SdkFeature::SensorName,
+        SdkFeature::FpgaVersion,
+        SdkFeature::PreciseExposureInfo,
+        SdkFeature::RollingShutterEndOffset,
+        SdkFeature::AbortReadout,
+        SdkFeature::TriggerInterfaces,
+        SdkFeature::Gps,
+        SdkFeature::Humidity,
+        SdkFeature::Pressure,
+        SdkFeature::FlashConfig,
+        SdkFeature::SensorPhaseReTrain,
+    ];
+
+    /// the SDK functions the feature needs
+    pub fn functions(self) -> &'static [&'static str] {
+        match self {
+            SdkFeature::Hotplug => &["RegisterPnpEventIn", "RegisterPnpEventOut"],
+            SdkFeature::ReadoutModes => &[
+                "GetQHYCCDNumberOfReadModes",
+                "GetQHYCCDReadModeName",
+                "GetQHYCCDReadModeResolution",
+                "GetQHYCCDReadMode",
+                "SetQHYCCDReadMode",
+            ],
+            SdkFeature::SensorName => &["GetQHYCCDSensorName"],
+            SdkFeature::FpgaVersion => &["GetQHYCCDFPGAVersion"],
+            SdkFeature::PreciseExposureInfo => &["GetQHYCCDPreciseExposureInfo"],
+            SdkFeature::RollingShutterEndOffset => &["GetQHYCCDRollingShutterEndOffset"],
+            SdkFeature::AbortReadout => &["CancelQHYCCDExposingAndReadout"],
+            SdkFeature::TriggerInterfaces => &[
+                "GetQHYCCDTrigerInterfaceNumber",
+                "GetQHYCCDTrigerInterfaceName",
+                "SetQHYCCDTrigerInterface",
+            ],
+            SdkFeature::Gps => &[
+                "SetQHYCCDGPSLedCalMode",
+                "SetQHYCCDGPSLedCal",
+                "SetQHYCCDGPSPOSA",
+                "SetQHYCCDGPSPOSB",
+                "SetQHYCCDGPSVCOXFreq",
+            ],
+            SdkFeature::Humidity => &["GetQHYCCDHumidity"],
+            SdkFeature::Pressure => &["GetQHYCCDPressure"],
+            SdkFeature::FlashConfig => &[
+                "QHYCCDReadInitConfigFlash",
+                "QHYCCDEraseInitConfigFlash",
+                "QHYCCDSetFlashInitPWM",
+                "QHYCCDResetFlashULVOError",
+            ],
+            SdkFeature::SensorPhaseReTrain => &["QHYCCDSensorPhaseReTrain"],
+        }
+    }
+
+    fn bit(self) -> u32 {
+        1 << SdkFeature::ALL
+            .iter()
+            .position(|feature| *feature == self)
+            .expect("every feature is in ALL")
+    }
+
+    fn is_available(self) -> bool {
+        self.functions()
+            .iter()
+            .all(|function| is_available(function))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+/// The optional parts of the SDK the installed libqhyccd provides, returned from `Sdk::features`
+pub struct SdkFeatures(u32);
+
+impl SdkFeatures {
+    /// Returns `true` if `feature` is in the set
+    pub fn contains(&self, feature: SdkFeature) -> bool {
+        self.0 & feature.bit() != 0
+    }
+
+    /// Adds `feature` to the set
+    pub fn insert(&mut self, feature: SdkFeature) {
+        self.0 |= feature.bit();
+    }
+
+    /// Returns the features in the set
+    pub fn iter(&self) -> impl Iterator<Item = SdkFeature> + '_ {
+        SdkFeature::ALL
+            .iter()
+            .copied()
+            .filter(|feature| self.contains(*feature))
+    }
+}
+
+impl std::fmt::Debug for SdkFeatures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<SdkFeature> for SdkFeatures {
+    fn from_iter<I: IntoIterator<Item = SdkFeature>>(iter: I) -> Self {
+        let mut features = SdkFeatures::default();
+        for feature in iter {
+            features.insert(feature);
+        }
+        features
+    }
+}
+
+impl Sdk {
+    /// Returns the optional parts of the SDK the installed libqhyccd provides
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{Sdk, SdkFeature};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// if !sdk.features().contains(SdkFeature::Humidity) {
+    ///     println!("this SDK cannot read the humidity sensor");
+    /// }
+    /// ```
+    pub fn features(&self) -> SdkFeatures {
+        SdkFeature::ALL
+            .iter()
+            .copied()
+            .filter(|feature| feature.is_available())
+            .collect()
+    }
+}
+
+/// fails with `UnsupportedSdkFeatureError` if the installed libqhyccd lacks `feature`
+pub(crate) fn require(feature: SdkFeature) -> Result<()> {
+    if feature.is_available() {
+        return Ok(());
+    }
+    let error = UnsupportedSdkFeatureError { feature };
+    tracing::error!(error = ?error);
+    Err(error)
+}
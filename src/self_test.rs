@@ -0,0 +1,252 @@
+//! Scripted self-test for a connected camera
+//!
+//! `Camera::self_test` opens and initializes the camera, reads its capabilities, takes a bias
+//! and a short dark frame and checks their statistics, briefly exercises the cooler if there is
+//! one and reads the state of the sensor chamber pumps. It is meant for troubleshooting in the field and for CI against hardware.
+
+use std::time::Duration;
+
+use crate::arithmetic::{max_sample, pixel_samples};
+use crate::QHYError::{self, SelfTestCheckError};
+use crate::{Camera, Control, ImageData, Result, StreamMode};
+
+#[derive(Debug, PartialEq, Clone)]
+/// Options for `Camera::self_test_with_options`
+pub struct SelfTestOptions {
+    /// the exposure time of the dark frame
+    pub dark_exposure: Duration,
+    /// how long the cooler gets to react after the set-point was lowered
+    pub cooler_settle_time: Duration,
+    /// frames with a mean above this fraction of the full scale count as saturated
+    pub max_mean_fraction: f64,
+}
+
+impl Default for SelfTestOptions {
+    fn default() -> Self {
+        Self {
+            dark_exposure: Duration::from_secs(1),
+            cooler_settle_time: Duration::from_secs(5),
+            max_mean_fraction: 0.9,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The steps of the self-test in the order they are run
+pub enum SelfTestStep {
+    /// opening the camera
+    Open,
+    /// switching to single frame mode and initializing the camera
+    Init,
+    /// reading chip info and probing the available controls
+    Capabilities,
+    /// taking a frame with the minimum exposure time
+    BiasFrame,
+    /// taking a short dark frame
+    DarkFrame,
+    /// lowering the cooler set-point and checking the cooler reacts
+    Cooler,
+    /// reading the state of the vacuum and chamber cycle pumps
+    Chamber,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// The outcome of a single `SelfTestStep`
+pub enum SelfTestOutcome {
+    /// the step passed, the string holds details like measured values
+    Passed(String),
+    /// the step failed, the string describes why
+    Failed(String),
+    /// the step was not run, the string describes why
+    Skipped(String),
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+/// The pass/fail report returned from `Camera::self_test`
+pub struct SelfTestReport {
+    /// the outcome of every step in the order they were run
+    pub results: Vec<(SelfTestStep, SelfTestOutcome)>,
+}
+
+impl SelfTestReport {
+    /// Returns `true` if no step failed, skipped steps do not count as failures
+    pub fn passed(&self) -> bool {
+        !self
+            .results
+            .iter()
+            .any(|(_, outcome)| matches!(outcome, SelfTestOutcome::Failed(_)))
+    }
+
+    /// Returns the outcome of the given step if it was part of the run
+    pub fn outcome(&self, step: SelfTestStep) -> Option<&SelfTestOutcome> {
+        self.results
+            .iter()
+            .find(|(s, _)| *s == step)
+            .map(|(_, outcome)| outcome)
+    }
+}
+
+fn outcome_of(result: Result<String>) -> SelfTestOutcome {
+    match result {
+        Ok(details) => SelfTestOutcome::Passed(details),
+        Err(error) => SelfTestOutcome::Failed(error.to_string()),
+    }
+}
+
+impl Camera {
+    /// Runs the self-test with the default `SelfTestOptions`. The camera is left open and in
+    /// single frame mode afterwards.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// let report = camera.self_test();
+    /// for (step, outcome) in &report.results {
+    ///     println!("{:?}: {:?}", step, outcome);
+    /// }
+    /// assert!(report.passed());
+    /// ```
+    pub fn self_test(&self) -> SelfTestReport {
+        self.self_test_with_options(&SelfTestOptions::default())
+    }
+
+    /// Runs the self-test with the given options, see `self_test`
+    pub fn self_test_with_options(&self, options: &SelfTestOptions) -> SelfTestReport {
+        let mut report = SelfTestReport::default();
+        let mut run = |step: SelfTestStep, result: Result<String>| {
+            let outcome = outcome_of(result);
+            let passed = !matches!(outcome, SelfTestOutcome::Failed(_));
+            report.results.push((step, outcome));
+            passed
+        };
+        let ready = run(
+            SelfTestStep::Open,
+            self.open().map(|_| format!("opened {}", self.id())),
+        ) && run(SelfTestStep::Init, self.self_test_init())
+            && run(SelfTestStep::Capabilities, self.self_test_capabilities());
+        if !ready {
+            let remaining = [
+                SelfTestStep::Init,
+                SelfTestStep::Capabilities,
+                SelfTestStep::BiasFrame,
+                SelfTestStep::DarkFrame,
+                SelfTestStep::Cooler,
+                SelfTestStep::Chamber,
+            ];
+            for step in remaining {
+                if report.outcome(step).is_none() {
+                    report.results.push((
+                        step,
+                        SelfTestOutcome::Skipped("camera is not ready".to_owned()),
+                    ));
+                }
+            }
+            return report;
+        }
+        let bias = self.self_test_bias(options);
+        report
+            .results
+            .push((SelfTestStep::BiasFrame, outcome_of(bias)));
+        let dark = self.self_test_dark(options);
+        report
+            .results
+            .push((SelfTestStep::DarkFrame, outcome_of(dark)));
+        let cooler = match self.is_control_available(Control::Cooler) {
+            Some(_) => outcome_of(self.self_test_cooler(options)),
+            None => SelfTestOutcome::Skipped("camera has no cooler".to_owned()),
+        };
+        report.results.push((SelfTestStep::Cooler, cooler));
+        let chamber = self.chamber();
+        let chamber = match chamber.is_present() {
+            true => outcome_of(chamber.status().map(|status| {
+                format!(
+                    "vacuum pump {:?}, cycle pump {:?}",
+                    status.vacuum_pump, status.cycle_pump
+                )
+            })),
+            false => SelfTestOutcome::Skipped("camera has no chamber pumps".to_owned()),
+        };
+        report.results.push((SelfTestStep::Chamber, chamber));
+        report
+    }
+
+    fn self_test_init(&self) -> Result<String> {
+        self.set_stream_mode(StreamMode::SingleFrameMode)?;
+        self.init()?;
+        Ok("initialized in single frame mode".to_owned())
+    }
+
+    fn self_test_capabilities(&self) -> Result<String> {
+        let info = self.get_ccd_info()?;
+        let available = self.available_controls().len();
+        Ok(format!(
+            "{}x{} pixels, {} bits, {} of {} known controls available",
+            info.image_width,
+            info.image_height,
+            info.bits_per_pixel,
+            available,
+            Control::all().len()
+        ))
+    }
+
+    fn self_test_bias(&self, options: &SelfTestOptions) -> Result<String> {
+        let (min, _, _) = self.get_parameter_min_max_step(Control::Exposure)?;
+        let image = self.self_test_frame(min)?;
+        check_mean(&image, options.max_mean_fraction)
+    }
+
+    fn self_test_dark(&self, options: &SelfTestOptions) -> Result<String> {
+        let image = self.self_test_frame(options.dark_exposure.as_micros() as f64)?;
+        check_mean(&image, options.max_mean_fraction)
+    }
+
+    fn self_test_frame(&self, exposure_us: f64) -> Result<ImageData> {
+        self.set_parameter(Control::Exposure, exposure_us)?;
+        self.start_single_frame_exposure()?;
+        let buffer_size = self.get_image_size()?;
+        self.get_single_frame(buffer_size)
+    }
+
+    pub(crate) fn self_test_cooler(&self, options: &SelfTestOptions) -> Result<String> {
+        let set_point = self.get_parameter(Control::Cooler)?;
+        let start = self.get_parameter(Control::CurTemp)?;
+        self.set_parameter(Control::Cooler, start - 2.0)?;
+        std::thread::sleep(options.cooler_settle_time);
+        let power = self.get_parameter(Control::CurPWM);
+        // hand back the set-point the camera had before, also if reading the power failed
+        self.set_parameter(Control::Cooler, set_point)?;
+        match power? {
+            power if power > 0.0 => Ok(format!("cooler power {:.0} at {:.1}°C", power, start)),
+            _ => Err(check_failed(
+                "cooler power stayed at 0 after lowering the set-point".to_owned(),
+            )),
+        }
+    }
+}
+
+fn check_failed(reason: String) -> QHYError {
+    let error = SelfTestCheckError { reason };
+    tracing::error!(error = ?error);
+    error
+}
+
+/// checks that a frame is neither empty nor saturated and returns its mean as details, the
+/// padding after the pixels is ignored and the full scale follows the reported bit depth
+fn check_mean(image: &ImageData, max_mean_fraction: f64) -> Result<String> {
+    let samples = pixel_samples(image)?;
+    if samples.is_empty() {
+        return Err(check_failed("frame contains no samples".to_owned()));
+    }
+    let mean = samples.iter().sum::<u64>() as f64 / samples.len() as f64;
+    let full_scale = max_sample(image.bits_per_pixel)? as f64;
+    match mean {
+        mean if mean <= 0.0 => Err(check_failed("frame is completely black".to_owned())),
+        mean if mean > full_scale * max_mean_fraction => Err(check_failed(format!(
+            "frame mean {:.1} is above {:.0}% of full scale",
+            mean,
+            max_mean_fraction * 100.0
+        ))),
+        mean => Ok(format!("mean {:.1}", mean)),
+    }
+}
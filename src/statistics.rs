@@ -0,0 +1,198 @@
+//! Frame statistics and histograms
+//!
+//! 8 and 16 bit frames are reduced to a histogram of the exact sample values in a single pass
+//! over the data, all statistics are derived from that histogram afterwards. 32 bit frames are
+//! sorted instead. With the `rayon` feature the pass over the data runs in parallel.
+
+use crate::{ImageData, PixelFormat, Result};
+
+/// the number of samples counted per task when running in parallel
+#[cfg(feature = "rayon")]
+const CHUNK_SAMPLES: usize = 1 << 20;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// Statistics of all samples of a frame returned from `ImageData::statistics`
+pub struct FrameStatistics {
+    /// the smallest sample
+    pub min: u32,
+    /// the largest sample
+    pub max: u32,
+    /// the mean of all samples
+    pub mean: f64,
+    /// the median of all samples, the mean of the two middle samples for an even count
+    pub median: f64,
+    /// the population standard deviation of all samples
+    pub stddev: f64,
+    /// the percentage of samples at the full scale of the sample type
+    pub saturated_percent: f64,
+}
+
+/// counts how often every value of `samples` occurs, `counts` has one entry per possible value
+fn count_values<T: Copy + Into<u32> + Sync>(samples: &[T], values: usize) -> Vec<u64> {
+    let count = |samples: &[T]| {
+        let mut counts = vec![0_u64; values];
+        samples
+            .iter()
+            .for_each(|&sample| counts[sample.into() as usize] += 1);
+        counts
+    };
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        samples.par_chunks(CHUNK_SAMPLES).map(count).reduce(
+            || vec![0_u64; values],
+            |mut total, counts| {
+                total.iter_mut().zip(counts).for_each(|(t, c)| *t += c);
+                total
+            },
+        )
+    }
+    #[cfg(not(feature = "rayon"))]
+    count(samples)
+}
+
+/// the value histogram of an 8 or 16 bit frame, or the sorted samples of a 32 bit frame
+enum Distribution {
+    Counts(Vec<u64>),
+    Sorted(Vec<u32>),
+}
+
+impl Distribution {
+    fn of(image: &ImageData) -> Result<(Distribution, PixelFormat)> {
+        let format = image.pixel_format()?;
+        let distribution = match format {
+            PixelFormat::U8 => Distribution::Counts(count_values(image.as_u8_slice()?, 1 << 8)),
+            PixelFormat::U16 => Distribution::Counts(count_values(&image.as_u16_slice()?, 1 << 16)),
+            PixelFormat::U32 => {
+                let mut samples = image.as_u32_slice()?.into_owned();
+                #[cfg(feature = "rayon")]
+                rayon::slice::ParallelSliceMut::par_sort_unstable(samples.as_mut_slice());
+                #[cfg(not(feature = "rayon"))]
+                samples.sort_unstable();
+                Distribution::Sorted(samples)
+            }
+        };
+        Ok((distribution, format))
+    }
+
+    /// iterates over all distinct values with their count in ascending order
+    fn values(&self) -> Box<dyn Iterator<Item = (u32, u64)> + '_> {
+        match self {
+            Distribution::Counts(counts) => Box::new(
+                counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &count)| count > 0)
+                    .map(|(value, &count)| (value as u32, count)),
+            ),
+            Distribution::Sorted(samples) => Box::new(
+                samples
+                    .iter()
+                    .enumerate()
+                    .filter(|&(index, value)| index == 0 || samples[index - 1] != *value)
+                    .map(|(index, &value)| {
+                        let run = samples[index..].partition_point(|&sample| sample == value);
+                        (value, run as u64)
+                    }),
+            ),
+        }
+    }
+
+    /// returns the sample at `index` in sorted order
+    fn nth(&self, index: u64) -> u32 {
+        let mut seen = 0;
+        for (value, count) in self.values() {
+            seen += count;
+            if index < seen {
+                return value;
+            }
+        }
+        0
+    }
+}
+
+/// the largest sample value of the given format
+fn full_scale(format: PixelFormat) -> u32 {
+    match format {
+        PixelFormat::U8 => u8::MAX as u32,
+        PixelFormat::U16 => u16::MAX as u32,
+        PixelFormat::U32 => u32::MAX,
+    }
+}
+
+impl ImageData {
+    /// Returns min, max, mean, median, standard deviation and the saturated percentage of all
+    /// samples of the frame, channels are not told apart. All values are zero for empty frames.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData { data: vec![1, 2, 3, 255], width: 2, height: 2, bits_per_pixel: 8, channels: 1 };
+    /// let statistics = image.statistics().expect("statistics failed");
+    /// assert_eq!(statistics.median, 2.5);
+    /// assert_eq!(statistics.saturated_percent, 25.0);
+    /// ```
+    pub fn statistics(&self) -> Result<FrameStatistics> {
+        let (distribution, format) = Distribution::of(self)?;
+        let count = distribution.values().map(|(_, count)| count).sum::<u64>();
+        if count == 0 {
+            return Ok(FrameStatistics {
+                min: 0,
+                max: 0,
+                mean: 0.0,
+                median: 0.0,
+                stddev: 0.0,
+                saturated_percent: 0.0,
+            });
+        }
+        let mean = distribution
+            .values()
+            .map(|(value, count)| value as f64 * count as f64)
+            .sum::<f64>()
+            / count as f64;
+        let variance = distribution
+            .values()
+            .map(|(value, count)| (value as f64 - mean).powi(2) * count as f64)
+            .sum::<f64>()
+            / count as f64;
+        let saturated = distribution
+            .values()
+            .filter(|(value, _)| *value == full_scale(format))
+            .map(|(_, count)| count)
+            .sum::<u64>();
+        let median = match count % 2 {
+            0 => {
+                (distribution.nth(count / 2 - 1) as f64 + distribution.nth(count / 2) as f64) / 2.0
+            }
+            _ => distribution.nth(count / 2) as f64,
+        };
+        let min = distribution.values().next().map_or(0, |(value, _)| value);
+        let max = distribution.values().last().map_or(0, |(value, _)| value);
+        Ok(FrameStatistics {
+            min,
+            max,
+            mean,
+            median,
+            stddev: variance.sqrt(),
+            saturated_percent: saturated as f64 * 100.0 / count as f64,
+        })
+    }
+
+    /// Returns a histogram with `bins` bins of equal width spanning the full scale of the sample
+    /// type, a `bins` of 0 is treated as 1
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::ImageData;
+    /// let image = ImageData { data: vec![1, 2, 3, 255], width: 2, height: 2, bits_per_pixel: 8, channels: 1 };
+    /// assert_eq!(image.histogram(2).expect("histogram failed"), vec![3, 1]);
+    /// ```
+    pub fn histogram(&self, bins: usize) -> Result<Vec<u64>> {
+        let bins = bins.max(1);
+        let (distribution, format) = Distribution::of(self)?;
+        let values = full_scale(format) as u128 + 1;
+        let mut histogram = vec![0_u64; bins];
+        for (value, count) in distribution.values() {
+            histogram[(value as u128 * bins as u128 / values) as usize] += count;
+        }
+        Ok(histogram)
+    }
+}
@@ -0,0 +1,126 @@
+//! Stream modes as types
+//!
+//! `Camera::into_live_mode` and `Camera::into_single_frame_mode` switch the camera and return
+//! `LiveCamera` and `SingleFrameCamera`, which only offer the frame methods that work in their
+//! mode. Calling `get_live_frame` on a camera in single frame mode then fails to compile instead
+//! of returning an error from the SDK. Settings are still changed through `camera()`.
+
+use std::time::Duration;
+
+use crate::{Camera, ExposureHandle, FrameInfo, ImageData, Result, StreamMode};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A camera streaming in live mode, returned from `Camera::into_live_mode`
+pub struct LiveCamera {
+    camera: Camera,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A camera in single frame mode, returned from `Camera::into_single_frame_mode`
+pub struct SingleFrameCamera {
+    camera: Camera,
+}
+
+impl Camera {
+    /// Switches the open camera to live mode, initializes it and starts streaming
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let live = camera.clone().into_live_mode().expect("into_live_mode failed");
+    /// let size = live.camera().get_image_size().expect("get_image_size failed");
+    /// let image = live.get_live_frame(size).expect("get_live_frame failed");
+    /// let camera = live.into_inner().expect("into_inner failed");
+    /// ```
+    pub fn into_live_mode(self) -> Result<LiveCamera> {
+        self.set_stream_mode(StreamMode::LiveMode)?;
+        self.init()?;
+        self.begin_live()?;
+        Ok(LiveCamera { camera: self })
+    }
+
+    /// Switches the open camera to single frame mode and initializes it
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::Sdk;
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let single = camera.clone().into_single_frame_mode().expect("into_single_frame_mode failed");
+    /// single.start_single_frame_exposure().expect("start_single_frame_exposure failed");
+    /// let size = single.camera().get_image_size().expect("get_image_size failed");
+    /// let image = single.get_single_frame(size).expect("get_single_frame failed");
+    /// ```
+    pub fn into_single_frame_mode(self) -> Result<SingleFrameCamera> {
+        self.set_stream_mode(StreamMode::SingleFrameMode)?;
+        self.init()?;
+        Ok(SingleFrameCamera { camera: self })
+    }
+}
+
+impl LiveCamera {
+    /// Returns the camera to change settings, its frame methods bypass the stream mode check
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Returns the next frame, see `Camera::get_live_frame`
+    pub fn get_live_frame(&self, buffer_size: usize) -> Result<ImageData> {
+        self.camera.get_live_frame(buffer_size)
+    }
+
+    /// Downloads the next frame into `buffer`, see `Camera::get_live_frame_into`
+    pub fn get_live_frame_into(&self, buffer: &mut [u8]) -> Result<FrameInfo> {
+        self.camera.get_live_frame_into(buffer)
+    }
+
+    /// Stops streaming and switches the camera to single frame mode
+    pub fn into_single_frame_mode(self) -> Result<SingleFrameCamera> {
+        self.into_inner()?.into_single_frame_mode()
+    }
+
+    /// Stops streaming and returns the untyped camera
+    pub fn into_inner(self) -> Result<Camera> {
+        self.camera.end_live()?;
+        Ok(self.camera)
+    }
+}
+
+impl SingleFrameCamera {
+    /// Returns the camera to change settings, its frame methods bypass the stream mode check
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Starts an exposure, see `Camera::start_single_frame_exposure`
+    pub fn start_single_frame_exposure(&self) -> Result<()> {
+        self.camera.start_single_frame_exposure()
+    }
+
+    /// Downloads the exposed frame, see `Camera::get_single_frame`
+    pub fn get_single_frame(&self, buffer_size: usize) -> Result<ImageData> {
+        self.camera.get_single_frame(buffer_size)
+    }
+
+    /// Downloads the exposed frame into `buffer`, see `Camera::get_single_frame_into`
+    pub fn get_single_frame_into(&self, buffer: &mut [u8]) -> Result<FrameInfo> {
+        self.camera.get_single_frame_into(buffer)
+    }
+
+    /// Starts an exposure on a background thread, see `Camera::start_exposure`
+    pub fn start_exposure(&self, exposure: Duration) -> Result<ExposureHandle> {
+        self.camera.start_exposure(exposure)
+    }
+
+    /// Switches the camera to live mode and starts streaming
+    pub fn into_live_mode(self) -> Result<LiveCamera> {
+        self.camera.into_live_mode()
+    }
+
+    /// Returns the untyped camera
+    pub fn into_inner(self) -> Camera {
+        self.camera
+    }
+}
@@ -0,0 +1,122 @@
+//! Screen stretch for previews
+//!
+//! Some cameras can stretch the black and white point of the image on the camera through
+//! `Control::ScreenStretchB` and `Control::ScreenStretchW`. `Camera::set_screen_stretch` uses
+//! them where available and otherwise tells the caller to apply the same stretch in software
+//! with `ImageData::apply_stretch`, so previews look the same across models.
+
+use crate::arithmetic::{read_samples, write_samples};
+use crate::QHYError::InvalidScreenStretchError;
+use crate::{Camera, Control, ImageData, PixelFormat, Result};
+
+/// the full scale of the stretch controls of the SDK
+const HARDWARE_FULL_SCALE: f64 = 65535.0;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// Black and white point of a screen stretch as fractions of the full scale
+pub struct ScreenStretch {
+    /// samples at or below this fraction of the full scale become black
+    pub black: f64,
+    /// samples at or above this fraction of the full scale become white
+    pub white: f64,
+}
+
+impl Default for ScreenStretch {
+    fn default() -> Self {
+        Self {
+            black: 0.0,
+            white: 1.0,
+        }
+    }
+}
+
+impl ScreenStretch {
+    fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.black)
+            || !(0.0..=1.0).contains(&self.white)
+            || self.black >= self.white
+        {
+            let error = InvalidScreenStretchError {
+                black: self.black,
+                white: self.white,
+            };
+            tracing::error!(error = ?error);
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// Where a `ScreenStretch` gets applied, returned from `Camera::set_screen_stretch`
+pub enum StretchMode {
+    /// the camera stretches the image itself
+    Hardware,
+    /// the camera does not support stretching, use `ImageData::apply_stretch`
+    Software,
+}
+
+impl Camera {
+    /// Sets the screen stretch on the camera if it supports `Control::ScreenStretchB` and
+    /// `Control::ScreenStretchW`. Returns `StretchMode::Software` if it does not, in which case
+    /// frames have to be stretched with `ImageData::apply_stretch`.
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{ScreenStretch, Sdk, StretchMode};
+    /// let sdk = Sdk::new().expect("SDK::new failed");
+    /// let camera = sdk.cameras().last().expect("no camera found");
+    /// camera.open().expect("open failed");
+    /// let stretch = ScreenStretch { black: 0.05, white: 0.6 };
+    /// let mode = camera.set_screen_stretch(&stretch).expect("set_screen_stretch failed");
+    /// # let image = camera.get_single_frame(camera.get_image_size().unwrap()).unwrap();
+    /// let preview = match mode {
+    ///     StretchMode::Hardware => image,
+    ///     StretchMode::Software => image.apply_stretch(&stretch).expect("apply_stretch failed"),
+    /// };
+    /// ```
+    pub fn set_screen_stretch(&self, stretch: &ScreenStretch) -> Result<StretchMode> {
+        stretch.validate()?;
+        if self.is_control_available(Control::ScreenStretchB).is_none()
+            || self.is_control_available(Control::ScreenStretchW).is_none()
+        {
+            return Ok(StretchMode::Software);
+        }
+        self.set_parameter(
+            Control::ScreenStretchB,
+            (stretch.black * HARDWARE_FULL_SCALE).round(),
+        )?;
+        self.set_parameter(
+            Control::ScreenStretchW,
+            (stretch.white * HARDWARE_FULL_SCALE).round(),
+        )?;
+        Ok(StretchMode::Hardware)
+    }
+}
+
+impl ImageData {
+    /// Applies the screen stretch in software, samples are mapped linearly from
+    /// `black..=white` to the full range of the sample type
+    /// # Example
+    /// ```no_run
+    /// use qhyccd_rs::{ImageData, ScreenStretch};
+    /// let image = ImageData { data: vec![0, 64, 128, 255], width: 4, height: 1, bits_per_pixel: 8, channels: 1 };
+    /// let stretch = ScreenStretch { black: 0.25, white: 0.5 };
+    /// let stretched = image.apply_stretch(&stretch).expect("apply_stretch failed");
+    /// assert_eq!(stretched.data, vec![0, 1, 255, 255]);
+    /// ```
+    pub fn apply_stretch(&self, stretch: &ScreenStretch) -> Result<ImageData> {
+        stretch.validate()?;
+        let max =
+            ((1_u64 << PixelFormat::from_bits_per_pixel(self.bits_per_pixel)?.bits()) - 1) as f64;
+        let black = stretch.black * max;
+        let range = (stretch.white - stretch.black) * max;
+        let samples = read_samples(self)?
+            .into_iter()
+            .map(|s| (((s as f64 - black) / range).clamp(0.0, 1.0) * max).round() as u64)
+            .collect::<Vec<_>>();
+        Ok(ImageData {
+            data: write_samples(&samples, self.bits_per_pixel)?,
+            ..self.clone()
+        })
+    }
+}
@@ -0,0 +1,79 @@
+//! Metrics for long running capture daemons
+//!
+//! With the `metrics` feature the crate reports through the `metrics` facade, so whatever
+//! recorder the application installs, e.g. a Prometheus exporter, picks these up:
+//! - `qhyccd_sdk_call_duration_seconds`, histogram labeled with the SDK `function`
+//! - `qhyccd_frames_captured_total`, counter labeled with `camera` and `mode`
+//! - `qhyccd_frame_download_duration_seconds`, histogram labeled with `camera` and `mode`
+//! - `qhyccd_live_frames_dropped_total`, counter labeled with `camera`
+//! - `qhyccd_cooler_power_percent`, gauge labeled with `camera`
+//! - `qhyccd_sensor_temperature_celsius`, gauge labeled with `camera`
+//!
+//! `mode` is `single`, `live` or `triggered`. Downloads are timed around the download call of
+//! the SDK, for single frames that includes the part of the exposure still running when the
+//! download started. Dropped live frames are counted from gaps in the hardware frame counter
+//! seen by `Camera::get_live_frame_with_meta`. The gauges are updated whenever the value is
+//! read, e.g. by `Cooler::cooler_status` or `Camera::start_keep_alive`.
+//!
+//! Without the feature the functions here do nothing.
+
+use std::time::Duration;
+
+/// the mode a frame was captured in, the value of the `mode` label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CaptureMode {
+    Single,
+    Live,
+    Triggered,
+}
+
+impl CaptureMode {
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    fn label(self) -> &'static str {
+        match self {
+            CaptureMode::Single => "single",
+            CaptureMode::Live => "live",
+            CaptureMode::Triggered => "triggered",
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn sdk_call(function: &'static str, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    ::metrics::histogram!("qhyccd_sdk_call_duration_seconds", "function" => function)
+        .record(duration);
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn frame_captured(camera: &str, mode: CaptureMode, download: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        let labels = [
+            ("camera", camera.to_owned()),
+            ("mode", mode.label().to_owned()),
+        ];
+        ::metrics::counter!("qhyccd_frames_captured_total", &labels).increment(1);
+        ::metrics::histogram!("qhyccd_frame_download_duration_seconds", &labels).record(download);
+    }
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn live_frames_dropped(camera: &str, dropped: u64) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!("qhyccd_live_frames_dropped_total", "camera" => camera.to_owned())
+        .increment(dropped);
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn cooler_power(camera: &str, percent: f64) {
+    #[cfg(feature = "metrics")]
+    ::metrics::gauge!("qhyccd_cooler_power_percent", "camera" => camera.to_owned()).set(percent);
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn sensor_temperature(camera: &str, celsius: f64) {
+    #[cfg(feature = "metrics")]
+    ::metrics::gauge!("qhyccd_sensor_temperature_celsius", "camera" => camera.to_owned())
+        .set(celsius);
+}
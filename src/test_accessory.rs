@@ -0,0 +1,70 @@
+use super::*;
+
+#[test]
+fn simulated_focus_motor_moves() {
+    //given
+    let focuser = SimulatedFocusMotor::new(10_000);
+    //when
+    focuser.move_to(5_000).unwrap();
+    focuser.move_by(-250).unwrap();
+    //then
+    assert_eq!(focuser.position().unwrap(), 4_750);
+    assert_eq!(focuser.max_position().unwrap(), 10_000);
+    assert!(!focuser.is_moving().unwrap());
+}
+
+#[test]
+fn simulated_focus_motor_out_of_range() {
+    //given
+    let focuser = SimulatedFocusMotor::new(10_000);
+    //when
+    let res = focuser.move_by(-1);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        InvalidFocuserPositionError {
+            position: -1,
+            max: 10_000
+        }
+        .to_string()
+    );
+    assert_eq!(focuser.position().unwrap(), 0);
+}
+
+#[test]
+fn simulated_rotator_normalizes_angle() {
+    //given
+    let rotator = SimulatedRotator::new();
+    //when
+    rotator.rotate_to(-90.0).unwrap();
+    let first = rotator.angle().unwrap();
+    rotator.rotate_to(725.0).unwrap();
+    //then
+    assert_eq!(first, 270.0);
+    assert_eq!(rotator.angle().unwrap(), 5.0);
+    assert!(!rotator.is_moving().unwrap());
+}
+
+#[test]
+fn simulated_rotator_invalid_angle() {
+    //given
+    let rotator = SimulatedRotator::new();
+    //when
+    let res = rotator.rotate_to(f64::NAN);
+    //then
+    assert!(res.is_err());
+    assert_eq!(rotator.angle().unwrap(), 0.0);
+}
+
+#[test]
+fn accessories_as_trait_objects() {
+    //given
+    let focuser: Box<dyn FocusMotor> = Box::new(SimulatedFocusMotor::new(100));
+    let rotator: Box<dyn Rotator> = Box::new(SimulatedRotator::new());
+    //when
+    focuser.move_to(42).unwrap();
+    rotator.rotate_to(180.0).unwrap();
+    //then
+    assert_eq!(focuser.position().unwrap(), 42);
+    assert_eq!(rotator.angle().unwrap(), 180.0);
+}
@@ -0,0 +1,87 @@
+use super::*;
+use crate::analysis::{detect_stars, half_flux_radius};
+
+/// a 16 bit frame with a background of 1000 and gaussian stars given as (x, y, sigma, peak)
+fn star_field(width: u32, height: u32, stars: &[(f64, f64, f64, f64)]) -> ImageData {
+    let data = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            // a small deterministic pattern stands in for the noise of a real frame
+            let noise = ((x * 7 + y * 13) % 5) as f64 - 2.0;
+            let signal = stars
+                .iter()
+                .map(|(sx, sy, sigma, peak)| {
+                    let r2 = (x as f64 - sx).powi(2) + (y as f64 - sy).powi(2);
+                    peak * (-r2 / (2.0 * sigma * sigma)).exp()
+                })
+                .sum::<f64>();
+            (1000.0 + noise + signal).round() as u16
+        })
+        .flat_map(|sample| sample.to_le_bytes())
+        .collect();
+    ImageData {
+        data,
+        width,
+        height,
+        bits_per_pixel: 16,
+        channels: 1,
+    }
+}
+
+#[test]
+fn detect_stars_finds_all_stars() {
+    //given
+    let image = star_field(
+        64,
+        48,
+        &[(15.3, 12.6, 1.5, 5000.0), (45.0, 30.5, 2.5, 20000.0)],
+    );
+    //when
+    let stars = detect_stars(&image).unwrap();
+    //then
+    assert_eq!(stars.len(), 2);
+    // the brightest star comes first
+    assert!((stars[0].x - 45.0).abs() < 0.1 && (stars[0].y - 30.5).abs() < 0.1);
+    assert!((stars[1].x - 15.3).abs() < 0.1 && (stars[1].y - 12.6).abs() < 0.1);
+    assert!((stars[0].fwhm - 2.5 * 2.354_82).abs() < 0.5);
+    assert!((stars[1].fwhm - 1.5 * 2.354_82).abs() < 0.5);
+    assert!(stars[0].hfr > stars[1].hfr);
+    assert!(stars[0].peak > 19_000.0);
+}
+
+#[test]
+fn detect_stars_empty_frame() {
+    //given
+    let image = star_field(32, 32, &[]);
+    //when
+    let stars = detect_stars(&image).unwrap();
+    //then
+    assert!(stars.is_empty());
+}
+
+#[test]
+fn half_flux_radius_grows_with_defocus() {
+    //given
+    let focused = star_field(40, 40, &[(20.0, 20.0, 1.0, 10000.0)]);
+    let defocused = star_field(40, 40, &[(20.0, 20.0, 3.0, 10000.0)]);
+    //when
+    let focused_hfr = half_flux_radius(&focused).unwrap();
+    let defocused_hfr = half_flux_radius(&defocused).unwrap();
+    //then
+    // the HFR of a gaussian profile is sigma * sqrt(2 ln 2)
+    assert!((focused_hfr - 1.177).abs() < 0.3);
+    assert!(defocused_hfr > 2.0 * focused_hfr);
+}
+
+#[test]
+fn half_flux_radius_no_stars() {
+    //given
+    let image = star_field(16, 16, &[]);
+    //when
+    let res = half_flux_radius(&image);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        NoStarSignalError.to_string()
+    );
+}
@@ -0,0 +1,186 @@
+use super::*;
+
+fn image_8bit(data: Vec<u8>) -> ImageData {
+    ImageData {
+        width: data.len() as u32,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 1,
+        data,
+    }
+}
+
+fn image_16bit(samples: &[u16]) -> ImageData {
+    ImageData {
+        data: samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+        width: samples.len() as u32,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 1,
+    }
+}
+
+#[test]
+fn add_saturating() {
+    //given
+    let lhs = image_8bit(vec![200, 1, 255]);
+    let rhs = image_8bit(vec![100, 2, 0]);
+    //when
+    let res = lhs.add(&rhs, OverflowPolicy::Saturating);
+    //then
+    assert_eq!(res.unwrap().data, vec![255, 3, 255]);
+}
+
+#[test]
+fn add_wrapping() {
+    //given
+    let lhs = image_8bit(vec![200, 1, 255]);
+    let rhs = image_8bit(vec![100, 2, 1]);
+    //when
+    let res = lhs.add(&rhs, OverflowPolicy::Wrapping);
+    //then
+    assert_eq!(res.unwrap().data, vec![44, 3, 0]);
+}
+
+#[test]
+fn subtract_saturating() {
+    //given
+    let lhs = image_16bit(&[1000, 10]);
+    let rhs = image_16bit(&[100, 20]);
+    //when
+    let res = lhs.subtract(&rhs, OverflowPolicy::Saturating);
+    //then
+    assert_eq!(res.unwrap(), image_16bit(&[900, 0]));
+}
+
+#[test]
+fn subtract_wrapping() {
+    //given
+    let lhs = image_16bit(&[1000, 10]);
+    let rhs = image_16bit(&[100, 20]);
+    //when
+    let res = lhs.subtract(&rhs, OverflowPolicy::Wrapping);
+    //then
+    assert_eq!(res.unwrap(), image_16bit(&[900, 65526]));
+}
+
+#[test]
+fn divide_rounds_and_handles_zero() {
+    //given
+    let lhs = image_16bit(&[1000, 10, 5]);
+    let rhs = image_16bit(&[3, 0, 2]);
+    //when
+    let saturating = lhs.divide(&rhs, 1.0, OverflowPolicy::Saturating);
+    let wrapping = lhs.divide(&rhs, 1.0, OverflowPolicy::Wrapping);
+    //then
+    assert_eq!(saturating.unwrap(), image_16bit(&[333, 65535, 3]));
+    assert_eq!(wrapping.unwrap(), image_16bit(&[333, 0, 3]));
+}
+
+#[test]
+fn divide_by_flat_keeps_light_level() {
+    //given
+    let light = image_16bit(&[1000, 1200, 900, 1100]);
+    let flat = image_16bit(&[20000, 24000, 18000, 22000]);
+    let scale = flat.statistics().unwrap().mean;
+    //when
+    let res = light.divide(&flat, scale, OverflowPolicy::Saturating);
+    //then
+    assert_eq!(res.unwrap(), image_16bit(&[1050, 1050, 1050, 1050]));
+}
+
+#[test]
+fn add_uses_reported_bit_depth() {
+    //given
+    let lhs = ImageData {
+        bits_per_pixel: 12,
+        ..image_16bit(&[4000, 10])
+    };
+    let rhs = ImageData {
+        bits_per_pixel: 12,
+        ..image_16bit(&[200, 20])
+    };
+    //when
+    let saturating = lhs.add(&rhs, OverflowPolicy::Saturating);
+    let wrapping = lhs.add(&rhs, OverflowPolicy::Wrapping);
+    //then
+    let expected = |samples| ImageData {
+        bits_per_pixel: 12,
+        ..image_16bit(samples)
+    };
+    assert_eq!(saturating.unwrap(), expected(&[4095, 30]));
+    assert_eq!(wrapping.unwrap(), expected(&[104, 30]));
+}
+
+#[test]
+fn mixed_bit_depth_promotes() {
+    //given
+    let lhs = image_8bit(vec![255, 10]);
+    let rhs = image_16bit(&[1000, 20]);
+    //when
+    let res = lhs.add(&rhs, OverflowPolicy::Saturating);
+    //then
+    assert_eq!(res.unwrap(), image_16bit(&[1255, 30]));
+}
+
+#[test]
+fn geometry_mismatch_fail() {
+    //given
+    let lhs = image_8bit(vec![1, 2, 3]);
+    let rhs = image_8bit(vec![1, 2]);
+    //when
+    let res = lhs.add(&rhs, OverflowPolicy::Saturating);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::ImageGeometryMismatchError.to_string()
+    );
+}
+
+#[test]
+fn short_buffer_fail() {
+    //given
+    let lhs = image_8bit(vec![1, 2, 3]);
+    let mut rhs = image_8bit(vec![1, 2, 3]);
+    rhs.data.truncate(2);
+    //when
+    let res = lhs.add(&rhs, OverflowPolicy::Saturating);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::BufferTooSmallError {
+            required: 3,
+            actual: 2
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn padding_is_ignored() {
+    //given
+    let mut lhs = image_8bit(vec![1, 2]);
+    lhs.data.extend([9, 9]);
+    let rhs = image_8bit(vec![3, 4]);
+    //when
+    let res = lhs.add(&rhs, OverflowPolicy::Saturating);
+    //then
+    assert_eq!(res.unwrap().data, vec![4, 6]);
+}
+
+#[test]
+fn unsupported_bit_depth_fail() {
+    //given
+    let mut lhs = image_8bit(vec![1, 2]);
+    lhs.bits_per_pixel = 64;
+    let rhs = lhs.clone();
+    //when
+    let res = lhs.subtract(&rhs, OverflowPolicy::Saturating);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::UnsupportedBitDepthError { bits_per_pixel: 64 }.to_string()
+    );
+}
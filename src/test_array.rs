@@ -0,0 +1,96 @@
+use super::*;
+use ndarray::{Array2, Array3};
+
+#[test]
+fn to_array2_success() {
+    //given
+    let image = ImageData {
+        data: vec![1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0],
+        width: 3,
+        height: 2,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    //when
+    let array = image.to_array2().unwrap();
+    //then
+    assert_eq!(array.dim(), (2, 3));
+    assert_eq!(array[[1, 0]], 4);
+    assert_eq!(ImageData::from_array2(&array), image);
+}
+
+#[test]
+fn to_array2_color_frame() {
+    //given
+    let image = ImageData {
+        data: vec![10, 20, 30],
+        width: 1,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 3,
+    };
+    //when
+    let res = image.to_array2();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        ChannelCountMismatchError {
+            expected: 1,
+            actual: 3
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn to_array3_widens_8_bit() {
+    //given
+    let image = ImageData {
+        data: vec![10, 20, 30, 11, 21, 31],
+        width: 2,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 3,
+    };
+    //when
+    let array = image.to_array3().unwrap();
+    //then
+    assert_eq!(array.dim(), (1, 2, 3));
+    assert_eq!(array[[0, 1, 2]], 31);
+}
+
+#[test]
+fn to_array3_rejects_32_bit() {
+    //given
+    let image = ImageData {
+        data: vec![0; 4],
+        width: 1,
+        height: 1,
+        bits_per_pixel: 32,
+        channels: 1,
+    };
+    //when
+    let res = image.to_array3();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        PixelFormatMismatchError {
+            expected: PixelFormat::U16,
+            actual: PixelFormat::U32
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn from_array3_success() {
+    //given
+    let array = Array3::from_shape_vec((1, 1, 3), vec![1, 2, 256]).unwrap();
+    //when
+    let image = ImageData::from_array3(&array);
+    //then
+    assert_eq!(image.data, vec![1, 0, 2, 0, 0, 1]);
+    assert_eq!(image.channels, 3);
+    assert_eq!(image.to_array3().unwrap(), array);
+    assert_eq!(ImageData::from_array2(&Array2::zeros((2, 1))).height, 2);
+}
@@ -0,0 +1,237 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::mocks::mock_libqhyccd_sys::{
+    CancelQHYCCDExposingAndReadout_context, ExpQHYCCDSingleFrame_context,
+    GetQHYCCDCFWStatus_context, GetQHYCCDLiveFrame_context, GetQHYCCDMemLength_context,
+    GetQHYCCDParam_context, GetQHYCCDSingleFrame_context, IsQHYCCDControlAvailable_context,
+    SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+
+// the SDK calls run on the executor thread, so the expectations below must not use the `_st`
+// variants, which only work on the thread that set them up
+fn new_camera() -> AsyncCamera {
+    AsyncCamera::new(crate::mocks::new_camera())
+}
+
+#[tokio::test]
+async fn expose_success() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf(|_, control, value| *control == Control::Exposure as u32 && *value == 1_500_000.0)
+        .once()
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(4_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame
+        .expect()
+        .once()
+        .returning(|_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 16;
+            *channels = 1;
+            buffer.copy_from([1_u8, 0, 2, 0].as_ptr(), 4);
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let image = cam.expose(Duration::from_millis(1500)).await.unwrap();
+    //then
+    assert_eq!(image.data, vec![1, 0, 2, 0]);
+    assert_eq!((image.width, image.height), (2, 1));
+}
+
+#[tokio::test]
+async fn expose_camera_not_open() {
+    //given
+    let cam = AsyncCamera::new(Camera::new("test_camera".to_owned()));
+    //when
+    let res = cam.expose(Duration::from_secs(1)).await;
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        CameraNotOpenError.to_string()
+    );
+}
+
+/// expects an exposure whose download only returns once it was aborted
+fn expect_exposure_until_aborted(aborted: &'static AtomicBool) -> Vec<Box<dyn std::any::Any>> {
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(1_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame
+        .expect()
+        .once()
+        .returning(move |_, _, _, _, _, _| {
+            while !aborted.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            QHYCCD_ERROR
+        });
+    let ctx_abort = CancelQHYCCDExposingAndReadout_context();
+    ctx_abort.expect().once().returning(move |_| {
+        aborted.store(true, Ordering::SeqCst);
+        QHYCCD_SUCCESS
+    });
+    vec![
+        Box::new(ctx_set),
+        Box::new(ctx_exp),
+        Box::new(ctx_size),
+        Box::new(ctx_frame),
+        Box::new(ctx_abort),
+    ]
+}
+
+#[tokio::test]
+async fn expose_dropped_aborts_exposure() {
+    //given
+    static ABORTED: AtomicBool = AtomicBool::new(false);
+    let _exposure = expect_exposure_until_aborted(&ABORTED);
+    let cam = new_camera();
+    //when
+    let res = tokio::time::timeout(
+        Duration::from_millis(50),
+        cam.expose(Duration::from_secs(60)),
+    )
+    .await;
+    //then
+    assert!(res.is_err());
+    let aborted = tokio::time::timeout(Duration::from_secs(5), async {
+        while !ABORTED.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await;
+    assert!(aborted.is_ok());
+}
+
+#[tokio::test]
+async fn run_is_not_queued_behind_expose() {
+    //given
+    static ABORTED: AtomicBool = AtomicBool::new(false);
+    let _exposure = expect_exposure_until_aborted(&ABORTED);
+    let cam = new_camera();
+    //when
+    let (image, aborted) = tokio::join!(cam.expose(Duration::from_secs(60)), async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cam.run(|camera| camera.abort_exposure_and_readout()).await
+    });
+    //then
+    assert!(aborted.is_ok());
+    assert!(image.is_err());
+}
+
+#[tokio::test]
+async fn next_live_frame_waits_for_frame() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(1_u32);
+    let ctx_frame = GetQHYCCDLiveFrame_context();
+    ctx_frame.expect().times(3).returning({
+        let results = std::sync::Mutex::new(vec![QHYCCD_SUCCESS, QHYCCD_ERROR, QHYCCD_ERROR]);
+        move |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            *buffer = 42;
+            results.lock().unwrap().pop().unwrap()
+        }
+    });
+    let cam = new_camera();
+    cam.camera().handle.set_state(CameraState::Live);
+    //when
+    let image = cam.next_live_frame().await.unwrap();
+    //then
+    assert_eq!(image.data, vec![42]);
+}
+
+#[tokio::test]
+async fn next_live_frame_fails_on_sdk_error() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(1_u32);
+    let ctx_frame = GetQHYCCDLiveFrame_context();
+    ctx_frame.expect().once().return_const(123_u32);
+    let cam = new_camera();
+    cam.camera().handle.set_state(CameraState::Live);
+    //when
+    let res = cam.next_live_frame().await;
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        GetLiveFrameError { error_code: 123 }.to_string()
+    );
+}
+
+#[tokio::test]
+async fn next_live_frame_fails_outside_live_mode() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(1_u32);
+    let cam = new_camera();
+    //when
+    let res = cam.next_live_frame().await;
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        CameraStateError {
+            state: CameraState::Open,
+            expected: CameraState::Live
+        }
+        .to_string()
+    );
+}
+
+#[tokio::test]
+async fn run_returns_result() {
+    //given
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get
+        .expect()
+        .withf(|_, control| *control == Control::CurTemp as u32)
+        .once()
+        .return_const(-10.5);
+    let cam = new_camera();
+    //when
+    let res = cam
+        .run(|camera| camera.get_parameter(Control::CurTemp))
+        .await;
+    //then
+    assert_eq!(res.unwrap(), -10.5);
+}
+
+#[tokio::test]
+async fn move_filter_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf(|_, _, value| *value == 50.0)
+        .once()
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status.expect().once().returning(|_, status| unsafe {
+        *status = b'2' as c_char;
+        QHYCCD_SUCCESS
+    });
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().once().return_const(50.0);
+    let fw = new_camera().filter_wheel();
+    //when
+    let res = fw.move_filter(2, Duration::from_secs(5)).await;
+    //then
+    assert!(res.is_ok());
+}
@@ -0,0 +1,182 @@
+use super::*;
+use std::cell::RefCell;
+
+fn hyperbola(position: i32, center: f64) -> f64 {
+    2.0 * (1.0 + ((position as f64 - center) / 150.0).powi(2)).sqrt()
+}
+
+/// a focuser recording every move
+struct RecordingFocuser {
+    position: RefCell<i32>,
+    moves: RefCell<Vec<i32>>,
+}
+
+impl FocusMotor for RecordingFocuser {
+    fn position(&self) -> Result<i32> {
+        Ok(*self.position.borrow())
+    }
+
+    fn max_position(&self) -> Result<i32> {
+        Ok(10_000)
+    }
+
+    fn move_to(&self, position: i32) -> Result<()> {
+        *self.position.borrow_mut() = position;
+        self.moves.borrow_mut().push(position);
+        Ok(())
+    }
+
+    fn is_moving(&self) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+#[test]
+fn half_flux_diameter_star() {
+    //given
+    let mut data = vec![10_u8; 25];
+    data[12] = 110;
+    for neighbour in [7, 11, 13, 17] {
+        data[neighbour] = 60;
+    }
+    let image = ImageData {
+        data,
+        width: 5,
+        height: 5,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let hfd = half_flux_diameter(&image).unwrap();
+    //then
+    assert!((hfd - 4.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn half_flux_diameter_noisy_background() {
+    //given
+    let (width, height) = (64_u32, 64_u32);
+    let mut seed = 0x2545_f491_u32;
+    let mut data = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            // a linear congruential generator stands in for the read noise of a real frame
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let noise = (seed >> 16) as f64 / 65_536.0 * 40.0 - 20.0;
+            let r2 = (x as f64 - 32.0).powi(2) + (y as f64 - 32.0).powi(2);
+            let signal = 5000.0 * (-r2 / (2.0 * 1.5 * 1.5)).exp();
+            data.extend_from_slice(&((1000.0 + noise + signal).round() as u16).to_le_bytes());
+        }
+    }
+    let image = ImageData {
+        data,
+        width,
+        height,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    //when
+    let hfd = half_flux_diameter(&image).unwrap();
+    //then
+    // the mean radius of a gaussian is sigma * sqrt(pi / 2), about 1.88 pixels here
+    assert!(hfd > 2.5 && hfd < 4.5, "hfd {hfd}");
+}
+
+#[test]
+fn half_flux_diameter_no_signal() {
+    //given
+    let image = ImageData {
+        data: vec![10; 25],
+        width: 5,
+        height: 5,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let res = half_flux_diameter(&image);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        NoStarSignalError.to_string()
+    );
+}
+
+#[test]
+fn run_autofocus_finds_center() {
+    //given
+    let focuser = SimulatedFocusMotor::new(10_000);
+    focuser.move_to(5_000).unwrap();
+    let settings = AutofocusSettings {
+        step_size: 100,
+        steps_per_side: 4,
+        ..Default::default()
+    };
+    //when
+    let result = run_autofocus(&focuser, &settings, || {
+        Ok(hyperbola(focuser.position()?, 5_130.0))
+    })
+    .unwrap();
+    //then
+    assert_eq!(result.best_position, 5_130);
+    assert_eq!(focuser.position().unwrap(), 5_130);
+    assert_eq!(result.samples.len(), 9);
+    assert!((result.best_hfd - 2.0).abs() < 1e-6);
+    assert!(result.r_squared > 0.999);
+}
+
+#[test]
+fn run_autofocus_compensates_backlash() {
+    //given
+    let focuser = RecordingFocuser {
+        position: RefCell::new(1_000),
+        moves: RefCell::new(Vec::new()),
+    };
+    let settings = AutofocusSettings {
+        step_size: 100,
+        steps_per_side: 1,
+        backlash: 50,
+        ..Default::default()
+    };
+    //when
+    let result = run_autofocus(&focuser, &settings, || {
+        Ok(hyperbola(focuser.position()?, 1_000.0))
+    })
+    .unwrap();
+    //then
+    assert_eq!(result.best_position, 1_000);
+    assert_eq!(
+        *focuser.moves.borrow(),
+        vec![850, 900, 1_000, 1_100, 950, 1_000]
+    );
+}
+
+#[test]
+fn run_autofocus_no_minimum() {
+    //given
+    let focuser = SimulatedFocusMotor::new(10_000);
+    focuser.move_to(5_000).unwrap();
+    //when
+    let res = run_autofocus(&focuser, &AutofocusSettings::default(), || {
+        Ok(10.0 - (focuser.position()? as f64 - 5_000.0).abs() / 100.0)
+    });
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        AutofocusFitError.to_string()
+    );
+}
+
+#[test]
+fn run_autofocus_measure_fails() {
+    //given
+    let focuser = SimulatedFocusMotor::new(10_000);
+    //when
+    let res = run_autofocus(&focuser, &AutofocusSettings::default(), || {
+        Err(NoStarSignalError)
+    });
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        NoStarSignalError.to_string()
+    );
+}
@@ -0,0 +1,147 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParam_context, IsQHYCCDControlAvailable_context, QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+fn image16(samples: &[u16]) -> ImageData {
+    ImageData {
+        data: samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+        width: samples.len() as u32,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 1,
+    }
+}
+
+#[test]
+fn get_sample_depth_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(2)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::OutputDataActualBits as u32 => 12.0,
+            c if c == Control::OutputDataAlignment as u32 => 0.0,
+            _ => panic!("unexpected control"),
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_sample_depth();
+    //then
+    assert_eq!(
+        res.unwrap(),
+        SampleDepth {
+            actual_bits: 12,
+            alignment: DataAlignment::HighBits
+        }
+    );
+}
+
+#[test]
+fn get_sample_depth_unknown_alignment() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(2)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::OutputDataActualBits as u32 => 12.0,
+            _ => 7.0,
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_sample_depth();
+    //then
+    assert!(matches!(res, Err(UnknownDataAlignmentError { value: 7 })));
+}
+
+#[test]
+fn get_sample_depth_not_available() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_sample_depth();
+    //then
+    assert!(matches!(
+        res,
+        Err(IsControlAvailableError {
+            control: Control::OutputDataActualBits
+        })
+    ));
+}
+
+#[test]
+fn normalize_to_16bit_low_bits() {
+    //given
+    let image = image16(&[0, 1, 0x0fff, 0xffff]);
+    let depth = SampleDepth {
+        actual_bits: 12,
+        alignment: DataAlignment::LowBits,
+    };
+    //when
+    let res = image.normalize_to_16bit(&depth);
+    //then
+    assert_eq!(
+        res.unwrap().into_u16_vec().unwrap(),
+        vec![0, 0x10, 0xfff0, 0xfff0]
+    );
+}
+
+#[test]
+fn normalize_to_16bit_high_bits_unchanged() {
+    //given
+    let image = image16(&[0, 0x10, 0xfff0]);
+    let depth = SampleDepth {
+        actual_bits: 12,
+        alignment: DataAlignment::HighBits,
+    };
+    //when
+    let res = image.normalize_to_16bit(&depth);
+    //then
+    assert_eq!(res.unwrap(), image);
+}
+
+#[test]
+fn normalize_to_16bit_invalid() {
+    //given
+    let image8 = ImageData {
+        data: vec![1, 2],
+        width: 2,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    let depth = SampleDepth {
+        actual_bits: 12,
+        alignment: DataAlignment::LowBits,
+    };
+    //when
+    let wrong_format = image8.normalize_to_16bit(&depth);
+    let too_many_bits = image16(&[1]).normalize_to_16bit(&SampleDepth {
+        actual_bits: 17,
+        ..depth
+    });
+    //then
+    assert!(matches!(wrong_format, Err(PixelFormatMismatchError { .. })));
+    assert!(matches!(
+        too_many_bits,
+        Err(InvalidActualBitsError { bits: 17 })
+    ));
+}
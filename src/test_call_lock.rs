@@ -0,0 +1,303 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+use super::*;
+use crate::call_lock::CallLock;
+use crate::mocks::mock_libqhyccd_sys::{
+    CancelQHYCCDExposingAndReadout_context, CloseQHYCCD_context, GetQHYCCDLiveFrame_context,
+    GetQHYCCDParam_context, GetQHYCCDSingleFrame_context, OpenQHYCCD_context,
+    SetQHYCCDParam_context, QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn public_handles_are_send_and_sync() {
+    assert_send_sync::<Camera>();
+    assert_send_sync::<FilterWheel>();
+    assert_send_sync::<Sdk>();
+}
+
+#[test]
+fn call_lock_is_reentrant() {
+    //given
+    let lock = CallLock::default();
+    //when
+    let outer = lock.lock();
+    let inner = lock.lock();
+    //then
+    drop(inner);
+    drop(outer);
+    thread::scope(|scope| {
+        scope.spawn(|| drop(lock.lock())).join().unwrap();
+    });
+}
+
+#[test]
+fn call_lock_excludes_other_threads() {
+    //given
+    let lock = CallLock::default();
+    let inside = AtomicUsize::new(0);
+    let overlapped = AtomicBool::new(false);
+    //when
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..50 {
+                    let _call = lock.lock();
+                    if inside.fetch_add(1, Ordering::SeqCst) != 0 {
+                        overlapped.store(true, Ordering::SeqCst);
+                    }
+                    thread::yield_now();
+                    inside.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+    //then
+    assert!(!overlapped.load(Ordering::SeqCst));
+}
+
+#[test]
+fn set_parameter_from_many_threads_is_serialized() {
+    //given
+    static INSIDE: AtomicUsize = AtomicUsize::new(0);
+    static OVERLAPPED: AtomicBool = AtomicBool::new(false);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .times(40)
+        .returning(|_handle, _control, _value| {
+            if INSIDE.fetch_add(1, Ordering::SeqCst) != 0 {
+                OVERLAPPED.store(true, Ordering::SeqCst);
+            }
+            thread::sleep(Duration::from_micros(200));
+            INSIDE.fetch_sub(1, Ordering::SeqCst);
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            let cam = cam.clone();
+            scope.spawn(move || {
+                for _ in 0..10 {
+                    cam.set_parameter(Control::Gain, 10.0).unwrap();
+                }
+            });
+        }
+    });
+    //then
+    assert!(!OVERLAPPED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn rate_limited_set_parameter_does_not_block_other_calls() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(2).return_const(QHYCCD_SUCCESS);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().times(1).return_const(-10.0);
+    let cam = new_camera();
+    cam.set_rate_limit(Control::Cooler, Some(Duration::from_millis(300)));
+    cam.set_parameter(Control::Cooler, -10.0).unwrap();
+    //when
+    let (read_in, delayed) = thread::scope(|scope| {
+        let writer = cam.clone();
+        let write = scope.spawn(move || writer.set_parameter(Control::Cooler, -11.0));
+        thread::sleep(Duration::from_millis(50));
+        let start = Instant::now();
+        cam.get_parameter(Control::CurTemp).unwrap();
+        (start.elapsed(), write.join().unwrap())
+    });
+    //then
+    assert!(delayed.is_ok());
+    assert!(read_in < Duration::from_millis(200));
+}
+
+#[test]
+fn waiting_for_live_frames_does_not_block_other_calls() {
+    //given
+    static POLLED: AtomicBool = AtomicBool::new(false);
+    let ctx_frame = GetQHYCCDLiveFrame_context();
+    ctx_frame
+        .expect()
+        .returning(|_handle, _width, _height, _bpp, _channels, _buffer| {
+            POLLED.store(true, Ordering::SeqCst);
+            QHYCCD_ERROR
+        });
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().times(1).return_const(-10.0);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    //when
+    let (read_in, batch) = thread::scope(|scope| {
+        let mut frames = cam.live_frames(1);
+        let batch = scope.spawn(move || frames.next_batch(1, Duration::from_millis(300)));
+        while !POLLED.load(Ordering::SeqCst) {
+            thread::yield_now();
+        }
+        let start = Instant::now();
+        cam.get_parameter(Control::CurTemp).unwrap();
+        (start.elapsed(), batch.join().unwrap())
+    });
+    //then
+    assert!(batch.unwrap().is_empty());
+    assert!(read_in < Duration::from_millis(200));
+}
+
+#[test]
+fn abort_is_not_blocked_by_download() {
+    //given
+    static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+    static ABORTED: AtomicBool = AtomicBool::new(false);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame
+        .expect()
+        .times(1)
+        .returning(|_handle, _width, _height, _bpp, _channels, _buffer| {
+            DOWNLOADING.store(true, Ordering::SeqCst);
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !ABORTED.load(Ordering::SeqCst) && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(1));
+            }
+            QHYCCD_ERROR
+        });
+    let ctx_abort = CancelQHYCCDExposingAndReadout_context();
+    ctx_abort.expect().times(1).returning(|_handle| {
+        ABORTED.store(true, Ordering::SeqCst);
+        QHYCCD_SUCCESS
+    });
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let download = {
+        let cam = cam.clone();
+        thread::spawn(move || cam.get_single_frame(1))
+    };
+    while !DOWNLOADING.load(Ordering::SeqCst) {
+        thread::yield_now();
+    }
+    let res = cam.abort_exposure_and_readout();
+    //then
+    assert!(res.is_ok());
+    assert!(download.join().unwrap().is_err());
+}
+
+#[test]
+fn parameters_do_not_overlap_download() {
+    //given
+    static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    static OVERLAPPED: AtomicBool = AtomicBool::new(false);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame
+        .expect()
+        .times(1)
+        .returning(|_handle, _width, _height, _bpp, _channels, _buffer| {
+            DOWNLOADING.store(true, Ordering::SeqCst);
+            STARTED.store(true, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(100));
+            DOWNLOADING.store(false, Ordering::SeqCst);
+            QHYCCD_SUCCESS
+        });
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).returning(|_, _, _| {
+        OVERLAPPED.fetch_or(DOWNLOADING.load(Ordering::SeqCst), Ordering::SeqCst);
+        QHYCCD_SUCCESS
+    });
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().times(1).returning(|_, _| {
+        OVERLAPPED.fetch_or(DOWNLOADING.load(Ordering::SeqCst), Ordering::SeqCst);
+        -10.0
+    });
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let download = {
+        let cam = cam.clone();
+        thread::spawn(move || cam.get_single_frame(1))
+    };
+    while !STARTED.load(Ordering::SeqCst) {
+        thread::yield_now();
+    }
+    let read = cam.get_parameter(Control::CurTemp);
+    let write = cam.set_parameter(Control::Cooler, -10.0);
+    //then
+    assert!(download.join().unwrap().is_ok());
+    assert!(read.is_ok());
+    assert!(write.is_ok());
+    assert!(!OVERLAPPED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn close_waits_for_download() {
+    //given
+    static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    static OVERLAPPED: AtomicBool = AtomicBool::new(false);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame
+        .expect()
+        .times(1)
+        .returning(|_handle, _width, _height, _bpp, _channels, _buffer| {
+            DOWNLOADING.store(true, Ordering::SeqCst);
+            STARTED.store(true, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(100));
+            DOWNLOADING.store(false, Ordering::SeqCst);
+            QHYCCD_ERROR
+        });
+    let ctx_abort = CancelQHYCCDExposingAndReadout_context();
+    ctx_abort.expect().times(1).return_const(QHYCCD_SUCCESS);
+    let ctx_close = CloseQHYCCD_context();
+    ctx_close.expect().times(1).returning(|_| {
+        OVERLAPPED.store(DOWNLOADING.load(Ordering::SeqCst), Ordering::SeqCst);
+        QHYCCD_SUCCESS
+    });
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let download = {
+        let cam = cam.clone();
+        thread::spawn(move || cam.get_single_frame(1))
+    };
+    while !STARTED.load(Ordering::SeqCst) {
+        thread::yield_now();
+    }
+    cam.abort_exposure_and_readout().unwrap();
+    let res = cam.close();
+    //then
+    assert!(download.join().unwrap().is_err());
+    assert!(res.is_ok());
+    assert!(!OVERLAPPED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn open_from_two_clones_opens_once() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).returning(|_id| TEST_HANDLE);
+    let cam = Camera::new("test_camera".to_owned());
+    crate::mocks::keep_open(&cam);
+    //when
+    let results = thread::scope(|scope| {
+        // both clones see a closed camera and then wait for the call lock
+        let call = cam.handle.calls.lock();
+        let opens = (0..2)
+            .map(|_| {
+                let cam = cam.clone();
+                scope.spawn(move || cam.open())
+            })
+            .collect::<Vec<_>>();
+        thread::sleep(Duration::from_millis(50));
+        drop(call);
+        opens
+            .into_iter()
+            .map(|open| open.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    //then
+    assert!(results.iter().all(|res| res.is_ok()));
+    assert!(cam.is_open().unwrap());
+}
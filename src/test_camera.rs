@@ -1,28 +1,24 @@
 use super::*;
 use crate::mocks::mock_libqhyccd_sys::{
     BeginQHYCCDLive_context, CancelQHYCCDExposingAndReadout_context, CancelQHYCCDExposing_context,
-    CloseQHYCCD_context, ExpQHYCCDSingleFrame_context, GetQHYCCDChipInfo_context,
-    GetQHYCCDEffectiveArea_context, GetQHYCCDExposureRemaining_context, GetQHYCCDFWVersion_context,
+    CloseQHYCCD_context, EnableQHYCCDTrigerOut_context, ExpQHYCCDSingleFrame_context,
+    GetQHYCCDChipInfo_context, GetQHYCCDEffectiveArea_context, GetQHYCCDExposureRemaining_context,
+    GetQHYCCDFPGAVersion_context, GetQHYCCDFWVersion_context, GetQHYCCDHumidity_context,
     GetQHYCCDLiveFrame_context, GetQHYCCDMemLength_context, GetQHYCCDModel_context,
     GetQHYCCDNumberOfReadModes_context, GetQHYCCDOverScanArea_context,
-    GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context, GetQHYCCDReadModeName_context,
-    GetQHYCCDReadModeResolution_context, GetQHYCCDReadMode_context, GetQHYCCDSingleFrame_context,
+    GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context, GetQHYCCDPreciseExposureInfo_context,
+    GetQHYCCDPressure_context, GetQHYCCDReadModeName_context, GetQHYCCDReadModeResolution_context,
+    GetQHYCCDReadMode_context, GetQHYCCDRollingShutterEndOffset_context,
+    GetQHYCCDSensorName_context, GetQHYCCDSingleFrame_context,
+    GetQHYCCDTrigerInterfaceName_context, GetQHYCCDTrigerInterfaceNumber_context,
     GetQHYCCDType_context, InitQHYCCD_context, IsQHYCCDControlAvailable_context,
     OpenQHYCCD_context, SetQHYCCDBinMode_context, SetQHYCCDBitsMode_context,
     SetQHYCCDDebayerOnOff_context, SetQHYCCDParam_context, SetQHYCCDReadMode_context,
-    SetQHYCCDResolution_context, SetQHYCCDStreamMode_context, StopQHYCCDLive_context,
+    SetQHYCCDResolution_context, SetQHYCCDStreamMode_context, SetQHYCCDTrigerFunction_context,
+    SetQHYCCDTrigerInterface_context, SetQHYCCDTrigerMode_context, StopQHYCCDLive_context,
     QHYCCD_SUCCESS,
 };
-
-const TEST_HANDLE: *const std::ffi::c_void = 0xdeadbeef as *const std::ffi::c_void;
-
-fn new_camera() -> Camera {
-    let ctx_open = OpenQHYCCD_context();
-    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
-    let camera = Camera::new("test_camera".to_owned());
-    camera.open().unwrap();
-    camera
-}
+use crate::mocks::{new_camera, TEST_HANDLE};
 
 #[test]
 fn set_stream_mode_success() {
@@ -136,11 +132,7 @@ fn get_model_utf8_error() {
     //when
     let res = cam.get_model();
     //then
-    assert!(res.is_err());
-    assert_eq!(
-        res.err().unwrap().to_string(),
-        "invalid utf-8 sequence of 1 bytes from index 0"
-    );
+    assert!(matches!(res, Err(InvalidUtf8Error { .. })));
 }
 
 #[test]
@@ -239,6 +231,113 @@ fn get_firmware_version_fail() {
     );
 }
 
+#[test]
+fn get_sensor_name_success() {
+    //given
+    let ctx = GetQHYCCDSensorName_context();
+    ctx.expect()
+        .withf_st(|handle, _name| *handle == TEST_HANDLE)
+        .times(1)
+        .returning_st(|_handle, name| unsafe {
+            let sensor = "IMX571\0";
+            name.copy_from(sensor.as_ptr() as *const c_char, sensor.len());
+
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_sensor_name();
+    //then
+    assert!(res.is_ok());
+    assert_eq!(res.unwrap(), "IMX571");
+}
+
+#[test]
+fn get_sensor_name_fail() {
+    //given
+    let ctx = GetQHYCCDSensorName_context();
+    ctx.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_sensor_name();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetSensorNameError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_sensor_name_utf8_error() {
+    //given
+    let ctx = GetQHYCCDSensorName_context();
+    ctx.expect().times(1).returning_st(|_handle, name| unsafe {
+        let sensor = b"\xc3\x28\0";
+        name.copy_from(sensor.as_ptr() as *const c_char, sensor.len());
+
+        QHYCCD_SUCCESS
+    });
+    let cam = new_camera();
+    //when
+    let res = cam.get_sensor_name();
+    //then
+    assert!(matches!(res, Err(InvalidUtf8Error { .. })));
+}
+
+#[test]
+fn get_fpga_version_success() {
+    //given
+    let ctx = GetQHYCCDFPGAVersion_context();
+    ctx.expect()
+        .withf_st(|handle, fpga_index, _buf| *handle == TEST_HANDLE && *fpga_index == 1)
+        .times(1)
+        .returning_st(|_handle, _fpga_index, buf| unsafe {
+            let version = [23u8, 7, 14, 2];
+            buf.copy_from(version.as_ptr(), version.len());
+
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_fpga_version(1);
+    //then
+    assert!(res.is_ok());
+    let version = res.unwrap();
+    assert_eq!(
+        version,
+        FpgaVersion {
+            year: 23,
+            month: 7,
+            day: 14,
+            revision: 2
+        }
+    );
+    assert_eq!(version.to_string(), "2023_7_14_2");
+}
+
+#[test]
+fn get_fpga_version_fail() {
+    //given
+    let ctx = GetQHYCCDFPGAVersion_context();
+    ctx.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_fpga_version(0);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetFpgaVersionError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
 #[test]
 fn get_number_of_readout_modes_success() {
     //given
@@ -334,11 +433,7 @@ fn get_readout_mode_name_utf8_error() {
     //when
     let res = cam.get_readout_mode_name(0);
     //then
-    assert!(res.is_err());
-    assert_eq!(
-        res.err().unwrap().to_string(),
-        "invalid utf-8 sequence of 1 bytes from index 0"
-    );
+    assert!(matches!(res, Err(InvalidUtf8Error { .. })));
 }
 
 #[test]
@@ -381,6 +476,96 @@ fn get_readout_mode_resolution_fail() {
     );
 }
 
+#[test]
+fn readout_modes_success() {
+    //given
+    let ctx_number = GetQHYCCDNumberOfReadModes_context();
+    ctx_number
+        .expect()
+        .times(1)
+        .returning_st(|_handle, number| unsafe {
+            *number = 2;
+            QHYCCD_SUCCESS
+        });
+    let ctx_name = GetQHYCCDReadModeName_context();
+    ctx_name
+        .expect()
+        .times(2)
+        .returning_st(|_handle, index, mode| unsafe {
+            let read_mode = match index {
+                0 => "STANDARD MODE\0",
+                _ => "HIGH GAIN MODE\0",
+            };
+            mode.copy_from(read_mode.as_ptr() as *const c_char, read_mode.len());
+            QHYCCD_SUCCESS
+        });
+    let ctx_resolution = GetQHYCCDReadModeResolution_context();
+    ctx_resolution
+        .expect()
+        .times(2)
+        .returning_st(|_handle, index, width, height| unsafe {
+            *width = 1024 >> index;
+            *height = 768 >> index;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.readout_modes();
+    //then
+    assert_eq!(
+        res.unwrap(),
+        vec![
+            ReadoutModeInfo {
+                id: 0,
+                name: "STANDARD MODE".to_owned(),
+                width: 1024,
+                height: 768
+            },
+            ReadoutModeInfo {
+                id: 1,
+                name: "HIGH GAIN MODE".to_owned(),
+                width: 512,
+                height: 384
+            }
+        ]
+    );
+}
+
+#[test]
+fn readout_modes_resolution_fail() {
+    //given
+    let ctx_number = GetQHYCCDNumberOfReadModes_context();
+    ctx_number
+        .expect()
+        .times(1)
+        .returning_st(|_handle, number| unsafe {
+            *number = 1;
+            QHYCCD_SUCCESS
+        });
+    let ctx_name = GetQHYCCDReadModeName_context();
+    ctx_name
+        .expect()
+        .times(1)
+        .returning_st(|_handle, _index, mode| unsafe {
+            let read_mode = "STANDARD MODE\0";
+            mode.copy_from(read_mode.as_ptr() as *const c_char, read_mode.len());
+            QHYCCD_SUCCESS
+        });
+    let ctx_resolution = GetQHYCCDReadModeResolution_context();
+    ctx_resolution
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.readout_modes();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetReadoutModeResolutionError.to_string()
+    );
+}
+
 #[test]
 fn get_readout_mode_success() {
     //given
@@ -535,6 +720,17 @@ fn set_debayer_fail() {
 #[test]
 fn set_roi_success() {
     //given
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area
+        .expect()
+        .times(1)
+        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 0;
+            *start_y = 0;
+            *width = 4096;
+            *height = 2048;
+            QHYCCD_SUCCESS
+        });
     let ctx = SetQHYCCDResolution_context();
     ctx.expect()
         .withf_st(|handle, start_x, stary_y, width, height| {
@@ -561,6 +757,17 @@ fn set_roi_success() {
 #[test]
 fn set_roi_fail() {
     //given
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area
+        .expect()
+        .times(1)
+        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 0;
+            *start_y = 0;
+            *width = 4096;
+            *height = 2048;
+            QHYCCD_SUCCESS
+        });
     let ctx = SetQHYCCDResolution_context();
     ctx.expect()
         .withf_st(|handle, start_x, stary_y, width, height| {
@@ -604,6 +811,7 @@ fn begin_live_success() {
     let res = cam.begin_live();
     //then
     assert!(res.is_ok());
+    assert_eq!(cam.state(), CameraState::Live);
 }
 
 #[test]
@@ -637,10 +845,12 @@ fn end_live_success() {
         .times(1)
         .return_const_st(QHYCCD_SUCCESS);
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
     //when
     let res = cam.end_live();
     //then
     assert!(res.is_ok());
+    assert_eq!(cam.state(), CameraState::Open);
 }
 
 #[test]
@@ -652,6 +862,7 @@ fn end_live_fail() {
         .times(1)
         .return_const_st(QHYCCD_ERROR);
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
     //when
     let res = cam.end_live();
     //then
@@ -665,6 +876,25 @@ fn end_live_fail() {
     );
 }
 
+#[test]
+fn end_live_not_live() {
+    //given
+    let ctx = StopQHYCCDLive_context();
+    ctx.expect().never();
+    let cam = new_camera();
+    //when
+    let res = cam.end_live();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CameraStateError {
+            state: CameraState::Open,
+            expected: CameraState::Live
+        }
+        .to_string()
+    );
+}
+
 #[test]
 fn get_image_size_success() {
     //given
@@ -717,6 +947,7 @@ fn get_live_frame_success() {
             QHYCCD_SUCCESS
         });
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
     //when
     let res = cam.get_live_frame(4);
     //then
@@ -742,6 +973,7 @@ fn get_live_frame_fail() {
         .times(1)
         .return_const_st(QHYCCD_ERROR);
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
     //when
     let res = cam.get_live_frame(4);
     //then
@@ -755,6 +987,115 @@ fn get_live_frame_fail() {
     );
 }
 
+#[test]
+fn live_frames_next_batch_drains_buffer() {
+    //given
+    let mut remaining = 3;
+    let ctx = GetQHYCCDLiveFrame_context();
+    ctx.expect()
+        .withf_st(|handle, _width, _height, _bpp, _channels, _buffer| *handle == TEST_HANDLE)
+        .times(4)
+        .returning_st(
+            move |_handle, width, height, bpp, channels, _buffer| unsafe {
+                if remaining == 0 {
+                    return QHYCCD_ERROR;
+                }
+                remaining -= 1;
+                *width = 2;
+                *height = 2;
+                *bpp = 8;
+                *channels = 1;
+                QHYCCD_SUCCESS
+            },
+        );
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let mut frames = cam.live_frames(4);
+    //when
+    let res = frames.next_batch(10, Duration::from_secs(1));
+    //then
+    assert_eq!(res.unwrap().len(), 3);
+}
+
+#[test]
+fn live_frames_next_batch_max_n() {
+    //given
+    let ctx = GetQHYCCDLiveFrame_context();
+    ctx.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let mut frames = cam.live_frames(4);
+    //when
+    let res = frames.next_batch(2, Duration::ZERO);
+    //then
+    assert_eq!(res.unwrap().len(), 2);
+}
+
+#[test]
+fn live_frames_next_batch_timeout() {
+    //given
+    let ctx = GetQHYCCDLiveFrame_context();
+    ctx.expect().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let mut frames = cam.live_frames(4);
+    //when
+    let res = frames.next_batch(2, Duration::from_millis(5));
+    //then
+    assert!(res.unwrap().is_empty());
+}
+
+#[test]
+fn live_frames_next_batch_error() {
+    //given
+    let ctx = GetQHYCCDLiveFrame_context();
+    ctx.expect().times(1).return_const_st(0x2002_u32);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let mut frames = cam.live_frames(4);
+    //when
+    let res = frames.next_batch(2, Duration::from_secs(1));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetLiveFrameError { error_code: 0x2002 }.to_string()
+    );
+}
+
+#[test]
+fn live_frames_next_batch_error_after_frames() {
+    //given
+    let mut calls = 0;
+    let ctx = GetQHYCCDLiveFrame_context();
+    ctx.expect().times(2).returning_st(
+        move |_handle, _width, _height, _bpp, _channels, _buffer| {
+            calls += 1;
+            match calls {
+                1 => QHYCCD_SUCCESS,
+                _ => 0x2002,
+            }
+        },
+    );
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let mut frames = cam.live_frames(4);
+    //when
+    let res = frames.next_batch(3, Duration::from_secs(1));
+    //then
+    assert_eq!(res.unwrap().len(), 1);
+}
+
+#[test]
+fn live_frames_next_camera_not_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    let mut frames = cam.live_frames(4);
+    //when
+    let res = frames.next();
+    //then
+    assert!(res.is_none());
+}
+
 #[test]
 fn get_single_frame_success() {
     //given
@@ -772,6 +1113,7 @@ fn get_single_frame_success() {
             QHYCCD_SUCCESS
         });
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
     //when
     let res = cam.get_single_frame(4);
     //then
@@ -797,6 +1139,7 @@ fn get_single_frame_fail() {
         .times(1)
         .return_const_st(QHYCCD_ERROR);
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
     //when
     let res = cam.get_single_frame(4);
     //then
@@ -811,52 +1154,145 @@ fn get_single_frame_fail() {
 }
 
 #[test]
-fn get_overscan_area_success() {
+fn get_single_frame_into_success() {
     //given
-    let ctx = GetQHYCCDOverScanArea_context();
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let ctx = GetQHYCCDSingleFrame_context();
     ctx.expect()
-        .withf_st(|handle, _start_x, _start_y, _width, _height| *handle == TEST_HANDLE)
+        .withf_st(|handle, _width, _height, _bpp, _channels, _buffer| *handle == TEST_HANDLE)
         .times(1)
-        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
-            *start_x = 2;
-            *start_y = 5;
-            *width = 1024;
-            *height = 768;
+        .returning_st(|_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 16;
+            *channels = 1;
+            buffer.copy_from(b"\x01\x02\x03\x04".as_ptr(), 4);
             QHYCCD_SUCCESS
         });
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    let mut buffer = [0_u8; 6];
     //when
-    let res = cam.get_overscan_area();
+    let res = cam.get_single_frame_into(&mut buffer);
     //then
-    assert!(res.is_ok());
     assert_eq!(
         res.unwrap(),
-        CCDChipArea {
-            start_x: 2,
-            start_y: 5,
-            width: 1024,
-            height: 768
+        FrameInfo {
+            width: 2,
+            height: 1,
+            bits_per_pixel: 16,
+            channels: 1
         }
-    )
+    );
+    assert_eq!(buffer, [1, 2, 3, 4, 0, 0]);
 }
 
 #[test]
-fn get_overscan_area_fail() {
+fn get_single_frame_into_buffer_too_small() {
     //given
-    let ctx = GetQHYCCDOverScanArea_context();
-    ctx.expect()
-        .withf_st(|handle, _start_x, _start_y, _width, _height| *handle == TEST_HANDLE)
-        .times(1)
-        .return_const_st(QHYCCD_ERROR);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let ctx = GetQHYCCDSingleFrame_context();
+    ctx.expect().never();
     let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    let mut buffer = [0_u8; 3];
     //when
-    let res = cam.get_overscan_area();
+    let res = cam.get_single_frame_into(&mut buffer);
     //then
-    assert!(res.is_err());
     assert_eq!(
         res.err().unwrap().to_string(),
-        QHYError::GetOverscanAreaError {
-            error_code: QHYCCD_ERROR
+        QHYError::BufferTooSmallError {
+            required: 4,
+            actual: 3
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_live_frame_into_reuses_buffer() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(2).return_const_st(1_u32);
+    let ctx = GetQHYCCDLiveFrame_context();
+    ctx.expect().times(2).returning_st({
+        let frames = std::cell::RefCell::new(vec![QHYCCD_ERROR, QHYCCD_SUCCESS]);
+        move |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            *buffer = 9;
+            frames.borrow_mut().pop().unwrap()
+        }
+    });
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let mut buffer = vec![0_u8; 1];
+    //when
+    let first = cam.get_live_frame_into(&mut buffer);
+    let second = cam.get_live_frame_into(&mut buffer);
+    //then
+    assert_eq!(first.unwrap().bits_per_pixel, 8);
+    assert_eq!(buffer, vec![9]);
+    assert_eq!(
+        second.err().unwrap().to_string(),
+        QHYError::GetLiveFrameError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_overscan_area_success() {
+    //given
+    let ctx = GetQHYCCDOverScanArea_context();
+    ctx.expect()
+        .withf_st(|handle, _start_x, _start_y, _width, _height| *handle == TEST_HANDLE)
+        .times(1)
+        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 2;
+            *start_y = 5;
+            *width = 1024;
+            *height = 768;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_overscan_area();
+    //then
+    assert!(res.is_ok());
+    assert_eq!(
+        res.unwrap(),
+        CCDChipArea {
+            start_x: 2,
+            start_y: 5,
+            width: 1024,
+            height: 768
+        }
+    )
+}
+
+#[test]
+fn get_overscan_area_fail() {
+    //given
+    let ctx = GetQHYCCDOverScanArea_context();
+    ctx.expect()
+        .withf_st(|handle, _start_x, _start_y, _width, _height| *handle == TEST_HANDLE)
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_overscan_area();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetOverscanAreaError {
+            error_code: QHYCCD_ERROR
         }
         .to_string()
     );
@@ -914,6 +1350,101 @@ fn get_effective_area_fail() {
     );
 }
 
+#[test]
+fn get_precise_exposure_info_success() {
+    //given
+    let ctx = GetQHYCCDPreciseExposureInfo_context();
+    ctx.expect()
+        .withf_st(|handle, _, _, _, _, _, _, _| *handle == TEST_HANDLE)
+        .times(1)
+        .returning_st(
+            |_handle, pixel, line, frame, clocks, lines, actual, long| unsafe {
+                *pixel = 13_468;
+                *line = 20_000;
+                *frame = 130_000;
+                *clocks = 1_485;
+                *lines = 6_500;
+                *actual = 100_020;
+                *long = 0;
+                QHYCCD_SUCCESS
+            },
+        );
+    let cam = new_camera();
+    //when
+    let res = cam.get_precise_exposure_info();
+    //then
+    assert!(res.is_ok());
+    assert_eq!(
+        res.unwrap(),
+        PreciseExposureInfo {
+            pixel_period_ps: 13_468,
+            line_period_ns: 20_000,
+            frame_period_us: 130_000,
+            clocks_per_line: 1_485,
+            lines_per_frame: 6_500,
+            actual_exposure: Duration::from_micros(100_020),
+            long_exposure_mode: false,
+        }
+    );
+}
+
+#[test]
+fn get_precise_exposure_info_fail() {
+    //given
+    let ctx = GetQHYCCDPreciseExposureInfo_context();
+    ctx.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_precise_exposure_info();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetPreciseExposureInfoError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_rolling_shutter_end_offset_success() {
+    //given
+    let ctx = GetQHYCCDRollingShutterEndOffset_context();
+    ctx.expect()
+        .withf_st(|handle, row, _offset| *handle == TEST_HANDLE && *row == 1000)
+        .times(1)
+        .returning_st(|_handle, _row, offset| unsafe {
+            *offset = 20_000.0;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_rolling_shutter_end_offset(1000);
+    //then
+    assert!(res.is_ok());
+    assert_eq!(res.unwrap(), Duration::from_millis(20));
+}
+
+#[test]
+fn get_rolling_shutter_end_offset_fail() {
+    //given
+    let ctx = GetQHYCCDRollingShutterEndOffset_context();
+    ctx.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_rolling_shutter_end_offset(0);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetRollingShutterEndOffsetError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
 #[test]
 fn start_single_frame_exposure_success() {
     //given
@@ -941,6 +1472,7 @@ fn start_single_frame_exposure_fail() {
     //when
     let res = cam.start_single_frame_exposure();
     //then
+    assert_eq!(cam.state(), CameraState::Open);
     assert!(res.is_err());
     assert_eq!(
         res.err().unwrap().to_string(),
@@ -1322,6 +1854,13 @@ fn set_parameter_fail() {
         })
         .times(1)
         .return_const_st(QHYCCD_ERROR);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().times(1).return_const_st(QHYCCD_ERROR);
     let cam = new_camera();
     //when
     let res = cam.set_parameter(Control::TransferBit, 16.0);
@@ -1404,7 +1943,7 @@ fn set_if_available_fail() {
         .withf_st(|handle, control| {
             *handle == TEST_HANDLE && *control == Control::TransferBit as u32
         })
-        .times(1)
+        .times(2)
         .return_const_st(QHYCCD_SUCCESS);
 
     let ctx_set = SetQHYCCDParam_context();
@@ -1415,6 +1954,8 @@ fn set_if_available_fail() {
         })
         .times(1)
         .return_const_st(QHYCCD_ERROR);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().times(1).return_const_st(QHYCCD_ERROR);
     let cam = new_camera();
     //when
     let res = cam.set_if_available(Control::TransferBit, 16.0);
@@ -1430,112 +1971,1413 @@ fn set_if_available_fail() {
 }
 
 #[test]
-fn open_success() {
+fn set_row_denoise_success() {
     //given
-    let cam = Camera::new("test_camera".to_owned());
-    let ctx_open = OpenQHYCCD_context();
-    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::RowDeNoise as u32
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::RowDeNoise as u32 && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
     //when
-    let res = cam.open();
+    let res = cam.set_row_denoise(true);
     //then
     assert!(res.is_ok());
-    assert_eq!(cam.id(), "test_camera".to_owned());
 }
 
 #[test]
-fn open_already_open() {
+fn is_amp_glow_suppression_enabled_success() {
     //given
-    let cam = Camera::new("test_camera".to_owned());
-    let ctx_open = OpenQHYCCD_context();
-    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
-    let _res = cam.open();
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| *handle == TEST_HANDLE && *control == Control::Ampv as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx = GetQHYCCDParam_context();
+    ctx.expect()
+        .withf_st(|handle, control| *handle == TEST_HANDLE && *control == Control::Ampv as u32)
+        .times(1)
+        .return_const_st(0.0);
+    let cam = new_camera();
     //when
-    let res = cam.open();
+    let res = cam.is_amp_glow_suppression_enabled();
     //then
     assert!(res.is_ok());
+    assert!(!res.unwrap());
 }
 
 #[test]
-fn open_fail() {
+fn set_amp_glow_suppression_fail() {
     //given
-    let cam = Camera::new("test_camera".to_owned());
-    let ctx = OpenQHYCCD_context();
-    ctx.expect().times(1).return_const_st(core::ptr::null());
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
     //when
-    let res = cam.open();
+    let res = cam.set_amp_glow_suppression(false);
     //then
     assert!(res.is_err());
     assert_eq!(
         res.err().unwrap().to_string(),
-        QHYError::OpenCameraError.to_string()
+        QHYError::IsControlAvailableError {
+            control: Control::Ampv
+        }
+        .to_string()
     );
 }
+
 #[test]
-fn open_nulerror() {
+fn set_conversion_gain_success() {
     //given
-    let cam = Camera::new("test_\0camera".to_owned());
-    let ctx = OpenQHYCCD_context();
-    ctx.expect().times(0);
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::CamLightPerformanceMode as u32
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE
+                && *control == Control::CamLightPerformanceMode as u32
+                && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
     //when
-    let res = cam.open();
+    let res = cam.set_conversion_gain(ConversionGain::High);
     //then
-    assert!(res.is_err());
-    assert_eq!(
-        res.err().unwrap().to_string(),
-        "nul byte found in provided data at position: 5"
-    );
+    assert!(res.is_ok());
 }
 
 #[test]
-fn close_success() {
+fn get_conversion_gain_success() {
     //given
-    let ctx = CloseQHYCCD_context();
-    ctx.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx = GetQHYCCDParam_context();
+    ctx.expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::CamLightPerformanceMode as u32
+        })
+        .times(1)
+        .return_const_st(0.0);
     let cam = new_camera();
     //when
-    let res = cam.close();
+    let res = cam.get_conversion_gain();
     //then
     assert!(res.is_ok());
+    assert_eq!(res.unwrap(), ConversionGain::Low);
 }
 
 #[test]
-fn close_already_closed() {
+fn set_image_processing_success() {
     //given
-    let ctx = CloseQHYCCD_context();
-    ctx.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| *handle == TEST_HANDLE && *control == Control::ImgProc as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::ImgProc as u32 && *value == 0.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
     let cam = new_camera();
-    let _res = cam.close();
     //when
-    let res = cam.close();
+    let res = cam.set_image_processing(false);
     //then
     assert!(res.is_ok());
 }
 
 #[test]
-fn close_fail() {
+fn is_image_processing_enabled_not_available() {
     //given
-    let ctx = CloseQHYCCD_context();
-    ctx.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get.expect().times(1).return_const_st(QHYCCD_ERROR);
     let cam = new_camera();
     //when
-    let res = cam.close();
+    let res = cam.is_image_processing_enabled();
     //then
-    assert!(res.is_err());
-    assert_eq!(
-        res.err().unwrap().to_string(),
-        QHYError::CloseCameraError {
-            error_code: QHYCCD_ERROR
-        }
-        .to_string()
-    );
+    assert!(res.is_ok());
+    assert!(!res.unwrap());
 }
 
 #[test]
-fn bayer_mode_try_from() {
-    assert_eq!(BayerMode::try_from(1).unwrap(), BayerMode::GBRG);
-    assert_eq!(BayerMode::try_from(2).unwrap(), BayerMode::GRBG);
-    assert_eq!(BayerMode::try_from(3).unwrap(), BayerMode::BGGR);
-    assert_eq!(BayerMode::try_from(4).unwrap(), BayerMode::RGGB);
-    assert!(BayerMode::try_from(0).is_err());
-    assert!(BayerMode::try_from(5).is_err());
+fn set_shutter_readout_mode_success() {
+    //given
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::GlobalReset as u32
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::GlobalReset as u32 && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_shutter_readout_mode(ShutterReadoutMode::GlobalReset);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn get_shutter_readout_mode_success() {
+    //given
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx = GetQHYCCDParam_context();
+    ctx.expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::GlobalReset as u32
+        })
+        .times(1)
+        .return_const_st(1.0);
+    let cam = new_camera();
+    //when
+    let res = cam.get_shutter_readout_mode();
+    //then
+    assert_eq!(res.unwrap(), ShutterReadoutMode::GlobalReset);
+}
+
+#[test]
+fn get_shutter_readout_mode_not_available() {
+    //given
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_shutter_readout_mode();
+    //then
+    assert_eq!(res.unwrap(), ShutterReadoutMode::Rolling);
+}
+
+#[test]
+fn flush_residual_image_success() {
+    //given
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|handle, control| *handle == TEST_HANDLE && *control == Control::Exposure as u32)
+        .times(1)
+        .return_const_st(300_000_000.0);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .times(1)
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 10.0;
+            *max = 3_600_000_000.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Exposure as u32 && *value == 10.0)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| {
+            *control == Control::Exposure as u32 && *value == 300_000_000.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(3).return_const_st(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(3).return_const_st(4_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(3).return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.flush_residual_image(3);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn flush_residual_image_restores_exposure_on_fail() {
+    //given
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().times(1).return_const_st(1000.0);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, _, value| *value == 0.0)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|_, _, value| *value == 1000.0)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.flush_residual_image(2);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::StartSingleFrameExposureError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn flush_residual_image_aborts_when_second_flush_fails() {
+    //given
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().times(1).return_const_st(1000.0);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, _, value| *value == 0.0)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|_, _, value| *value == 1000.0)
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(2).return_const_st(4_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    let mut downloads = 0;
+    ctx_frame
+        .expect()
+        .times(2)
+        .returning_st(move |_, _, _, _, _, _| {
+            downloads += 1;
+            match downloads {
+                1 => QHYCCD_SUCCESS,
+                _ => 0x2002,
+            }
+        });
+    let ctx_abort = CancelQHYCCDExposingAndReadout_context();
+    ctx_abort
+        .expect()
+        .withf_st(|handle| *handle == TEST_HANDLE)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.flush_residual_image(3);
+    //then
+    assert_eq!(cam.state(), CameraState::Open);
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetSingleFrameError { error_code: 0x2002 }.to_string()
+    );
+}
+
+#[test]
+fn set_rbi_removal_success() {
+    //given
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| *handle == TEST_HANDLE && *control == Control::RemoveRbi as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::RemoveRbi as u32 && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_rbi_removal(true);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_heater_success() {
+    //given
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::CamShutterMotorHeatingInterface as u32
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE
+                && *control == Control::CamShutterMotorHeatingInterface as u32
+                && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_heater(1.0);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn get_heater_success() {
+    //given
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::CamShutterMotorHeatingInterface as u32
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx = GetQHYCCDParam_context();
+    ctx.expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::CamShutterMotorHeatingInterface as u32
+        })
+        .times(1)
+        .return_const_st(1.0);
+    let cam = new_camera();
+    //when
+    let res = cam.get_heater();
+    //then
+    assert!(res.is_ok());
+    assert_eq!(res.unwrap(), 1.0);
+}
+
+#[test]
+fn get_heater_not_available_fail() {
+    //given
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_heater();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::IsControlAvailableError {
+            control: Control::CamShutterMotorHeatingInterface
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn open_success() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    //when
+    let res = cam.open();
+    crate::mocks::keep_open(&cam);
+    //then
+    assert!(res.is_ok());
+    assert_eq!(cam.id(), "test_camera".to_owned());
+}
+
+#[test]
+fn open_already_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let _res = cam.open();
+    crate::mocks::keep_open(&cam);
+    //when
+    let res = cam.open();
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn open_fail() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    let ctx = OpenQHYCCD_context();
+    ctx.expect().times(1).return_const_st(core::ptr::null());
+    //when
+    let res = cam.open();
+    crate::mocks::keep_open(&cam);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::OpenCameraError.to_string()
+    );
+}
+#[test]
+fn open_nulerror() {
+    //given
+    let cam = Camera::new("test_\0camera".to_owned());
+    let ctx = OpenQHYCCD_context();
+    ctx.expect().times(0);
+    //when
+    let res = cam.open();
+    crate::mocks::keep_open(&cam);
+    //then
+    assert!(matches!(res, Err(CameraIdNulError { .. })));
+}
+
+#[test]
+fn close_success() {
+    //given
+    let ctx = CloseQHYCCD_context();
+    ctx.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.close();
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn close_already_closed() {
+    //given
+    let ctx = CloseQHYCCD_context();
+    ctx.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let _res = cam.close();
+    //when
+    let res = cam.close();
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn close_while_exposing() {
+    //given
+    let ctx = CloseQHYCCD_context();
+    ctx.expect().never();
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.close();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CameraStateError {
+            state: CameraState::Exposing,
+            expected: CameraState::Open
+        }
+        .to_string()
+    );
+    assert!(cam.is_open().unwrap());
+}
+
+#[test]
+fn close_fail() {
+    //given
+    let ctx = CloseQHYCCD_context();
+    ctx.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.close();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CloseCameraError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+/// an open camera that is closed when its last clone is dropped
+fn new_camera_closed_on_drop() -> Camera {
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let camera = Camera::new("test_camera".to_owned());
+    camera.open().unwrap();
+    camera
+}
+
+#[test]
+fn drop_last_clone_closes() {
+    //given
+    let ctx = CloseQHYCCD_context();
+    ctx.expect()
+        .withf_st(|handle| *handle == TEST_HANDLE)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera_closed_on_drop();
+    let clone = cam.clone();
+    drop(cam);
+    //when
+    drop(clone);
+    //then
+    ctx.checkpoint();
+}
+
+#[test]
+fn drop_other_clone_does_not_close() {
+    //given
+    let ctx = CloseQHYCCD_context();
+    ctx.expect().never();
+    let cam = new_camera_closed_on_drop();
+    let clone = cam.clone();
+    //when
+    drop(cam);
+    //then
+    ctx.checkpoint();
+    assert!(clone.is_open().unwrap());
+    ctx.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    drop(clone);
+    ctx.checkpoint();
+}
+
+#[test]
+fn bayer_mode_try_from() {
+    assert_eq!(BayerMode::try_from(1).unwrap(), BayerMode::GBRG);
+    assert_eq!(BayerMode::try_from(2).unwrap(), BayerMode::GRBG);
+    assert_eq!(BayerMode::try_from(3).unwrap(), BayerMode::BGGR);
+    assert_eq!(BayerMode::try_from(4).unwrap(), BayerMode::RGGB);
+    assert!(BayerMode::try_from(0).is_err());
+    assert!(BayerMode::try_from(5).is_err());
+}
+
+#[test]
+fn arm_external_trigger_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::CamTrigerInterface as u32
+        })
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger
+        .expect()
+        .withf_st(|handle, on| *handle == TEST_HANDLE && *on)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.arm_external_trigger();
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn arm_external_trigger_not_supported() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.arm_external_trigger();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::IsControlAvailableError {
+            control: Control::CamTrigerInterface
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn arm_external_trigger_fail() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.arm_external_trigger();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::SetTriggerFunctionError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn arm_external_trigger_start_fail_disarms() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger
+        .expect()
+        .withf_st(|_, on| *on)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_trigger
+        .expect()
+        .withf_st(|_, on| !*on)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.arm_external_trigger();
+    //then
+    assert!(res.is_err());
+    assert_eq!(cam.state(), CameraState::Open);
+}
+
+#[test]
+fn disarm_external_trigger_success() {
+    //given
+    let ctx_cancel = CancelQHYCCDExposingAndReadout_context();
+    ctx_cancel
+        .expect()
+        .withf_st(|handle| *handle == TEST_HANDLE)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger
+        .expect()
+        .withf_st(|handle, on| *handle == TEST_HANDLE && !*on)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.disarm_external_trigger();
+    //then
+    assert!(res.is_ok());
+    assert_eq!(cam.state(), CameraState::Open);
+}
+
+#[test]
+fn disarm_external_trigger_not_armed() {
+    //given
+    let ctx_cancel = CancelQHYCCDExposingAndReadout_context();
+    ctx_cancel.expect().never();
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger
+        .expect()
+        .withf_st(|_, on| !*on)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.disarm_external_trigger();
+    //then
+    assert!(res.is_ok());
+    assert_eq!(cam.state(), CameraState::Open);
+}
+
+#[test]
+fn disarm_external_trigger_cancel_fail() {
+    //given
+    let ctx_cancel = CancelQHYCCDExposingAndReadout_context();
+    ctx_cancel.expect().once().return_const_st(QHYCCD_ERROR);
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger.expect().never();
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.disarm_external_trigger();
+    //then
+    assert_eq!(cam.state(), CameraState::Exposing);
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::AbortExposureAndReadoutError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn wait_for_triggered_frame_success() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let ctx = GetQHYCCDSingleFrame_context();
+    let mut calls = 0;
+    ctx.expect()
+        .times(3)
+        .returning_st(move |_handle, width, height, bpp, channels, buffer| {
+            calls += 1;
+            if calls < 3 {
+                return QHYCCD_ERROR;
+            }
+            unsafe {
+                *width = 2;
+                *height = 2;
+                *bpp = 8;
+                *channels = 1;
+                let test_image = b"\x01\x02\x03\x04";
+                buffer.copy_from(test_image.as_ptr(), 4);
+            }
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.wait_for_triggered_frame(Duration::from_secs(10));
+    //then
+    assert_eq!(
+        res.unwrap(),
+        ImageData {
+            data: vec![0x01, 0x02, 0x03, 0x04],
+            width: 2,
+            height: 2,
+            bits_per_pixel: 8,
+            channels: 1
+        }
+    )
+}
+
+#[test]
+fn wait_for_triggered_frame_error() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let ctx = GetQHYCCDSingleFrame_context();
+    ctx.expect().once().return_const_st(0x2002_u32);
+    let ctx_cancel = CancelQHYCCDExposingAndReadout_context();
+    ctx_cancel
+        .expect()
+        .withf_st(|handle| *handle == TEST_HANDLE)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.wait_for_triggered_frame(Duration::from_secs(10));
+    //then
+    assert_eq!(cam.state(), CameraState::Open);
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetSingleFrameError { error_code: 0x2002 }.to_string()
+    );
+}
+
+#[test]
+fn wait_for_triggered_frame_not_armed() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let cam = new_camera();
+    //when
+    let res = cam.wait_for_triggered_frame(Duration::from_secs(10));
+    //then
+    assert!(res.is_err());
+}
+
+#[test]
+fn wait_for_triggered_frame_timeout() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let ctx = GetQHYCCDSingleFrame_context();
+    ctx.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_cancel = CancelQHYCCDExposingAndReadout_context();
+    ctx_cancel
+        .expect()
+        .withf_st(|handle| *handle == TEST_HANDLE)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.wait_for_triggered_frame(Duration::from_millis(30));
+    //then
+    assert_eq!(cam.state(), CameraState::Open);
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::TriggerTimeoutError {
+            timeout: Duration::from_millis(30)
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn wait_for_triggered_frame_timeout_cancel_fail() {
+    //given
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let ctx = GetQHYCCDSingleFrame_context();
+    ctx.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_cancel = CancelQHYCCDExposingAndReadout_context();
+    ctx_cancel.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.wait_for_triggered_frame(Duration::from_millis(30));
+    //then
+    assert_eq!(cam.state(), CameraState::Exposing);
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::AbortExposureAndReadoutError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn arm_wait_and_arm_again() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger
+        .expect()
+        .withf_st(|_, on| *on)
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(1_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().once().returning_st(
+        |_handle, width, height, bpp, channels, _buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = new_camera();
+    //when
+    cam.arm_external_trigger().unwrap();
+    let frame = cam.wait_for_triggered_frame(Duration::from_secs(10));
+    let rearmed = cam.arm_external_trigger();
+    //then
+    assert!(frame.is_ok());
+    assert!(rearmed.is_ok());
+    assert_eq!(cam.state(), CameraState::Exposing);
+}
+
+#[test]
+fn get_humidity_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CamHumidity as u32)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_humidity = GetQHYCCDHumidity_context();
+    ctx_humidity
+        .expect()
+        .withf_st(|handle, _| *handle == TEST_HANDLE)
+        .once()
+        .returning_st(|_, humidity| unsafe {
+            *humidity = 12.5;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_humidity();
+    //then
+    assert_eq!(res.unwrap(), 12.5);
+}
+
+#[test]
+fn get_humidity_not_available() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_humidity();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::IsControlAvailableError {
+            control: Control::CamHumidity
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_pressure_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CamPressure as u32)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_pressure = GetQHYCCDPressure_context();
+    ctx_pressure
+        .expect()
+        .once()
+        .returning_st(|_, pressure| unsafe {
+            *pressure = 1013.25;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_pressure();
+    //then
+    assert_eq!(res.unwrap(), 1013.25);
+}
+
+#[test]
+fn get_pressure_fail() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_pressure = GetQHYCCDPressure_context();
+    ctx_pressure.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_pressure();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetPressureError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn set_trigger_mode_external_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CamTriggerMode as u32)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_mode = SetQHYCCDTrigerMode_context();
+    ctx_mode
+        .expect()
+        .withf_st(|handle, mode| *handle == TEST_HANDLE && *mode == 1)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger
+        .expect()
+        .withf_st(|_, on| *on)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_trigger_mode(TriggerMode::External(1));
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_trigger_mode_off_success() {
+    //given
+    let ctx_trigger = SetQHYCCDTrigerFunction_context();
+    ctx_trigger
+        .expect()
+        .withf_st(|_, on| !*on)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_trigger_mode(TriggerMode::Off);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_trigger_mode_fail() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_mode = SetQHYCCDTrigerMode_context();
+    ctx_mode.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_trigger_mode(TriggerMode::External(0));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::SetTriggerModeError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn enable_trigger_out_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CamTriggerOut as u32)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_out = EnableQHYCCDTrigerOut_context();
+    ctx_out
+        .expect()
+        .withf_st(|handle| *handle == TEST_HANDLE)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.enable_trigger_out();
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn enable_trigger_out_not_supported() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.enable_trigger_out();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::IsControlAvailableError {
+            control: Control::CamTriggerOut
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_trigger_interfaces_success() {
+    //given
+    let ctx_number = GetQHYCCDTrigerInterfaceNumber_context();
+    ctx_number.expect().once().returning_st(|_, number| unsafe {
+        *number = 2;
+        QHYCCD_SUCCESS
+    });
+    let ctx_name = GetQHYCCDTrigerInterfaceName_context();
+    ctx_name
+        .expect()
+        .times(2)
+        .returning_st(|_, index, name| unsafe {
+            let interface = match index {
+                0 => "GPIO\0",
+                _ => "OPTO\0",
+            };
+            name.copy_from(interface.as_ptr() as *const c_char, interface.len());
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.get_trigger_interfaces();
+    //then
+    assert_eq!(res.unwrap(), vec!["GPIO".to_owned(), "OPTO".to_owned()]);
+}
+
+#[test]
+fn get_trigger_interfaces_fail() {
+    //given
+    let ctx_number = GetQHYCCDTrigerInterfaceNumber_context();
+    ctx_number.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.get_trigger_interfaces();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::GetTriggerInterfacesError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn set_trigger_interface_success() {
+    //given
+    let ctx_interface = SetQHYCCDTrigerInterface_context();
+    ctx_interface
+        .expect()
+        .withf_st(|_, index| *index == 1)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_trigger_interface(1);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_ddr_enabled_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::DDR as u32)
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::DDR as u32 && *value == 1.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().once().return_const_st(1.0);
+    let cam = new_camera();
+    //when
+    let res = cam.set_ddr_enabled(true);
+    //then
+    assert!(res.is_ok());
+    assert!(cam.is_ddr_enabled().unwrap());
+}
+
+#[test]
+fn set_ddr_enabled_not_available() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_ddr_enabled(true);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::IsControlAvailableError {
+            control: Control::DDR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_ddr_fill_level_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::DDRBufferCapacity as u32)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().once().return_const_st(4096.0);
+    let cam = new_camera();
+    //when
+    let res = cam.get_ddr_fill_level();
+    //then
+    assert_eq!(res.unwrap(), 4096);
+}
+
+#[test]
+fn set_ddr_read_threshold_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::DDRBufferReadThreshold as u32)
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, _, value| *value == 2.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().once().return_const_st(2.0);
+    let cam = new_camera();
+    //when
+    let res = cam.set_ddr_read_threshold(2);
+    //then
+    assert!(res.is_ok());
+    assert_eq!(cam.get_ddr_read_threshold().unwrap(), 2);
+}
+
+#[test]
+fn state_follows_open_and_close() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let ctx_close = CloseQHYCCD_context();
+    ctx_close.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let cam = Camera::new("test_camera".to_owned());
+    assert_eq!(cam.state(), CameraState::Closed);
+    //when
+    cam.open().unwrap();
+    crate::mocks::keep_open(&cam);
+    //then
+    assert_eq!(cam.state(), CameraState::Open);
+    cam.close().unwrap();
+    assert_eq!(cam.state(), CameraState::Closed);
+}
+
+#[test]
+fn state_single_frame_exposure_cycle() {
+    //given
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(1).returning_st(
+        |_handle, width, height, bpp, channels, _buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = new_camera();
+    //when
+    cam.start_single_frame_exposure().unwrap();
+    //then
+    assert_eq!(cam.state(), CameraState::Exposing);
+    cam.get_single_frame(1).unwrap();
+    assert_eq!(cam.state(), CameraState::Open);
+}
+
+#[test]
+fn get_live_frame_wrong_state() {
+    //given
+    let cam = new_camera();
+    //when
+    let res = cam.get_live_frame(1);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CameraStateError {
+            state: CameraState::Open,
+            expected: CameraState::Live
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_single_frame_into_wrong_state() {
+    //given
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let mut buffer = vec![0u8; 1];
+    //when
+    let res = cam.get_single_frame_into(&mut buffer);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CameraStateError {
+            state: CameraState::Live,
+            expected: CameraState::Exposing
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn start_single_frame_exposure_wrong_state() {
+    //given
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    //when
+    let res = cam.start_single_frame_exposure();
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CameraStateError {
+            state: CameraState::Live,
+            expected: CameraState::Open
+        }
+        .to_string()
+    );
+    assert_eq!(cam.state(), CameraState::Live);
+}
+
+#[test]
+fn set_stream_mode_while_exposing() {
+    //given
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let res = cam.set_stream_mode(StreamMode::LiveMode);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CameraStateError {
+            state: CameraState::Exposing,
+            expected: CameraState::Open
+        }
+        .to_string()
+    );
 }
@@ -0,0 +1,94 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    InitQHYCCDResource_context, ReleaseQHYCCDResource_context, QHYCCD_SUCCESS,
+};
+use crate::test_sdk::sdk_lock;
+
+fn new_sdk() -> Sdk {
+    Sdk {
+        cameras: vec![
+            Camera::new("QHY178M-222b16468c5966524".to_owned()),
+            Camera::new("QHY600M-5b8c2a1e4d3f7a90".to_owned()),
+            Camera::new("QHY178M-222b16468c5966525".to_owned()),
+        ],
+        filter_wheels: Vec::new(),
+        resources: SdkResources::acquire().unwrap(),
+    }
+}
+
+#[test]
+fn parse_success() {
+    //given
+    let id = "QHY178M-222b16468c5966524";
+    //when
+    let res = CameraId::parse(id);
+    //then
+    assert_eq!(
+        res,
+        Some(CameraId {
+            model: "QHY178M".to_owned(),
+            serial: "222b16468c5966524".to_owned()
+        })
+    );
+    assert_eq!(res.unwrap().to_string(), id);
+}
+
+#[test]
+fn parse_model_with_dash() {
+    //given
+    let id = "QHY5-M-0123456789";
+    //when
+    let res = CameraId::parse(id).unwrap();
+    //then
+    assert_eq!(res.model, "QHY5-M");
+    assert_eq!(res.serial, "0123456789");
+}
+
+#[test]
+fn parse_fail() {
+    //given
+    //when
+    //then
+    assert_eq!(CameraId::parse("QHY178M"), None);
+    assert_eq!(CameraId::parse("QHY178M-"), None);
+    assert_eq!(CameraId::parse("-222b16468c5966524"), None);
+    assert_eq!(CameraId::parse(""), None);
+}
+
+#[test]
+fn camera_id_success() {
+    //given
+    let camera = Camera::new("QHY600M-5b8c2a1e4d3f7a90".to_owned());
+    //when
+    let res = camera.camera_id();
+    //then
+    assert_eq!(res.unwrap().serial, "5b8c2a1e4d3f7a90");
+}
+
+#[test]
+fn sdk_lookups() {
+    //given
+    let _lock = sdk_lock();
+    let ctx_init = InitQHYCCDResource_context();
+    ctx_init.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let sdk = new_sdk();
+    //when
+    let by_id = sdk.camera_by_id("QHY600M-5b8c2a1e4d3f7a90");
+    let by_serial = sdk.camera_by_serial("222b16468c5966525");
+    let by_model = sdk.cameras_by_model("QHY178M").collect::<Vec<_>>();
+    //then
+    assert_eq!(by_id.unwrap().id(), "QHY600M-5b8c2a1e4d3f7a90");
+    assert_eq!(by_serial.unwrap().id(), "QHY178M-222b16468c5966525");
+    assert_eq!(
+        by_model
+            .iter()
+            .map(|camera| camera.id())
+            .collect::<Vec<_>>(),
+        vec!["QHY178M-222b16468c5966524", "QHY178M-222b16468c5966525"]
+    );
+    assert!(sdk.camera_by_id("QHY178M").is_none());
+    assert!(sdk.camera_by_serial("unknown").is_none());
+    assert_eq!(sdk.cameras_by_model("QHY268M").count(), 0);
+}
@@ -0,0 +1,63 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{IsQHYCCDControlAvailable_context, QHYCCD_SUCCESS};
+use crate::mocks::new_camera;
+
+#[test]
+fn capabilities_color_camera() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(Control::all().len())
+        .returning_st(|_, control| match control {
+            c if c == Control::CamColor as u32 => BayerMode::RGGB as u32,
+            c if c == Control::Cooler as u32
+                || c == Control::CamBin1x1mode as u32
+                || c == Control::CamBin2x2mode as u32
+                || c == Control::CamBin4x4mode as u32
+                || c == Control::Cam8bits as u32
+                || c == Control::Cam16bits as u32
+                || c == Control::CamGps as u32 =>
+            {
+                QHYCCD_SUCCESS
+            }
+            _ => QHYCCD_ERROR,
+        });
+    let cam = new_camera();
+    //when
+    let capabilities = cam.capabilities();
+    //then
+    assert_eq!(capabilities.controls.len(), 8);
+    assert!(capabilities.supports(Control::CamGps));
+    assert!(!capabilities.supports(Control::CamHumidity));
+    assert!(capabilities.cooler);
+    assert!(capabilities.is_color());
+    assert_eq!(capabilities.bayer_mode, Some(BayerMode::RGGB));
+    assert_eq!(capabilities.bin_modes, vec![1, 2, 4]);
+    assert_eq!(capabilities.max_bin, 4);
+    assert_eq!(
+        capabilities.pixel_formats,
+        vec![PixelFormat::U8, PixelFormat::U16]
+    );
+    assert!(capabilities.gps);
+    assert!(!capabilities.trigger);
+    assert!(!capabilities.filter_wheel_port);
+    assert!(!capabilities.humidity);
+}
+
+#[test]
+fn capabilities_camera_not_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let capabilities = cam.capabilities();
+    //then
+    assert_eq!(
+        capabilities,
+        CameraCapabilities {
+            max_bin: 1,
+            ..Default::default()
+        }
+    );
+    assert!(!capabilities.is_color());
+}
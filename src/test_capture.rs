@@ -0,0 +1,558 @@
+use mockall::Sequence;
+
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    self, CancelQHYCCDExposingAndReadout_context, ExpQHYCCDSingleFrame_context,
+    GetQHYCCDChipInfo_context, GetQHYCCDEffectiveArea_context, GetQHYCCDMemLength_context,
+    GetQHYCCDNumberOfReadModes_context, GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context,
+    GetQHYCCDReadMode_context, GetQHYCCDSingleFrame_context, InitQHYCCD_context,
+    IsQHYCCDControlAvailable_context, SetQHYCCDBinMode_context, SetQHYCCDBitsMode_context,
+    SetQHYCCDParam_context, SetQHYCCDReadMode_context, SetQHYCCDResolution_context,
+    SetQHYCCDStreamMode_context, QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+/// every numeric control accepts 0..=100, the exposure up to 10s
+fn expect_limits() -> mock_libqhyccd_sys::__GetQHYCCDParamMinMaxStep::Context {
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .returning(|_handle, control, c_min, c_max, c_step| unsafe {
+            *c_min = 0.0;
+            *c_max = match control {
+                c if c == Control::Exposure as u32 => 10_000_000.0,
+                _ => 100.0,
+            };
+            *c_step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    ctx_limits
+}
+
+/// the values the settings had before they were applied
+fn expect_current_values() -> mock_libqhyccd_sys::__GetQHYCCDParam::Context {
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .returning(|_handle, control| match control {
+            c if c == Control::TransferBit as u32 => 8.0,
+            c if c == Control::UsbTraffic as u32 => 10.0,
+            c if c == Control::Gain as u32 => 5.0,
+            c if c == Control::Offset as u32 => 6.0,
+            c if c == Control::Exposure as u32 => 1_000.0,
+            _ => panic!("unexpected control"),
+        });
+    ctx_param
+}
+
+fn expect_effective_area() -> mock_libqhyccd_sys::__GetQHYCCDEffectiveArea::Context {
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area
+        .expect()
+        .returning(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 0;
+            *start_y = 0;
+            *width = 1000;
+            *height = 800;
+            QHYCCD_SUCCESS
+        });
+    ctx_area
+}
+
+fn expect_chip_info() -> mock_libqhyccd_sys::__GetQHYCCDChipInfo::Context {
+    let ctx_info = GetQHYCCDChipInfo_context();
+    ctx_info.expect().returning(
+        |_handle, _chipw, _chiph, imagew, imageh, _pixelw, _pixelh, bpp| unsafe {
+            *imagew = 1000;
+            *imageh = 800;
+            *bpp = 16;
+            QHYCCD_SUCCESS
+        },
+    );
+    ctx_info
+}
+
+#[test]
+fn capture_settings_builder() {
+    //given
+    let roi = CCDChipArea {
+        start_x: 0,
+        start_y: 0,
+        width: 100,
+        height: 50,
+    };
+    //when
+    let settings = CaptureSettings::new(Duration::from_secs(2))
+        .gain(26.0)
+        .offset(30.0)
+        .roi(roi)
+        .binning(2, 2)
+        .bit_depth(16)
+        .readout_mode(1)
+        .usb_traffic(20)
+        .timeout(Duration::from_secs(5));
+    //then
+    assert_eq!(
+        settings,
+        CaptureSettings {
+            exposure: Duration::from_secs(2),
+            gain: Some(26.0),
+            offset: Some(30.0),
+            roi: Some(roi),
+            binning: Some((2, 2)),
+            bit_depth: Some(16),
+            readout_mode: Some(1),
+            usb_traffic: Some(20),
+            timeout: Some(Duration::from_secs(5)),
+        }
+    );
+}
+
+#[test]
+fn apply_success_in_order() {
+    //given
+    let _limits = expect_limits();
+    let _current = expect_current_values();
+    let _info = expect_chip_info();
+    let _area = expect_effective_area();
+    let ctx_modes = GetQHYCCDNumberOfReadModes_context();
+    ctx_modes.expect().once().returning(|_handle, num| unsafe {
+        *num = 3;
+        QHYCCD_SUCCESS
+    });
+    let ctx_mode = GetQHYCCDReadMode_context();
+    ctx_mode.expect().once().returning(|_handle, mode| unsafe {
+        *mode = 1;
+        QHYCCD_SUCCESS
+    });
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const(QHYCCD_SUCCESS);
+    let mut seq = Sequence::new();
+    let ctx_bits = SetQHYCCDBitsMode_context();
+    ctx_bits
+        .expect()
+        .withf(|_, bits| *bits == 16)
+        .once()
+        .in_sequence(&mut seq)
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_bin = SetQHYCCDBinMode_context();
+    ctx_bin
+        .expect()
+        .withf(|_, x, y| (*x, *y) == (2, 2))
+        .once()
+        .in_sequence(&mut seq)
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_roi = SetQHYCCDResolution_context();
+    ctx_roi
+        .expect()
+        .withf(|_, x, y, width, height| (*x, *y, *width, *height) == (0, 0, 500, 400))
+        .once()
+        .in_sequence(&mut seq)
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_param = SetQHYCCDParam_context();
+    for (control, value) in [
+        (Control::UsbTraffic, 20.0),
+        (Control::Gain, 26.0),
+        (Control::Offset, 30.0),
+        (Control::Exposure, 2_000_000.0),
+    ] {
+        ctx_param
+            .expect()
+            .withf(move |_, c, v| *c == control as u32 && *v == value)
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(QHYCCD_SUCCESS);
+    }
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::from_secs(2))
+        .readout_mode(1)
+        .bit_depth(16)
+        .binning(2, 2)
+        .roi(CCDChipArea {
+            start_x: 0,
+            start_y: 0,
+            width: 500,
+            height: 400,
+        })
+        .usb_traffic(20)
+        .gain(26.0)
+        .offset(30.0);
+    //when
+    let res = cam.apply(&settings);
+    //then
+    assert!(res.is_ok());
+    assert_eq!(cam.frame_geometry().binning, Some((2, 2)));
+}
+
+#[test]
+fn apply_out_of_range_changes_nothing() {
+    //given
+    let _limits = expect_limits();
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::from_secs(2))
+        .gain(26.0)
+        .offset(300.0);
+    //when
+    let res = cam.apply(&settings);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        ParameterOutOfRangeError {
+            control: Control::Offset,
+            value: 300.0,
+            min: 0.0,
+            max: 100.0
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn apply_restores_previous_values_on_failure() {
+    //given
+    let _limits = expect_limits();
+    let _current = expect_current_values();
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const(QHYCCD_SUCCESS);
+    let mut seq = Sequence::new();
+    let ctx_param = SetQHYCCDParam_context();
+    for (control, value, result) in [
+        (Control::Gain, 26.0, QHYCCD_SUCCESS),
+        (Control::Offset, 30.0, QHYCCD_ERROR),
+        (Control::Gain, 5.0, QHYCCD_SUCCESS),
+    ] {
+        ctx_param
+            .expect()
+            .withf(move |_, c, v| *c == control as u32 && *v == value)
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(result);
+    }
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::from_secs(2))
+        .gain(26.0)
+        .offset(30.0);
+    //when
+    let res = cam.apply(&settings);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        SetParameterError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn apply_unsupported_bin_mode() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .returning(|_handle, control| match control {
+            c if c == Control::CamBin3x3mode as u32 => QHYCCD_ERROR,
+            _ => QHYCCD_SUCCESS,
+        });
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::from_secs(2)).binning(3, 3);
+    //when
+    let res = cam.apply(&settings);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        UnsupportedBinModeError { bin_x: 3, bin_y: 3 }.to_string()
+    );
+}
+
+#[test]
+fn apply_roi_outside_effective_area() {
+    //given
+    let _area = expect_effective_area();
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let roi = CCDChipArea {
+        start_x: 100,
+        start_y: 0,
+        width: 500,
+        height: 400,
+    };
+    let settings = CaptureSettings::new(Duration::from_secs(2))
+        .binning(2, 2)
+        .roi(roi);
+    //when
+    let res = cam.apply(&settings);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        RoiError {
+            roi,
+            constraint: RoiConstraint::WithinEffectiveArea(CCDChipArea {
+                start_x: 0,
+                start_y: 0,
+                width: 500,
+                height: 400
+            })
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn apply_readout_mode_out_of_range() {
+    //given
+    let ctx_modes = GetQHYCCDNumberOfReadModes_context();
+    ctx_modes.expect().once().returning(|_handle, num| unsafe {
+        *num = 2;
+        QHYCCD_SUCCESS
+    });
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::from_secs(2)).readout_mode(2);
+    //when
+    let res = cam.apply(&settings);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        ReadoutModeOutOfRangeError { mode: 2, count: 2 }.to_string()
+    );
+}
+
+#[test]
+fn apply_readout_mode_change_fail() {
+    //given
+    let ctx_modes = GetQHYCCDNumberOfReadModes_context();
+    ctx_modes.expect().once().returning(|_handle, num| unsafe {
+        *num = 3;
+        QHYCCD_SUCCESS
+    });
+    let ctx_mode = GetQHYCCDReadMode_context();
+    ctx_mode.expect().once().returning(|_handle, mode| unsafe {
+        *mode = 0;
+        QHYCCD_SUCCESS
+    });
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::from_secs(2))
+        .readout_mode(1)
+        .gain(26.0);
+    //when
+    let res = cam.apply(&settings);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        ReadoutModeChangeError {
+            mode: 1,
+            current: 0
+        }
+        .to_string()
+    );
+}
+
+// the download runs on a background thread, so the expectations must not use the `_st` variants
+fn expect_capture(frame_delay: Duration, frame_result: u32) -> Vec<Box<dyn std::any::Any>> {
+    expect_captures(1, frame_delay, frame_result)
+}
+
+/// `count` captures in a row, the camera is only initialized for the first one
+fn expect_captures(
+    count: usize,
+    frame_delay: Duration,
+    frame_result: u32,
+) -> Vec<Box<dyn std::any::Any>> {
+    let ctx_mode = SetQHYCCDStreamMode_context();
+    ctx_mode
+        .expect()
+        .withf(|_, mode| *mode == StreamMode::SingleFrameMode as u8)
+        .once()
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_param = SetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf(|_, control, value| *control == Control::Exposure as u32 && *value == 0.0)
+        .times(count)
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(count).return_const(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(count).return_const(2_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(count).returning(
+        move |_handle, width, height, bpp, channels, buffer| unsafe {
+            std::thread::sleep(frame_delay);
+            *width = 2;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            buffer.copy_from([7_u8, 9].as_ptr(), 2);
+            frame_result
+        },
+    );
+    vec![
+        Box::new(expect_limits()),
+        Box::new(expect_current_values()),
+        Box::new(ctx_mode),
+        Box::new(ctx_init),
+        Box::new(ctx_param),
+        Box::new(ctx_exp),
+        Box::new(ctx_size),
+        Box::new(ctx_frame),
+    ]
+}
+
+#[test]
+fn capture_success() {
+    //given
+    let _capture = expect_capture(Duration::ZERO, QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::ZERO).timeout(Duration::from_secs(5));
+    //when
+    let image = cam.capture(&settings).unwrap();
+    //then
+    assert_eq!(image.data, vec![7, 9]);
+}
+
+#[test]
+fn capture_twice_initializes_once() {
+    //given
+    let _capture = expect_captures(2, Duration::ZERO, QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::ZERO).timeout(Duration::from_secs(5));
+    //when
+    let first = cam.capture(&settings);
+    let second = cam.capture(&settings);
+    //then
+    assert!(first.is_ok());
+    assert_eq!(second.unwrap().data, vec![7, 9]);
+}
+
+#[test]
+fn capture_sets_readout_mode_before_init() {
+    //given
+    let ctx_modes = GetQHYCCDNumberOfReadModes_context();
+    ctx_modes
+        .expect()
+        .times(2)
+        .returning(|_handle, num| unsafe {
+            *num = 3;
+            QHYCCD_SUCCESS
+        });
+    let ctx_mode = GetQHYCCDReadMode_context();
+    let current = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let read = current.clone();
+    ctx_mode
+        .expect()
+        .times(2)
+        .returning(move |_handle, mode| unsafe {
+            *mode = read.load(std::sync::atomic::Ordering::SeqCst);
+            QHYCCD_SUCCESS
+        });
+    let mut seq = Sequence::new();
+    let ctx_set_mode = SetQHYCCDReadMode_context();
+    ctx_set_mode
+        .expect()
+        .withf(|_, mode| *mode == 2)
+        .once()
+        .in_sequence(&mut seq)
+        .returning(move |_handle, mode| {
+            current.store(mode, std::sync::atomic::Ordering::SeqCst);
+            QHYCCD_SUCCESS
+        });
+    let ctx_stream = SetQHYCCDStreamMode_context();
+    ctx_stream
+        .expect()
+        .once()
+        .in_sequence(&mut seq)
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init
+        .expect()
+        .once()
+        .in_sequence(&mut seq)
+        .return_const(QHYCCD_SUCCESS);
+    let _limits = expect_limits();
+    let _current = expect_current_values();
+    let ctx_param = SetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .once()
+        .in_sequence(&mut seq)
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(2_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame
+        .expect()
+        .once()
+        .returning(|_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            buffer.copy_from([7_u8, 9].as_ptr(), 2);
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::ZERO)
+        .readout_mode(2)
+        .timeout(Duration::from_secs(5));
+    //when
+    let image = cam.capture(&settings).unwrap();
+    //then
+    assert_eq!(image.data, vec![7, 9]);
+}
+
+#[test]
+fn capture_timeout_aborts_exposure() {
+    //given
+    let _capture = expect_capture(Duration::from_millis(200), QHYCCD_ERROR);
+    let ctx_abort = CancelQHYCCDExposingAndReadout_context();
+    ctx_abort.expect().once().return_const(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let settings = CaptureSettings::new(Duration::ZERO).timeout(Duration::from_millis(20));
+    //when
+    let res = cam.capture(&settings);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        CaptureTimeoutError {
+            timeout: Duration::from_millis(20)
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn capture_download_failure_aborts_exposure() {
+    //given
+    let _capture = expect_capture(Duration::ZERO, QHYCCD_ERROR);
+    let ctx_abort = CancelQHYCCDExposingAndReadout_context();
+    ctx_abort.expect().once().return_const(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.capture(&CaptureSettings::default());
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        GetSingleFrameError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn capture_camera_not_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let res = cam.capture(&CaptureSettings::default());
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        CameraNotOpenError.to_string()
+    );
+}
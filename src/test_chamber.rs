@@ -0,0 +1,153 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParam_context, IsQHYCCDControlAvailable_context, SetQHYCCDParam_context,
+    QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+#[test]
+fn start_pump_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::VacuumPump as u32
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::VacuumPump as u32 && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.chamber().start_pump(Pump::Vacuum);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn stop_pump_not_available_fail() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.chamber().stop_pump(Pump::ChamberCycle);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::IsControlAvailableError {
+            control: Control::SensorChamberCyclePump
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn is_pump_running_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::SensorChamberCyclePump as u32
+        })
+        .times(1)
+        .return_const_st(1.0);
+    let cam = new_camera();
+    //when
+    let res = cam.chamber().is_pump_running(Pump::ChamberCycle);
+    //then
+    assert!(res.is_ok());
+    assert!(res.unwrap());
+}
+
+#[test]
+fn status_only_vacuum_pump() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(2)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::VacuumPump as u32 => QHYCCD_SUCCESS,
+            _ => QHYCCD_ERROR,
+        });
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::VacuumPump as u32)
+        .times(1)
+        .return_const_st(0.0);
+    let cam = new_camera();
+    //when
+    let res = cam.chamber().status();
+    //then
+    assert_eq!(
+        res.unwrap(),
+        ChamberStatus {
+            vacuum_pump: Some(false),
+            cycle_pump: None
+        }
+    );
+}
+
+#[test]
+fn set_chamber_pump_off_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::SensorChamberCyclePump as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| {
+            *control == Control::SensorChamberCyclePump as u32 && *value == 0.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_chamber_pump(false);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn is_vacuum_pump_running_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::VacuumPump as u32)
+        .times(1)
+        .return_const_st(0.0);
+    let cam = new_camera();
+    //when
+    let res = cam.is_vacuum_pump_running();
+    //then
+    assert!(!res.unwrap());
+}
@@ -0,0 +1,149 @@
+use super::*;
+
+/// fails to compile when a variant is added to `Control`, so it cannot be forgotten in `Control::all`
+fn is_listed(control: Control) -> bool {
+    match control {
+        Control::Brightness
+        | Control::Contrast
+        | Control::Wbr
+        | Control::Wbb
+        | Control::Wbg
+        | Control::Gamma
+        | Control::Gain
+        | Control::Offset
+        | Control::Exposure
+        | Control::Speed
+        | Control::TransferBit
+        | Control::Channels
+        | Control::UsbTraffic
+        | Control::RowDeNoise
+        | Control::CurTemp
+        | Control::CurPWM
+        | Control::ManualPWM
+        | Control::CfwPort
+        | Control::Cooler
+        | Control::St4Port
+        | Control::CamColor
+        | Control::CamBin1x1mode
+        | Control::CamBin2x2mode
+        | Control::CamBin3x3mode
+        | Control::CamBin4x4mode
+        | Control::CamMechanicalShutter
+        | Control::CamTrigerInterface
+        | Control::CamTecoverprotectInterface
+        | Control::CamSignalClampInterface
+        | Control::CamFinetoneInterface
+        | Control::CamShutterMotorHeatingInterface
+        | Control::CamCalibrateFpnInterface
+        | Control::CamChipTemperatureSensorInterface
+        | Control::CamUsbReadoutSlowestInterface
+        | Control::Cam8bits
+        | Control::Cam16bits
+        | Control::CamGps
+        | Control::CamIgnoreOverscanInterface
+        | Control::Qhyccd3aAutoexposure
+        | Control::Qhyccd3aAutofocus
+        | Control::Ampv
+        | Control::Vcam
+        | Control::CamViewMode
+        | Control::CfwSlotsNum
+        | Control::IsExposingDone
+        | Control::ScreenStretchB
+        | Control::ScreenStretchW
+        | Control::DDR
+        | Control::CamLightPerformanceMode
+        | Control::CamQhy5IIGuideMode
+        | Control::DDRBufferCapacity
+        | Control::DDRBufferReadThreshold
+        | Control::DefaultGain
+        | Control::DefaultOffset
+        | Control::OutputDataActualBits
+        | Control::OutputDataAlignment
+        | Control::CamSingleFrameMode
+        | Control::CamLiveVideoMode
+        | Control::CamIsColor
+        | Control::HasHardwareFrameCounter
+        | Control::MaxIdError
+        | Control::CamHumidity
+        | Control::CamPressure
+        | Control::VacuumPump
+        | Control::SensorChamberCyclePump
+        | Control::Cam32bits
+        | Control::CamSensorUlvoStatus
+        | Control::CamSensorPhaseReTrain
+        | Control::CamInitConfigFromFlash
+        | Control::CamTriggerMode
+        | Control::CamTriggerOut
+        | Control::CamBurstMode
+        | Control::CamSpeakerLedAlarm
+        | Control::CamWatchDogFpga
+        | Control::CamBin6x6mode
+        | Control::CamBin8x8mode
+        | Control::CamGlobalSensorGpsLED
+        | Control::ImgProc
+        | Control::RemoveRbi
+        | Control::GlobalReset
+        | Control::FrameDetect
+        | Control::CamGainDbConversion
+        | Control::CamCurveSystemGain
+        | Control::CamCurveFullWell
+        | Control::CamCurveReadoutNoise
+        | Control::MaxId
+        | Control::Autowhitebalance
+        | Control::Autoexposure
+        | Control::AutoexpMessureValue
+        | Control::AutoexpMessureMethod
+        | Control::ImageStabilization
+        | Control::GaindB => Control::all().contains(&control),
+    }
+}
+
+#[test]
+fn all_lists_every_control() {
+    //given
+    let controls = Control::all();
+    //when
+    let unlisted = controls.iter().filter(|c| !is_listed(**c)).count();
+    //then
+    assert_eq!(unlisted, 0);
+    assert_eq!(controls.len(), 92);
+}
+
+#[test]
+fn all_ids_unique_and_sorted() {
+    //given
+    let ids = Control::all().iter().map(|c| *c as u32).collect::<Vec<_>>();
+    //when
+    let mut sorted = ids.clone();
+    sorted.sort();
+    sorted.dedup();
+    //then
+    assert_eq!(ids, sorted);
+}
+
+#[test]
+fn try_from_roundtrip() {
+    //given
+    let controls = Control::all();
+    //when
+    let res = controls
+        .iter()
+        .map(|c| Control::try_from(*c as u32))
+        .collect::<std::result::Result<Vec<_>, _>>();
+    //then
+    assert_eq!(res.unwrap(), controls);
+}
+
+#[test]
+fn try_from_unknown_fail() {
+    //given
+    let id = 38;
+    //when
+    let res = Control::try_from(id);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::UnknownControlError { id: 38 }.to_string()
+    );
+}
@@ -0,0 +1,375 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context, IsQHYCCDControlAvailable_context,
+    SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+fn new_cooler(settings: RampSettings) -> Cooler {
+    let camera = new_camera();
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::Cooler as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    Cooler::with_settings(camera, settings).unwrap()
+}
+
+fn settings() -> RampSettings {
+    RampSettings {
+        step_interval: Duration::from_secs(30),
+        tolerance: 0.5,
+        stable_steps: 2,
+    }
+}
+
+#[test]
+fn new_without_cooler_fail() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let camera = new_camera();
+    //when
+    let res = Cooler::new(camera);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::IsControlAvailableError {
+            control: Control::Cooler
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn with_settings_zero_step_interval_fail() {
+    //given
+    let camera = new_camera();
+    //when
+    let res = Cooler::with_settings(
+        camera,
+        RampSettings {
+            step_interval: Duration::ZERO,
+            ..settings()
+        },
+    );
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::InvalidRampSettingsError {
+            step_interval: Duration::ZERO,
+            stable_steps: 2
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn with_settings_zero_stable_steps_fail() {
+    //given
+    let camera = new_camera();
+    //when
+    let res = Cooler::with_settings(
+        camera,
+        RampSettings {
+            stable_steps: 0,
+            ..settings()
+        },
+    );
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::InvalidRampSettingsError {
+            step_interval: Duration::from_secs(30),
+            stable_steps: 0
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn ramp_to_invalid_rate_fail() {
+    //given
+    let cooler = new_cooler(settings());
+    //when
+    let res = cooler.ramp_to(-10.0, 0.0);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::InvalidRampRateError { rate: 0.0 }.to_string()
+    );
+}
+
+#[test]
+fn ramp_to_steps_and_stabilizes() {
+    //given
+    let cooler = new_cooler(RampSettings {
+        step_interval: Duration::from_millis(1),
+        ..settings()
+    });
+    let mut temperatures = vec![1.0, 0.5, -0.6, -1.8, -2.3, -2.6].into_iter();
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::CurTemp as u32)
+        .times(6)
+        .returning_st(move |_, _| temperatures.next().unwrap());
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::CurPWM as u32)
+        .times(5)
+        .return_const_st(51.0);
+    let set_points = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let recorded = set_points.clone();
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, _| *control == Control::Cooler as u32)
+        .times(5)
+        .returning_st(move |_, _, value| {
+            recorded.borrow_mut().push(value);
+            QHYCCD_SUCCESS
+        });
+    //when
+    let ramp = cooler.ramp_to(-2.5, 60_000.0).unwrap();
+    let events = ramp.collect::<Result<Vec<_>>>().unwrap();
+    //then
+    assert_eq!(*set_points.borrow(), vec![0.0, -1.0, -2.0, -2.5, -2.5]);
+    assert_eq!(events.len(), 5);
+    assert_eq!(
+        events[0],
+        RampEvent::Step {
+            set_point: 0.0,
+            temperature: 0.5
+        }
+    );
+    assert_eq!(events[4], RampEvent::Stabilized { temperature: -2.6 });
+}
+
+#[test]
+fn ramp_stops_on_error() {
+    //given
+    let cooler = new_cooler(settings());
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().times(1).return_const_st(-5.0);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    //when
+    let mut ramp = cooler.ramp_to(-10.0, 2.0).unwrap();
+    let first = ramp.next();
+    //then
+    assert!(matches!(first, Some(Err(_))));
+    assert!(ramp.next().is_none());
+}
+
+#[test]
+fn ramp_ends_when_target_unreachable() {
+    //given
+    let cooler = new_cooler(RampSettings {
+        step_interval: Duration::from_millis(1),
+        ..settings()
+    });
+    let mut temperatures = vec![10.0, 5.0, 0.0, -5.0].into_iter();
+    let mut powers = vec![76.5, 153.0, 229.5].into_iter();
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::CurTemp as u32)
+        .times(4)
+        .returning_st(move |_, _| temperatures.next().unwrap());
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::CurPWM as u32)
+        .times(3)
+        .returning_st(move |_, _| powers.next().unwrap());
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(3).return_const_st(QHYCCD_SUCCESS);
+    //when
+    let ramp = cooler.ramp_to(-30.0, 300_000.0).unwrap();
+    let events = ramp.collect::<Result<Vec<_>>>().unwrap();
+    //then
+    assert_eq!(events.len(), 3);
+    match events[2] {
+        RampEvent::TargetUnreachable {
+            temperature,
+            achievable,
+        } => {
+            assert_eq!(temperature, -5.0);
+            assert!((achievable - -20.0 / 3.0).abs() < 1e-9);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn thermal_model_estimate() {
+    //given
+    let mut model = ThermalModel::new();
+    model.observe(15.0, 20.0);
+    model.observe(10.0, 40.0);
+    //when
+    let too_few = model.estimate();
+    model.observe(5.0, 60.0);
+    let estimate = model.estimate().unwrap();
+    //then
+    assert!(too_few.is_none());
+    assert!((estimate.ambient - 20.0).abs() < 1e-9);
+    assert!((estimate.max_delta - 25.0).abs() < 1e-9);
+    assert!(estimate.is_reachable(-4.0));
+    assert!(!estimate.is_reachable(-6.0));
+}
+
+#[test]
+fn thermal_model_needs_power_variation() {
+    //given
+    let mut model = ThermalModel::new();
+    for temperature in [10.0, 9.0, 8.0] {
+        model.observe(temperature, 50.0);
+    }
+    //when
+    let estimate = model.estimate();
+    //then
+    assert!(estimate.is_none());
+}
+
+#[test]
+fn set_target_temperature_success() {
+    //given
+    let cooler = new_cooler(settings());
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Cooler as u32 && *value == -10.0)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    //when
+    let res = cooler.set_target_temperature(-10.0);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn cooler_status_success() {
+    //given
+    let cooler = new_cooler(settings());
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(3)
+        .returning_st(|_, control| match control {
+            c if c == Control::CurTemp as u32 => -9.5,
+            c if c == Control::CurPWM as u32 => 127.5,
+            c if c == Control::Cooler as u32 => -10.0,
+            _ => panic!("unexpected control"),
+        });
+    //when
+    let status = cooler.cooler_status().unwrap();
+    //then
+    assert_eq!(
+        status,
+        CoolerStatus {
+            current_temp: -9.5,
+            power_percent: 50.0,
+            target: -10.0
+        }
+    );
+}
+
+// the ramp runs on a background thread, so the expectations must not use the `_st` variants
+#[test]
+fn ramp_in_background_stabilizes() {
+    //given
+    let cooler = new_cooler(RampSettings {
+        step_interval: Duration::from_millis(1),
+        ..settings()
+    });
+    let temperatures = Mutex::new(vec![1.0, 0.5, -0.6, -1.8, -2.3, -2.6].into_iter());
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf(|_, control| *control == Control::CurTemp as u32)
+        .times(6)
+        .returning(move |_, _| temperatures.lock().unwrap().next().unwrap());
+    ctx_param
+        .expect()
+        .withf(|_, control| *control == Control::CurPWM as u32)
+        .times(5)
+        .return_const(51.0);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf(|_, control, _| *control == Control::Cooler as u32)
+        .times(5)
+        .return_const(QHYCCD_SUCCESS);
+    //when
+    let ramp = cooler.ramp_in_background(-2.5, 60_000.0).unwrap();
+    let last = ramp.wait().unwrap();
+    //then
+    assert_eq!(last, Some(RampEvent::Stabilized { temperature: -2.6 }));
+}
+
+#[test]
+fn ramp_in_background_stop() {
+    //given
+    let cooler = new_cooler(settings());
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf(|_, control| *control == Control::CurTemp as u32)
+        .times(2)
+        .return_const(5.0);
+    ctx_param
+        .expect()
+        .withf(|_, control| *control == Control::CurPWM as u32)
+        .times(1)
+        .return_const(51.0);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const(QHYCCD_SUCCESS);
+    //when
+    let ramp = cooler.ramp_in_background(-10.0, 2.0).unwrap();
+    let last = ramp.stop().unwrap();
+    //then
+    assert_eq!(
+        last,
+        Some(RampEvent::Step {
+            set_point: 4.0,
+            temperature: 5.0
+        })
+    );
+}
+
+#[test]
+fn ramp_in_background_fail() {
+    //given
+    let cooler = new_cooler(settings());
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().times(1).return_const(-5.0);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const(QHYCCD_ERROR);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().times(1).return_const(QHYCCD_SUCCESS);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().times(1).return_const(QHYCCD_ERROR);
+    //when
+    let ramp = cooler.ramp_in_background(-10.0, 2.0).unwrap();
+    let res = ramp.wait();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::SetParameterError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+/// a raw 16 bit frame of a scene with the same color everywhere
+fn uniform_mosaic(bayer_mode: BayerMode, width: u32, height: u32, bgr: [u16; 3]) -> ImageData {
+    let pattern = match bayer_mode {
+        BayerMode::RGGB => [[2, 1], [1, 0]],
+        BayerMode::BGGR => [[0, 1], [1, 2]],
+        BayerMode::GRBG => [[1, 2], [0, 1]],
+        BayerMode::GBRG => [[1, 0], [2, 1]],
+    };
+    let data = (0..height as usize)
+        .flat_map(|y| (0..width as usize).map(move |x| pattern[y % 2][x % 2]))
+        .flat_map(|color| bgr[color].to_le_bytes())
+        .collect();
+    ImageData {
+        data,
+        width,
+        height,
+        bits_per_pixel: 16,
+        channels: 1,
+    }
+}
+
+fn pixels(image: &ImageData) -> Vec<[u16; 3]> {
+    image
+        .as_u16_slice()
+        .unwrap()
+        .chunks_exact(3)
+        .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect()
+}
+
+#[test]
+fn demosaic_superpixel() {
+    //given
+    let raw = uniform_mosaic(BayerMode::GRBG, 6, 4, [20, 500, 1000]);
+    //when
+    let color = raw
+        .demosaic(BayerMode::GRBG, DemosaicAlgorithm::Superpixel)
+        .unwrap();
+    //then
+    assert_eq!((color.width, color.height, color.channels), (3, 2, 3));
+    assert_eq!(pixels(&color), vec![[20, 500, 1000]; 6]);
+}
+
+#[test]
+fn demosaic_bilinear_uniform_scene() {
+    //given
+    let raw = uniform_mosaic(BayerMode::RGGB, 6, 6, [20, 500, 1000]);
+    //when
+    let color = raw
+        .demosaic(BayerMode::RGGB, DemosaicAlgorithm::Bilinear)
+        .unwrap();
+    //then
+    assert_eq!(
+        (color.width, color.height, color.bits_per_pixel),
+        (6, 6, 16)
+    );
+    assert_eq!(pixels(&color), vec![[20, 500, 1000]; 36]);
+}
+
+#[test]
+fn demosaic_vng_uniform_scene() {
+    //given
+    let raw = uniform_mosaic(BayerMode::GBRG, 7, 5, [60_000, 300, 7]);
+    //when
+    let color = raw
+        .demosaic(BayerMode::GBRG, DemosaicAlgorithm::Vng)
+        .unwrap();
+    //then
+    assert_eq!(pixels(&color), vec![[60_000, 300, 7]; 35]);
+}
+
+#[test]
+fn demosaic_vng_keeps_edge() {
+    //given
+    // the left half of a gray scene is dark, the right half bright
+    let data = (0..8)
+        .flat_map(|_| (0..8).map(|x| if x < 4 { 10_u8 } else { 200 }))
+        .collect();
+    let raw = ImageData {
+        data,
+        width: 8,
+        height: 8,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let bilinear = raw
+        .demosaic(BayerMode::BGGR, DemosaicAlgorithm::Bilinear)
+        .unwrap();
+    let vng = raw
+        .demosaic(BayerMode::BGGR, DemosaicAlgorithm::Vng)
+        .unwrap();
+    //then
+    let error = |image: &ImageData| {
+        image
+            .data
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(i, pixel)| {
+                let expected = if i % 8 < 4 { 10 } else { 200 };
+                pixel
+                    .iter()
+                    .map(|&v| (v as i32 - expected).abs())
+                    .sum::<i32>()
+            })
+            .sum::<i32>()
+    };
+    assert!(error(&vng) < error(&bilinear));
+}
+
+#[test]
+fn demosaic_color_frame() {
+    //given
+    let image = ImageData {
+        data: vec![1, 2, 3],
+        width: 1,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 3,
+    };
+    //when
+    let res = image.demosaic(BayerMode::RGGB, DemosaicAlgorithm::Bilinear);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        ChannelCountMismatchError {
+            expected: 1,
+            actual: 3
+        }
+        .to_string()
+    );
+}
@@ -0,0 +1,86 @@
+use super::*;
+
+#[test]
+fn to_dynamic_image_mono_16_bit() {
+    //given
+    let image = ImageData {
+        data: vec![1, 0, 0, 1],
+        width: 2,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    //when
+    let dynamic = image.to_dynamic_image().unwrap();
+    //then
+    let luma = dynamic.as_luma16().unwrap();
+    assert_eq!(luma.dimensions(), (2, 1));
+    assert_eq!(luma.as_raw(), &vec![1, 256]);
+}
+
+#[test]
+fn to_dynamic_image_swaps_bgr() {
+    //given
+    let image = ImageData {
+        data: vec![1, 2, 3, 4, 5, 6],
+        width: 1,
+        height: 2,
+        bits_per_pixel: 8,
+        channels: 3,
+    };
+    //when
+    let dynamic = image.to_dynamic_image().unwrap();
+    //then
+    assert_eq!(dynamic.as_rgb8().unwrap().as_raw(), &vec![3, 2, 1, 6, 5, 4]);
+}
+
+#[test]
+fn to_dynamic_image_rgba_16_bit() {
+    //given
+    let image = ImageData {
+        data: vec![1, 0, 2, 0, 3, 0, 4, 0],
+        width: 1,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 4,
+    };
+    //when
+    let dynamic = image.to_dynamic_image().unwrap();
+    //then
+    assert_eq!(dynamic.as_rgba16().unwrap().as_raw(), &vec![3, 2, 1, 4]);
+}
+
+#[test]
+fn to_dynamic_image_unsupported_channels() {
+    //given
+    let image = ImageData {
+        data: vec![1, 2],
+        width: 1,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 2,
+    };
+    //when
+    let res = image.to_dynamic_image();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        UnsupportedChannelCountError { channels: 2 }.to_string()
+    );
+}
+
+#[test]
+fn to_dynamic_image_truncated_data() {
+    //given
+    let image = ImageData {
+        data: vec![1, 2],
+        width: 2,
+        height: 2,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let res = image.to_dynamic_image();
+    //then
+    assert!(res.is_err());
+}
@@ -0,0 +1,154 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParamMinMaxStep_context, IsQHYCCDControlAvailable_context, SetQHYCCDParam_context,
+    QHYCCD_DELAY_200MS, QHYCCD_ERROR, QHYCCD_READ_DIRECTLY, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+#[test]
+fn sdk_error_code_roundtrip() {
+    for code in [QHYCCD_ERROR, QHYCCD_READ_DIRECTLY, QHYCCD_DELAY_200MS, 42] {
+        assert_eq!(SdkErrorCode::from(code).code(), code);
+    }
+    assert_eq!(SdkErrorCode::from(QHYCCD_ERROR), SdkErrorCode::Error);
+    assert_eq!(SdkErrorCode::from(42), SdkErrorCode::Unknown(42));
+}
+
+#[test]
+fn error_display_names_code() {
+    //given
+    let error = SetParameterError {
+        error_code: QHYCCD_ERROR,
+    };
+    //when
+    let message = error.to_string();
+    //then
+    assert_eq!(
+        message,
+        "Error setting camera parameter, error code QHYCCD_ERROR, the SDK gives no reason; \
+         check the USB connection and the SDK log"
+    );
+    assert_eq!(
+        SetRoiError { error_code: 42 }.to_string(),
+        "Error setting camera sub frame, error code 42"
+    );
+}
+
+#[test]
+fn error_kind() {
+    assert_eq!(CameraNotOpenError.kind(), ErrorKind::NotOpen);
+    assert_eq!(
+        TriggerTimeoutError {
+            timeout: Duration::from_secs(1)
+        }
+        .kind(),
+        ErrorKind::Timeout
+    );
+    assert_eq!(
+        UnsupportedSdkFeatureError {
+            feature: SdkFeature::SensorName
+        }
+        .kind(),
+        ErrorKind::Unsupported
+    );
+    assert_eq!(CameraNotFoundError { id: None }.kind(), ErrorKind::NotFound);
+    assert_eq!(
+        SetParameterError {
+            error_code: QHYCCD_ERROR
+        }
+        .kind(),
+        ErrorKind::Sdk
+    );
+    assert_eq!(ExposureThreadError.kind(), ErrorKind::Other);
+}
+
+#[test]
+fn set_parameter_unsupported_control() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_parameter(Control::Cooler, 1.0);
+    //then
+    assert!(matches!(
+        res,
+        Err(IsControlAvailableError {
+            control: Control::Cooler
+        })
+    ));
+}
+
+#[test]
+fn set_parameter_out_of_range() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const_st(QHYCCD_ERROR);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .times(1)
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 0.0;
+            *max = 100.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.set_parameter(Control::Gain, 200.0);
+    //then
+    assert!(matches!(
+        res,
+        Err(ParameterOutOfRangeError {
+            control: Control::Gain,
+            max: 100.0,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn set_parameter_within_range_keeps_sdk_code() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_DELAY_200MS);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .times(1)
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 0.0;
+            *max = 100.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.set_parameter(Control::Gain, 50.0);
+    //then
+    match res {
+        Err(SetParameterError { error_code }) => {
+            assert_eq!(SdkErrorCode::from(error_code), SdkErrorCode::Delay200Ms)
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
@@ -0,0 +1,142 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    CancelQHYCCDExposingAndReadout_context, CancelQHYCCDExposing_context,
+    ExpQHYCCDSingleFrame_context, GetQHYCCDExposureRemaining_context, GetQHYCCDMemLength_context,
+    GetQHYCCDSingleFrame_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+// the exposure runs on a background thread, so the expectations must not use the `_st` variants
+fn expect_exposure(frame_result: u32) -> Vec<Box<dyn std::any::Any>> {
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf(|_, control, value| *control == Control::Exposure as u32 && *value == 2_000_000.0)
+        .once()
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(2_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().once().returning(
+        move |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            buffer.copy_from([7_u8, 9].as_ptr(), 2);
+            frame_result
+        },
+    );
+    vec![
+        Box::new(ctx_set),
+        Box::new(ctx_exp),
+        Box::new(ctx_size),
+        Box::new(ctx_frame),
+    ]
+}
+
+#[test]
+fn start_exposure_wait_success() {
+    //given
+    let _exposure = expect_exposure(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let exposure = cam.start_exposure(Duration::from_secs(2)).unwrap();
+    let image = exposure.wait().unwrap();
+    //then
+    assert_eq!(image.data, vec![7, 9]);
+}
+
+#[test]
+fn start_exposure_camera_not_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let res = cam.start_exposure(Duration::from_secs(2));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        CameraNotOpenError.to_string()
+    );
+}
+
+#[test]
+fn start_exposure_start_fail() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.start_exposure(Duration::from_secs(2));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::StartSingleFrameExposureError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+    assert_eq!(cam.state(), CameraState::Open);
+}
+
+#[test]
+fn exposure_progress() {
+    //given
+    let _exposure = expect_exposure(QHYCCD_SUCCESS);
+    let ctx_remaining = GetQHYCCDExposureRemaining_context();
+    ctx_remaining.expect().once().return_const(500_000_u32);
+    let cam = new_camera();
+    let exposure = cam.start_exposure(Duration::from_secs(2)).unwrap();
+    //when
+    let progress = exposure.progress().unwrap();
+    //then
+    assert_eq!(
+        progress,
+        ExposureProgress {
+            remaining: Duration::from_millis(500),
+            fraction: 0.75
+        }
+    );
+    assert_eq!(exposure.exposure(), Duration::from_secs(2));
+    assert!(exposure.wait().is_ok());
+}
+
+#[test]
+fn exposure_cancel_returns_frame() {
+    //given
+    let _exposure = expect_exposure(QHYCCD_SUCCESS);
+    let ctx_cancel = CancelQHYCCDExposing_context();
+    ctx_cancel.expect().once().return_const(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let exposure = cam.start_exposure(Duration::from_secs(2)).unwrap();
+    //when
+    let image = exposure.cancel().unwrap();
+    //then
+    assert_eq!(image.data, vec![7, 9]);
+}
+
+#[test]
+fn exposure_abort_and_discard() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const(QHYCCD_SUCCESS);
+    // the download may already have seen the aborted camera and given up before calling the SDK
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(0..=1).return_const(2_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(0..=1).return_const(QHYCCD_ERROR);
+    let ctx_abort = CancelQHYCCDExposingAndReadout_context();
+    ctx_abort.expect().once().return_const(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let exposure = cam.start_exposure(Duration::from_secs(2)).unwrap();
+    //when
+    let res = exposure.abort_and_discard();
+    //then
+    assert!(res.is_ok());
+}
@@ -0,0 +1,161 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParam_context, IsQHYCCDControlAvailable_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+fn new_filter_wheel() -> FilterWheel {
+    FilterWheel::new(Camera::new("test_camera".to_owned()))
+}
+
+#[test]
+fn set_slot_labels_success() {
+    //given
+    let fw = new_filter_wheel();
+    //when
+    fw.set_slot_labels(["L", "R", "G", "B", "Ha"]);
+    //then
+    assert_eq!(fw.slots().len(), 5);
+    assert_eq!(fw.slot(2).unwrap().name(), "G");
+    assert_eq!(fw.slot(4).unwrap(), FilterSlot::new("Ha"));
+    assert_eq!(fw.slot(5), None);
+    assert_eq!(fw.find_slot("B"), Some(3));
+    assert_eq!(fw.find_slot("OIII"), None);
+}
+
+#[test]
+fn set_slot_fills_gaps() {
+    //given
+    let fw = new_filter_wheel();
+    let ha = FilterSlot {
+        name: "Ha".to_owned(),
+        focus_offset: 35,
+        exposure_factor: 6.0,
+    };
+    //when
+    fw.set_slot(2, ha.clone()).unwrap();
+    //then
+    assert_eq!(
+        fw.slots(),
+        vec![FilterSlot::new(""), FilterSlot::new(""), ha]
+    );
+}
+
+#[test]
+fn set_slot_beyond_max_slots_fail() {
+    //given
+    let fw = new_filter_wheel();
+    //when
+    let res = fw.set_slot(u32::MAX, FilterSlot::new("Ha"));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::FilterSlotOutOfRangeError {
+            index: u32::MAX,
+            count: 32
+        }
+        .to_string()
+    );
+    assert!(fw.slots().is_empty());
+}
+
+#[test]
+fn set_slot_beyond_number_of_filters_fail() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CfwSlotsNum as u32)
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::CfwSlotsNum as u32)
+        .times(2)
+        .return_const_st(7.0);
+    let fw = FilterWheel::new(new_camera());
+    //when
+    let last = fw.set_slot(6, FilterSlot::new("Ha"));
+    let beyond = fw.set_slot(7, FilterSlot::new("OIII"));
+    //then
+    assert!(last.is_ok());
+    assert_eq!(
+        beyond.err().unwrap().to_string(),
+        QHYError::FilterSlotOutOfRangeError { index: 7, count: 7 }.to_string()
+    );
+}
+
+#[test]
+fn slots_are_shared_between_clones() {
+    //given
+    let fw = new_filter_wheel();
+    let clone = fw.clone();
+    //when
+    fw.set_slot_labels(["L"]);
+    //then
+    assert_eq!(clone.find_slot("L"), Some(0));
+}
+
+#[test]
+fn save_and_load_slots() {
+    //given
+    let fw = new_filter_wheel();
+    fw.set_slot_labels(["L", "R"]);
+    fw.set_slot(
+        2,
+        FilterSlot {
+            name: "Ha 7nm".to_owned(),
+            focus_offset: -12,
+            exposure_factor: 0.1,
+        },
+    )
+    .unwrap();
+    let path = std::env::temp_dir().join(format!("filter_slots_{}.tsv", std::process::id()));
+    let other = new_filter_wheel();
+    //when
+    fw.save_slots(&path).unwrap();
+    let res = other.load_slots(&path);
+    std::fs::remove_file(&path).unwrap();
+    //then
+    assert!(res.is_ok());
+    assert_eq!(other.slots(), fw.slots());
+}
+
+#[test]
+fn save_and_load_slots_with_special_characters() {
+    //given
+    let fw = new_filter_wheel();
+    fw.set_slot_labels(["tab\there", "two\nlines", "back\\slash\\t", "\r"]);
+    let path =
+        std::env::temp_dir().join(format!("filter_slots_escaped_{}.tsv", std::process::id()));
+    let other = new_filter_wheel();
+    //when
+    fw.save_slots(&path).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    let res = other.load_slots(&path);
+    std::fs::remove_file(&path).unwrap();
+    //then
+    assert_eq!(content.lines().count(), 4);
+    assert!(res.is_ok());
+    assert_eq!(other.slots(), fw.slots());
+}
+
+#[test]
+fn load_slots_invalid_line() {
+    //given
+    let fw = new_filter_wheel();
+    fw.set_slot_labels(["L"]);
+    let path =
+        std::env::temp_dir().join(format!("filter_slots_invalid_{}.tsv", std::process::id()));
+    std::fs::write(&path, "R\t0\t1.0\nG\t0\t1,5\n").unwrap();
+    //when
+    let res = fw.load_slots(&path);
+    std::fs::remove_file(&path).unwrap();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        InvalidSlotConfigError { line: 2 }.to_string()
+    );
+    assert_eq!(fw.slots(), vec![FilterSlot::new("L")]);
+}
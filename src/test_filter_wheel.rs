@@ -1,17 +1,13 @@
 use super::*;
 use crate::mocks::mock_libqhyccd_sys::{
-    CloseQHYCCD_context, GetQHYCCDParam_context, IsQHYCCDCFWPlugged_context,
-    IsQHYCCDControlAvailable_context, OpenQHYCCD_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
+    CloseQHYCCD_context, GetQHYCCDCFWStatus_context, GetQHYCCDParamMinMaxStep_context,
+    GetQHYCCDParam_context, IsQHYCCDCFWPlugged_context, IsQHYCCDControlAvailable_context,
+    OpenQHYCCD_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
 };
-
-const TEST_HANDLE: *const std::ffi::c_void = 0xdeadbeef as *const std::ffi::c_void;
+use crate::mocks::{new_camera, TEST_HANDLE};
 
 fn new_filter_wheel() -> FilterWheel {
-    let ctx_open = OpenQHYCCD_context();
-    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
-    let camera = Camera::new("test_camera".to_owned());
-    camera.open().unwrap();
-    FilterWheel::new(camera)
+    FilterWheel::new(new_camera())
 }
 
 #[test]
@@ -20,6 +16,7 @@ fn open_success() {
     let fw = new_filter_wheel();
     //when
     let res = fw.open();
+    crate::mocks::keep_open(&fw);
     //then
     assert!(res.is_ok());
     assert_eq!(fw.id(), "test_camera");
@@ -34,6 +31,7 @@ fn open_fail() {
     let fw = FilterWheel::new(camera);
     //when
     let res = fw.open();
+    crate::mocks::keep_open(&fw);
     //then
     assert!(res.is_err());
 }
@@ -70,6 +68,7 @@ fn close_success() {
     let fw = FilterWheel::new(camera);
     //when
     let res = fw.open();
+    crate::mocks::keep_open(&fw);
     assert!(res.is_ok());
 
     let res = fw.close();
@@ -287,7 +286,7 @@ fn set_fw_position_fail_set_parameter() {
     ctx_available
         .expect()
         .withf_st(|handle, control| *handle == TEST_HANDLE && *control == Control::CfwPort as u32)
-        .times(1)
+        .times(2)
         .return_const_st(QHYCCD_SUCCESS);
     let ctx_num = SetQHYCCDParam_context();
     ctx_num
@@ -298,9 +297,211 @@ fn set_fw_position_fail_set_parameter() {
         })
         .once()
         .return_const_st(QHYCCD_ERROR);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().times(1).return_const_st(QHYCCD_ERROR);
     let fw = new_filter_wheel();
     //when
     let res = fw.set_fw_position(5);
     //then
     assert!(res.is_err());
 }
+
+#[test]
+fn home_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::CfwPort as u32 && *value == 48.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status.expect().times(2).returning_st({
+        let reported = std::cell::RefCell::new(vec![b'0', b'N']);
+        move |_, status| unsafe {
+            *status = reported.borrow_mut().pop().unwrap() as c_char;
+            QHYCCD_SUCCESS
+        }
+    });
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().once().return_const_st(48.0);
+    let fw = new_filter_wheel();
+    //when
+    let res = fw.home(Duration::from_secs(5));
+    //then
+    assert!(res.is_ok());
+    assert!(fw.is_homed());
+}
+
+#[test]
+fn home_timeout() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().return_const_st(51.0);
+    let fw = new_filter_wheel();
+    //when
+    let res = fw.home(Duration::from_millis(10));
+    //then
+    assert!(!fw.is_homed());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        FilterWheelHomeError {
+            timeout: Duration::from_millis(10)
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn save_and_check_position() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(3)
+        .return_const_st(QHYCCD_SUCCESS);
+    let positions = std::cell::RefCell::new(vec![50.0, 52.0, 52.0]);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get
+        .expect()
+        .times(3)
+        .returning_st(move |_, _| positions.borrow_mut().pop().unwrap());
+    let path = std::env::temp_dir().join(format!("fw_position_{}.pos", std::process::id()));
+    let fw = new_filter_wheel();
+    //when
+    let saved = fw.save_position(&path).unwrap();
+    let matches = fw.check_saved_position(&path).unwrap();
+    let moved = fw.check_saved_position(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    //then
+    assert_eq!(saved, 4);
+    assert_eq!(matches, SavedPosition::Matches(4));
+    assert_eq!(
+        moved,
+        SavedPosition::Moved {
+            saved: 4,
+            actual: 2
+        }
+    );
+}
+
+#[test]
+fn check_saved_position_unknown() {
+    //given
+    let other = std::env::temp_dir().join(format!("fw_position_other_{}.pos", std::process::id()));
+    std::fs::write(&other, "other_camera\t3\n").unwrap();
+    let missing = std::env::temp_dir().join("fw_position_missing.pos");
+    let fw = new_filter_wheel();
+    //when
+    let other_wheel = fw.check_saved_position(&other);
+    let no_file = fw.check_saved_position(&missing);
+    std::fs::remove_file(&other).unwrap();
+    //then
+    assert_eq!(other_wheel.unwrap(), SavedPosition::Unknown);
+    assert_eq!(no_file.unwrap(), SavedPosition::Unknown);
+}
+
+#[test]
+fn status_success() {
+    //given
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status
+        .expect()
+        .withf_st(|handle, _| *handle == TEST_HANDLE)
+        .times(3)
+        .returning_st({
+            let reported = std::cell::RefCell::new(vec![0_u8, b'N', b'2']);
+            move |_, status| unsafe {
+                *status = reported.borrow_mut().pop().unwrap() as c_char;
+                QHYCCD_SUCCESS
+            }
+        });
+    let fw = new_filter_wheel();
+    //when
+    let idle = fw.status().unwrap();
+    let moving = fw.status().unwrap();
+    let error = fw.status().unwrap();
+    //then
+    assert_eq!(idle, FilterWheelStatus::Idle);
+    assert_eq!(moving, FilterWheelStatus::Moving);
+    assert_eq!(error, FilterWheelStatus::Error);
+}
+
+#[test]
+fn status_fail() {
+    //given
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status.expect().once().return_const_st(QHYCCD_ERROR);
+    let fw = new_filter_wheel();
+    //when
+    let res = fw.status();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        GetCfwStatusError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn move_to_position_waits_until_settled() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, _, value| *value == 51.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status.expect().times(2).returning_st({
+        let reported = std::cell::RefCell::new(vec![b'3', b'N']);
+        move |_, status| unsafe {
+            *status = reported.borrow_mut().pop().unwrap() as c_char;
+            QHYCCD_SUCCESS
+        }
+    });
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().once().return_const_st(51.0);
+    let fw = new_filter_wheel();
+    //when
+    let res = fw.move_to_position(3, Duration::from_secs(5));
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn move_to_position_timeout() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().return_const_st(48.0);
+    let fw = new_filter_wheel();
+    //when
+    let res = fw.move_to_position(3, Duration::from_millis(10));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        FilterWheelMoveTimeoutError {
+            position: 3,
+            timeout: Duration::from_millis(10)
+        }
+        .to_string()
+    );
+}
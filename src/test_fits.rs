@@ -0,0 +1,150 @@
+use super::*;
+use crate::fits::{encode_fits, HeaderValue};
+
+fn header(buffer: &[u8]) -> Vec<String> {
+    buffer[..2880]
+        .chunks(80)
+        .map(|card| String::from_utf8(card.to_vec()).unwrap())
+        .take_while(|card| !card.starts_with("END"))
+        .collect()
+}
+
+#[test]
+fn encode_fits_8bit() {
+    //given
+    let image = ImageData {
+        data: vec![0, 1, 2, 255],
+        width: 2,
+        height: 2,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let buffer = encode_fits(&image, &[]).unwrap();
+    //then
+    assert_eq!(buffer.len(), 2 * 2880);
+    let header = header(&buffer);
+    assert_eq!(
+        header[0],
+        format!(
+            "{:<80}",
+            "SIMPLE  =                    T / conforms to FITS standard"
+        )
+    );
+    assert!(header[1].starts_with("BITPIX  =                    8"));
+    assert!(header[2].starts_with("NAXIS   =                    2"));
+    assert!(header[3].starts_with("NAXIS1  =                    2"));
+    assert!(header[4].starts_with("NAXIS2  =                    2"));
+    assert_eq!(header.len(), 5);
+    assert_eq!(&buffer[2880..2884], &[0, 1, 2, 255]);
+    assert!(buffer[2884..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn encode_fits_16bit_with_cards() {
+    //given
+    let image = ImageData {
+        data: vec![0x00, 0x00, 0xff, 0xff, 0x00, 0x80],
+        width: 3,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    //when
+    let buffer = encode_fits(
+        &image,
+        &[
+            (
+                "EXPTIME",
+                HeaderValue::Float(2.0),
+                "exposure time in seconds",
+            ),
+            ("INSTRUME", HeaderValue::Str("QHY178M".to_owned()), ""),
+        ],
+    )
+    .unwrap();
+    //then
+    let header = header(&buffer);
+    assert!(header[1].starts_with("BITPIX  =                   16"));
+    assert!(header[5].starts_with("BZERO   =                32768"));
+    assert!(header[6].starts_with("BSCALE  =                    1"));
+    assert!(header[7].starts_with("EXPTIME =                  2.0 / exposure time in seconds"));
+    assert_eq!(header[8].trim_end(), "INSTRUME= 'QHY178M '");
+    assert_eq!(&buffer[2880..2886], &[0x80, 0x00, 0x7f, 0xff, 0x00, 0x00]);
+}
+
+#[test]
+fn encode_fits_color_is_planar() {
+    //given
+    let image = ImageData {
+        data: vec![1, 2, 3, 4, 5, 6],
+        width: 2,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 3,
+    };
+    //when
+    let buffer = encode_fits(&image, &[]).unwrap();
+    //then
+    let header = header(&buffer);
+    assert!(header[2].starts_with("NAXIS   =                    3"));
+    assert!(header[5].starts_with("NAXIS3  =                    3"));
+    assert_eq!(&buffer[2880..2886], &[1, 4, 2, 5, 3, 6]);
+}
+
+#[test]
+fn encode_fits_unsupported_bit_depth() {
+    //given
+    let image = ImageData {
+        data: vec![],
+        width: 0,
+        height: 0,
+        bits_per_pixel: 0,
+        channels: 1,
+    };
+    //when
+    let res = encode_fits(&image, &[]);
+    //then
+    assert!(res.is_err());
+}
+
+#[cfg(feature = "fits")]
+#[test]
+fn write_fits_with_metadata() {
+    //given
+    let image = ImageData {
+        data: vec![0, 0],
+        width: 1,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    let metadata = FrameMetadata {
+        instrument: Some("QHY268C".to_owned()),
+        exposure: Some(Duration::from_secs(300)),
+        gain: Some(26.0),
+        offset: None,
+        ccd_temperature: Some(-10.0),
+        binning: Some((2, 2)),
+        bayer_mode: Some(BayerMode::RGGB),
+        ..Default::default()
+    };
+    let path = std::env::temp_dir().join(format!("metadata_{}.fits", std::process::id()));
+    //when
+    image.write_fits(&path, &metadata).unwrap();
+    //then
+    let file = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let header = header(&file);
+    let keys = header
+        .iter()
+        .skip(7)
+        .map(|card| card[..8].trim_end())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        vec!["INSTRUME", "EXPTIME", "GAIN", "CCD-TEMP", "XBINNING", "YBINNING", "BAYERPAT"]
+    );
+    assert!(header[7].starts_with("INSTRUME= 'QHY268C '"));
+    assert!(header[13].starts_with("BAYERPAT= 'RGGB    '"));
+}
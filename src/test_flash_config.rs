@@ -0,0 +1,124 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    IsQHYCCDControlAvailable_context, QHYCCDReadInitConfigFlash_context,
+    QHYCCDSetFlashInitPWM_context, QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+fn config_block() -> [u8; FLASH_CONFIG_LEN] {
+    let mut raw = [0u8; FLASH_CONFIG_LEN];
+    raw[0..3].copy_from_slice(b"cfg");
+    raw[8..11].copy_from_slice(b"err");
+    raw[15] = 1;
+    raw[16..19].copy_from_slice(b"pwm");
+    raw[19] = b'+';
+    raw[23] = 128;
+    raw
+}
+
+#[test]
+fn decode_fields() {
+    //given
+    let config = FlashConfig::from_bytes(config_block());
+    let erased = FlashConfig::from_bytes([0xff; FLASH_CONFIG_LEN]);
+    //when
+    //then
+    assert!(config.is_initialized());
+    assert_eq!(config.init_pwm(), Some(128));
+    assert_eq!(config.ulvo_error(), Some(true));
+    assert_eq!(config.as_bytes(), &config_block());
+    assert!(!erased.is_initialized());
+    assert_eq!(erased.init_pwm(), None);
+    assert_eq!(erased.ulvo_error(), None);
+}
+
+#[test]
+fn read_flash_config_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf(|_, control| *control == Control::CamInitConfigFromFlash as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_read = QHYCCDReadInitConfigFlash_context();
+    ctx_read
+        .expect()
+        .times(1)
+        .returning_st(|_handle, config| unsafe {
+            config.copy_from(config_block().as_ptr(), FLASH_CONFIG_LEN)
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.read_flash_config();
+    //then
+    assert_eq!(res.unwrap().init_pwm(), Some(128));
+}
+
+#[test]
+fn read_flash_config_unsupported() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.read_flash_config();
+    //then
+    assert!(matches!(
+        res,
+        Err(IsControlAvailableError {
+            control: Control::CamInitConfigFromFlash
+        })
+    ));
+}
+
+#[test]
+fn set_flash_init_pwm_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_read = QHYCCDReadInitConfigFlash_context();
+    ctx_read
+        .expect()
+        .times(1)
+        .returning_st(|_handle, config| unsafe {
+            config.copy_from(config_block().as_ptr(), FLASH_CONFIG_LEN)
+        });
+    let ctx_pwm = QHYCCDSetFlashInitPWM_context();
+    ctx_pwm
+        .expect()
+        .withf(|_, pwm| *pwm == 42)
+        .times(1)
+        .return_const_st(());
+    let cam = new_camera();
+    //when
+    let res = cam.set_flash_init_pwm(42);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_flash_init_pwm_without_section() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_read = QHYCCDReadInitConfigFlash_context();
+    ctx_read.expect().times(1).return_const_st(());
+    let cam = new_camera();
+    //when
+    let res = cam.set_flash_init_pwm(42);
+    //then
+    assert!(matches!(
+        res,
+        Err(FlashConfigSectionError { section: "pwm" })
+    ));
+}
@@ -0,0 +1,88 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParam_context, IsQHYCCDControlAvailable_context, SetQHYCCDParam_context, QHYCCD_ERROR,
+    QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+#[test]
+fn calibrate_fpn_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf(|_, control| *control == Control::CamCalibrateFpnInterface as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf(|_, control, value| {
+            *control == Control::CamCalibrateFpnInterface as u32 && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let mut polls = 0;
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().times(2).returning_st(move |_, _| {
+        polls += 1;
+        match polls {
+            1 => 1.0,
+            _ => 0.0,
+        }
+    });
+    let cam = new_camera();
+    let mut statuses = Vec::new();
+    //when
+    let res = cam.calibrate_fpn(Duration::from_secs(10), |status| statuses.push(status));
+    //then
+    assert!(res.is_ok());
+    assert_eq!(statuses.len(), 3);
+    assert_eq!(statuses[0], FpnCalibrationStatus::Started);
+    assert!(matches!(statuses[1], FpnCalibrationStatus::Running { .. }));
+    assert!(matches!(statuses[2], FpnCalibrationStatus::Finished { .. }));
+}
+
+#[test]
+fn calibrate_fpn_timeout() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().times(1).return_const_st(1.0);
+    let cam = new_camera();
+    //when
+    let res = cam.calibrate_fpn(Duration::ZERO, |_| {});
+    //then
+    assert!(matches!(
+        res,
+        Err(FpnCalibrationTimeoutError {
+            timeout: Duration::ZERO
+        })
+    ));
+}
+
+#[test]
+fn calibrate_fpn_unsupported() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.calibrate_fpn(Duration::from_secs(10), |_| {});
+    //then
+    assert!(matches!(
+        res,
+        Err(IsControlAvailableError {
+            control: Control::CamCalibrateFpnInterface
+        })
+    ));
+}
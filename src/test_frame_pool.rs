@@ -0,0 +1,94 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDLiveFrame_context, GetQHYCCDMemLength_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+fn expect_frames(frames: Vec<u32>) -> Vec<Box<dyn std::any::Any>> {
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().return_const_st(2_u32);
+    let ctx_frame = GetQHYCCDLiveFrame_context();
+    ctx_frame.expect().returning_st({
+        let frames = std::cell::RefCell::new(frames);
+        move |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            buffer.copy_from([5_u8, 6].as_ptr(), 2);
+            frames.borrow_mut().pop().unwrap()
+        }
+    });
+    vec![Box::new(ctx_size), Box::new(ctx_frame)]
+}
+
+#[test]
+fn get_live_frame_pooled_recycles_buffer() {
+    //given
+    let _frames = expect_frames(vec![QHYCCD_SUCCESS, QHYCCD_SUCCESS]);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let pool = FramePool::new(2);
+    //when
+    let frame = cam.get_live_frame_pooled(&pool).unwrap();
+    let first_buffer = frame.data.as_ptr();
+    assert_eq!(pool.available(), 0);
+    drop(frame);
+    let available = pool.available();
+    let frame = cam.get_live_frame_pooled(&pool).unwrap();
+    //then
+    assert_eq!(available, 1);
+    assert_eq!(frame.data.as_ptr(), first_buffer);
+    assert_eq!(frame.data, vec![5, 6]);
+}
+
+#[test]
+fn get_live_frame_pooled_returns_buffer_on_error() {
+    //given
+    let _frames = expect_frames(vec![QHYCCD_ERROR]);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let pool = FramePool::new(2);
+    //when
+    let res = cam.get_live_frame_pooled(&pool);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        GetLiveFrameError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+    assert_eq!(pool.available(), 1);
+}
+
+#[test]
+fn pooled_frame_into_image_detaches() {
+    //given
+    let _frames = expect_frames(vec![QHYCCD_SUCCESS]);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let pool = FramePool::new(2);
+    let frame = cam.get_live_frame_pooled(&pool).unwrap();
+    //when
+    let image = frame.into_image();
+    //then
+    assert_eq!(image.data, vec![5, 6]);
+    assert_eq!(pool.available(), 0);
+}
+
+#[test]
+fn frame_pool_limits_buffers() {
+    //given
+    let _frames = expect_frames(vec![QHYCCD_SUCCESS; 3]);
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let pool = FramePool::new(1);
+    //when
+    let frames = (0..3)
+        .map(|_| cam.get_live_frame_pooled(&pool).unwrap())
+        .collect::<Vec<_>>();
+    drop(frames);
+    //then
+    assert_eq!(pool.available(), 1);
+}
@@ -0,0 +1,176 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    IsQHYCCDControlAvailable_context, SetQHYCCDGPSLedCal_context, SetQHYCCDGPSPOSA_context,
+    SetQHYCCDGPSVCOXFreq_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+use std::time::SystemTime;
+
+fn gps_frame() -> ImageData {
+    let mut data = vec![0_u8; 64];
+    data[0..4].copy_from_slice(&7_u32.to_be_bytes());
+    data[5..7].copy_from_slice(&1920_u16.to_be_bytes());
+    data[7..9].copy_from_slice(&1200_u16.to_be_bytes());
+    data[9..13].copy_from_slice(&523_000_000_u32.to_be_bytes());
+    data[13..17].copy_from_slice(&1_013_240_000_u32.to_be_bytes());
+    data[17] = 1;
+    data[18..22].copy_from_slice(&1_000_u32.to_be_bytes());
+    data[22..25].copy_from_slice(&5_000_000_u32.to_be_bytes()[1..]);
+    data[25] = 2;
+    data[26..30].copy_from_slice(&1_002_u32.to_be_bytes());
+    data[30..33].copy_from_slice(&5_000_000_u32.to_be_bytes()[1..]);
+    data[33] = 3;
+    data[34..38].copy_from_slice(&1_003_u32.to_be_bytes());
+    data[41..44].copy_from_slice(&10_000_000_u32.to_be_bytes()[1..]);
+    ImageData {
+        data,
+        width: 8,
+        height: 8,
+        bits_per_pixel: 8,
+        channels: 1,
+    }
+}
+
+#[test]
+fn gps_info_success() {
+    //given
+    let image = gps_frame();
+    //when
+    let gps = image.gps_info().unwrap();
+    //then
+    let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(813_283_200);
+    assert_eq!(gps.sequence, 7);
+    assert_eq!((gps.width, gps.height), (1920, 1200));
+    assert!((gps.latitude - 52.5).abs() < 1e-9);
+    assert!((gps.longitude + 13.4).abs() < 1e-9);
+    assert_eq!((gps.start_flag, gps.end_flag, gps.now_flag), (1, 2, 3));
+    assert_eq!(gps.start_time, epoch + Duration::from_millis(1_000_500));
+    assert_eq!(gps.end_time, epoch + Duration::from_millis(1_002_500));
+    assert_eq!(gps.now_time, epoch + Duration::from_secs(1_003));
+    assert_eq!(gps.exposure(), Duration::from_secs(2));
+    assert_eq!(gps.pps_counter, 10_000_000);
+}
+
+#[test]
+fn gps_info_frame_too_short() {
+    //given
+    let image = ImageData {
+        data: vec![0; 10],
+        width: 10,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let res = image.gps_info();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        GpsHeaderError { len: 10 }.to_string()
+    );
+}
+
+#[test]
+fn set_gps_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CamGps as u32)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::CamGps as u32 && *value == 1.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_gps(true);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_gps_not_available() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_gps(true);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        IsControlAvailableError {
+            control: Control::CamGps
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn set_gps_pos_a_success() {
+    //given
+    let ctx_pos = SetQHYCCDGPSPOSA_context();
+    ctx_pos
+        .expect()
+        .withf_st(|handle, is_slave, pos, width| {
+            *handle == TEST_HANDLE && *is_slave == 1 && *pos == 1000 && *width == 50
+        })
+        .once()
+        .return_const_st(());
+    let cam = new_camera();
+    //when
+    let res = cam.set_gps_pos_a(true, 1000, 50);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_gps_led_calibration_success() {
+    //given
+    let ctx_cal = SetQHYCCDGPSLedCal_context();
+    ctx_cal
+        .expect()
+        .withf_st(|handle, pos, width| *handle == TEST_HANDLE && *pos == 500 && *width == 20)
+        .once()
+        .return_const_st(());
+    let cam = new_camera();
+    //when
+    let res = cam.set_gps_led_calibration(500, 20);
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_gps_vcox_frequency_fail() {
+    //given
+    let ctx_freq = SetQHYCCDGPSVCOXFreq_context();
+    ctx_freq.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_gps_vcox_frequency(2000);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        SetGpsError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn set_gps_led_calibration_mode_camera_not_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let res = cam.set_gps_led_calibration_mode(true);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        CameraNotOpenError.to_string()
+    );
+}
@@ -0,0 +1,118 @@
+use super::*;
+
+/// a 4x4 dark frame with hot pixels at (1, 1) and (3, 2)
+fn dark_frame() -> ImageData {
+    ImageData {
+        data: vec![
+            10, 11, 9, 10, //
+            10, 240, 10, 11, //
+            9, 10, 11, 200, //
+            10, 10, 9, 10,
+        ],
+        width: 4,
+        height: 4,
+        bits_per_pixel: 8,
+        channels: 1,
+    }
+}
+
+#[test]
+fn detect_from_dark_success() {
+    //given
+    let dark = dark_frame();
+    //when
+    let map = HotPixelMap::detect_from_dark(&dark, 1.5).unwrap();
+    //then
+    assert_eq!(map.pixels(), &[(1, 1), (3, 2)]);
+    assert_eq!(map.len(), 2);
+    assert!(map.contains(3, 2));
+    assert!(!map.contains(2, 3));
+}
+
+#[test]
+fn detect_from_dark_high_sigma() {
+    //given
+    let dark = dark_frame();
+    //when
+    let map = HotPixelMap::detect_from_dark(&dark, 10.0).unwrap();
+    //then
+    assert!(map.is_empty());
+}
+
+#[test]
+fn remove_hot_pixels_success() {
+    //given
+    let map = HotPixelMap::detect_from_dark(&dark_frame(), 1.5).unwrap();
+    let light = ImageData {
+        data: [
+            100_u16, 101, 102, 103, //
+            100, 4000, 102, 103, //
+            104, 105, 106, 65535, //
+            100, 101, 102, 103,
+        ]
+        .iter()
+        .flat_map(|sample| sample.to_le_bytes())
+        .collect(),
+        width: 4,
+        height: 4,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    //when
+    let corrected = light.remove_hot_pixels(&map).unwrap();
+    //then
+    let samples = corrected.as_u16_slice().unwrap();
+    // the neighbors of (1, 1) are 100, 101, 102, 100, 102, 104, 105, 106
+    assert_eq!(samples[5], 102);
+    // the neighbors of (3, 2) are 102, 103, 106, 102, 103
+    assert_eq!(samples[11], 103);
+    assert_eq!(samples[0], 100);
+}
+
+#[test]
+fn remove_hot_pixels_color_frame() {
+    //given
+    let map = HotPixelMap::detect_from_dark(
+        &ImageData {
+            data: vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 0, 0],
+            width: 2,
+            height: 2,
+            bits_per_pixel: 8,
+            channels: 3,
+        },
+        1.0,
+    )
+    .unwrap();
+    let light = ImageData {
+        data: vec![10, 20, 30, 10, 20, 30, 10, 20, 30, 99, 99, 99],
+        width: 2,
+        height: 2,
+        bits_per_pixel: 8,
+        channels: 3,
+    };
+    //when
+    let corrected = light.remove_hot_pixels(&map).unwrap();
+    //then
+    assert_eq!(map.pixels(), &[(1, 1)]);
+    assert_eq!(&corrected.data[9..], &[10, 20, 30]);
+}
+
+#[test]
+fn remove_hot_pixels_geometry_mismatch() {
+    //given
+    let map = HotPixelMap::detect_from_dark(&dark_frame(), 1.5).unwrap();
+    let light = ImageData {
+        data: vec![0; 4],
+        width: 2,
+        height: 2,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let res = light.remove_hot_pixels(&map);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        ImageGeometryMismatchError.to_string()
+    );
+}
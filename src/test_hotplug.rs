@@ -0,0 +1,104 @@
+use std::ffi::CString;
+
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    InitQHYCCDResource_context, RegisterPnpEventIn_context, RegisterPnpEventOut_context,
+    ReleaseQHYCCDResource_context, QHYCCD_SUCCESS,
+};
+use crate::test_sdk::sdk_lock;
+
+fn new_sdk() -> Sdk {
+    Sdk {
+        cameras: Vec::new(),
+        filter_wheels: Vec::new(),
+        resources: SdkResources::acquire().unwrap(),
+    }
+}
+
+fn received(events: &std::sync::mpsc::Receiver<HotplugEvent>, expected: &HotplugEvent) -> bool {
+    // other tests dispatch events in parallel
+    events.try_iter().any(|event| event == *expected)
+}
+
+#[test]
+fn subscribe_hotplug_registers_callbacks_once() {
+    //given
+    let _lock = sdk_lock();
+    let ctx_init = InitQHYCCDResource_context();
+    ctx_init.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let sdk = new_sdk();
+    let ctx_in = RegisterPnpEventIn_context();
+    ctx_in.expect().times(1).return_const_st(());
+    let ctx_out = RegisterPnpEventOut_context();
+    ctx_out.expect().times(1).return_const_st(());
+    //when
+    let _first = sdk.subscribe_hotplug();
+    let _second = sdk.subscribe_hotplug();
+    //then
+    ctx_in.checkpoint();
+    ctx_out.checkpoint();
+}
+
+#[test]
+fn hotplug_events_reach_all_subscribers() {
+    //given
+    let first = hotplug::SUBSCRIBERS.subscribe();
+    let second = hotplug::SUBSCRIBERS.subscribe();
+    let id = CString::new("QHY600M-hotplug-attach").unwrap();
+    //when
+    hotplug::on_attached(id.as_ptr() as *mut c_char);
+    //then
+    let expected = HotplugEvent::Attached("QHY600M-hotplug-attach".to_owned());
+    assert!(received(&first, &expected));
+    assert!(received(&second, &expected));
+}
+
+#[test]
+fn hotplug_detached_event() {
+    //given
+    let events = hotplug::SUBSCRIBERS.subscribe();
+    let id = CString::new("QHY600M-hotplug-detach").unwrap();
+    //when
+    hotplug::on_detached(id.as_ptr() as *mut c_char);
+    //then
+    assert!(received(
+        &events,
+        &HotplugEvent::Detached("QHY600M-hotplug-detach".to_owned())
+    ));
+}
+
+#[test]
+fn hotplug_forgets_dropped_subscribers() {
+    //given
+    let subscribers = hotplug::Subscribers::new();
+    let dropped = subscribers.subscribe();
+    let events = subscribers.subscribe();
+    drop(dropped);
+    //when
+    subscribers.dispatch(HotplugEvent::Attached("QHY600M".to_owned()));
+    subscribers.dispatch(HotplugEvent::Detached("QHY600M".to_owned()));
+    //then
+    assert_eq!(
+        events.try_iter().collect::<Vec<_>>(),
+        vec![
+            HotplugEvent::Attached("QHY600M".to_owned()),
+            HotplugEvent::Detached("QHY600M".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn hotplug_ignores_null_and_invalid_ids() {
+    //given
+    let events = hotplug::SUBSCRIBERS.subscribe();
+    let invalid = b"\xc3\x28\0";
+    //when
+    hotplug::on_attached(std::ptr::null_mut());
+    hotplug::on_detached(invalid.as_ptr() as *mut c_char);
+    //then
+    assert!(!events.try_iter().any(|event| match event {
+        HotplugEvent::Attached(id) | HotplugEvent::Detached(id) => !id.starts_with("QHY"),
+    }));
+}
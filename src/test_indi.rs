@@ -0,0 +1,569 @@
+use std::time::Instant;
+
+use super::*;
+use crate::indi::{base64, parse_element, IndiSession};
+use crate::mocks::mock_libqhyccd_sys::{
+    CloseQHYCCD_context, ExpQHYCCDSingleFrame_context, GetQHYCCDCFWStatus_context,
+    GetQHYCCDEffectiveArea_context, GetQHYCCDMemLength_context, GetQHYCCDModel_context,
+    GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context, GetQHYCCDPreciseExposureInfo_context,
+    GetQHYCCDReadMode_context, GetQHYCCDSingleFrame_context, InitQHYCCD_context,
+    IsQHYCCDControlAvailable_context, OpenQHYCCD_context, SetQHYCCDParam_context,
+    SetQHYCCDStreamMode_context, QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+const DEVICE: &str = "QHY CCD test_camera";
+
+/// feeds `messages` to a new session and returns everything it answered
+fn exchange(driver: IndiDriver, messages: &[&str]) -> String {
+    let mut output = Vec::new();
+    let mut session = IndiSession::new(driver, &mut output);
+    for message in messages {
+        session.receive(message.as_bytes()).unwrap();
+    }
+    drop(session);
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn parse_element_complete() {
+    //given
+    let input = br#"<?xml version="1.0"?>
+        <newNumberVector device='cam' name="CCD_FRAME">
+          <oneNumber name="X">10</oneNumber><!-- comment -->
+          <oneNumber name="Y">&lt;20&#x3e;</oneNumber>
+        </newNumberVector>trailing"#;
+    //when
+    let (element, len) = parse_element(input).unwrap().unwrap();
+    //then
+    assert_eq!(&input[len..], b"trailing");
+    assert_eq!(element.name, "newNumberVector");
+    assert_eq!(element.attribute("device"), Some("cam"));
+    assert_eq!(element.attribute("name"), Some("CCD_FRAME"));
+    assert_eq!(element.children.len(), 2);
+    assert_eq!(element.children[0].text, "10");
+    assert_eq!(element.children[1].text, "<20>");
+}
+
+#[test]
+fn parse_element_incomplete() {
+    for input in [
+        "",
+        "  <?xml version",
+        "<getProperties version=\"1.7\"",
+        "<getProperties version=\"1.7\"/",
+        "<enableBLOB device=\"cam\">Also</enab",
+    ] {
+        assert!(
+            parse_element(input.as_bytes()).unwrap().is_none(),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn parse_element_malformed() {
+    for input in ["<a>text</b>", "<a x=1/>", "text<a/>"] {
+        assert!(
+            matches!(
+                parse_element(input.as_bytes()),
+                Err(IndiProtocolError { .. })
+            ),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn parse_element_too_deep() {
+    //given
+    let nested = |depth: usize| format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth));
+    //when
+    let allowed = parse_element(nested(17).as_bytes());
+    let too_deep = parse_element(nested(18).as_bytes());
+    //then
+    assert!(matches!(allowed, Ok(Some(_))));
+    assert!(matches!(too_deep, Err(IndiProtocolError { .. })));
+}
+
+#[test]
+fn parse_element_too_long() {
+    //given
+    let unterminated = format!("<a>{}", "x".repeat(64 * 1024));
+    //when
+    let result = parse_element(unterminated.as_bytes());
+    //then
+    assert!(matches!(result, Err(IndiProtocolError { .. })));
+}
+
+#[test]
+fn receive_drops_too_long_message() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned()));
+    let unterminated = format!("<a>{}", "<b>x</b>".repeat(10 * 1024));
+    //when
+    let output = exchange(driver, &[&unterminated, "<getProperties version=\"1.7\"/>"]);
+    //then
+    assert!(output.starts_with("<defSwitchVector"));
+}
+
+#[test]
+fn base64_encodes_with_padding() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64(&[0xff, 0xfe]), "//4=");
+}
+
+#[test]
+fn get_properties_while_disconnected() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned()));
+    //when
+    let output = exchange(driver, &["<getProperties version=\"1.7\"/>"]);
+    //then
+    assert!(output.starts_with(&format!(
+        "<defSwitchVector device=\"{}\" name=\"CONNECTION\"",
+        DEVICE
+    )));
+    assert!(output.contains("<defSwitch name=\"DISCONNECT\" label=\"Disconnect\">On</defSwitch>"));
+    assert!(!output.contains("CCD_EXPOSURE"));
+}
+
+#[test]
+fn get_properties_of_other_device_is_ignored() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned())).with_device_name("Mine");
+    //when
+    let output = exchange(
+        driver,
+        &["<getProperties version=\"1.7\" device=\"Other\"/>"],
+    );
+    //then
+    assert!(output.is_empty());
+}
+
+#[test]
+fn message_split_across_reads() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned()));
+    //when
+    let output = exchange(driver, &["<getProp", "erties version=\"1.7\"/>  <bogus"]);
+    //then
+    assert_eq!(output.matches("<defSwitchVector").count(), 1);
+}
+
+#[test]
+fn connect_defines_device_properties() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let ctx_mode = SetQHYCCDStreamMode_context();
+    ctx_mode.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .times(1)
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 1000.0;
+            *max = 60_000_000.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area
+        .expect()
+        .times(2)
+        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 10;
+            *start_y = 4;
+            *width = 1000;
+            *height = 760;
+            QHYCCD_SUCCESS
+        });
+    let camera = Camera::new("test_camera".to_owned());
+    crate::mocks::keep_open(&camera);
+    let driver = IndiDriver::new(camera);
+    //when
+    let output = exchange(
+        driver,
+        &[
+            r#"<newSwitchVector device="QHY CCD test_camera" name="CONNECTION">
+               <oneSwitch name="CONNECT">On</oneSwitch>
+             </newSwitchVector>"#,
+        ],
+    );
+    //then
+    assert!(output.starts_with(&format!(
+        "<setSwitchVector device=\"{}\" name=\"CONNECTION\" state=\"Ok\">",
+        DEVICE
+    )));
+    assert!(output.contains(
+        "<defNumber name=\"CCD_EXPOSURE_VALUE\" label=\"Duration (s)\" format=\"%5.3f\" \
+         min=\"0.001\" max=\"60.0\" step=\"0.000001\">0.0</defNumber>"
+    ));
+    assert!(output.contains("<defNumber name=\"X\" label=\"Left\" format=\"%4.0f\" min=\"10.0\" max=\"1009.0\" step=\"1.0\">10.0</defNumber>"));
+    assert!(output.contains("<defNumber name=\"WIDTH\" label=\"Width\" format=\"%4.0f\" min=\"1.0\" max=\"1000.0\" step=\"1.0\">1000.0</defNumber>"));
+    assert!(output.contains("<defBLOBVector"));
+    assert!(!output.contains("CCD_TEMPERATURE"));
+    assert!(!output.contains("FILTER_SLOT"));
+}
+
+#[test]
+fn second_client_joins_without_init() {
+    //given
+    let ctx_mode = SetQHYCCDStreamMode_context();
+    ctx_mode.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().return_const_st(QHYCCD_SUCCESS);
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area.expect().return_const_st(QHYCCD_SUCCESS);
+    let driver = IndiDriver::new(new_camera());
+    let connect = r#"<newSwitchVector device="QHY CCD test_camera" name="CONNECTION">
+                       <oneSwitch name="CONNECT">On</oneSwitch>
+                     </newSwitchVector>"#;
+    let (mut first_output, mut second_output) = (Vec::new(), Vec::new());
+    let mut first = IndiSession::new(driver.clone(), &mut first_output);
+    let mut second = IndiSession::new(driver, &mut second_output);
+    //when
+    first.receive(connect.as_bytes()).unwrap();
+    second.receive(connect.as_bytes()).unwrap();
+    drop((first, second));
+    //then
+    for output in [first_output, second_output] {
+        assert!(String::from_utf8(output).unwrap().starts_with(&format!(
+            "<setSwitchVector device=\"{}\" name=\"CONNECTION\" state=\"Ok\">",
+            DEVICE
+        )));
+    }
+}
+
+#[test]
+fn empty_effective_area_defines_no_frame() {
+    //given
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area
+        .expect()
+        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 0;
+            *start_y = 0;
+            *width = 0;
+            *height = 0;
+            QHYCCD_SUCCESS
+        });
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_ERROR);
+    let driver = IndiDriver::new(new_camera());
+    //when
+    let output = exchange(
+        driver,
+        &[r#"<getProperties version="1.7" device="QHY CCD test_camera" name="CCD_FRAME"/>"#],
+    );
+    //then
+    assert!(!output.contains("CCD_FRAME"));
+}
+
+#[test]
+fn disconnect_closes_camera_after_last_client() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_close = CloseQHYCCD_context();
+    ctx_close
+        .expect()
+        .withf_st(|handle| *handle == TEST_HANDLE)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let camera = new_camera();
+    let driver = IndiDriver::new(camera.clone());
+    let disconnect = r#"<newSwitchVector device="QHY CCD test_camera" name="CONNECTION">
+                          <oneSwitch name="DISCONNECT">On</oneSwitch>
+                        </newSwitchVector>"#;
+    let (mut first_output, mut second_output) = (Vec::new(), Vec::new());
+    let mut first = IndiSession::new(driver.clone(), &mut first_output);
+    let mut second = IndiSession::new(driver, &mut second_output);
+    //when
+    first.receive(disconnect.as_bytes()).unwrap();
+    let open_after_first = camera.is_open().unwrap();
+    second.receive(disconnect.as_bytes()).unwrap();
+    //then
+    assert!(open_after_first);
+    assert!(!camera.is_open().unwrap());
+}
+
+#[test]
+fn missing_member_sets_alert() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned()));
+    //when
+    let output = exchange(
+        driver,
+        &[
+            r#"<newNumberVector name="CCD_FRAME"><oneNumber name="X">0</oneNumber></newNumberVector>"#,
+        ],
+    );
+    //then
+    assert_eq!(
+        output,
+        format!(
+            "<setNumberVector device=\"{}\" name=\"CCD_FRAME\" state=\"Alert\" \
+             message=\"Error malformed INDI message: CCD_FRAME has no member Y\"></setNumberVector>\n",
+            DEVICE
+        )
+    );
+}
+
+#[test]
+fn negative_frame_sets_alert() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned()));
+    let message = r#"<newNumberVector name="CCD_FRAME">
+                       <oneNumber name="X">-8</oneNumber><oneNumber name="Y">0</oneNumber>
+                       <oneNumber name="WIDTH">100</oneNumber><oneNumber name="HEIGHT">100</oneNumber>
+                     </newNumberVector>"#;
+    //when
+    let output = exchange(driver, &[message]);
+    //then
+    assert!(output.contains(
+        "name=\"CCD_FRAME\" state=\"Alert\" message=\"Error malformed INDI message: invalid X -8\""
+    ));
+}
+
+#[test]
+fn malformed_message_is_skipped() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned()));
+    //when
+    let output = exchange(driver, &["<bogus x=1/><getProperties version=\"1.7\"/>"]);
+    //then
+    assert_eq!(output.matches("<defSwitchVector").count(), 1);
+}
+
+// the exposure runs on a background thread, so the expectations must not use the `_st` variants
+fn expect_exposure(frame_result: u32) -> Vec<Box<dyn std::any::Any>> {
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf(|_, control, value| *control == Control::Exposure as u32 && *value == 1_500_000.0)
+        .once()
+        .return_const(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const(2_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().once().returning(
+        move |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            buffer.copy_from([7_u8, 9].as_ptr(), 2);
+            frame_result
+        },
+    );
+    vec![
+        Box::new(ctx_set),
+        Box::new(ctx_exp),
+        Box::new(ctx_size),
+        Box::new(ctx_frame),
+    ]
+}
+
+/// the camera supports none of the optional settings read for the FITS header
+fn expect_no_metadata() -> Vec<Box<dyn std::any::Any>> {
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_model = GetQHYCCDModel_context();
+    ctx_model.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().return_const_st(1_500_000.0);
+    let ctx_mode = GetQHYCCDReadMode_context();
+    ctx_mode.expect().return_const_st(QHYCCD_ERROR);
+    let ctx_precise = GetQHYCCDPreciseExposureInfo_context();
+    ctx_precise.expect().return_const_st(QHYCCD_ERROR);
+    vec![
+        Box::new(ctx_available),
+        Box::new(ctx_model),
+        Box::new(ctx_param),
+        Box::new(ctx_mode),
+        Box::new(ctx_precise),
+    ]
+}
+
+/// starts an exposure of 1.5s and polls the session until its frame was sent
+fn expose(driver: IndiDriver, enable_blob: &str) -> String {
+    let mut output = Vec::new();
+    let mut session = IndiSession::new(driver, &mut output);
+    session
+        .receive(
+            format!(
+                "<enableBLOB device=\"{}\">{}</enableBLOB>",
+                DEVICE, enable_blob
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    session
+        .receive(
+            br#"<newNumberVector device="QHY CCD test_camera" name="CCD_EXPOSURE">
+                  <oneNumber name="CCD_EXPOSURE_VALUE">1.5</oneNumber>
+                </newNumberVector>"#,
+        )
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while session.is_exposing() && Instant::now() < deadline {
+        session.poll().unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    drop(session);
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn exposure_sends_fits_blob() {
+    //given
+    let _exposure = expect_exposure(QHYCCD_SUCCESS);
+    let _metadata = expect_no_metadata();
+    let driver = IndiDriver::new(new_camera());
+    //when
+    let output = expose(driver, "Also");
+    //then
+    let mut messages = output.lines();
+    assert_eq!(
+        messages.next(),
+        Some(
+            "<setNumberVector device=\"QHY CCD test_camera\" name=\"CCD_EXPOSURE\" state=\"Busy\">\
+             <oneNumber name=\"CCD_EXPOSURE_VALUE\">1.5</oneNumber></setNumberVector>"
+        )
+    );
+    let blob = messages.next().unwrap();
+    assert!(blob.starts_with(
+        "<setBLOBVector device=\"QHY CCD test_camera\" name=\"CCD1\" state=\"Ok\">\
+         <oneBLOB name=\"CCD1\" size=\"5760\" format=\".fits\">U0lNUExFICA9"
+    ));
+    assert_eq!(
+        messages.next(),
+        Some(
+            "<setNumberVector device=\"QHY CCD test_camera\" name=\"CCD_EXPOSURE\" state=\"Ok\">\
+             <oneNumber name=\"CCD_EXPOSURE_VALUE\">0.0</oneNumber></setNumberVector>"
+        )
+    );
+}
+
+#[test]
+fn exposure_without_enabled_blobs_sends_no_blob() {
+    //given
+    let _exposure = expect_exposure(QHYCCD_SUCCESS);
+    let _metadata = expect_no_metadata();
+    let driver = IndiDriver::new(new_camera());
+    //when
+    let output = expose(driver, "Never");
+    //then
+    assert!(!output.contains("setBLOBVector"));
+    assert!(output
+        .contains("name=\"CCD_EXPOSURE\" state=\"Ok\"><oneNumber name=\"CCD_EXPOSURE_VALUE\">0.0"));
+}
+
+#[test]
+fn failed_exposure_sets_alert() {
+    //given
+    let _exposure = expect_exposure(QHYCCD_ERROR);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().return_const_st(QHYCCD_ERROR);
+    let driver = IndiDriver::new(new_camera());
+    //when
+    let output = expose(driver, "Also");
+    //then
+    assert!(!output.contains("setBLOBVector"));
+    assert!(output.contains(
+        "name=\"CCD_EXPOSURE\" state=\"Alert\" message=\"Error getting camera single frame"
+    ));
+}
+
+#[test]
+fn invalid_exposure_time_sets_alert() {
+    //given
+    let driver = IndiDriver::new(Camera::new("test_camera".to_owned()));
+    let message = r#"<newNumberVector name="CCD_EXPOSURE">
+                       <oneNumber name="CCD_EXPOSURE_VALUE">-1</oneNumber>
+                     </newNumberVector>"#;
+    //when
+    let output = exchange(driver, &[message]);
+    //then
+    assert!(output.contains(
+        "state=\"Alert\" message=\"Error malformed INDI message: invalid exposure time -1\""
+    ));
+}
+
+#[test]
+fn filter_slot_reports_arrival() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(2).return_const_st(TEST_HANDLE);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .returning_st(|_handle, control| match control {
+            c if c == Control::CfwPort as u32 => QHYCCD_SUCCESS,
+            _ => QHYCCD_ERROR,
+        });
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::CfwPort as u32 && *value == 50.0)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_status = GetQHYCCDCFWStatus_context();
+    ctx_status
+        .expect()
+        .times(1)
+        .returning_st(|_, status| unsafe {
+            *status = b'2' as c_char;
+            QHYCCD_SUCCESS
+        });
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf_st(|_, control| *control == Control::CfwPort as u32)
+        .times(1)
+        .return_const_st(50.0);
+    let camera = Camera::new("test_camera".to_owned());
+    camera.open().unwrap();
+    crate::mocks::keep_open(&camera);
+    let filter_wheel = FilterWheel::new(Camera::new("test_camera".to_owned()));
+    filter_wheel.open().unwrap();
+    crate::mocks::keep_open(&filter_wheel);
+    let driver = IndiDriver::new(camera).with_filter_wheel(filter_wheel);
+    let mut output = Vec::new();
+    let mut session = IndiSession::new(driver, &mut output);
+    //when
+    session
+        .receive(
+            br#"<newNumberVector name="FILTER_SLOT">
+                  <oneNumber name="FILTER_SLOT_VALUE">3</oneNumber>
+                </newNumberVector>"#,
+        )
+        .unwrap();
+    session.report().unwrap();
+    drop(session);
+    //then
+    let output = String::from_utf8(output).unwrap();
+    let messages = output.lines().collect::<Vec<_>>();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].contains("name=\"FILTER_SLOT\" state=\"Busy\""));
+    assert!(messages[1].contains("name=\"FILTER_SLOT\" state=\"Ok\""));
+    assert!(messages[1].contains("<oneNumber name=\"FILTER_SLOT_VALUE\">3.0</oneNumber>"));
+}
@@ -0,0 +1,93 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParam_context, IsQHYCCDControlAvailable_context, SetQHYCCDParam_context, QHYCCD_ERROR,
+    QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+#[test]
+fn simulated_signal_alarm_toggles() {
+    //given
+    let alarm = SimulatedSignalAlarm::new();
+    //when
+    alarm.set_alarm(true).unwrap();
+    let on = alarm.is_alarm_on().unwrap();
+    alarm.set_alarm(false).unwrap();
+    //then
+    assert!(on);
+    assert!(!alarm.is_alarm_on().unwrap());
+}
+
+#[test]
+fn simulated_fpga_watchdog_timeout() {
+    //given
+    let watchdog = SimulatedFpgaWatchdog::new();
+    //when
+    let too_short = watchdog.enable_watchdog(Duration::from_millis(500));
+    watchdog.enable_watchdog(Duration::from_secs(30)).unwrap();
+    let enabled = watchdog.watchdog_timeout().unwrap();
+    watchdog.disable_watchdog().unwrap();
+    //then
+    assert!(matches!(too_short, Err(InvalidWatchdogTimeoutError { .. })));
+    assert_eq!(enabled, Some(Duration::from_secs(30)));
+    assert_eq!(watchdog.watchdog_timeout().unwrap(), None);
+}
+
+#[test]
+fn camera_enable_watchdog_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CamWatchDogFpga as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::CamWatchDogFpga as u32 && *value == 30.0)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.enable_watchdog(Duration::from_secs(30));
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn camera_watchdog_disabled() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().times(1).return_const_st(0.0);
+    let cam = new_camera();
+    //when
+    let res = cam.watchdog_timeout();
+    //then
+    assert_eq!(res.unwrap(), None);
+}
+
+#[test]
+fn camera_alarm_not_available() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_alarm(true);
+    //then
+    assert!(matches!(
+        res,
+        Err(IsControlAvailableError {
+            control: Control::CamSpeakerLedAlarm
+        })
+    ));
+}
@@ -0,0 +1,89 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    IsQHYCCDControlAvailable_context, OpenQHYCCD_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::TEST_HANDLE;
+
+fn image(data: Vec<u8>) -> ImageData {
+    ImageData {
+        data,
+        width: 2,
+        height: 2,
+        bits_per_pixel: 8,
+        channels: 1,
+    }
+}
+
+#[test]
+fn validate_valid_and_frozen() {
+    //given
+    let mut validator = FrameValidator::new();
+    //when
+    let first = validator.validate(&image(vec![1, 2, 3, 4]));
+    let second = validator.validate(&image(vec![1, 2, 3, 5]));
+    let third = validator.validate(&image(vec![1, 2, 3, 5, 0, 0]));
+    //then
+    assert_eq!(first, FrameIntegrity::Valid);
+    assert_eq!(second, FrameIntegrity::Valid);
+    assert_eq!(third, FrameIntegrity::Frozen);
+}
+
+#[test]
+fn validate_truncated() {
+    //given
+    let mut validator = FrameValidator::new();
+    //when
+    let res = validator.validate(&image(vec![1, 2, 3]));
+    //then
+    assert_eq!(
+        res,
+        FrameIntegrity::Truncated {
+            expected: 4,
+            actual: 3
+        }
+    );
+}
+
+#[test]
+fn validate_invalid_geometry() {
+    //given
+    let mut validator = FrameValidator::new();
+    let mut empty = image(vec![]);
+    empty.width = 0;
+    let mut deep = image(vec![0; 32]);
+    deep.bits_per_pixel = 64;
+    //when
+    let res = [validator.validate(&empty), validator.validate(&deep)];
+    //then
+    assert_eq!(res, [FrameIntegrity::InvalidGeometry; 2]);
+}
+
+#[test]
+fn set_frame_detect_success() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let ctx_get = IsQHYCCDControlAvailable_context();
+    ctx_get
+        .expect()
+        .withf_st(|handle, control| {
+            *handle == TEST_HANDLE && *control == Control::FrameDetect as u32
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::FrameDetect as u32 && *value == 1.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = Camera::new("test_camera".to_owned());
+    cam.open().unwrap();
+    crate::mocks::keep_open(&cam);
+    //when
+    let res = cam.set_frame_detect(true);
+    //then
+    assert!(res.is_ok());
+}
@@ -0,0 +1,58 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{GetQHYCCDParam_context, OpenQHYCCD_context};
+use crate::mocks::TEST_HANDLE;
+
+#[test]
+fn keep_alive_reads_temperature() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .withf(|_, control| *control == Control::CurTemp as u32)
+        .times(1..)
+        .return_const(-10.0);
+    let cam = Camera::new("test_camera".to_owned());
+    cam.open().unwrap();
+    crate::mocks::keep_open(&cam);
+    //when
+    let keep_alive = cam.start_keep_alive(Duration::from_millis(1));
+    std::thread::sleep(Duration::from_millis(50));
+    keep_alive.stop();
+    //then
+    ctx_param.checkpoint();
+}
+
+#[test]
+fn keep_alive_skips_closed_camera() {
+    //given
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().never();
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let keep_alive = cam.start_keep_alive(Duration::from_millis(1));
+    std::thread::sleep(Duration::from_millis(20));
+    drop(keep_alive);
+    //then
+    ctx_param.checkpoint();
+}
+
+#[test]
+fn keep_alive_skips_exposing_camera() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(1).return_const_st(TEST_HANDLE);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().never();
+    let cam = Camera::new("test_camera".to_owned());
+    cam.open().unwrap();
+    crate::mocks::keep_open(&cam);
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let keep_alive = cam.start_keep_alive(Duration::from_millis(1));
+    std::thread::sleep(Duration::from_millis(20));
+    drop(keep_alive);
+    //then
+    ctx_param.checkpoint();
+}
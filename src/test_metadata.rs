@@ -0,0 +1,264 @@
+use std::time::SystemTime;
+
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDEffectiveArea_context, GetQHYCCDLiveFrame_context, GetQHYCCDModel_context,
+    GetQHYCCDParam_context, GetQHYCCDPreciseExposureInfo_context, GetQHYCCDReadMode_context,
+    IsQHYCCDControlAvailable_context, SetQHYCCDBinMode_context, SetQHYCCDResolution_context,
+    QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+fn expect_settings() -> Vec<Box<dyn std::any::Any>> {
+    expect_settings_with_precise_exposure(None)
+}
+
+fn expect_settings_with_precise_exposure(
+    precise: Option<PreciseExposureInfo>,
+) -> Vec<Box<dyn std::any::Any>> {
+    let ctx_model = GetQHYCCDModel_context();
+    ctx_model
+        .expect()
+        .once()
+        .returning_st(|_handle, model| unsafe {
+            let cam_model = "QHY268C\0";
+            model.copy_from(cam_model.as_ptr() as *const c_char, cam_model.len());
+            QHYCCD_SUCCESS
+        });
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(7)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::CamColor as u32 => BayerMode::RGGB as u32,
+            _ => QHYCCD_SUCCESS,
+        });
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(7)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::Exposure as u32 => 2_500_000.0,
+            c if c == Control::Gain as u32 => 26.0,
+            c if c == Control::Offset as u32 => 30.0,
+            c if c == Control::CurTemp as u32 => -10.0,
+            c if c == Control::HasHardwareFrameCounter as u32 => 42.0,
+            c if c == Control::OutputDataActualBits as u32 => 14.0,
+            c if c == Control::OutputDataAlignment as u32 => 1.0,
+            _ => panic!("unexpected control"),
+        });
+    let ctx_mode = GetQHYCCDReadMode_context();
+    ctx_mode
+        .expect()
+        .once()
+        .returning_st(|_handle, mode| unsafe {
+            *mode = 1;
+            QHYCCD_SUCCESS
+        });
+    let ctx_precise = GetQHYCCDPreciseExposureInfo_context();
+    ctx_precise.expect().once().returning_st(
+        move |_handle, pixel, line, frame, clocks, lines, actual, long| unsafe {
+            match precise {
+                Some(precise) => {
+                    *pixel = precise.pixel_period_ps;
+                    *line = precise.line_period_ns;
+                    *frame = precise.frame_period_us;
+                    *clocks = precise.clocks_per_line;
+                    *lines = precise.lines_per_frame;
+                    *actual = precise.actual_exposure.as_micros() as u32;
+                    *long = precise.long_exposure_mode as u8;
+                    QHYCCD_SUCCESS
+                }
+                None => QHYCCD_ERROR,
+            }
+        },
+    );
+    vec![
+        Box::new(ctx_model),
+        Box::new(ctx_available),
+        Box::new(ctx_param),
+        Box::new(ctx_mode),
+        Box::new(ctx_precise),
+    ]
+}
+
+fn expected_metadata() -> FrameMetadata {
+    FrameMetadata {
+        instrument: Some("QHY268C".to_owned()),
+        exposure: Some(Duration::from_millis(2500)),
+        gain: Some(26.0),
+        offset: Some(30.0),
+        ccd_temperature: Some(-10.0),
+        binning: None,
+        bayer_mode: Some(BayerMode::RGGB),
+        capture_start: None,
+        readout_mode: Some(1),
+        roi: None,
+        frame_counter: Some(42),
+        precise_exposure: None,
+        sample_depth: Some(SampleDepth {
+            actual_bits: 14,
+            alignment: DataAlignment::LowBits,
+        }),
+    }
+}
+
+#[test]
+fn frame_metadata_success() {
+    //given
+    let _settings = expect_settings();
+    let cam = new_camera();
+    //when
+    let metadata = cam.frame_metadata();
+    //then
+    assert_eq!(metadata, expected_metadata());
+}
+
+#[test]
+fn frame_metadata_with_binning_and_roi() {
+    //given
+    let _settings = expect_settings();
+    let ctx_bin = SetQHYCCDBinMode_context();
+    ctx_bin.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area
+        .expect()
+        .once()
+        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 0;
+            *start_y = 0;
+            *width = 4096;
+            *height = 2048;
+            QHYCCD_SUCCESS
+        });
+    let ctx_roi = SetQHYCCDResolution_context();
+    ctx_roi.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let roi = CCDChipArea {
+        start_x: 10,
+        start_y: 20,
+        width: 100,
+        height: 200,
+    };
+    cam.set_bin_mode(2, 2).unwrap();
+    cam.set_roi(roi).unwrap();
+    //when
+    let metadata = cam.frame_metadata();
+    //then
+    assert_eq!(
+        metadata,
+        FrameMetadata {
+            binning: Some((2, 2)),
+            roi: Some(roi),
+            ..expected_metadata()
+        }
+    );
+}
+
+#[test]
+fn frame_metadata_keeps_geometry_on_failed_set() {
+    //given
+    let ctx_bin = SetQHYCCDBinMode_context();
+    ctx_bin.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_bin_mode(2, 2);
+    //then
+    assert!(res.is_err());
+    assert_eq!(cam.frame_geometry().binning, None);
+}
+
+#[test]
+fn get_live_frame_with_meta_success() {
+    //given
+    let _settings = expect_settings();
+    let ctx_frame = GetQHYCCDLiveFrame_context();
+    ctx_frame.expect().once().returning_st(
+        |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            *buffer = 7;
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let before = SystemTime::now();
+    //when
+    let (image, metadata) = cam.get_live_frame_with_meta(1).unwrap();
+    //then
+    assert_eq!(image.data, vec![7]);
+    let capture_start = metadata.capture_start.unwrap();
+    assert!(capture_start <= before);
+    assert!(capture_start + Duration::from_millis(2500) >= before);
+    assert_eq!(
+        metadata,
+        FrameMetadata {
+            capture_start: Some(capture_start),
+            ..expected_metadata()
+        }
+    );
+}
+
+#[test]
+fn get_live_frame_with_meta_precise_exposure() {
+    //given
+    let precise = PreciseExposureInfo {
+        pixel_period_ps: 13_468,
+        line_period_ns: 20_000,
+        frame_period_us: 130_000,
+        clocks_per_line: 1_485,
+        lines_per_frame: 6_500,
+        actual_exposure: Duration::from_millis(3000),
+        long_exposure_mode: true,
+    };
+    let _settings = expect_settings_with_precise_exposure(Some(precise));
+    let ctx_frame = GetQHYCCDLiveFrame_context();
+    ctx_frame.expect().once().returning_st(
+        |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            *buffer = 7;
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Live);
+    let before = SystemTime::now();
+    //when
+    let (_image, metadata) = cam.get_live_frame_with_meta(1).unwrap();
+    //then
+    assert_eq!(metadata.precise_exposure, Some(precise));
+    let capture_start = metadata.capture_start.unwrap();
+    assert!(capture_start + Duration::from_millis(2500) < before);
+    assert!(capture_start + Duration::from_millis(3000) >= before);
+}
+
+#[test]
+fn frame_metadata_camera_not_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let metadata = cam.frame_metadata();
+    //then
+    assert_eq!(metadata, FrameMetadata::default());
+}
+
+#[test]
+fn count_dropped_frames_from_counter_gaps() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let dropped = [1, 2, 5, 5, 3, 4]
+        .map(|counter| cam.count_dropped_frames(counter))
+        .to_vec();
+    cam.reset_frame_counter();
+    let after_reset = cam.count_dropped_frames(100);
+    //then
+    assert_eq!(dropped, vec![0, 0, 2, 0, 0, 0]);
+    assert_eq!(after_reset, 0);
+}
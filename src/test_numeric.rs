@@ -0,0 +1,70 @@
+use super::*;
+
+#[test]
+fn format_float_plain_decimal() {
+    assert_eq!(format_float(300.0), "300.0");
+    assert_eq!(format_float(-12.25), "-12.25");
+    assert_eq!(format_float(0.1), "0.1");
+    assert_eq!(format_float(0.0), "0.0");
+}
+
+#[test]
+fn format_float_exponent() {
+    assert_eq!(format_float(1.5e-12), "0.0000000000015");
+    assert_eq!(format_float(1.5e-20), "1.5E-20");
+    assert_eq!(format_float(1e-20), "1.0E-20");
+    assert_eq!(format_float(6.02214076e23), "6.02214076E23");
+}
+
+#[test]
+fn format_float_fits_into_fits_value_field() {
+    for value in [
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        -1.0 / 3.0,
+        123_456_789.123_456_78,
+    ] {
+        assert!(format_float(value).len() <= 24);
+    }
+}
+
+#[test]
+fn format_float_roundtrip() {
+    for value in [
+        0.1,
+        -1.0 / 3.0,
+        2.0 / 7.0 * 1e-9,
+        1e300,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        -0.0,
+        42.0,
+    ] {
+        assert_eq!(parse_float(&format_float(value)).unwrap(), value);
+    }
+}
+
+#[test]
+fn format_float_non_finite() {
+    assert_eq!(format_float(f64::INFINITY), "inf");
+    assert!(parse_float(&format_float(f64::NAN)).unwrap().is_nan());
+}
+
+#[test]
+fn parse_float_fits_exponent() {
+    assert_eq!(parse_float(" 2.0D3 ").unwrap(), 2000.0);
+    assert_eq!(parse_float("1.5e-3").unwrap(), 0.0015);
+}
+
+#[test]
+fn parse_float_rejects_locale_formats() {
+    for value in ["1,5", "1.000,5", "1 000.5", ""] {
+        assert_eq!(
+            parse_float(value).err().unwrap().to_string(),
+            ParseFloatError {
+                value: value.to_owned()
+            }
+            .to_string()
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context, SetQHYCCDParam_context,
+    QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+fn expect_limits(min: f64, max: f64) -> impl Sized {
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().once().returning_st(
+        move |_handle, _control, c_min, c_max, c_step| unsafe {
+            *c_min = min;
+            *c_max = max;
+            *c_step = 1.0;
+            QHYCCD_SUCCESS
+        },
+    );
+    ctx_limits
+}
+
+#[test]
+fn set_exposure_success() {
+    //given
+    let _ctx_limits = expect_limits(1.0, 3_600_000_000.0);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::Exposure as u32 && *value == 2_500_000.0
+        })
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_exposure(Duration::from_millis(2500));
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn set_exposure_out_of_range() {
+    //given
+    let _ctx_limits = expect_limits(1.0, 1_000_000.0);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().never();
+    let cam = new_camera();
+    //when
+    let res = cam.set_exposure(Duration::from_secs(2));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        ParameterOutOfRangeError {
+            control: Control::Exposure,
+            value: 2_000_000.0,
+            min: 1.0,
+            max: 1_000_000.0
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_exposure_success() {
+    //given
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get
+        .expect()
+        .withf_st(|_, control| *control == Control::Exposure as u32)
+        .once()
+        .return_const_st(1_500.0);
+    let cam = new_camera();
+    //when
+    let res = cam.get_exposure();
+    //then
+    assert_eq!(res.unwrap(), Duration::from_micros(1_500));
+}
+
+#[test]
+fn set_gain_and_offset_success() {
+    //given
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .times(2)
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 0.0;
+            *max = 100.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| {
+            *control == Control::Gain as u32 && *value == 26.0
+                || *control == Control::Offset as u32 && *value == 30.0
+        })
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let gain = cam.set_gain(26.0);
+    let offset = cam.set_offset(30.0);
+    //then
+    assert!(gain.is_ok());
+    assert!(offset.is_ok());
+}
+
+#[test]
+fn set_gain_below_min() {
+    //given
+    let _ctx_limits = expect_limits(0.0, 100.0);
+    let cam = new_camera();
+    //when
+    let res = cam.set_gain(-1.0);
+    //then
+    assert!(res.is_err());
+}
+
+#[test]
+fn set_usb_traffic_limits_fail() {
+    //given
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_usb_traffic(30);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        GetMinMaxStepError {
+            control: Control::UsbTraffic
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn get_usb_traffic_success() {
+    //given
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().once().return_const_st(30.0);
+    let cam = new_camera();
+    //when
+    let res = cam.get_usb_traffic();
+    //then
+    assert_eq!(res.unwrap(), 30);
+}
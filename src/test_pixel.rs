@@ -0,0 +1,240 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDChipInfo_context, GetQHYCCDMemLength_context, IsQHYCCDControlAvailable_context,
+    SetQHYCCDBitsMode_context, QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+#[test]
+fn pixel_format_from_bits_per_pixel() {
+    //given
+    let bit_depths = [8, 12, 16, 20, 32];
+    //when
+    let formats = bit_depths
+        .iter()
+        .map(|&bits| PixelFormat::from_bits_per_pixel(bits).unwrap())
+        .collect::<Vec<_>>();
+    //then
+    assert_eq!(
+        formats,
+        vec![
+            PixelFormat::U8,
+            PixelFormat::U16,
+            PixelFormat::U16,
+            PixelFormat::U32,
+            PixelFormat::U32
+        ]
+    );
+    assert_eq!(PixelFormat::U32.bits(), 32);
+    assert_eq!(PixelFormat::U32.bytes_per_sample(), 4);
+}
+
+#[test]
+fn pixel_format_from_bits_per_pixel_fail() {
+    //given
+    let bits_per_pixel = 0;
+    //when
+    let res = PixelFormat::from_bits_per_pixel(bits_per_pixel);
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::UnsupportedBitDepthError { bits_per_pixel: 0 }.to_string()
+    );
+}
+
+#[test]
+fn expected_len_32bit() {
+    //given
+    let image = ImageData {
+        data: vec![0; 64],
+        width: 3,
+        height: 2,
+        bits_per_pixel: 32,
+        channels: 1,
+    };
+    //when
+    let res = image.expected_len();
+    //then
+    assert_eq!(image.pixel_format().unwrap(), PixelFormat::U32);
+    assert_eq!(res.unwrap(), 24);
+}
+
+#[test]
+fn supported_pixel_formats_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(3)
+        .returning_st(|handle, control| match control {
+            c if handle == TEST_HANDLE && c == Control::Cam16bits as u32 => QHYCCD_SUCCESS,
+            c if handle == TEST_HANDLE && c == Control::Cam32bits as u32 => QHYCCD_SUCCESS,
+            _ => QHYCCD_ERROR,
+        });
+    let cam = new_camera();
+    //when
+    let res = cam.supported_pixel_formats();
+    //then
+    assert_eq!(res, vec![PixelFormat::U16, PixelFormat::U32]);
+}
+
+#[test]
+fn as_u16_slice_success() {
+    //given
+    let image = ImageData {
+        data: vec![1, 0, 0, 1, 255, 255, 0, 0],
+        width: 3,
+        height: 1,
+        bits_per_pixel: 12,
+        channels: 1,
+    };
+    //when
+    let samples = image.as_u16_slice().unwrap();
+    //then
+    assert_eq!(*samples, [1, 256, 65535]);
+}
+
+#[test]
+fn as_u16_slice_wrong_format() {
+    //given
+    let image = ImageData {
+        data: vec![1, 2],
+        width: 2,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let res = image.as_u16_slice();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        PixelFormatMismatchError {
+            expected: PixelFormat::U16,
+            actual: PixelFormat::U8
+        }
+        .to_string()
+    );
+    assert_eq!(image.as_u8_slice().unwrap(), &[1, 2]);
+}
+
+#[test]
+fn into_pixel_buffer_all_formats() {
+    //given
+    let image = |bits_per_pixel: u32, data: Vec<u8>| ImageData {
+        data,
+        width: 1,
+        height: 1,
+        bits_per_pixel,
+        channels: 1,
+    };
+    //when
+    let buffers = [
+        image(8, vec![7, 0]).into_pixel_buffer().unwrap(),
+        image(16, vec![0, 1]).into_pixel_buffer().unwrap(),
+        image(32, vec![1, 0, 0, 1]).into_pixel_buffer().unwrap(),
+    ];
+    //then
+    assert_eq!(
+        buffers,
+        [
+            PixelBuffer::U8(vec![7]),
+            PixelBuffer::U16(vec![256]),
+            PixelBuffer::U32(vec![0x0100_0001])
+        ]
+    );
+    assert_eq!(buffers[2].pixel_format(), PixelFormat::U32);
+    assert_eq!(buffers[1].len(), 1);
+}
+
+#[test]
+fn pixel_with_channels() {
+    //given
+    let image = ImageData {
+        data: vec![1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0],
+        width: 2,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 3,
+    };
+    //when
+    let pixel = image.pixel(1, 0, 1);
+    //then
+    assert_eq!(pixel, Some(5));
+    assert_eq!(image.pixel(2, 0, 0), None);
+    assert_eq!(image.pixel(0, 0, 3), None);
+}
+
+#[test]
+fn simulated_frame_ramps_to_full_scale() {
+    //given
+    let formats = [PixelFormat::U8, PixelFormat::U16, PixelFormat::U32];
+    //when
+    let images = formats.map(|format| ImageData::simulated(3, 2, format));
+    //then
+    for image in &images {
+        assert_eq!(image.data.len(), image.expected_len().unwrap());
+    }
+    assert_eq!(images[0].data, vec![0, 127, 255, 0, 127, 255]);
+    assert_eq!(
+        images[2].clone().into_pixel_buffer().unwrap(),
+        PixelBuffer::U32(vec![0, u32::MAX / 2, u32::MAX, 0, u32::MAX / 2, u32::MAX])
+    );
+}
+
+#[test]
+fn set_bit_mode_32_not_supported() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::Cam32bits as u32)
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_bit_mode(32);
+    //then
+    assert!(matches!(
+        res,
+        Err(UnsupportedBitDepthError { bits_per_pixel: 32 })
+    ));
+    assert_eq!(cam.frame_geometry().bits_per_pixel, None);
+}
+
+#[test]
+fn get_image_size_in_32_bit_mode() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_bits = SetQHYCCDBitsMode_context();
+    ctx_bits
+        .expect()
+        .withf_st(|_, mode| *mode == 32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_length = GetQHYCCDMemLength_context();
+    ctx_length
+        .expect()
+        .times(1)
+        .return_const_st(1024_u32 * 768 * 2);
+    let ctx_info = GetQHYCCDChipInfo_context();
+    ctx_info.expect().times(1).returning_st(
+        |_handle, _chipw, _chiph, imagew, imageh, _pixelw, _pixelh, bpp| unsafe {
+            *imagew = 1024;
+            *imageh = 768;
+            *bpp = 32;
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = new_camera();
+    cam.set_bit_mode(32).unwrap();
+    //when
+    let res = cam.get_image_size();
+    //then
+    assert_eq!(res.unwrap(), 1024 * 768 * 4);
+}
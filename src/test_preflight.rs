@@ -0,0 +1,172 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDMemLength_context, GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context,
+    IsQHYCCDControlAvailable_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+#[test]
+fn preflight_all_checks_pass() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(2)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::CurTemp as u32 => -9.6,
+            c if c == Control::CfwPort as u32 => 50.0,
+            _ => panic!("unexpected control"),
+        });
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(1024_u32);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .once()
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 1.0;
+            *max = 3_600_000_000.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    cam.handle.set_initialized();
+    let fw = FilterWheel::new(cam.clone());
+    fw.homed.store(true, std::sync::atomic::Ordering::SeqCst);
+    let plan = PreflightPlan {
+        exposure_us: 300_000_000.0,
+        frame_count: 2,
+        target_temperature: Some(-10.0),
+        temperature_tolerance: 0.5,
+        output_directory: Some(std::env::temp_dir()),
+    };
+    //when
+    let report = preflight(&cam, Some(&fw), &plan);
+    //then
+    assert!(report.passed());
+    assert_eq!(report.results.len(), 6);
+    assert!(report
+        .results
+        .iter()
+        .all(|(_, outcome)| *outcome == PreflightOutcome::Passed));
+}
+
+#[test]
+fn preflight_camera_not_open() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let report = preflight(&cam, None, &PreflightPlan::default());
+    //then
+    assert!(!report.passed());
+    assert_eq!(
+        report.failures().collect::<Vec<_>>(),
+        vec![(PreflightCheck::CameraOpen, "camera is not open")]
+    );
+    assert_eq!(
+        report.outcome(PreflightCheck::ExposureWithinLimits),
+        Some(&PreflightOutcome::Skipped("camera is not open".to_owned()))
+    );
+}
+
+#[test]
+fn preflight_reports_all_failures() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param.expect().once().return_const_st(5.0);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(u32::MAX - 1);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .once()
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 1.0;
+            *max = 1000.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    let plan = PreflightPlan {
+        exposure_us: 2000.0,
+        frame_count: u32::MAX,
+        target_temperature: Some(-10.0),
+        temperature_tolerance: 0.5,
+        output_directory: Some(std::env::temp_dir()),
+    };
+    //when
+    let report = preflight(&cam, None, &plan);
+    //then
+    assert!(!report.passed());
+    assert_eq!(
+        report
+            .failures()
+            .map(|(check, _)| check)
+            .collect::<Vec<_>>(),
+        vec![
+            PreflightCheck::CameraInitialized,
+            PreflightCheck::CoolerAtSetPoint,
+            PreflightCheck::DiskSpace,
+            PreflightCheck::ExposureWithinLimits
+        ]
+    );
+    assert!(matches!(
+        report.outcome(PreflightCheck::FilterWheelReady),
+        Some(PreflightOutcome::Skipped(_))
+    ));
+}
+
+#[test]
+fn preflight_camera_without_cooler() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available.expect().once().return_const_st(QHYCCD_ERROR);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    cam.handle.set_initialized();
+    let plan = PreflightPlan {
+        target_temperature: Some(-10.0),
+        ..Default::default()
+    };
+    //when
+    let report = preflight(&cam, None, &plan);
+    //then
+    assert_eq!(
+        report.failures().next(),
+        Some((PreflightCheck::CoolerAtSetPoint, "camera has no cooler"))
+    );
+}
+
+#[test]
+fn preflight_filter_wheel_not_homed() {
+    //given
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    cam.handle.set_initialized();
+    let fw = FilterWheel::new(cam.clone());
+    //when
+    let report = preflight(&cam, Some(&fw), &PreflightPlan::default());
+    //then
+    assert_eq!(
+        report.outcome(PreflightCheck::CameraInitialized),
+        Some(&PreflightOutcome::Passed)
+    );
+    assert_eq!(
+        report.outcome(PreflightCheck::FilterWheelReady),
+        Some(&PreflightOutcome::Failed(
+            "filter wheel has not been homed".to_owned()
+        ))
+    );
+}
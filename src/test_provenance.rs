@@ -0,0 +1,58 @@
+use super::*;
+
+fn frame(data: Vec<u8>) -> ImageData {
+    ImageData {
+        width: data.len() as u32,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 1,
+        data,
+    }
+}
+
+#[test]
+fn record_links_frames() {
+    //given
+    let mut chain = ProvenanceChain::new();
+    //when
+    let first = chain.record(&frame(vec![1, 2, 3]));
+    let second = chain.record(&frame(vec![4, 5, 6]));
+    //then
+    assert_eq!(first.sequence, 0);
+    assert_eq!(first.previous_hash, [0; 32]);
+    assert_eq!(second.sequence, 1);
+    assert_eq!(second.previous_hash, first.chain_hash);
+    assert_ne!(first.chain_hash, second.chain_hash);
+    assert_eq!(chain.last(), Some(&second));
+    assert_eq!(first.chain_hash_hex().len(), 64);
+}
+
+#[test]
+fn record_same_data_different_position() {
+    //given
+    let mut chain = ProvenanceChain::new();
+    //when
+    let first = chain.record(&frame(vec![1, 2, 3]));
+    let second = chain.record(&frame(vec![1, 2, 3]));
+    //then
+    assert_eq!(first.frame_hash, second.frame_hash);
+    assert_ne!(first.chain_hash, second.chain_hash);
+}
+
+#[test]
+fn verify_detects_tampering() {
+    //given
+    let mut chain = ProvenanceChain::new();
+    let first_frame = frame(vec![1, 2, 3]);
+    let second_frame = frame(vec![4, 5, 6]);
+    let first = chain.record(&first_frame);
+    let second = chain.record(&second_frame);
+    //when
+    let tampered = frame(vec![4, 5, 7]);
+    //then
+    assert!(first.verify(&first_frame, None));
+    assert!(second.verify(&second_frame, Some(&first)));
+    assert!(!second.verify(&tampered, Some(&first)));
+    assert!(!second.verify(&second_frame, None));
+    assert!(!first.verify(&first_frame, Some(&second)));
+}
@@ -0,0 +1,117 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    CloseQHYCCD_context, ExpQHYCCDSingleFrame_context, GetQHYCCDId_context,
+    GetQHYCCDMemLength_context, GetQHYCCDSingleFrame_context, InitQHYCCDResource_context,
+    InitQHYCCD_context, IsQHYCCDCFWPlugged_context, IsQHYCCDControlAvailable_context,
+    OpenQHYCCD_context, ReleaseQHYCCDResource_context, ScanQHYCCD_context, SetQHYCCDParam_context,
+    SetQHYCCDStreamMode_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::TEST_HANDLE;
+
+fn expect_sdk(cameras: u32) -> Vec<Box<dyn std::any::Any>> {
+    let ctx_init = InitQHYCCDResource_context();
+    ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_scan = ScanQHYCCD_context();
+    ctx_scan.expect().times(1).return_const_st(cameras);
+    let ctx_id = GetQHYCCDId_context();
+    ctx_id
+        .expect()
+        .times(cameras as usize)
+        .returning_st(|_index, c_id| unsafe {
+            let cam_id = "QHY178M-222b16468c5966524\0";
+            c_id.copy_from(cam_id.as_ptr() as *const c_char, cam_id.len());
+            QHYCCD_SUCCESS
+        });
+    let ctx_plugged = IsQHYCCDCFWPlugged_context();
+    ctx_plugged
+        .expect()
+        .times(cameras as usize)
+        .return_const_st(QHYCCD_ERROR);
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    vec![
+        Box::new(ctx_init),
+        Box::new(ctx_scan),
+        Box::new(ctx_id),
+        Box::new(ctx_plugged),
+        Box::new(ctx_release),
+    ]
+}
+
+#[test]
+fn capture_success() {
+    //given
+    let _sdk = expect_sdk(1);
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().times(2).return_const_st(TEST_HANDLE);
+    let ctx_close = CloseQHYCCD_context();
+    ctx_close.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_mode = SetQHYCCDStreamMode_context();
+    ctx_mode
+        .expect()
+        .withf_st(|_, mode| *mode == 0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::TransferBit as u32)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| {
+            *control == Control::TransferBit as u32 && *value == 16.0
+                || *control == Control::Exposure as u32 && *value == 2_000_000.0
+        })
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().once().return_const_st(4_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().once().returning_st(
+        |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 16;
+            *channels = 1;
+            buffer.copy_from([1_u8, 0, 2, 0].as_ptr(), 4);
+            QHYCCD_SUCCESS
+        },
+    );
+    let path = std::env::temp_dir().join(format!("quick_capture_{}.fits", std::process::id()));
+    //when
+    let image = quick::capture(None, Duration::from_secs(2), &path).unwrap();
+    //then
+    assert_eq!(image.data, vec![1, 0, 2, 0]);
+    let file = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(file.len(), 2 * 2880);
+    assert!(String::from_utf8_lossy(&file[..2880]).contains("EXPTIME =                  2.0"));
+}
+
+#[test]
+fn capture_camera_not_found() {
+    //given
+    let _sdk = expect_sdk(0);
+    let path = std::env::temp_dir().join("quick_capture_not_found.fits");
+    //when
+    let res = quick::capture(Some("QHY600M"), Duration::from_secs(1), &path);
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::CameraNotFoundError {
+            id: Some("QHY600M".to_owned())
+        }
+        .to_string()
+    );
+    assert!(!path.exists());
+}
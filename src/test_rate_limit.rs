@@ -0,0 +1,109 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{SetQHYCCDParam_context, QHYCCD_SUCCESS};
+use crate::mocks::new_camera;
+
+#[test]
+fn set_rate_limit_roundtrip() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    cam.set_rate_limit(Control::Cooler, Some(Duration::from_secs(2)));
+    let clone = cam.clone();
+    //then
+    assert_eq!(
+        clone.rate_limit(Control::Cooler),
+        Some(Duration::from_secs(2))
+    );
+    assert_eq!(cam.rate_limit(Control::UsbTraffic), None);
+    cam.set_rate_limit(Control::Cooler, None);
+    assert_eq!(clone.rate_limit(Control::Cooler), None);
+}
+
+#[test]
+fn set_parameter_delays_rate_limited_writes() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(3).return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.set_rate_limit(Control::Cooler, Some(Duration::from_millis(50)));
+    //when
+    let start = Instant::now();
+    cam.set_parameter(Control::Cooler, -10.0).unwrap();
+    cam.set_parameter(Control::Gain, 10.0).unwrap();
+    let unlimited = start.elapsed();
+    cam.set_parameter(Control::Cooler, -11.0).unwrap();
+    //then
+    assert!(unlimited < Duration::from_millis(50));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn reserve_without_limit_does_not_wait() {
+    //given
+    let mut limiter = rate_limit::RateLimiter::default();
+    //when
+    let first = limiter.reserve(Control::Cooler);
+    let second = limiter.reserve(Control::Cooler);
+    //then
+    assert!(first.wait.is_zero());
+    assert!(second.wait.is_zero());
+    assert!(!limiter.is_superseded(Control::Cooler, &first));
+}
+
+#[test]
+fn reserve_coalesces_pending_writes() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    cam.set_rate_limit(Control::Cooler, Some(Duration::from_secs(10)));
+    let mut limiter = cam.rate_limits.lock().unwrap();
+    //when
+    let first = limiter.reserve(Control::Cooler);
+    let second = limiter.reserve(Control::Cooler);
+    let third = limiter.reserve(Control::Cooler);
+    //then
+    assert!(first.wait.is_zero());
+    assert!(second.wait > Duration::from_secs(9));
+    assert!(third.wait <= second.wait);
+    assert!(limiter.is_superseded(Control::Cooler, &second));
+    assert!(!limiter.is_superseded(Control::Cooler, &third));
+}
+
+#[test]
+fn set_parameter_writes_only_the_last_of_a_burst() {
+    //given
+    static WRITTEN: Mutex<Vec<f64>> = Mutex::new(Vec::new());
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .times(2)
+        .returning(|_handle, _control, value| {
+            WRITTEN.lock().unwrap().push(value);
+            QHYCCD_SUCCESS
+        });
+    let cam = new_camera();
+    cam.set_rate_limit(Control::Cooler, Some(Duration::from_millis(300)));
+    //when
+    cam.set_parameter(Control::Cooler, -10.0).unwrap();
+    let results = std::thread::scope(|scope| {
+        let writes = [-11.0, -12.0, -13.0].map(|value| {
+            let cam = cam.clone();
+            let write = scope.spawn(move || cam.set_parameter(Control::Cooler, value));
+            std::thread::sleep(Duration::from_millis(30));
+            write
+        });
+        writes.map(|write| write.join().unwrap())
+    });
+    //then
+    let [first, second, last] = results;
+    for superseded in [first, second] {
+        assert_eq!(
+            superseded.err().unwrap().to_string(),
+            QHYError::SupersededWriteError {
+                control: Control::Cooler
+            }
+            .to_string()
+        );
+    }
+    assert!(last.is_ok());
+    assert_eq!(*WRITTEN.lock().unwrap(), vec![-10.0, -13.0]);
+}
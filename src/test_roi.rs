@@ -0,0 +1,204 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    self, GetQHYCCDEffectiveArea_context, IsQHYCCDControlAvailable_context,
+    SetQHYCCDBinMode_context, SetQHYCCDResolution_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+/// an effective area of 1000x800 pixels starting at 10, 4
+fn expect_effective_area() -> mock_libqhyccd_sys::__GetQHYCCDEffectiveArea::Context {
+    expect_effective_area_times(1)
+}
+
+/// an effective area of 1000x800 pixels starting at 10, 4, read `times` times
+fn expect_effective_area_times(
+    times: usize,
+) -> mock_libqhyccd_sys::__GetQHYCCDEffectiveArea::Context {
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area.expect().times(times).returning_st(
+        |_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 10;
+            *start_y = 4;
+            *width = 1000;
+            *height = 800;
+            QHYCCD_SUCCESS
+        },
+    );
+    ctx_area
+}
+
+fn roi(start_x: u32, start_y: u32, width: u32, height: u32) -> CCDChipArea {
+    CCDChipArea {
+        start_x,
+        start_y,
+        width,
+        height,
+    }
+}
+
+fn roi_error(roi: CCDChipArea, constraint: RoiConstraint) -> String {
+    RoiError { roi, constraint }.to_string()
+}
+
+#[test]
+fn set_roi_within_effective_area() {
+    //given
+    let _area = expect_effective_area();
+    let ctx_roi = SetQHYCCDResolution_context();
+    ctx_roi.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_roi(roi(10, 4, 1000, 800));
+    //then
+    assert!(res.is_ok());
+    assert_eq!(cam.get_roi().unwrap(), roi(10, 4, 1000, 800));
+}
+
+#[test]
+fn set_roi_empty() {
+    //given
+    let _area = expect_effective_area();
+    let cam = new_camera();
+    //when
+    let res = cam.set_roi(roi(10, 4, 0, 800));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        roi_error(roi(10, 4, 0, 800), RoiConstraint::NotEmpty)
+    );
+}
+
+#[test]
+fn set_roi_outside_effective_area() {
+    //given
+    let _area = expect_effective_area();
+    let cam = new_camera();
+    //when
+    let res = cam.set_roi(roi(0, 4, 100, 100));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        roi_error(
+            roi(0, 4, 100, 100),
+            RoiConstraint::WithinEffectiveArea(roi(10, 4, 1000, 800))
+        )
+    );
+}
+
+#[test]
+fn set_roi_outside_binned_effective_area() {
+    //given
+    let _area = expect_effective_area();
+    let ctx_bin = SetQHYCCDBinMode_context();
+    ctx_bin.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.set_bin_mode(2, 2).unwrap();
+    //when
+    let res = cam.set_roi(roi(5, 2, 500, 401));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        roi_error(
+            roi(5, 2, 500, 401),
+            RoiConstraint::WithinEffectiveArea(roi(5, 2, 500, 400))
+        )
+    );
+}
+
+#[test]
+fn set_roi_bayer_aligned() {
+    //given
+    let _area = expect_effective_area();
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf_st(|_, control| *control == Control::CamColor as u32)
+        .once()
+        .return_const_st(BayerMode::RGGB as u32);
+    let cam = new_camera();
+    //when
+    let res = cam.set_roi(roi(11, 4, 100, 100));
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        roi_error(roi(11, 4, 100, 100), RoiConstraint::BayerAligned)
+    );
+}
+
+#[test]
+fn get_roi_defaults_to_effective_area() {
+    //given
+    let _area = expect_effective_area();
+    let ctx_bin = SetQHYCCDBinMode_context();
+    ctx_bin.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.set_bin_mode(2, 2).unwrap();
+    //when
+    let res = cam.get_roi();
+    //then
+    assert_eq!(res.unwrap(), roi(5, 2, 500, 400));
+}
+
+#[test]
+fn get_roi_degenerate_effective_area() {
+    //given
+    let ctx_area = GetQHYCCDEffectiveArea_context();
+    ctx_area
+        .expect()
+        .once()
+        .returning_st(|_handle, start_x, start_y, width, height| unsafe {
+            *start_x = 1;
+            *start_y = 3;
+            *width = 0;
+            *height = 1;
+            QHYCCD_SUCCESS
+        });
+    let ctx_bin = SetQHYCCDBinMode_context();
+    ctx_bin.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.set_bin_mode(2, 2).unwrap();
+    //when
+    let res = cam.get_roi();
+    //then
+    assert_eq!(res.unwrap(), roi(1, 2, 0, 0));
+}
+
+#[test]
+fn default_roi_can_be_set() {
+    //given
+    let _area = expect_effective_area_times(2);
+    let ctx_roi = SetQHYCCDResolution_context();
+    ctx_roi
+        .expect()
+        .withf_st(|_, start_x, start_y, width, height| {
+            (*start_x, *start_y, *width, *height) == (10, 4, 1000, 800)
+        })
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_roi(cam.get_roi().unwrap());
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn offset_roi_round_trips() {
+    //given
+    let _area = expect_effective_area_times(2);
+    let ctx_roi = SetQHYCCDResolution_context();
+    ctx_roi
+        .expect()
+        .withf_st(|_, start_x, start_y, width, height| {
+            (*start_x, *start_y, *width, *height) == (20, 10, 100, 50)
+        })
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    cam.set_roi(roi(20, 10, 100, 50)).unwrap();
+    //when
+    let res = cam.set_roi(cam.get_roi().unwrap());
+    //then
+    assert!(res.is_ok());
+    assert_eq!(cam.get_roi().unwrap(), roi(20, 10, 100, 50));
+}
@@ -1,11 +1,23 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use super::*;
 use crate::mocks::mock_libqhyccd_sys::{
-    CloseQHYCCD_context, GetQHYCCDId_context, GetQHYCCDSDKVersion_context,
-    InitQHYCCDResource_context, IsQHYCCDCFWPlugged_context, OpenQHYCCD_context,
-    ReleaseQHYCCDResource_context, ScanQHYCCD_context, QHYCCD_SUCCESS,
+    CloseQHYCCD_context, EnableQHYCCDLogFile_context, EnableQHYCCDMessage_context,
+    GetQHYCCDId_context, GetQHYCCDSDKVersion_context, InitQHYCCDResource_context,
+    IsQHYCCDCFWPlugged_context, OpenQHYCCD_context, ReleaseQHYCCDResource_context,
+    ScanQHYCCD_context, SetQHYCCDLogLevel_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+
+use crate::QHYError::{
+    CameraClosedError, CameraNotOpenError, GetCameraIdError, InitSDKError, ScanQHYCCDError,
 };
 
-use crate::QHYError::{GetCameraIdError, InitSDKError, ScanQHYCCDError};
+/// the SDK resources are shared by all `Sdk` values in the process, tests creating one hold this
+/// lock so they see the init and release calls they expect
+pub(crate) fn sdk_lock() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
 
 fn new_sdk() -> Sdk {
     let ctx_init = InitQHYCCDResource_context();
@@ -60,6 +72,7 @@ fn new_sdk() -> Sdk {
 #[test]
 fn new_success() {
     //given
+    let _lock = sdk_lock();
     //when
     let ctx_release = ReleaseQHYCCDResource_context();
     ctx_release
@@ -75,6 +88,7 @@ fn new_success() {
 #[test]
 fn version_success() {
     //given
+    let _lock = sdk_lock();
     let ctx_version = GetQHYCCDSDKVersion_context();
     ctx_version
         .expect()
@@ -110,6 +124,7 @@ fn version_success() {
 #[test]
 fn version_fail() {
     //given
+    let _lock = sdk_lock();
     let ctx_version = GetQHYCCDSDKVersion_context();
     ctx_version
         .expect()
@@ -137,6 +152,7 @@ fn version_fail() {
 #[test]
 fn filter_wheels_success() {
     //given
+    let _lock = sdk_lock();
     //filter wheels context is set up in new_sdk()
     let ctx_release = ReleaseQHYCCDResource_context();
     ctx_release
@@ -150,9 +166,46 @@ fn filter_wheels_success() {
     assert!(sdk.filter_wheels().last().is_some());
 }
 
+#[test]
+fn filter_wheel_shares_the_call_lock_of_its_camera() {
+    //given
+    let _lock = sdk_lock();
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let sdk = new_sdk();
+    let fw = sdk.filter_wheels().last().unwrap();
+    let camera = sdk.cameras().find(|camera| camera.id() == fw.id()).unwrap();
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open
+        .expect()
+        .times(1)
+        .return_const_st(0xdeadbeef as *const std::ffi::c_void);
+    let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+    //when
+    let waited = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            let _call = camera.handle.calls.lock();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        });
+        locked_rx.recv().unwrap();
+        let start = std::time::Instant::now();
+        fw.open().unwrap();
+        crate::mocks::keep_open(fw);
+        start.elapsed()
+    });
+    //then
+    assert!(waited >= std::time::Duration::from_millis(50));
+    assert!(camera.is_open().unwrap());
+}
+
 #[test]
 fn new_init_fail() {
     //given
+    let _lock = sdk_lock();
     let ctx_init = InitQHYCCDResource_context();
     ctx_init.expect().times(1).return_const_st(QHYCCD_ERROR);
     let ctx_release = ReleaseQHYCCDResource_context();
@@ -173,6 +226,7 @@ fn new_init_fail() {
 #[test]
 fn new_scan_fail() {
     //given
+    let _lock = sdk_lock();
     let ctx_init = InitQHYCCDResource_context();
     ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
     let ctx_scan = ScanQHYCCD_context();
@@ -189,6 +243,7 @@ fn new_scan_fail() {
 #[test]
 fn new_get_id_fail() {
     //given
+    let _lock = sdk_lock();
     let ctx_init = InitQHYCCDResource_context();
     ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
     let ctx_scan = ScanQHYCCD_context();
@@ -213,6 +268,7 @@ fn new_get_id_fail() {
 #[test]
 fn new_get_id_invalid_utf8_fail() {
     //given
+    let _lock = sdk_lock();
     let ctx_init = InitQHYCCDResource_context();
     ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
     let ctx_scan = ScanQHYCCD_context();
@@ -240,6 +296,7 @@ fn new_get_id_invalid_utf8_fail() {
 
 #[test]
 fn new_with_broken_filter_wheel() {
+    let _lock = sdk_lock();
     let ctx_init = InitQHYCCDResource_context();
     ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
     let ctx_scan = ScanQHYCCD_context();
@@ -303,6 +360,7 @@ fn new_with_broken_filter_wheel() {
 
 #[test]
 fn new_fail_close() {
+    let _lock = sdk_lock();
     let ctx_init = InitQHYCCDResource_context();
     ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
     let ctx_scan = ScanQHYCCD_context();
@@ -339,8 +397,9 @@ fn new_fail_close() {
             ADDR1 => QHYCCD_SUCCESS,
             _ => panic!("invalid handle"),
         });
+    // the camera stays open when closing it fails, dropping it tries again
     let ctx_close = CloseQHYCCD_context();
-    ctx_close.expect().once().return_const_st(QHYCCD_ERROR);
+    ctx_close.expect().times(2).return_const_st(QHYCCD_ERROR);
     let ctx_release = ReleaseQHYCCDResource_context();
     ctx_release.expect().return_const_st(QHYCCD_SUCCESS);
     //when
@@ -351,3 +410,190 @@ fn new_fail_close() {
     assert_eq!(sdk.filter_wheels().count(), 0);
     assert!(sdk.filter_wheels().last().is_none());
 }
+
+#[test]
+fn set_log_level_success() {
+    //given
+    let ctx = SetQHYCCDLogLevel_context();
+    ctx.expect()
+        .withf_st(|level| *level == 1)
+        .times(1)
+        .return_const_st(());
+    //when
+    Sdk::set_log_level(QhyLogLevel::Error);
+    //then
+    ctx.checkpoint();
+}
+
+#[test]
+fn enable_messages_success() {
+    //given
+    let ctx = EnableQHYCCDMessage_context();
+    ctx.expect()
+        .withf_st(|enable| !*enable)
+        .times(1)
+        .return_const_st(());
+    //when
+    Sdk::enable_messages(false);
+    //then
+    ctx.checkpoint();
+}
+
+#[test]
+fn enable_log_file_success() {
+    //given
+    let ctx = EnableQHYCCDLogFile_context();
+    ctx.expect()
+        .withf_st(|enable| *enable)
+        .times(1)
+        .return_const_st(());
+    //when
+    Sdk::enable_log_file(true);
+    //then
+    ctx.checkpoint();
+}
+
+#[test]
+fn sdk_values_share_resources() {
+    //given
+    let _lock = sdk_lock();
+    let ctx_init = InitQHYCCDResource_context();
+    ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_scan = ScanQHYCCD_context();
+    ctx_scan.expect().times(2).return_const_st(0_u32);
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release.expect().never();
+    let first = Sdk::new().unwrap();
+    let second = Sdk::new().unwrap();
+    let clone = second.clone();
+    //when
+    drop(first);
+    drop(second);
+    //then
+    ctx_release.checkpoint();
+    ctx_release
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    drop(clone);
+    ctx_release.checkpoint();
+}
+
+#[test]
+fn sdk_reinitializes_after_last_drop() {
+    //given
+    let _lock = sdk_lock();
+    let ctx_init = InitQHYCCDResource_context();
+    ctx_init.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_scan = ScanQHYCCD_context();
+    ctx_scan.expect().times(2).return_const_st(0_u32);
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release
+        .expect()
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    //when
+    drop(Sdk::new().unwrap());
+    let sdk = Sdk::new();
+    //then
+    assert!(sdk.is_ok());
+}
+
+#[test]
+fn camera_handle_invalid_after_sdk_released() {
+    //given
+    let _lock = sdk_lock();
+    let ctx_init = InitQHYCCDResource_context();
+    ctx_init.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let ctx_scan = ScanQHYCCD_context();
+    ctx_scan.expect().times(1).return_const_st(0_u32);
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open
+        .expect()
+        .times(1)
+        .return_const_st(0xdeadbeef as *const std::ffi::c_void);
+    let sdk = Sdk::new().unwrap();
+    let camera = Camera::new("QHY178M-222b16468c5966524".to_owned());
+    camera.open().unwrap();
+    crate::mocks::keep_open(&camera);
+    //when
+    drop(sdk);
+    //then
+    assert!(!camera.is_open().unwrap());
+    let res = camera.get_model();
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        CameraClosedError.to_string()
+    );
+    assert!(camera.close().is_ok());
+    assert_eq!(
+        camera.get_model().err().unwrap().to_string(),
+        CameraNotOpenError.to_string()
+    );
+}
+
+#[test]
+fn sdk_release_waits_for_running_call() {
+    //given
+    static IN_CALL: AtomicBool = AtomicBool::new(false);
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    static OVERLAPPED: AtomicBool = AtomicBool::new(false);
+    let _lock = sdk_lock();
+    let ctx_init = InitQHYCCDResource_context();
+    ctx_init.expect().times(1).return_const(QHYCCD_SUCCESS);
+    let ctx_scan = ScanQHYCCD_context();
+    ctx_scan.expect().times(1).return_const(0_u32);
+    let ctx_release = ReleaseQHYCCDResource_context();
+    ctx_release.expect().times(1).returning(|| {
+        OVERLAPPED.store(IN_CALL.load(Ordering::SeqCst), Ordering::SeqCst);
+        QHYCCD_SUCCESS
+    });
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open
+        .expect()
+        .times(1)
+        .return_const_st(0xdeadbeef as *const std::ffi::c_void);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).returning(|_, _, _| {
+        IN_CALL.store(true, Ordering::SeqCst);
+        STARTED.store(true, Ordering::SeqCst);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        IN_CALL.store(false, Ordering::SeqCst);
+        QHYCCD_SUCCESS
+    });
+    let sdk = Sdk::new().unwrap();
+    let camera = Camera::new("QHY178M-222b16468c5966524".to_owned());
+    camera.open().unwrap();
+    crate::mocks::keep_open(&camera);
+    //when
+    let call = {
+        let camera = camera.clone();
+        std::thread::spawn(move || camera.set_parameter(Control::Gain, 10.0))
+    };
+    while !STARTED.load(Ordering::SeqCst) {
+        std::thread::yield_now();
+    }
+    drop(sdk);
+    //then
+    assert!(call.join().unwrap().is_ok());
+    assert!(!OVERLAPPED.load(Ordering::SeqCst));
+    assert!(!camera.is_open().unwrap());
+}
+
+#[cfg(feature = "eyre")]
+#[test]
+fn eyre_report_converts_to_external_error() {
+    //given
+    let report = eyre::eyre!("focuser stalled");
+    //when
+    let error = QHYError::from(report);
+    //then
+    assert!(matches!(error, ExternalError(_)));
+    assert_eq!(error.to_string(), "focuser stalled");
+}
@@ -0,0 +1,62 @@
+use super::*;
+use crate::mocks::{new_camera, set_missing_functions};
+use crate::sdk_features::require;
+
+#[test]
+fn features_set_operations() {
+    //given
+    let mut features = SdkFeatures::default();
+    //when
+    features.insert(SdkFeature::Gps);
+    features.insert(SdkFeature::Hotplug);
+    //then
+    assert!(features.contains(SdkFeature::Gps));
+    assert!(!features.contains(SdkFeature::Humidity));
+    assert_eq!(
+        features.iter().collect::<Vec<_>>(),
+        vec![SdkFeature::Hotplug, SdkFeature::Gps]
+    );
+    assert_eq!(format!("{:?}", features), "{Hotplug, Gps}");
+    assert_eq!(
+        SdkFeature::ALL
+            .iter()
+            .copied()
+            .collect::<SdkFeatures>()
+            .iter()
+            .count(),
+        SdkFeature::ALL.len()
+    );
+}
+
+#[test]
+fn require_missing_function() {
+    //given
+    set_missing_functions(&["GetQHYCCDReadModeName"]);
+    //when
+    let missing = require(SdkFeature::ReadoutModes);
+    let present = require(SdkFeature::Humidity);
+    //then
+    assert!(matches!(
+        missing,
+        Err(UnsupportedSdkFeatureError {
+            feature: SdkFeature::ReadoutModes
+        })
+    ));
+    assert!(present.is_ok());
+}
+
+#[test]
+fn unsupported_feature_skips_sdk_call() {
+    //given
+    set_missing_functions(&["GetQHYCCDPressure"]);
+    let cam = new_camera();
+    //when
+    let res = cam.get_pressure();
+    //then
+    assert!(matches!(
+        res,
+        Err(UnsupportedSdkFeatureError {
+            feature: SdkFeature::Pressure
+        })
+    ));
+}
@@ -0,0 +1,296 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    ExpQHYCCDSingleFrame_context, GetQHYCCDChipInfo_context, GetQHYCCDMemLength_context,
+    GetQHYCCDParamMinMaxStep_context, GetQHYCCDParam_context, GetQHYCCDSingleFrame_context,
+    InitQHYCCD_context, IsQHYCCDControlAvailable_context, OpenQHYCCD_context,
+    SetQHYCCDParam_context, SetQHYCCDStreamMode_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+fn options() -> SelfTestOptions {
+    SelfTestOptions {
+        dark_exposure: Duration::from_millis(500),
+        cooler_settle_time: Duration::ZERO,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn self_test_success() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().once().return_const_st(TEST_HANDLE);
+    let ctx_stream = SetQHYCCDStreamMode_context();
+    ctx_stream
+        .expect()
+        .withf_st(|_, mode| *mode == StreamMode::SingleFrameMode as u8)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_info = GetQHYCCDChipInfo_context();
+    ctx_info.expect().once().returning_st(
+        |_handle, _chipw, _chiph, imagew, imageh, _pixelw, _pixelh, bpp| unsafe {
+            *imagew = 2;
+            *imageh = 1;
+            *bpp = 16;
+            QHYCCD_SUCCESS
+        },
+    );
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(Control::all().len() + 4)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits
+        .expect()
+        .once()
+        .returning_st(|_handle, _control, min, max, step| unsafe {
+            *min = 10.0;
+            *max = 1_000_000.0;
+            *step = 1.0;
+            QHYCCD_SUCCESS
+        });
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Exposure as u32 && *value == 10.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Exposure as u32 && *value == 500_000.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Cooler as u32 && *value == 18.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Cooler as u32 && *value == -10.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(2).return_const_st(4_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(2).returning_st(
+        |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 16;
+            *channels = 1;
+            let test_image = [0xe8_u8, 0x03, 0xd0, 0x07]; // 1000 and 2000
+            buffer.copy_from(test_image.as_ptr(), 4);
+            QHYCCD_SUCCESS
+        },
+    );
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(5)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::Cooler as u32 => -10.0,
+            c if c == Control::CurTemp as u32 => 20.0,
+            c if c == Control::CurPWM as u32 => 40.0,
+            c if c == Control::VacuumPump as u32 => 1.0,
+            c if c == Control::SensorChamberCyclePump as u32 => 0.0,
+            _ => panic!("unexpected control"),
+        });
+    let cam = Camera::new("test_camera".to_owned());
+    crate::mocks::keep_open(&cam);
+    //when
+    let report = cam.self_test_with_options(&options());
+    //then
+    assert!(report.passed(), "{:?}", report);
+    assert_eq!(report.results.len(), 7);
+    assert_eq!(
+        report.outcome(SelfTestStep::BiasFrame),
+        Some(&SelfTestOutcome::Passed("mean 1500.0".to_owned()))
+    );
+    assert_eq!(
+        report.outcome(SelfTestStep::Capabilities),
+        Some(&SelfTestOutcome::Passed(
+            "2x1 pixels, 16 bits, 92 of 92 known controls available".to_owned()
+        ))
+    );
+    assert_eq!(
+        report.outcome(SelfTestStep::Chamber),
+        Some(&SelfTestOutcome::Passed(
+            "vacuum pump Some(true), cycle pump Some(false)".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn self_test_open_fail() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().once().return_const_st(std::ptr::null());
+    let cam = Camera::new("test_camera".to_owned());
+    crate::mocks::keep_open(&cam);
+    //when
+    let report = cam.self_test_with_options(&options());
+    //then
+    assert!(!report.passed());
+    assert_eq!(report.results.len(), 7);
+    assert!(matches!(
+        report.outcome(SelfTestStep::Open),
+        Some(SelfTestOutcome::Failed(_))
+    ));
+    assert_eq!(
+        report.outcome(SelfTestStep::Cooler),
+        Some(&SelfTestOutcome::Skipped("camera is not ready".to_owned()))
+    );
+}
+
+#[test]
+fn self_test_saturated_frames_without_cooler() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().once().return_const_st(TEST_HANDLE);
+    let ctx_stream = SetQHYCCDStreamMode_context();
+    ctx_stream.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_info = GetQHYCCDChipInfo_context();
+    ctx_info.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(Control::all().len() + 3)
+        .return_const_st(QHYCCD_ERROR);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(2).return_const_st(2_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(2).returning_st(
+        |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            let test_image = [255_u8, 250];
+            buffer.copy_from(test_image.as_ptr(), 2);
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = Camera::new("test_camera".to_owned());
+    crate::mocks::keep_open(&cam);
+    //when
+    let report = cam.self_test_with_options(&options());
+    //then
+    assert!(!report.passed());
+    assert!(matches!(
+        report.outcome(SelfTestStep::DarkFrame),
+        Some(SelfTestOutcome::Failed(_))
+    ));
+    assert_eq!(
+        report.outcome(SelfTestStep::Cooler),
+        Some(&SelfTestOutcome::Skipped("camera has no cooler".to_owned()))
+    );
+    assert_eq!(
+        report.outcome(SelfTestStep::Chamber),
+        Some(&SelfTestOutcome::Skipped(
+            "camera has no chamber pumps".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn self_test_cooler_power_fail_restores_set_point() {
+    //given
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(3)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::Cooler as u32 => -20.0,
+            c if c == Control::CurTemp as u32 => 5.0,
+            _ => QHYCCD_ERROR_F64,
+        });
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Cooler as u32 && *value == 3.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|_, control, value| *control == Control::Cooler as u32 && *value == -20.0)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.self_test_cooler(&options());
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        GetParameterError {
+            control: Control::CurPWM
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn self_test_mean_uses_reported_bit_depth_and_ignores_padding() {
+    //given
+    let ctx_open = OpenQHYCCD_context();
+    ctx_open.expect().once().return_const_st(TEST_HANDLE);
+    let ctx_stream = SetQHYCCDStreamMode_context();
+    ctx_stream.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_info = GetQHYCCDChipInfo_context();
+    ctx_info.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(Control::all().len() + 3)
+        .return_const_st(QHYCCD_ERROR);
+    let ctx_limits = GetQHYCCDParamMinMaxStep_context();
+    ctx_limits.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_exp = ExpQHYCCDSingleFrame_context();
+    ctx_exp.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_size = GetQHYCCDMemLength_context();
+    ctx_size.expect().times(2).return_const_st(8_u32);
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(2).returning_st(
+        |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 2;
+            *height = 1;
+            *bpp = 12;
+            *channels = 1;
+            // 3800 twice followed by padding
+            let test_image = [0xd8_u8, 0x0e, 0xd8, 0x0e, 0, 0, 0, 0];
+            buffer.copy_from(test_image.as_ptr(), 8);
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = Camera::new("test_camera".to_owned());
+    crate::mocks::keep_open(&cam);
+    //when
+    let report = cam.self_test_with_options(&options());
+    //then
+    assert_eq!(
+        report.outcome(SelfTestStep::BiasFrame),
+        Some(&SelfTestOutcome::Failed(
+            SelfTestCheckError {
+                reason: "frame mean 3800.0 is above 90% of full scale".to_owned()
+            }
+            .to_string()
+        ))
+    );
+}
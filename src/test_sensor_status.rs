@@ -0,0 +1,75 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParam_context, IsQHYCCDControlAvailable_context, QHYCCDSensorPhaseReTrain_context,
+    QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+#[test]
+fn retrain_sensor_phase_success() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf(|_, control| *control == Control::CamSensorPhaseReTrain as u32)
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_retrain = QHYCCDSensorPhaseReTrain_context();
+    ctx_retrain
+        .expect()
+        .withf(|handle| *handle == TEST_HANDLE)
+        .times(1)
+        .return_const_st(());
+    let cam = new_camera();
+    //when
+    let res = cam.retrain_sensor_phase();
+    //then
+    assert!(res.is_ok());
+}
+
+#[test]
+fn retrain_sensor_phase_unsupported() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.retrain_sensor_phase();
+    //then
+    assert!(matches!(
+        res,
+        Err(IsControlAvailableError {
+            control: Control::CamSensorPhaseReTrain
+        })
+    ));
+}
+
+#[test]
+fn ulvo_status_values() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .withf(|_, control| *control == Control::CamSensorUlvoStatus as u32)
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let mut reads = 0;
+    let ctx_get = GetQHYCCDParam_context();
+    ctx_get.expect().times(2).returning_st(move |_, _| {
+        reads += 1;
+        match reads {
+            1 => 0.0,
+            _ => 2.0,
+        }
+    });
+    let cam = new_camera();
+    //when
+    let first = cam.ulvo_status();
+    let second = cam.ulvo_status();
+    //then
+    assert_eq!(first.unwrap(), UlvoStatus::Normal);
+    assert_eq!(second.unwrap(), UlvoStatus::Undervoltage { code: 2 });
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+#[test]
+fn statistics_8_bit() {
+    //given
+    let image = ImageData {
+        data: vec![2, 4, 4, 4, 5, 5, 7, 9],
+        width: 4,
+        height: 2,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let statistics = image.statistics().unwrap();
+    //then
+    assert_eq!(
+        statistics,
+        FrameStatistics {
+            min: 2,
+            max: 9,
+            mean: 5.0,
+            median: 4.5,
+            stddev: 2.0,
+            saturated_percent: 0.0
+        }
+    );
+}
+
+#[test]
+fn statistics_16_bit_saturated() {
+    //given
+    let image = ImageData {
+        data: vec![0, 1, 255, 255, 16, 0, 0xff, 0xff, 0, 0],
+        width: 5,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    //when
+    let statistics = image.statistics().unwrap();
+    //then
+    assert_eq!(statistics.min, 0);
+    assert_eq!(statistics.max, 65535);
+    assert_eq!(statistics.median, 256.0);
+    assert_eq!(statistics.saturated_percent, 40.0);
+}
+
+#[test]
+fn statistics_32_bit() {
+    //given
+    let image = ImageData {
+        data: [7_u32, 1, 100_000]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect(),
+        width: 3,
+        height: 1,
+        bits_per_pixel: 32,
+        channels: 1,
+    };
+    //when
+    let statistics = image.statistics().unwrap();
+    //then
+    assert_eq!((statistics.min, statistics.max), (1, 100_000));
+    assert_eq!(statistics.median, 7.0);
+    assert_eq!(statistics.mean, 100_008.0 / 3.0);
+}
+
+#[test]
+fn statistics_empty_frame() {
+    //given
+    let image = ImageData {
+        data: vec![],
+        width: 0,
+        height: 0,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    //when
+    let statistics = image.statistics().unwrap();
+    //then
+    assert_eq!(statistics.mean, 0.0);
+    assert_eq!(image.histogram(4).unwrap(), vec![0; 4]);
+}
+
+#[test]
+fn histogram_bins() {
+    //given
+    let image = ImageData {
+        data: vec![0, 63, 64, 128, 200, 255],
+        width: 6,
+        height: 1,
+        bits_per_pixel: 8,
+        channels: 1,
+    };
+    //when
+    let histogram = image.histogram(4).unwrap();
+    //then
+    assert_eq!(histogram, vec![2, 1, 1, 2]);
+    assert_eq!(image.histogram(0).unwrap(), vec![6]);
+}
+
+#[test]
+fn statistics_unsupported_bit_depth() {
+    //given
+    let image = ImageData {
+        data: vec![0],
+        width: 1,
+        height: 1,
+        bits_per_pixel: 0,
+        channels: 1,
+    };
+    //when
+    let res = image.statistics();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        UnsupportedBitDepthError { bits_per_pixel: 0 }.to_string()
+    );
+}
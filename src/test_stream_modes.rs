@@ -0,0 +1,84 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    BeginQHYCCDLive_context, GetQHYCCDLiveFrame_context, InitQHYCCD_context,
+    SetQHYCCDStreamMode_context, StopQHYCCDLive_context, QHYCCD_ERROR, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+#[test]
+fn into_live_mode_and_back() {
+    //given
+    let ctx_mode = SetQHYCCDStreamMode_context();
+    ctx_mode
+        .expect()
+        .withf_st(|_, mode| *mode == StreamMode::LiveMode as u8)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_mode
+        .expect()
+        .withf_st(|_, mode| *mode == StreamMode::SingleFrameMode as u8)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().times(2).return_const_st(QHYCCD_SUCCESS);
+    let ctx_begin = BeginQHYCCDLive_context();
+    ctx_begin.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let ctx_frame = GetQHYCCDLiveFrame_context();
+    ctx_frame.expect().once().returning_st(
+        |_handle, width, height, bpp, channels, buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            *buffer = 3;
+            QHYCCD_SUCCESS
+        },
+    );
+    let ctx_stop = StopQHYCCDLive_context();
+    ctx_stop.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let live = cam.into_live_mode().unwrap();
+    let image = live.get_live_frame(1).unwrap();
+    let single = live.into_single_frame_mode();
+    //then
+    assert_eq!(image.data, vec![3]);
+    assert!(single.is_ok());
+}
+
+#[test]
+fn into_live_mode_fail() {
+    //given
+    let ctx_mode = SetQHYCCDStreamMode_context();
+    ctx_mode.expect().once().return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.into_live_mode();
+    //then
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        SetStreamModeError {
+            error_code: QHYCCD_ERROR
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn single_frame_camera_into_inner() {
+    //given
+    let ctx_mode = SetQHYCCDStreamMode_context();
+    ctx_mode
+        .expect()
+        .withf_st(|_, mode| *mode == StreamMode::SingleFrameMode as u8)
+        .once()
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_init = InitQHYCCD_context();
+    ctx_init.expect().once().return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let single = cam.clone().into_single_frame_mode().unwrap();
+    //then
+    assert_eq!(single.camera(), &cam);
+    assert_eq!(single.into_inner(), cam);
+}
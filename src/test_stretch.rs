@@ -0,0 +1,104 @@
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    IsQHYCCDControlAvailable_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::{new_camera, TEST_HANDLE};
+
+#[test]
+fn set_screen_stretch_hardware() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(2)
+        .return_const_st(QHYCCD_SUCCESS);
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE && *control == Control::ScreenStretchB as u32 && *value == 0.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    ctx_set
+        .expect()
+        .withf_st(|handle, control, value| {
+            *handle == TEST_HANDLE
+                && *control == Control::ScreenStretchW as u32
+                && *value == 32768.0
+        })
+        .times(1)
+        .return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let res = cam.set_screen_stretch(&ScreenStretch {
+        black: 0.0,
+        white: 0.5,
+    });
+    //then
+    assert_eq!(res.unwrap(), StretchMode::Hardware);
+}
+
+#[test]
+fn set_screen_stretch_software() {
+    //given
+    let ctx_available = IsQHYCCDControlAvailable_context();
+    ctx_available
+        .expect()
+        .times(1)
+        .return_const_st(QHYCCD_ERROR);
+    let cam = new_camera();
+    //when
+    let res = cam.set_screen_stretch(&ScreenStretch::default());
+    //then
+    assert_eq!(res.unwrap(), StretchMode::Software);
+}
+
+#[test]
+fn set_screen_stretch_invalid_fail() {
+    //given
+    let cam = new_camera();
+    //when
+    let res = cam.set_screen_stretch(&ScreenStretch {
+        black: 0.6,
+        white: 0.4,
+    });
+    //then
+    assert!(res.is_err());
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        QHYError::InvalidScreenStretchError {
+            black: 0.6,
+            white: 0.4
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn apply_stretch_16bit() {
+    //given
+    let image = ImageData {
+        data: [0_u16, 16384, 32768, 49152, 65535]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect(),
+        width: 5,
+        height: 1,
+        bits_per_pixel: 16,
+        channels: 1,
+    };
+    let stretch = ScreenStretch {
+        black: 0.25,
+        white: 0.75,
+    };
+    //when
+    let res = image.apply_stretch(&stretch).unwrap();
+    //then
+    let samples = res
+        .data
+        .chunks_exact(2)
+        .map(|s| u16::from_le_bytes([s[0], s[1]]))
+        .collect::<Vec<_>>();
+    assert_eq!(samples, vec![0, 1, 32769, 65535, 65535]);
+}
@@ -0,0 +1,128 @@
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{
+    GetQHYCCDParam_context, GetQHYCCDSingleFrame_context, SetQHYCCDParam_context, QHYCCD_SUCCESS,
+};
+use crate::mocks::new_camera;
+
+type Recorded = Vec<(String, Vec<(String, String)>, DebugValue)>;
+
+/// runs `f` with a recorder of its own and returns the metrics it recorded
+fn record(f: impl FnOnce()) -> Recorded {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, f);
+    snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _unit, _description, value)| {
+            let key = key.key();
+            let labels = key
+                .labels()
+                .map(|label| (label.key().to_owned(), label.value().to_owned()))
+                .collect();
+            (key.name().to_owned(), labels, value)
+        })
+        .collect()
+}
+
+fn find<'a>(recorded: &'a Recorded, name: &str) -> (&'a [(String, String)], &'a DebugValue) {
+    recorded
+        .iter()
+        .find(|(metric, _, _)| metric == name)
+        .map(|(_, labels, value)| (labels.as_slice(), value))
+        .unwrap_or_else(|| panic!("{} was not recorded in {:?}", name, recorded))
+}
+
+fn labels(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn sdk_call_duration_is_recorded() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    //when
+    let recorded = record(|| cam.set_parameter(Control::Gain, 10.0).unwrap());
+    //then
+    let (labels_, value) = find(&recorded, "qhyccd_sdk_call_duration_seconds");
+    assert_eq!(labels_, labels(&[("function", "SetQHYCCDParam")]));
+    assert!(matches!(value, DebugValue::Histogram(samples) if samples.len() == 1));
+}
+
+#[test]
+fn single_frame_is_counted() {
+    //given
+    let ctx_frame = GetQHYCCDSingleFrame_context();
+    ctx_frame.expect().times(1).returning_st(
+        |_handle, width, height, bpp, channels, _buffer| unsafe {
+            *width = 1;
+            *height = 1;
+            *bpp = 8;
+            *channels = 1;
+            QHYCCD_SUCCESS
+        },
+    );
+    let cam = new_camera();
+    cam.handle.set_state(CameraState::Exposing);
+    //when
+    let recorded = record(|| {
+        cam.get_single_frame(1).unwrap();
+    });
+    //then
+    let expected = labels(&[("camera", "test_camera"), ("mode", "single")]);
+    let (labels_, value) = find(&recorded, "qhyccd_frames_captured_total");
+    assert_eq!(labels_, expected);
+    assert_eq!(value, &DebugValue::Counter(1));
+    let (labels_, value) = find(&recorded, "qhyccd_frame_download_duration_seconds");
+    assert_eq!(labels_, expected);
+    assert!(matches!(value, DebugValue::Histogram(samples) if samples.len() == 1));
+}
+
+#[test]
+fn cooler_gauges_follow_reads() {
+    //given
+    let ctx_param = GetQHYCCDParam_context();
+    ctx_param
+        .expect()
+        .times(2)
+        .returning_st(|_handle, control| match control {
+            c if c == Control::CurTemp as u32 => -10.5,
+            _ => 127.5,
+        });
+    let cam = new_camera();
+    //when
+    let recorded = record(|| {
+        cam.get_parameter(Control::CurTemp).unwrap();
+        cam.get_parameter(Control::CurPWM).unwrap();
+    });
+    //then
+    let (labels_, value) = find(&recorded, "qhyccd_sensor_temperature_celsius");
+    assert_eq!(labels_, labels(&[("camera", "test_camera")]));
+    assert_eq!(value, &DebugValue::Gauge((-10.5).into()));
+    let (_, value) = find(&recorded, "qhyccd_cooler_power_percent");
+    assert_eq!(value, &DebugValue::Gauge(50.0.into()));
+}
+
+#[test]
+fn dropped_live_frames_are_counted() {
+    //given
+    let cam = Camera::new("test_camera".to_owned());
+    //when
+    let recorded = record(|| {
+        for counter in [1, 2, 5, 6, 9] {
+            cam.count_dropped_frames(counter);
+        }
+    });
+    //then
+    let (labels_, value) = find(&recorded, "qhyccd_live_frames_dropped_total");
+    assert_eq!(labels_, labels(&[("camera", "test_camera")]));
+    assert_eq!(value, &DebugValue::Counter(4));
+}
@@ -0,0 +1,51 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use super::*;
+use crate::mocks::mock_libqhyccd_sys::{SetQHYCCDParam_context, QHYCCD_SUCCESS};
+use crate::mocks::new_camera;
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn sdk_call_is_traced_in_a_span() {
+    //given
+    let ctx_set = SetQHYCCDParam_context();
+    ctx_set.expect().times(1).return_const_st(QHYCCD_SUCCESS);
+    let cam = new_camera();
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    //when
+    tracing::subscriber::with_default(subscriber, || {
+        cam.set_parameter(Control::Gain, 10.0).unwrap()
+    });
+    //then
+    let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    let line = output
+        .lines()
+        .find(|line| line.contains("sdk_call{"))
+        .unwrap_or_else(|| panic!("no sdk_call span in {:?}", output));
+    assert!(line.contains("function=\"SetQHYCCDParam\""), "{}", line);
+    assert!(line.contains("camera=test_camera"), "{}", line);
+    assert!(line.contains("0xdeadbeef"), "{}", line);
+    assert!(line.contains("10.0"), "{}", line);
+    assert!(line.contains("result=0"), "{}", line);
+    assert!(line.contains("duration="), "{}", line);
+}
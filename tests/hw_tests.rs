@@ -0,0 +1,183 @@
+//! Hardware-in-the-loop tests, these talk to real devices through the QHYCCD SDK.
+//!
+//! The tests are only compiled with the `hw-tests` feature and are ignored by default, so a
+//! regular `cargo test` never touches attached hardware. To validate a release run
+//!
+//! ```sh
+//! QHYCCD_TEST_CAMERA=QHY178M-222b16468c5966524 \
+//!     cargo test --features hw-tests --test hw_tests -- --ignored --test-threads=1
+//! ```
+//!
+//! The following environment variables select the devices and tune the tests:
+//! * `QHYCCD_TEST_CAMERA` - the id of the camera to test, defaults to the last camera found
+//! * `QHYCCD_TEST_FILTER_WHEEL` - the id of the filter wheel to test, defaults to the last one found
+//! * `QHYCCD_TEST_EXPOSURE_US` - the exposure time for the capture tests, defaults to 10000
+#![cfg(feature = "hw-tests")]
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use qhyccd_rs::{Camera, Control, FilterWheel, Sdk, StreamMode};
+
+/// the SDK can only be initialized once per process, so the tests take turns
+static SDK_LOCK: Mutex<()> = Mutex::new(());
+
+fn with_sdk(test: impl FnOnce(&Sdk)) {
+    let _guard = SDK_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let sdk = Sdk::new().expect("SDK::new failed");
+    test(&sdk);
+}
+
+fn camera(sdk: &Sdk) -> &Camera {
+    match std::env::var("QHYCCD_TEST_CAMERA") {
+        Ok(id) => sdk
+            .cameras()
+            .find(|camera| camera.id() == id)
+            .unwrap_or_else(|| panic!("camera {} not found", id)),
+        Err(_) => sdk.cameras().last().expect("no camera found"),
+    }
+}
+
+fn filter_wheel(sdk: &Sdk) -> &FilterWheel {
+    match std::env::var("QHYCCD_TEST_FILTER_WHEEL") {
+        Ok(id) => sdk
+            .filter_wheels()
+            .find(|filter_wheel| filter_wheel.id() == id)
+            .unwrap_or_else(|| panic!("filter wheel {} not found", id)),
+        Err(_) => sdk.filter_wheels().last().expect("no filter wheel found"),
+    }
+}
+
+fn exposure_us() -> f64 {
+    std::env::var("QHYCCD_TEST_EXPOSURE_US")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(10_000.0)
+}
+
+fn prepare(camera: &Camera, mode: StreamMode) {
+    camera.open().expect("open failed");
+    camera
+        .set_stream_mode(mode)
+        .expect("set_stream_mode failed");
+    camera.init().expect("init failed");
+    camera
+        .set_parameter(Control::Exposure, exposure_us())
+        .expect("set_parameter failed");
+}
+
+#[test]
+#[ignore]
+fn hw_self_test() {
+    with_sdk(|sdk| {
+        let camera = camera(sdk);
+        let report = camera.self_test();
+        camera.close().expect("close failed");
+        for (step, outcome) in &report.results {
+            println!("{:?}: {:?}", step, outcome);
+        }
+        assert!(report.passed());
+    });
+}
+
+#[test]
+#[ignore]
+fn hw_capabilities() {
+    with_sdk(|sdk| {
+        let camera = camera(sdk);
+        camera.open().expect("open failed");
+        let capabilities = camera.capabilities();
+        camera.close().expect("close failed");
+        println!("{:?}", capabilities);
+        assert!(capabilities.supports(Control::Exposure));
+        assert!(capabilities.bin_modes.contains(&1));
+        assert!(!capabilities.pixel_formats.is_empty());
+    });
+}
+
+#[test]
+#[ignore]
+fn hw_single_frame_capture() {
+    with_sdk(|sdk| {
+        let camera = camera(sdk);
+        prepare(camera, StreamMode::SingleFrameMode);
+        let info = camera.get_ccd_info().expect("get_ccd_info failed");
+        camera
+            .start_single_frame_exposure()
+            .expect("start_single_frame_exposure failed");
+        let buffer_size = camera.get_image_size().expect("get_image_size failed");
+        let image = camera
+            .get_single_frame(buffer_size)
+            .expect("get_single_frame failed");
+        camera.close().expect("close failed");
+        assert_eq!(image.width, info.image_width);
+        assert_eq!(image.height, info.image_height);
+        assert!(image.data.len() <= buffer_size);
+    });
+}
+
+#[test]
+#[ignore]
+fn hw_live_capture() {
+    with_sdk(|sdk| {
+        let camera = camera(sdk);
+        if camera
+            .is_control_available(Control::CamLiveVideoMode)
+            .is_none()
+        {
+            println!("{} does not support live mode, skipping", camera.id());
+            return;
+        }
+        prepare(camera, StreamMode::LiveMode);
+        camera.begin_live().expect("begin_live failed");
+        let buffer_size = camera.get_image_size().expect("get_image_size failed");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut frames = 0;
+        while frames < 3 && Instant::now() < deadline {
+            match camera.get_live_frame(buffer_size) {
+                Ok(image) => {
+                    assert!(image.width > 0 && image.height > 0);
+                    frames += 1;
+                }
+                Err(_) => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        camera.end_live().expect("end_live failed");
+        camera.close().expect("close failed");
+        assert_eq!(frames, 3);
+    });
+}
+
+#[test]
+#[ignore]
+fn hw_filter_wheel_cycle() {
+    with_sdk(|sdk| {
+        let filter_wheel = filter_wheel(sdk);
+        filter_wheel.open().expect("open failed");
+        let filters = filter_wheel
+            .get_number_of_filters()
+            .expect("get_number_of_filters failed");
+        let start = filter_wheel
+            .get_fw_position()
+            .expect("get_fw_position failed");
+        let target = (start + 1) % filters;
+        filter_wheel
+            .set_fw_position(target)
+            .expect("set_fw_position failed");
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while filter_wheel.get_fw_position().ok() != Some(target) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(500));
+        }
+        let reached = filter_wheel
+            .get_fw_position()
+            .expect("get_fw_position failed");
+        filter_wheel
+            .set_fw_position(start)
+            .expect("set_fw_position failed");
+        filter_wheel.close().expect("close failed");
+        assert_eq!(reached, target);
+    });
+}